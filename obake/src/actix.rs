@@ -0,0 +1,198 @@
+//! [`actix_web`] integration for [`versioned`](crate::versioned) data-structures.
+//!
+//! [`VersionedJson`] is an extractor accepting a JSON request body of any declared version of a
+//! data-structure (see [`json`](crate::json) for the accepted encodings) and handing the
+//! handler the migrated latest version. Bodies which cannot be decoded (or whose fallible
+//! migrations fail) are rejected with `400 Bad Request` and a JSON body naming the supported
+//! versions.
+//!
+//! [`Downgraded`] is a responder encoding the latest version of a data-structure marked with
+//! `#[obake(downgrade)]` in the canonical JSON envelope, downgraded to the version negotiated
+//! from the request's `Accept-Version` or `X-Api-Version` header (see [`http`](crate::http)).
+//! Requests for undeclared versions are answered with `406 Not Acceptable` and a JSON body
+//! naming the supported versions.
+//!
+//! Requires the feature `actix-web`.
+//!
+//! ```
+//! use obake::actix::{Downgraded, VersionedJson};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(downgrade)]
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct User {
+//!     #[obake(cfg("0.1.0"))]
+//!     name: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     display_name: String,
+//! }
+//!
+//! impl From<User!["0.1.0"]> for User!["0.2.0"] {
+//!     fn from(from: User!["0.1.0"]) -> Self {
+//!         Self { display_name: from.name }
+//!     }
+//! }
+//!
+//! impl From<User!["0.2.0"]> for User!["0.1.0"] {
+//!     fn from(from: User!["0.2.0"]) -> Self {
+//!         Self { name: from.display_name }
+//!     }
+//! }
+//!
+//! async fn echo(VersionedJson(user): VersionedJson<User>) -> Downgraded<User> {
+//!     Downgraded(user)
+//! }
+//!
+//! let app = actix_web::App::new().route("/echo", actix_web::web::post().to(echo));
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+
+use actix_web::body::BoxBody;
+use actix_web::dev::Payload;
+use actix_web::http::header::ContentType;
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use actix_web::{FromRequest, HttpRequest, HttpResponse, Responder, ResponseError};
+
+use crate::codec::{Decode, Json};
+use crate::http::{error_body, requested_version, respond};
+use crate::json::{from_slice, Error, ToEnvelope};
+use crate::{
+    try_upgrade_rendered, Accept, AnyVersion, Dispatch, Downgrade, StepError, TryUpgrade, Versioned,
+};
+
+/// An extractor deserializing a JSON request body of any declared version of `T`, migrated to
+/// the latest version.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VersionedJson<T>(pub T);
+
+/// The error produced by [`VersionedJson`] when the request body cannot be decoded or migrated.
+#[derive(Debug)]
+pub struct Rejection {
+    error: RejectionKind,
+    supported_versions: &'static [&'static str],
+}
+
+#[derive(Debug)]
+enum RejectionKind {
+    Body(String),
+    Decode(Error),
+    Migrate(StepError<String>),
+}
+
+impl Rejection {
+    /// The semantic version numbers of the versions the extractor would have accepted.
+    #[must_use]
+    pub fn supported_versions(&self) -> &'static [&'static str] {
+        self.supported_versions
+    }
+
+    /// The decoding error, or `None` if the request body could not be read or migrated.
+    #[must_use]
+    pub fn decode_error(&self) -> Option<&Error> {
+        match &self.error {
+            RejectionKind::Decode(err) => Some(err),
+            RejectionKind::Body(_) | RejectionKind::Migrate(_) => None,
+        }
+    }
+
+    /// The error of the migration which failed on the decoded request body (rendered with its
+    /// `Display` implementation), or `None` if the body could not be read or decoded.
+    #[must_use]
+    pub fn migrate_error(&self) -> Option<&StepError<String>> {
+        match &self.error {
+            RejectionKind::Migrate(err) => Some(err),
+            RejectionKind::Body(_) | RejectionKind::Decode(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            RejectionKind::Body(err) => write!(f, "failed to read request body: {err}"),
+            RejectionKind::Decode(err) => write!(f, "{err}"),
+            RejectionKind::Migrate(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl ResponseError for Rejection {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        HttpResponse::build(self.status_code()).json(error_body(self, self.supported_versions))
+    }
+}
+
+impl<T> FromRequest for VersionedJson<T>
+where
+    T: Versioned + 'static,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    type Error = Rejection;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let bytes = Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let bytes = bytes.await.map_err(|err| Rejection {
+                error: RejectionKind::Body(err.to_string()),
+                supported_versions: T::VERSIONS,
+            })?;
+
+            let tagged = from_slice::<T>(&bytes).map_err(|err| Rejection {
+                error: RejectionKind::Decode(err),
+                supported_versions: T::VERSIONS,
+            })?;
+            try_upgrade_rendered::<T>(tagged)
+                .map(Self)
+                .map_err(|err| Rejection {
+                    error: RejectionKind::Migrate(err),
+                    supported_versions: T::VERSIONS,
+                })
+        })
+    }
+}
+
+/// A responder encoding the latest version of `T` in the canonical JSON envelope, downgraded to
+/// the version negotiated from the request's version headers.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Downgraded<T>(pub T);
+
+impl<T> Responder for Downgraded<T>
+where
+    T: Versioned,
+    AnyVersion<T>: Downgrade<T> + Accept<T, ToEnvelope>,
+{
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<BoxBody> {
+        let requested = requested_version(|name| {
+            req.headers()
+                .get(name)
+                .and_then(|version| version.to_str().ok())
+        });
+
+        match respond(self.0, requested) {
+            Ok(Ok(body)) => HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .body(body),
+            Ok(Err(err)) => HttpResponse::InternalServerError().body(err.to_string()),
+            Err(err) => HttpResponse::NotAcceptable().json(error_body(&err, err.supported)),
+        }
+    }
+}
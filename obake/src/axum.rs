@@ -0,0 +1,154 @@
+//! [`axum`] integration for [`versioned`](crate::versioned) data-structures.
+//!
+//! [`VersionedJson`] is an extractor accepting a JSON request body of any declared version of a
+//! data-structure (see [`json`](crate::json) for the accepted encodings) and handing the
+//! handler the migrated latest version. Bodies which cannot be decoded (or whose fallible
+//! migrations fail) are rejected with `400 Bad Request` and a JSON body naming the supported
+//! versions:
+//!
+//! ```json
+//! {
+//!     "error": "payload does not match any declared version",
+//!     "supported_versions": ["0.1.0", "0.2.0"]
+//! }
+//! ```
+//!
+//! Requires the feature `axum`.
+//!
+//! ```
+//! use obake::axum::VersionedJson;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(serde::Deserialize)]
+//! struct CreateUser {
+//!     #[obake(cfg("0.1.0"))]
+//!     name: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     display_name: String,
+//! }
+//!
+//! impl From<CreateUser!["0.1.0"]> for CreateUser!["0.2.0"] {
+//!     fn from(from: CreateUser!["0.1.0"]) -> Self {
+//!         Self { display_name: from.name }
+//!     }
+//! }
+//!
+//! async fn create_user(VersionedJson(user): VersionedJson<CreateUser>) -> String {
+//!     user.display_name
+//! }
+//!
+//! let app: axum::Router = axum::Router::new().route("/users", axum::routing::post(create_user));
+//! ```
+
+use alloc::string::String;
+
+use core::fmt;
+
+use ::axum::body::Bytes;
+use ::axum::extract::{FromRequest, Request};
+use ::axum::http::StatusCode;
+use ::axum::response::{IntoResponse, Response};
+
+use crate::codec::{Decode, Json};
+use crate::http::error_body;
+use crate::json::{from_slice, Error};
+use crate::{try_upgrade_rendered, AnyVersion, Dispatch, StepError, TryUpgrade, Versioned};
+
+/// An extractor deserializing a JSON request body of any declared version of `T`, migrated to
+/// the latest version.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VersionedJson<T>(pub T);
+
+/// The rejection produced by [`VersionedJson`] when the request body cannot be decoded or
+/// migrated.
+#[derive(Debug)]
+pub struct Rejection {
+    error: RejectionKind,
+    supported_versions: &'static [&'static str],
+}
+
+#[derive(Debug)]
+enum RejectionKind {
+    Body(String),
+    Decode(Error),
+    Migrate(StepError<String>),
+}
+
+impl Rejection {
+    /// The semantic version numbers of the versions the extractor would have accepted.
+    #[must_use]
+    pub fn supported_versions(&self) -> &'static [&'static str] {
+        self.supported_versions
+    }
+
+    /// The decoding error, or `None` if the request body could not be read or migrated.
+    #[must_use]
+    pub fn decode_error(&self) -> Option<&Error> {
+        match &self.error {
+            RejectionKind::Decode(err) => Some(err),
+            RejectionKind::Body(_) | RejectionKind::Migrate(_) => None,
+        }
+    }
+
+    /// The error of the migration which failed on the decoded request body (rendered with its
+    /// `Display` implementation), or `None` if the body could not be read or decoded.
+    #[must_use]
+    pub fn migrate_error(&self) -> Option<&StepError<String>> {
+        match &self.error {
+            RejectionKind::Migrate(err) => Some(err),
+            RejectionKind::Body(_) | RejectionKind::Decode(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            RejectionKind::Body(err) => write!(f, "failed to read request body: {err}"),
+            RejectionKind::Decode(err) => write!(f, "{err}"),
+            RejectionKind::Migrate(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        let body = error_body(&self, self.supported_versions);
+        (StatusCode::BAD_REQUEST, ::axum::Json(body)).into_response()
+    }
+}
+
+impl<T, S> FromRequest<S> for VersionedJson<T>
+where
+    T: Versioned + Send,
+    S: Send + Sync,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    type Rejection = Rejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|err| Rejection {
+                error: RejectionKind::Body(err.body_text()),
+                supported_versions: T::VERSIONS,
+            })?;
+
+        let tagged = from_slice::<T>(&bytes).map_err(|err| Rejection {
+            error: RejectionKind::Decode(err),
+            supported_versions: T::VERSIONS,
+        })?;
+        try_upgrade_rendered::<T>(tagged)
+            .map(Self)
+            .map_err(|err| Rejection {
+                error: RejectionKind::Migrate(err),
+                supported_versions: T::VERSIONS,
+            })
+    }
+}
@@ -0,0 +1,215 @@
+//! Benchmarks of decoding and migrating [`versioned`](crate::versioned) data-structures.
+//!
+//! [`cases`] encodes a sample value of each version of a data-structure with a [`Codec`],
+//! producing a [`Case`] per version. Running a case decodes its payload as the version it was
+//! encoded at and migrates it through the chain of migrations to the latest version, which is
+//! exactly the work done when reading old data (e.g., by `obake::store`). Timing each case
+//! tracks the performance of every migration path from release to release.
+//!
+//! Requires the features `alloc` and `serde`.
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use obake::codec::Json;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg(">=0.2"))]
+//!     bar: u32,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(_: Foo!["0.1.0"]) -> Self {
+//!         Self { bar: 42 }
+//!     }
+//! }
+//!
+//! let samples = vec![Foo_v0_1_0 {}.into(), Foo { bar: 7 }.into()];
+//! let cases = obake::bench::cases::<Foo, _, _>(&Json, samples).unwrap();
+//!
+//! assert_eq!(cases[0].name(), "0.1.0 -> 0.2.0");
+//! assert_eq!(cases[0].run().unwrap(), Foo { bar: 42 });
+//! assert_eq!(cases[1].run().unwrap(), Foo { bar: 7 });
+//! # }
+//! ```
+//!
+//! Cases are independent of any particular benchmarking framework. With
+//! [`criterion`](https://docs.rs/criterion), for example, the cases for `Foo` above can be
+//! benchmarked with:
+//!
+//! ```ignore
+//! use criterion::{criterion_group, criterion_main, Criterion};
+//! use obake::codec::Json;
+//!
+//! fn upgrade(c: &mut Criterion) {
+//!     let samples = vec![Foo_v0_1_0 {}.into(), Foo { bar: 7 }.into()];
+//!
+//!     let mut group = c.benchmark_group("Foo");
+//!     for case in obake::bench::cases::<Foo, _, _>(&Json, samples).unwrap() {
+//!         group.bench_function(case.name(), |b| b.iter(|| case.run().unwrap()));
+//!     }
+//!     group.finish();
+//! }
+//!
+//! criterion_group!(benches, upgrade);
+//! criterion_main!(benches);
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::Serialize;
+
+use crate::codec::{decode_version, Codec, Decode};
+use crate::{
+    Accept, AnyVersion, Dispatch, TestValues, VersionOf, VersionTagged, VersionVisitor, Versioned,
+    VisitValue,
+};
+
+/// The [`VersionVisitor`] used by [`cases`] to encode a sample as the version it holds.
+pub struct Encode<'c, C> {
+    codec: &'c C,
+}
+
+impl<'c, C> Encode<'c, C> {
+    /// Create a visitor encoding values using `codec`.
+    #[must_use]
+    pub fn new(codec: &'c C) -> Self {
+        Self { codec }
+    }
+}
+
+impl<T, C> VersionVisitor<T> for Encode<'_, C>
+where
+    T: Versioned,
+    C: Codec,
+{
+    type Output = Result<Vec<u8>, C::Error>;
+}
+
+impl<T, V, C> VisitValue<T, V> for Encode<'_, C>
+where
+    T: Versioned,
+    V: VersionOf<T> + Serialize,
+    C: Codec,
+{
+    fn visit_value(self, value: &V) -> Self::Output {
+        self.codec.encode(value)
+    }
+}
+
+/// A benchmark of decoding a payload encoded at one version of `T` and migrating it to the
+/// latest version, created by [`cases`].
+pub struct Case<'c, T, C> {
+    version: &'static str,
+    payload: Vec<u8>,
+    codec: &'c C,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, C> Case<'_, T, C>
+where
+    T: Versioned,
+    C: Codec,
+{
+    /// The semantic version number the payload was encoded at.
+    #[must_use]
+    pub fn version(&self) -> &'static str {
+        self.version
+    }
+
+    /// The encoded payload.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// A name for this benchmark, giving the migration path measured (e.g., `0.1.0 -> 0.2.0`).
+    #[must_use]
+    pub fn name(&self) -> String {
+        let latest = T::VERSIONS.last().copied().unwrap_or_default();
+        format!("{} -> {}", self.version, latest)
+    }
+
+    /// Decode the payload and migrate it to the latest version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the payload cannot be decoded.
+    ///
+    /// ## Panics
+    ///
+    /// Never panics, as cases are only created for declared versions.
+    pub fn run(&self) -> Result<T, C::Error>
+    where
+        for<'de, 'd> AnyVersion<T>: Dispatch<T, Decode<'de, 'd, C>>,
+    {
+        decode_version::<T, C>(self.codec, self.version, &self.payload)
+            .expect("cases are only created for declared versions")
+            .map(Into::into)
+    }
+}
+
+impl<T, C> fmt::Debug for Case<'_, T, C>
+where
+    T: Versioned,
+    C: Codec,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Case")
+            .field("version", &self.version)
+            .field("payload", &self.payload)
+            .finish()
+    }
+}
+
+/// Encode each of `samples` with `codec`, producing a [`Case`] measuring the migration of each
+/// one from its version to the latest.
+///
+/// Samples are usually one value of each declared version, oldest first, so that every
+/// migration path is covered.
+///
+/// ## Errors
+///
+/// Fails if any sample cannot be encoded.
+pub fn cases<T, C, I>(codec: &C, samples: I) -> Result<Vec<Case<'_, T, C>>, C::Error>
+where
+    T: Versioned,
+    C: Codec,
+    I: IntoIterator<Item = AnyVersion<T>>,
+    for<'e> AnyVersion<T>: Accept<T, Encode<'e, C>>,
+{
+    samples
+        .into_iter()
+        .map(|sample| {
+            Ok(Case {
+                version: sample.version_str(),
+                payload: sample.accept(Encode::new(codec))?,
+                codec,
+                _marker: PhantomData,
+            })
+        })
+        .collect()
+}
+
+/// Produce a [`Case`] for each of the values given to `T` with `#[obake(test_values(...))]`
+/// (see [`TestValues`]), as [`cases`] does for samples given explicitly.
+///
+/// ## Errors
+///
+/// Fails if any value cannot be encoded.
+pub fn test_value_cases<T, C>(codec: &C) -> Result<Vec<Case<'_, T, C>>, C::Error>
+where
+    T: TestValues,
+    C: Codec,
+    for<'e> AnyVersion<T>: Accept<T, Encode<'e, C>>,
+{
+    cases(codec, T::test_values())
+}
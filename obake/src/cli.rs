@@ -0,0 +1,404 @@
+//! The engine of a command migrating files of any declared version of a
+//! [`versioned`](crate::versioned) data-structure to the latest version, for applications to
+//! wire into their own command-line interface (e.g., `myapp migrate-config <input> <output>`).
+//!
+//! [`convert`] reads a file of any declared version in any enabled format (see
+//! [`config`](crate::config)), reports the [`Plan`] of migrations (and the change of format, if
+//! any) it passes through, and writes it at the latest version. Its building blocks, [`read`],
+//! [`plan`] and [`write`], can also be used on their own, such as to ask for confirmation before
+//! writing anything.
+//!
+//! As rewriting configuration files in place is nerve-wracking, [`Options::backup`] keeps a
+//! timestamped copy of any file replaced (e.g., `settings.toml.20240601T120000Z.bak`), and
+//! [`Options::atomic`] writes to a temporary file renamed over the output once complete, so that
+//! a crash never leaves a file half-written. The paths written are returned in [`Plan::written`].
+//! Files are written back with [`config::write`](crate::config::write), which applications
+//! encoding their configuration themselves can also use.
+//!
+//! Requires at least one of the features `json`, `json5`, `ron`, `toml` or `yaml`, each
+//! enabling the corresponding format.
+//!
+//! ```
+//! # #[cfg(all(feature = "json", feature = "toml"))] {
+//! use obake::cli::{self, Options};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Settings {
+//!     #[obake(cfg("0.1.0"))]
+//!     port: u16,
+//!     #[obake(cfg(">=0.2"))]
+//!     address: String,
+//! }
+//!
+//! impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+//!     fn from(from: Settings!["0.1.0"]) -> Self {
+//!         Self { address: format!("localhost:{}", from.port) }
+//!     }
+//! }
+//!
+//! let dir = std::env::temp_dir().join("obake-cli-doctest");
+//! std::fs::create_dir_all(&dir).unwrap();
+//! let (input, output) = (dir.join("settings.json"), dir.join("settings.toml"));
+//! std::fs::write(&input, r#"{ "port": 80 }"#).unwrap();
+//!
+//! let plan = cli::convert::<Settings>(&input, &output, &Options::default()).unwrap();
+//! assert_eq!(plan.to_string(), "0.1.0 -> 0.2.0 (JSON -> TOML)");
+//! assert_eq!(
+//!     std::fs::read_to_string(&output).unwrap(),
+//!     "address = \"localhost:80\"\n",
+//! );
+//! # }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::codec::UnsupportedVersion;
+use crate::config::{self, Decodable, Format, WriteOptions};
+use crate::{
+    try_upgrade_rendered, AnyVersion, StepError, TryUpgrade, VersionOf, VersionTagged, Versioned,
+};
+
+/// Options controlling [`convert`] (and [`write_with`]).
+#[derive(Copy, Clone, Default, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Options {
+    /// The format of the input, or `None` to detect it (see [`config::load`]).
+    pub input_format: Option<Format>,
+    /// The format to write the output in, or `None` for the format named by the extension of
+    /// the output (or, failing that, the format of the input).
+    pub output_format: Option<Format>,
+    /// Wrap the output in the canonical envelope (see `obake::json`), recording its version.
+    pub envelope: bool,
+    /// Report the plan without writing the output.
+    pub dry_run: bool,
+    /// Keep a timestamped backup of the file replaced by the output, if any, alongside it.
+    pub backup: bool,
+    /// Write the output to a temporary file alongside it, then rename it over the output, so
+    /// that the output is never left half-written.
+    pub atomic: bool,
+    /// How to read an input wrapped in the canonical envelope with an undeclared version (e.g.,
+    /// written by a newer release), rejecting it by default (see [`read_with`]).
+    pub unsupported_version: UnsupportedVersion,
+}
+
+/// The migrations (and change of format) a file passes through on its way to the latest
+/// version, displayed as, e.g., `0.1.0 -> 0.2.0 -> 0.3.0 (JSON -> TOML)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Plan {
+    /// The version the file was read at.
+    pub from: &'static str,
+    /// The latest version, which the file is written at.
+    pub to: &'static str,
+    /// Each migration between consecutive versions, from the oldest.
+    pub migrations: Vec<(&'static str, &'static str)>,
+    /// The format the file was read in.
+    pub input_format: Format,
+    /// The format the file is written in.
+    pub output_format: Format,
+    /// The paths written by [`convert`], or `None` if nothing was written (e.g., with
+    /// [`Options::dry_run`]).
+    pub written: Option<Written>,
+}
+
+pub use crate::config::Written;
+
+impl Plan {
+    /// Whether the file is already at the latest version, in the requested format.
+    #[must_use]
+    pub fn is_noop(&self) -> bool {
+        self.migrations.is_empty() && self.input_format == self.output_format
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.from)?;
+        for (_, to) in &self.migrations {
+            write!(f, " -> {to}")?;
+        }
+
+        if self.input_format == self.output_format {
+            write!(f, " ({})", self.input_format)
+        } else {
+            write!(f, " ({} -> {})", self.input_format, self.output_format)
+        }
+    }
+}
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Errors produced when converting files.
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read or decoded.
+    Read(config::Error),
+    /// The input was decoded, but failed to migrate to the latest version (see [`TryUpgrade`]).
+    Migrate(StepError<String>),
+    /// The output could not be encoded in the requested format.
+    Encode {
+        /// The format the output was encoded in.
+        format: Format,
+        /// The error produced by encoding it.
+        error: BoxedError,
+    },
+    /// The output could not be written.
+    Write(io::Error),
+    /// The file replaced by the output could not be backed up, so nothing was written.
+    Backup(io::Error),
+    /// The feature enabling the output format is disabled.
+    Disabled(Format),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(err) => err.fmt(f),
+            Self::Migrate(err) => write!(f, "failed to migrate input: {err}"),
+            Self::Encode { format, error } => write!(f, "failed to encode {format}: {error}"),
+            Self::Write(err) => write!(f, "failed to write output: {err}"),
+            Self::Backup(err) => write!(f, "failed to back up output: {err}"),
+            Self::Disabled(format) => write!(f, "support for {format} is disabled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(err) => Some(err),
+            Self::Migrate(err) => Some(err),
+            Self::Encode { error, .. } => Some(&**error),
+            Self::Write(err) | Self::Backup(err) => Some(err),
+            Self::Disabled(_) => None,
+        }
+    }
+}
+
+impl From<config::Error> for Error {
+    fn from(err: config::Error) -> Self {
+        Self::Read(err)
+    }
+}
+
+/// Read the file at `input`, of any declared version of `T`, in `format` (or, if `None`, in the
+/// format detected as [`config::load`] does), returning it along with the format it was read in.
+///
+/// ## Errors
+///
+/// Fails if the file can't be read or decoded.
+pub fn read<T>(
+    input: impl AsRef<Path>,
+    format: Option<Format>,
+) -> Result<(AnyVersion<T>, Format), Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+{
+    read_with::<T>(input, format, UnsupportedVersion::Reject)
+}
+
+/// Read the file at `input` as [`read`] does, handling a file wrapped in the canonical envelope
+/// with an undeclared version according to `policy` (see [`config::load_with`]).
+///
+/// ## Errors
+///
+/// Fails if the file can't be read or decoded.
+pub fn read_with<T>(
+    input: impl AsRef<Path>,
+    format: Option<Format>,
+    policy: UnsupportedVersion,
+) -> Result<(AnyVersion<T>, Format), Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+{
+    let input = input.as_ref();
+    let s = std::fs::read_to_string(input).map_err(config::Error::Io)?;
+    if let Some(format) = format {
+        return Ok((format.decode_with::<T>(&s, policy)?, format));
+    }
+
+    let hint = input
+        .extension()
+        .and_then(|extension| Format::from_extension(extension.to_str()?));
+    Ok(config::detect::<T>(&s, hint, policy)?)
+}
+
+/// The plan of migrating `tagged`, read in `input_format`, to the latest version of `T` and
+/// writing it in `output_format`.
+#[must_use]
+pub fn plan<T>(tagged: &AnyVersion<T>, input_format: Format, output_format: Format) -> Plan
+where
+    T: Versioned,
+{
+    let from = tagged.version_str();
+    let index = T::VERSIONS
+        .iter()
+        .position(|version| *version == from)
+        .unwrap_or_default();
+    let migrations = T::VERSIONS[index..]
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+
+    Plan {
+        from,
+        to: T::VERSIONS[T::VERSIONS.len() - 1],
+        migrations,
+        input_format,
+        output_format,
+        written: None,
+    }
+}
+
+/// Write `value`, the latest version of `T`, to `output` in `format`, wrapped in the canonical
+/// envelope if `envelope` is set.
+///
+/// Formats supporting it are pretty-printed, as they're likely to be edited by hand.
+///
+/// ## Errors
+///
+/// Fails if `format` isn't enabled, or if `value` can't be encoded in it or written.
+pub fn write<T>(
+    output: impl AsRef<Path>,
+    value: &T,
+    format: Format,
+    envelope: bool,
+) -> Result<(), Error>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+{
+    let options = Options {
+        envelope,
+        ..Options::default()
+    };
+    write_with(output, value, format, &options).map(drop)
+}
+
+/// Write `value`, the latest version of `T`, to `output` in `format` as [`write`] does, wrapped
+/// in the canonical envelope, backing up the file replaced and writing atomically as `options`
+/// ask, returning the paths written.
+///
+/// ## Errors
+///
+/// Fails if `format` isn't enabled, if `value` can't be encoded in it or written, or if the file
+/// replaced can't be backed up (in which case nothing is written).
+pub fn write_with<T>(
+    output: impl AsRef<Path>,
+    value: &T,
+    format: Format,
+    options: &Options,
+) -> Result<Written, Error>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+{
+    #[derive(Serialize)]
+    struct Envelope<'a, V> {
+        version: &'static str,
+        data: &'a V,
+    }
+
+    let output = output.as_ref();
+    let mut encoded = if options.envelope {
+        encode(
+            format,
+            &Envelope {
+                version: T::VERSION,
+                data: value,
+            },
+        )?
+    } else {
+        encode(format, value)?
+    };
+    if !encoded.ends_with('\n') {
+        encoded.push('\n');
+    }
+
+    let write_options = WriteOptions {
+        backup: options.backup,
+        atomic: options.atomic,
+    };
+    config::write(output, encoded, write_options).map_err(|err| match err {
+        config::Error::Backup(err) => Error::Backup(err),
+        config::Error::Write(err) => Error::Write(err),
+        // Writing fails with no other error
+        err => Error::Read(err),
+    })
+}
+
+/// Migrate the file at `input`, of any declared version of `T` in any enabled format, to the
+/// latest version and write it to `output` (which may be `input` itself), returning the [`Plan`]
+/// it followed.
+///
+/// With [`Options::dry_run`], nothing is written.
+///
+/// ## Errors
+///
+/// Fails if the input can't be read, decoded or migrated, or if the output can't be encoded or
+/// written.
+pub fn convert<T>(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    options: &Options,
+) -> Result<Plan, Error>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+    AnyVersion<T>: Decodable<T>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    let output = output.as_ref();
+    let (tagged, input_format) =
+        read_with::<T>(input, options.input_format, options.unsupported_version)?;
+    let output_format = options
+        .output_format
+        .or_else(|| Format::from_extension(output.extension()?.to_str()?))
+        .unwrap_or(input_format);
+    if !output_format.is_enabled() {
+        return Err(Error::Disabled(output_format));
+    }
+
+    let mut plan = plan::<T>(&tagged, input_format, output_format);
+    if !options.dry_run {
+        let latest = try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate)?;
+        plan.written = Some(write_with(output, &latest, output_format, options)?);
+    }
+
+    Ok(plan)
+}
+
+fn encode<S>(format: Format, value: &S) -> Result<String, Error>
+where
+    S: Serialize,
+{
+    let encoded: Result<String, BoxedError> = match format {
+        #[cfg(feature = "json")]
+        Format::Json => serde_json::to_string_pretty(value).map_err(Into::into),
+        #[cfg(feature = "json5")]
+        Format::Json5 => json5::to_string(value).map_err(Into::into),
+        #[cfg(feature = "ron")]
+        Format::Ron => {
+            ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).map_err(Into::into)
+        }
+        #[cfg(feature = "toml")]
+        Format::Toml => toml::to_string_pretty(value).map_err(Into::into),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => serde_yaml::to_string(value).map_err(Into::into),
+        #[allow(unreachable_patterns)]
+        _ => return Err(Error::Disabled(format)),
+    };
+
+    encoded.map_err(|error| Error::Encode { format, error })
+}
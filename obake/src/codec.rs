@@ -0,0 +1,740 @@
+//! Byte-oriented encodings of [`versioned`](crate::versioned) data-structures.
+//!
+//! A [`Codec`] describes how a single version of a data-structure is turned into bytes and back.
+//! Combined with [`Dispatch`], this is enough to decode a payload of any declared version given
+//! only its semantic version number (see [`decode_version`]).
+//!
+//! Requires the features `alloc` and `serde`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AnyVersion, Dispatch, TagIndex, VersionOf, VersionVisitor, Versioned, VisitValue, VisitVersion,
+};
+
+/// A [`serde`] format used to encode and decode individual versions of a data-structure.
+pub trait Codec {
+    /// The error produced when encoding or decoding fails.
+    type Error;
+
+    /// A short name identifying this format, recorded alongside payloads where the format must
+    /// be checked before decoding (e.g., by `obake::save`).
+    const NAME: &'static str = "";
+
+    /// Encode `value` as bytes.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `value` cannot be represented in this format.
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized;
+
+    /// Decode a value from `bytes`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `bytes` is not a valid encoding of a `D`.
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>;
+}
+
+/// The [`VersionVisitor`] used by [`decode_version`] to decode a payload as a particular version.
+pub struct Decode<'de, 'c, C> {
+    bytes: &'de [u8],
+    codec: &'c C,
+}
+
+impl<'de, 'c, C> Decode<'de, 'c, C> {
+    /// Create a visitor decoding `bytes` using `codec`.
+    #[must_use]
+    pub fn new(codec: &'c C, bytes: &'de [u8]) -> Self {
+        Self { bytes, codec }
+    }
+}
+
+impl<T, C> VersionVisitor<T> for Decode<'_, '_, C>
+where
+    T: Versioned,
+    C: Codec,
+{
+    type Output = Result<AnyVersion<T>, C::Error>;
+}
+
+impl<'de, T, V, C> VisitVersion<T, V> for Decode<'de, '_, C>
+where
+    T: Versioned,
+    V: VersionOf<T> + Deserialize<'de>,
+    C: Codec,
+{
+    fn visit(self) -> Self::Output {
+        let decoded = self.codec.decode::<V>(self.bytes)?;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_decoded::<T>(V::VERSION);
+        Ok(decoded.into())
+    }
+}
+
+/// The [`VisitValue`] used to encode the version held by a version-tagged value with a codec
+/// (e.g., by `obake::export`).
+#[derive(Copy, Clone, Debug)]
+pub struct Encode<'c, C> {
+    codec: &'c C,
+}
+
+impl<'c, C> Encode<'c, C> {
+    /// Create a visitor encoding values using `codec`.
+    #[must_use]
+    pub fn new(codec: &'c C) -> Self {
+        Self { codec }
+    }
+}
+
+impl<T, C> VersionVisitor<T> for Encode<'_, C>
+where
+    T: Versioned,
+    C: Codec,
+{
+    type Output = Result<Vec<u8>, C::Error>;
+}
+
+impl<T, V, C> VisitValue<T, V> for Encode<'_, C>
+where
+    T: Versioned,
+    V: VersionOf<T> + Serialize,
+    C: Codec,
+{
+    fn visit_value(self, value: &V) -> Self::Output {
+        self.codec.encode(value)
+    }
+}
+
+/// Decode `bytes` as the declared version of `T` whose semantic version number is `version`.
+///
+/// Returns `None` if `T` declares no such version.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a valid encoding of the selected version.
+pub fn decode_version<'de, T, C>(
+    codec: &C,
+    version: &str,
+    bytes: &'de [u8],
+) -> Option<Result<AnyVersion<T>, C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+{
+    AnyVersion::<T>::dispatch(version, Decode::new(codec, bytes))
+}
+
+/// What to do with a payload tagged with a version that isn't declared (e.g., one written by a
+/// newer release), chosen when decoding rather than when compiling.
+///
+/// Each decoding helper rejects such payloads by default, and takes a policy in its `_with`
+/// variant (e.g., `obake::json::from_slice_with`) or builder (e.g.,
+/// `VersionedTree::with_unsupported_version`), so that different deployments of the same binary
+/// can be more or less strict.
+///
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use obake::codec::{decode_version_with, Json, UnsupportedVersion};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { bar: from.bar }
+/// #     }
+/// # }
+/// #
+/// let payload = br#"{"bar":1,"baz":2}"#;
+/// let decode = |policy| decode_version_with::<Foo, _>(&Json, "0.3.0", payload, policy);
+///
+/// assert!(decode(UnsupportedVersion::Reject).is_none());
+///
+/// let x = decode(UnsupportedVersion::BestEffortLatest).unwrap().unwrap();
+/// assert_eq!(Foo::from(x), Foo { bar: 1 });
+///
+/// let x = decode(UnsupportedVersion::Callback(|version| {
+///     version.starts_with("0.").then(|| "0.1.0")
+/// }));
+/// assert_eq!(Foo::from(x.unwrap().unwrap()), Foo { bar: 1 });
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub enum UnsupportedVersion {
+    /// Fail, reporting the undeclared version.
+    #[default]
+    Reject,
+    /// Decode the payload as the latest declared version, which suits payloads written by newer
+    /// releases that only added fields.
+    BestEffortLatest,
+    /// Call the function with the undeclared version, returning the declared version to decode
+    /// the payload as (or `None` to reject it).
+    Callback(fn(&str) -> Option<&'static str>),
+}
+
+impl UnsupportedVersion {
+    /// The declared version of `T` to decode a payload tagged with `version` as, which is
+    /// `version` itself if it is declared, or `None` if the payload is rejected.
+    #[must_use]
+    pub fn resolve<T>(self, version: &str) -> Option<&str>
+    where
+        T: Versioned,
+    {
+        if T::VERSIONS.contains(&version) {
+            return Some(version);
+        }
+
+        match self {
+            Self::Reject => None,
+            Self::BestEffortLatest => T::VERSIONS.last().copied(),
+            Self::Callback(callback) => {
+                callback(version).filter(|version| T::VERSIONS.contains(version))
+            }
+        }
+    }
+}
+
+/// Decode `bytes` as [`decode_version`] does, handling an undeclared `version` according to
+/// `policy`.
+///
+/// Returns `None` if the payload is rejected by `policy`.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a valid encoding of the selected version.
+pub fn decode_version_with<'de, T, C>(
+    codec: &C,
+    version: &str,
+    bytes: &'de [u8],
+    policy: UnsupportedVersion,
+) -> Option<Result<AnyVersion<T>, C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+{
+    decode_version::<T, C>(codec, policy.resolve::<T>(version)?, bytes)
+}
+
+/// Decode `bytes` as the declared version of `T` at `index` (see [`VersionOf::INDEX`]), looked up
+/// in constant time (see [`Dispatch::dispatch_index`]).
+///
+/// Returns `None` if `index` is out of bounds, or the version there is excluded by
+/// `#[obake(cfg_when(...))]`.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a valid encoding of the selected version.
+pub fn decode_index<'de, T, C>(
+    codec: &C,
+    index: usize,
+    bytes: &'de [u8],
+) -> Option<Result<AnyVersion<T>, C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+{
+    AnyVersion::<T>::dispatch_index(index, Decode::new(codec, bytes))
+}
+
+/// Decode `bytes` as the declared version of `T` tagged with `index` (see [`TagIndex`]).
+///
+/// Returns `None` if `T` declares no such version.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a valid encoding of the selected version.
+pub fn decode_tag_index<'de, T, C>(
+    codec: &C,
+    index: <AnyVersion<T> as TagIndex<T>>::Index,
+    bytes: &'de [u8],
+) -> Option<Result<AnyVersion<T>, C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    AnyVersion<T>: TagIndex<T>,
+    for<'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+{
+    AnyVersion::<T>::from_tag_index(index, Decode::new(codec, bytes))
+}
+
+/// A binary header framing each payload with the version it was encoded at, used by
+/// [`encode_framed`] and [`decode_framed`].
+///
+/// A frame consists of [`Envelope::MAGIC`], the version, the length of the payload and the
+/// payload itself, each written by the corresponding method. Implementing this trait for a
+/// legacy framing (e.g., one numbering versions with an integer) lets payloads framed by it be
+/// decoded and migrated without adopting the framing used by `obake`.
+///
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use obake::codec::{decode_framed, encode_framed, Envelope, Json};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Foo {
+///     #[obake(cfg(">=0.2"))]
+///     bar: u32,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(_: Foo!["0.1.0"]) -> Self {
+///         Self { bar: 42 }
+///     }
+/// }
+///
+/// // A legacy framing, numbering versions with a single byte
+/// struct Legacy;
+///
+/// impl Envelope for Legacy {
+///     const MAGIC: &'static [u8] = b"FOO";
+///
+///     fn write_version(&self, version: &str, bytes: &mut Vec<u8>) -> Option<()> {
+///         bytes.push(match version {
+///             "0.1.0" => 1,
+///             "0.2.0" => 2,
+///             _ => return None,
+///         });
+///         Some(())
+///     }
+///
+///     fn read_version<'a>(&self, bytes: &'a [u8]) -> Option<(&'a str, &'a [u8])> {
+///         match bytes.split_first()? {
+///             (1, rest) => Some(("0.1.0", rest)),
+///             (2, rest) => Some(("0.2.0", rest)),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let (x, rest) = decode_framed::<Foo, _, _>(&Legacy, &Json, b"FOO\x01{}").unwrap();
+/// assert_eq!(Foo::from(x), Foo { bar: 42 });
+/// assert!(rest.is_empty());
+///
+/// let framed = encode_framed(&Legacy, &Json, &Foo { bar: 7 }).unwrap();
+/// assert_eq!(framed, b"FOO\x02{\"bar\":7}");
+/// # }
+/// ```
+pub trait Envelope {
+    /// The bytes every frame begins with (e.g., a file signature), which may be empty.
+    const MAGIC: &'static [u8] = &[];
+
+    /// Append the encoding of `version` to `bytes`, or return `None` if it can't be encoded (e.g.,
+    /// it is too long for the header).
+    fn write_version(&self, version: &str, bytes: &mut Vec<u8>) -> Option<()>;
+
+    /// Read the version encoded at the start of `bytes`, returning it along with the remaining
+    /// bytes, or `None` if it is malformed.
+    fn read_version<'a>(&self, bytes: &'a [u8]) -> Option<(&'a str, &'a [u8])>;
+
+    /// Append the length of the payload to `bytes`.
+    ///
+    /// Writes nothing by default, in which case payloads run to the end of the frame.
+    fn write_len(&self, len: usize, bytes: &mut Vec<u8>) {
+        let _ = (len, bytes);
+    }
+
+    /// Read the length of the payload encoded at the start of `bytes`, returning it along with the
+    /// remaining bytes, or `None` if it is malformed.
+    ///
+    /// By default, payloads run to the end of the frame.
+    fn read_len<'a>(&self, bytes: &'a [u8]) -> Option<(usize, &'a [u8])> {
+        Some((bytes.len(), bytes))
+    }
+}
+
+/// The [`Envelope`] used by `obake::store`, prefixing each payload with its version as a single
+/// byte length followed by that many bytes of UTF-8.
+///
+/// Versions longer than 255 bytes can't be written.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VersionPrefixed;
+
+impl Envelope for VersionPrefixed {
+    fn write_version(&self, version: &str, bytes: &mut Vec<u8>) -> Option<()> {
+        let len = u8::try_from(version.len()).ok()?;
+        bytes.push(len);
+        bytes.extend_from_slice(version.as_bytes());
+        Some(())
+    }
+
+    fn read_version<'a>(&self, bytes: &'a [u8]) -> Option<(&'a str, &'a [u8])> {
+        let (&len, rest) = bytes.split_first()?;
+        let len = usize::from(len);
+        if rest.len() < len {
+            return None;
+        }
+
+        let (version, rest) = rest.split_at(len);
+        Some((core::str::from_utf8(version).ok()?, rest))
+    }
+}
+
+/// Errors produced by [`encode_framed`] and [`decode_framed`].
+#[derive(Debug)]
+pub enum FrameError<C> {
+    /// The [`Codec`] failed.
+    Codec(C),
+    /// The frame does not begin with [`Envelope::MAGIC`].
+    BadMagic,
+    /// The version or length of the frame is malformed (e.g., truncated).
+    Malformed,
+    /// The frame holds a version that is not declared.
+    UnknownVersion(String),
+    /// The [`Envelope`] can't encode the version of the value being framed.
+    Unframeable(String),
+}
+
+impl<C> fmt::Display for FrameError<C>
+where
+    C: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Codec(err) => write!(f, "codec error: {err}"),
+            Self::BadMagic => write!(f, "frame has the wrong magic number"),
+            Self::Malformed => write!(f, "frame has a malformed header"),
+            Self::UnknownVersion(version) => write!(f, "frame has undeclared version {version}"),
+            Self::Unframeable(version) => write!(f, "version {version} can't be framed"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl<C> crate::error::Error for FrameError<C> where C: fmt::Debug + fmt::Display {}
+
+/// Encode `latest` with `codec`, framed by `envelope`.
+///
+/// ## Errors
+///
+/// Fails if `latest` cannot be encoded, or if `envelope` can't encode its version.
+pub fn encode_framed<T, C, E>(
+    envelope: &E,
+    codec: &C,
+    latest: &T,
+) -> Result<Vec<u8>, FrameError<C::Error>>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+    C: Codec,
+    E: Envelope,
+{
+    let payload = codec.encode(latest).map_err(FrameError::Codec)?;
+
+    let mut bytes = Vec::with_capacity(E::MAGIC.len() + payload.len() + 16);
+    bytes.extend_from_slice(E::MAGIC);
+    envelope
+        .write_version(T::VERSION, &mut bytes)
+        .ok_or_else(|| FrameError::Unframeable(T::VERSION.to_string()))?;
+    envelope.write_len(payload.len(), &mut bytes);
+    bytes.extend_from_slice(&payload);
+
+    Ok(bytes)
+}
+
+/// A value of any declared version of `T` decoded by [`decode_framed`], along with the bytes
+/// following its frame.
+pub type Unframed<'de, T> = (AnyVersion<T>, &'de [u8]);
+
+/// Decode a payload of any declared version of `T` framed by `envelope` with `codec`, returning
+/// it along with the bytes following the frame.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a well-formed frame, if it holds an undeclared version, or if its
+/// payload is not a valid encoding of that version.
+pub fn decode_framed<'de, T, C, E>(
+    envelope: &E,
+    codec: &C,
+    bytes: &'de [u8],
+) -> Result<Unframed<'de, T>, FrameError<C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    E: Envelope,
+    for<'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+{
+    decode_framed_with::<T, C, E>(envelope, codec, bytes, UnsupportedVersion::Reject)
+}
+
+/// Decode a framed payload as [`decode_framed`] does, handling an undeclared version according
+/// to `policy`.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a well-formed frame, if it holds an undeclared version rejected by
+/// `policy`, or if its payload is not a valid encoding of the selected version.
+pub fn decode_framed_with<'de, T, C, E>(
+    envelope: &E,
+    codec: &C,
+    bytes: &'de [u8],
+    policy: UnsupportedVersion,
+) -> Result<Unframed<'de, T>, FrameError<C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    E: Envelope,
+    for<'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+{
+    let rest = bytes.strip_prefix(E::MAGIC).ok_or(FrameError::BadMagic)?;
+    let (version, rest) = envelope.read_version(rest).ok_or(FrameError::Malformed)?;
+    let (len, rest) = envelope.read_len(rest).ok_or(FrameError::Malformed)?;
+    if rest.len() < len {
+        return Err(FrameError::Malformed);
+    }
+
+    let (payload, rest) = rest.split_at(len);
+    let tagged = decode_version_with::<T, C>(codec, version, payload, policy)
+        .ok_or_else(|| FrameError::UnknownVersion(version.to_string()))?
+        .map_err(FrameError::Codec)?;
+    Ok((tagged, rest))
+}
+
+/// A [`Codec`] encoding values as JSON using [`serde_json`].
+///
+/// Requires the feature `json`.
+#[cfg(feature = "json")]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl Codec for Json {
+    type Error = serde_json::Error;
+
+    const NAME: &'static str = "json";
+
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized,
+    {
+        serde_json::to_vec(value)
+    }
+
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>,
+    {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// A [`Codec`] encoding values as RON using [`ron`](::ron).
+///
+/// Requires the feature `ron`.
+#[cfg(feature = "ron")]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Ron;
+
+#[cfg(feature = "ron")]
+impl Codec for Ron {
+    type Error = ron::Error;
+
+    const NAME: &'static str = "ron";
+
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized,
+    {
+        ron::to_string(value).map(String::into_bytes)
+    }
+
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>,
+    {
+        ron::de::from_bytes(bytes).map_err(|err| err.code)
+    }
+}
+
+/// A [`Codec`] encoding values as JSON5 using [`json5`](::json5).
+///
+/// Requires the feature `json5`.
+#[cfg(feature = "json5")]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Json5;
+
+#[cfg(feature = "json5")]
+impl Codec for Json5 {
+    type Error = json5::Error;
+
+    const NAME: &'static str = "json5";
+
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized,
+    {
+        // Unlike most formats, `json5` only serializes `Sized` values
+        json5::to_string(&value).map(String::into_bytes)
+    }
+
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>,
+    {
+        let input =
+            core::str::from_utf8(bytes).map_err(<json5::Error as serde::de::Error>::custom)?;
+        json5::from_str(input)
+    }
+}
+
+/// A [`Codec`] encoding values as TOML using [`toml`](::toml).
+///
+/// Requires the feature `toml`.
+#[cfg(feature = "toml")]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Toml;
+
+#[cfg(feature = "toml")]
+impl Codec for Toml {
+    type Error = toml::de::Error;
+
+    const NAME: &'static str = "toml";
+
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized,
+    {
+        // `toml` has separate errors for serializing and deserializing
+        toml::to_string(value)
+            .map(String::into_bytes)
+            .map_err(<toml::de::Error as serde::de::Error>::custom)
+    }
+
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>,
+    {
+        let input =
+            core::str::from_utf8(bytes).map_err(<toml::de::Error as serde::de::Error>::custom)?;
+        // `toml::from_str` only deserializes owned values
+        D::deserialize(toml::Deserializer::new(input))
+    }
+}
+
+/// A [`Codec`] encoding values as YAML using [`serde_yaml`].
+///
+/// Requires the feature `yaml`.
+#[cfg(feature = "yaml")]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Yaml;
+
+#[cfg(feature = "yaml")]
+impl Codec for Yaml {
+    type Error = serde_yaml::Error;
+
+    const NAME: &'static str = "yaml";
+
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized,
+    {
+        serde_yaml::to_string(value).map(String::into_bytes)
+    }
+
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>,
+    {
+        serde_yaml::from_slice(bytes)
+    }
+}
+
+/// A [`Codec`] wrapping values in the `data` field of the canonical envelope (see
+/// `obake::json`) and encoding them with the codec it wraps.
+///
+/// Used to decode the payloads of envelopes in formats whose payloads can't be borrowed as raw
+/// values, leaving the version to be read separately (e.g., by `obake::ron`).
+#[cfg(any(feature = "ron", feature = "json5", feature = "toml", feature = "yaml"))]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct EnvelopeData<C>(pub C);
+
+#[cfg(any(feature = "ron", feature = "json5", feature = "toml", feature = "yaml"))]
+impl<C> Codec for EnvelopeData<C>
+where
+    C: Codec,
+{
+    type Error = C::Error;
+
+    const NAME: &'static str = C::NAME;
+
+    fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+    where
+        S: Serialize + ?Sized,
+    {
+        #[derive(Serialize)]
+        struct Data<'a, S: ?Sized> {
+            data: &'a S,
+        }
+
+        self.0.encode(&Data { data: value })
+    }
+
+    fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+    where
+        D: Deserialize<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Data<D> {
+            data: D,
+        }
+
+        self.0
+            .decode::<Data<D>>(bytes)
+            .map(|envelope| envelope.data)
+    }
+}
+
+/// The [`VisitValue`] used to encode a value in the canonical envelope (see `obake::json`)
+/// with a codec.
+#[cfg(any(feature = "ron", feature = "json5"))]
+#[derive(Copy, Clone, Debug)]
+pub struct ToEnvelope<'c, C>(pub &'c C);
+
+#[cfg(any(feature = "ron", feature = "json5"))]
+impl<T, C> VersionVisitor<T> for ToEnvelope<'_, C>
+where
+    T: Versioned,
+    C: Codec,
+{
+    type Output = Result<Vec<u8>, C::Error>;
+}
+
+#[cfg(any(feature = "ron", feature = "json5"))]
+impl<T, V, C> VisitValue<T, V> for ToEnvelope<'_, C>
+where
+    T: Versioned,
+    V: VersionOf<T> + Serialize,
+    C: Codec,
+{
+    fn visit_value(self, value: &V) -> Self::Output {
+        #[derive(Serialize)]
+        struct Envelope<'a, V> {
+            version: &'static str,
+            data: &'a V,
+        }
+
+        self.0.encode(&Envelope {
+            version: V::VERSION,
+            data: value,
+        })
+    }
+}
@@ -0,0 +1,373 @@
+//! Migrating collections whose values were each written at a different version of a
+//! [`versioned`](crate::versioned) data-structure.
+//!
+//! [`upgrade_map`] and [`upgrade_vec`] migrate every value of a collection to the latest version,
+//! whatever version each was written at. For data-structures with fallible migrations (see
+//! [`TryUpgrade`]), [`try_upgrade_map`] and [`try_upgrade_vec`] report every value that fails to
+//! migrate, keyed by its key (or index).
+//!
+//! With the feature `serde`, [`VersionedMap`], [`VersionedHashMap`] and [`VersionedVec`]
+//! deserialize collections of the version-tagged representation (with whichever `serde`
+//! representation is derived for it) and migrate each value, naming the key (or index) of any
+//! value which fails to deserialize (or to migrate).
+//!
+//! Requires the feature `alloc`.
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq)]
+//! struct Foo {
+//!     #[obake(cfg("0.1.0"))]
+//!     bar: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     baz: u64,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(from: Foo!["0.1.0"]) -> Self {
+//!         Self { baz: from.bar.into() }
+//!     }
+//! }
+//!
+//! let mut saved: HashMap<&str, obake::AnyVersion<Foo>> = HashMap::new();
+//! saved.insert("x", Foo_v0_1_0 { bar: 1 }.into());
+//! saved.insert("y", Foo_v0_2_0 { baz: 2 }.into());
+//!
+//! let upgraded: HashMap<_, Foo> = obake::collections::upgrade_map(saved);
+//! assert_eq!(upgraded["x"], Foo { baz: 1 });
+//! assert_eq!(upgraded["y"], Foo { baz: 2 });
+//! ```
+
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::iter::FromIterator;
+
+use crate::{AnyVersion, StepError, TryUpgrade, Versioned};
+
+/// A value of a collection which failed to migrate (see [`try_upgrade_map`]), along with its key
+/// (or index).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct KeyedError<K, E> {
+    /// The key (or index) of the value.
+    pub key: K,
+    /// The error the value failed with.
+    pub error: E,
+}
+
+impl<K, E> fmt::Display for KeyedError<K, E>
+where
+    K: fmt::Debug,
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.key, self.error)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl<K, E> crate::error::Error for KeyedError<K, E>
+where
+    K: fmt::Debug,
+    E: crate::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn crate::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The errors of the values of a collection which failed to migrate to the latest version of
+/// `T` with [`TryUpgrade`], keyed by `K`.
+pub type KeyedErrors<K, T> = Vec<KeyedError<K, StepError<<AnyVersion<T> as TryUpgrade<T>>::Error>>>;
+
+/// Migrate the values of a map (or any other collection of key-value pairs), each of any declared
+/// version of `T`, to the latest version.
+pub fn upgrade_map<K, T, I, M>(entries: I) -> M
+where
+    T: Versioned,
+    I: IntoIterator<Item = (K, AnyVersion<T>)>,
+    M: FromIterator<(K, T)>,
+{
+    entries
+        .into_iter()
+        .map(|(key, tagged)| (key, upgrade(tagged)))
+        .collect()
+}
+
+/// Migrate the values of a `Vec`, each of any declared version of `T`, to the latest version.
+#[must_use]
+pub fn upgrade_vec<T>(values: Vec<AnyVersion<T>>) -> Vec<T>
+where
+    T: Versioned,
+{
+    values.into_iter().map(upgrade).collect()
+}
+
+fn upgrade<T>(tagged: AnyVersion<T>) -> T
+where
+    T: Versioned,
+{
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_upgraded::<T>(&tagged);
+    tagged.into()
+}
+
+/// Migrate the values of a map (or any other collection of key-value pairs), each of any declared
+/// version of `T`, to the latest version with [`TryUpgrade`].
+///
+/// ## Errors
+///
+/// Fails with the error of every value which fails to migrate, in the order of `entries`.
+pub fn try_upgrade_map<K, T, I, M>(entries: I) -> Result<M, KeyedErrors<K, T>>
+where
+    T: Versioned,
+    AnyVersion<T>: TryUpgrade<T>,
+    I: IntoIterator<Item = (K, AnyVersion<T>)>,
+    M: FromIterator<(K, T)>,
+{
+    let mut upgraded = Vec::new();
+    let mut errors = Vec::new();
+    for (key, tagged) in entries {
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_upgraded::<T>(&tagged);
+        match tagged.try_upgrade() {
+            Ok(value) => upgraded.push((key, value)),
+            Err(error) => errors.push(KeyedError { key, error }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(upgraded.into_iter().collect())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Migrate the values of a `Vec`, each of any declared version of `T`, to the latest version with
+/// [`TryUpgrade`].
+///
+/// ## Errors
+///
+/// Fails with the error of every value which fails to migrate, keyed by its index.
+pub fn try_upgrade_vec<T>(values: Vec<AnyVersion<T>>) -> Result<Vec<T>, KeyedErrors<usize, T>>
+where
+    T: Versioned,
+    AnyVersion<T>: TryUpgrade<T>,
+{
+    try_upgrade_map::<_, T, _, Vec<_>>(values.into_iter().enumerate())
+        .map(|upgraded| upgraded.into_iter().map(|(_, value)| value).collect())
+}
+
+#[cfg(feature = "serde")]
+pub use self::serde_impls::{VersionedMap, VersionedVec};
+
+#[cfg(all(feature = "serde", feature = "std"))]
+pub use self::serde_impls::VersionedHashMap;
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer};
+
+    use crate::{AnyVersion, TryUpgrade, Versioned};
+
+    /// A [`BTreeMap`] deserialized from a map whose values are each of any declared version of
+    /// `T`, migrated to the latest version.
+    ///
+    /// ```
+    /// # #[cfg(feature = "json")] {
+    /// use obake::collections::VersionedMap;
+    ///
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// #[obake(derive(serde::Deserialize))]
+    /// #[obake(serde(tag = "version", content = "data"))]
+    /// #[derive(Debug, PartialEq, serde::Deserialize)]
+    /// struct Foo {
+    ///     #[obake(cfg(">=0.2"))]
+    ///     bar: u32,
+    /// }
+    ///
+    /// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    ///     fn from(_: Foo!["0.1.0"]) -> Self {
+    ///         Self { bar: 0 }
+    ///     }
+    /// }
+    ///
+    /// let json = r#"{
+    ///     "x": { "version": "Foo_v0_1_0", "data": {} },
+    ///     "y": { "version": "Foo_v0_2_0", "data": { "bar": 2 } }
+    /// }"#;
+    /// let VersionedMap(map) = serde_json::from_str::<VersionedMap<String, Foo>>(json).unwrap();
+    /// assert_eq!(map["x"], Foo { bar: 0 });
+    /// assert_eq!(map["y"], Foo { bar: 2 });
+    ///
+    /// let json = r#"{ "x": { "version": "Foo_v0_3_0", "data": {} } }"#;
+    /// let err = serde_json::from_str::<VersionedMap<String, Foo>>(json).unwrap_err();
+    /// assert!(err.to_string().starts_with("invalid value for key \"x\""));
+    /// # }
+    /// ```
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct VersionedMap<K, T>(pub BTreeMap<K, T>);
+
+    /// A [`HashMap`](std::collections::HashMap) deserialized from a map whose values are each of
+    /// any declared version of `T`, migrated to the latest version (see [`VersionedMap`]).
+    ///
+    /// Requires the feature `std`.
+    #[cfg(feature = "std")]
+    #[derive(Clone, Debug)]
+    pub struct VersionedHashMap<K, T, S = std::collections::hash_map::RandomState>(
+        pub std::collections::HashMap<K, T, S>,
+    );
+
+    /// A `Vec` deserialized from a sequence whose values are each of any declared version of `T`,
+    /// migrated to the latest version (see [`VersionedMap`]).
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct VersionedVec<T>(pub Vec<T>);
+
+    /// The `serde` visitor of a map migrating each value as it's deserialized into `M`.
+    struct MigratingMapVisitor<M, K, T> {
+        map: PhantomData<fn() -> M>,
+        entries: PhantomData<fn() -> (K, T)>,
+    }
+
+    impl<'de, M, K, T> Visitor<'de> for MigratingMapVisitor<M, K, T>
+    where
+        M: Default + Extend<(K, T)>,
+        K: Deserialize<'de> + fmt::Debug,
+        T: Versioned,
+        AnyVersion<T>: Deserialize<'de> + TryUpgrade<T>,
+        <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+    {
+        type Value = M;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a map of versioned values")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut migrated = M::default();
+            while let Some(key) = map.next_key::<K>()? {
+                let tagged = map.next_value::<AnyVersion<T>>().map_err(|err| {
+                    A::Error::custom(format_args!("invalid value for key {key:?}: {err}"))
+                })?;
+                let value = tagged.try_upgrade().map_err(|err| {
+                    A::Error::custom(format_args!("invalid value for key {key:?}: {err}"))
+                })?;
+                migrated.extend(Some((key, value)));
+            }
+
+            Ok(migrated)
+        }
+    }
+
+    impl<'de, K, T> Deserialize<'de> for VersionedMap<K, T>
+    where
+        K: Deserialize<'de> + Ord + fmt::Debug,
+        T: Versioned,
+        AnyVersion<T>: Deserialize<'de> + TryUpgrade<T>,
+        <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer
+                .deserialize_map(MigratingMapVisitor {
+                    map: PhantomData,
+                    entries: PhantomData,
+                })
+                .map(Self)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<'de, K, T, S> Deserialize<'de> for VersionedHashMap<K, T, S>
+    where
+        K: Deserialize<'de> + Eq + core::hash::Hash + fmt::Debug,
+        T: Versioned,
+        AnyVersion<T>: Deserialize<'de> + TryUpgrade<T>,
+        <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+        S: core::hash::BuildHasher + Default,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer
+                .deserialize_map(MigratingMapVisitor {
+                    map: PhantomData,
+                    entries: PhantomData,
+                })
+                .map(Self)
+        }
+    }
+
+    struct MigratingSeqVisitor<T> {
+        _marker: PhantomData<fn() -> T>,
+    }
+
+    impl<'de, T> Visitor<'de> for MigratingSeqVisitor<T>
+    where
+        T: Versioned,
+        AnyVersion<T>: Deserialize<'de> + TryUpgrade<T>,
+        <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a sequence of versioned values")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut migrated = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            loop {
+                let index = migrated.len();
+                let next = seq.next_element::<AnyVersion<T>>().map_err(|err| {
+                    A::Error::custom(format_args!("invalid value at index {index}: {err}"))
+                })?;
+                match next {
+                    Some(tagged) => migrated.push(tagged.try_upgrade().map_err(|err| {
+                        A::Error::custom(format_args!("invalid value at index {index}: {err}"))
+                    })?),
+                    None => return Ok(migrated),
+                }
+            }
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for VersionedVec<T>
+    where
+        T: Versioned,
+        AnyVersion<T>: Deserialize<'de> + TryUpgrade<T>,
+        <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer
+                .deserialize_seq(MigratingSeqVisitor {
+                    _marker: PhantomData,
+                })
+                .map(Self)
+        }
+    }
+}
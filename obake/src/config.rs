@@ -0,0 +1,670 @@
+//! Loading configuration files of any declared version of a [`versioned`](crate::versioned)
+//! data-structure, written in any of the enabled formats, and writing them back once migrated.
+//!
+//! Configuration files outlive the releases that wrote them, and may have been written in a
+//! format that has since been replaced. [`load`] detects the [`Format`] of a file, first by its
+//! extension, then by sniffing its content, and finally by trying every enabled format in turn.
+//! Each format accepts payloads wrapped in the canonical envelope (see `obake::json`) as well as
+//! untagged payloads, trying each declared version in turn, from newest to oldest.
+//!
+//! As rewriting configuration files in place is nerve-wracking, [`write`] can keep a timestamped
+//! copy of the file replaced (e.g., `settings.toml.20240601T120000Z.bak`) and write to a
+//! temporary file renamed over it once complete, so that a crash never leaves a file
+//! half-written (see [`WriteOptions`]), returning the paths written. `obake::cli` writes the
+//! files it converts this way.
+//!
+//! Requires at least one of the features `json`, `json5`, `ron`, `toml` or `yaml`, each
+//! enabling the corresponding format.
+//!
+//! ```
+//! # #[cfg(all(feature = "json", feature = "toml"))] {
+//! use obake::config::{self, Format};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Deserialize)]
+//! struct Settings {
+//!     #[obake(cfg("0.1.0"))]
+//!     port: u16,
+//!     #[obake(cfg(">=0.2"))]
+//!     address: String,
+//! }
+//!
+//! impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+//!     fn from(from: Settings!["0.1.0"]) -> Self {
+//!         Self { address: format!("localhost:{}", from.port) }
+//!     }
+//! }
+//!
+//! let old = config::from_str::<Settings>(r#"{ "port": 80 }"#, None).unwrap();
+//! assert_eq!(Settings::from(old), Settings { address: "localhost:80".into() });
+//!
+//! let new = config::from_str::<Settings>("address = \"example.com:443\"", None).unwrap();
+//! assert_eq!(Settings::from(new), Settings { address: "example.com:443".into() });
+//!
+//! assert_eq!(Format::sniff("[server]\nport = 80"), Some(Format::Toml));
+//! assert_eq!(Format::from_extension("JSON"), Some(Format::Json));
+//! # }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use serde::de::IgnoredAny;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use serde::Deserialize;
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use crate::codec::{decode_version, decode_version_with, Codec, EnvelopeData};
+use crate::codec::{Decode, UnsupportedVersion};
+use crate::{AnyVersion, Dispatch, Versioned};
+
+/// A format configuration files may be written in.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Format {
+    /// JSON, decoded by `obake::json`.
+    ///
+    /// Requires the feature `json`.
+    Json,
+    /// JSON5, decoded by `obake::json5`.
+    ///
+    /// Requires the feature `json5`.
+    Json5,
+    /// RON, decoded by `obake::ron`.
+    ///
+    /// Requires the feature `ron`.
+    Ron,
+    /// TOML, decoded with [`Toml`](crate::codec::Toml).
+    ///
+    /// Requires the feature `toml`.
+    Toml,
+    /// YAML, decoded with [`Yaml`](crate::codec::Yaml).
+    ///
+    /// Requires the feature `yaml`.
+    Yaml,
+}
+
+impl Format {
+    /// Every format, in the order they're tried when a file's format can't be detected.
+    ///
+    /// YAML comes last, as most JSON (and plenty of malformed input) is also valid YAML.
+    pub const ALL: [Self; 5] = [Self::Json, Self::Json5, Self::Toml, Self::Ron, Self::Yaml];
+
+    /// Whether the feature enabling this format is enabled.
+    #[must_use]
+    pub fn is_enabled(self) -> bool {
+        match self {
+            Self::Json => cfg!(feature = "json"),
+            Self::Json5 => cfg!(feature = "json5"),
+            Self::Ron => cfg!(feature = "ron"),
+            Self::Toml => cfg!(feature = "toml"),
+            Self::Yaml => cfg!(feature = "yaml"),
+        }
+    }
+
+    /// The format conventionally named by the file extension `extension` (without the leading
+    /// `.`, in any case), if any.
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "json5" => Some(Self::Json5),
+            "ron" => Some(Self::Ron),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Guess the format of `s` from its first line of content (skipping blank lines and
+    /// comments), if it's distinctive of a particular format.
+    #[must_use]
+    pub fn sniff(s: &str) -> Option<Self> {
+        let line = s.lines().map(str::trim).find(|line| {
+            !line.is_empty()
+                && !line.starts_with("//")
+                && (!line.starts_with('#') || line.starts_with("#!["))
+        })?;
+
+        // RON extensions (e.g., `#![enable(implicit_some)]`) precede the value
+        if line.starts_with("#![") || line.starts_with('(') {
+            return Some(Self::Ron);
+        }
+        if line.starts_with('{') {
+            return Some(Self::Json);
+        }
+        if line.starts_with("---") {
+            return Some(Self::Yaml);
+        }
+        if let Some(rest) = line.strip_prefix('[') {
+            // A table header (e.g., `[server]` or `[[servers]]`) rather than an array
+            let rest = rest.trim_start_matches('[').trim_start();
+            return if rest.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                Some(Self::Toml)
+            } else {
+                Some(Self::Json)
+            };
+        }
+
+        let key = line.trim_start_matches('"');
+        let rest = key
+            .trim_start_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '-')
+            .trim_start_matches('"')
+            .trim_start();
+        if rest.len() == key.len() {
+            return None;
+        }
+        match rest.chars().next()? {
+            '(' => Some(Self::Ron),
+            '=' => Some(Self::Toml),
+            ':' => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Decode `s` as a payload of any declared version of `T` in this format, either wrapped in
+    /// the canonical envelope or untagged.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if this format isn't enabled, or if `s` can't be decoded in this format (see
+    /// `obake::json::from_slice`).
+    pub fn decode<T>(self, s: &str) -> Result<AnyVersion<T>, Error>
+    where
+        T: Versioned,
+        AnyVersion<T>: Decodable<T>,
+    {
+        self.decode_with::<T>(s, UnsupportedVersion::Reject)
+    }
+
+    /// Decode `s` as [`Format::decode`] does, handling a payload wrapped in the canonical
+    /// envelope with an undeclared version according to `policy`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if this format isn't enabled, or if `s` can't be decoded in this format (see
+    /// `obake::json::from_slice_with`).
+    pub fn decode_with<T>(self, s: &str, policy: UnsupportedVersion) -> Result<AnyVersion<T>, Error>
+    where
+        T: Versioned,
+        AnyVersion<T>: Decodable<T>,
+    {
+        let decoded: Result<AnyVersion<T>, BoxedError> = match self {
+            #[cfg(feature = "json")]
+            Self::Json => {
+                crate::json::from_slice_with::<T>(s.as_bytes(), policy).map_err(Into::into)
+            }
+            #[cfg(feature = "json5")]
+            Self::Json5 => crate::json5::from_str_with::<T>(s, policy).map_err(Into::into),
+            #[cfg(feature = "ron")]
+            Self::Ron => crate::ron::from_str_with::<T>(s, policy).map_err(Into::into),
+            #[cfg(feature = "toml")]
+            Self::Toml => decode_enveloped::<T, _>(crate::codec::Toml, s, policy),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => decode_enveloped::<T, _>(crate::codec::Yaml, s, policy),
+            #[allow(unreachable_patterns)]
+            _ => return Err(Error::Disabled(self)),
+        };
+
+        decoded.map_err(|error| Error::Decode {
+            format: self,
+            error,
+        })
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Json => "JSON",
+            Self::Json5 => "JSON5",
+            Self::Ron => "RON",
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+        })
+    }
+}
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Errors produced when loading (or writing) configuration files.
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file could not be decoded in the format it was detected to be in (or, if its format
+    /// couldn't be detected, in any enabled format, in which case the error is that of the first
+    /// format tried).
+    Decode {
+        /// The format the file was decoded as.
+        format: Format,
+        /// The error produced by decoding it.
+        error: BoxedError,
+    },
+    /// The format was selected explicitly, but the feature enabling it is disabled.
+    Disabled(Format),
+    /// The file could not be written.
+    Write(io::Error),
+    /// The file replaced could not be backed up, so nothing was written.
+    Backup(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read configuration: {err}"),
+            Self::Decode { format, error } => {
+                write!(f, "malformed {format} configuration: {error}")
+            }
+            Self::Disabled(format) => write!(f, "support for {format} is disabled"),
+            Self::Write(err) => write!(f, "failed to write configuration: {err}"),
+            Self::Backup(err) => write!(f, "failed to back up configuration: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) | Self::Write(err) | Self::Backup(err) => Some(err),
+            Self::Decode { error, .. } => Some(&**error),
+            Self::Disabled(_) => None,
+        }
+    }
+}
+
+/// Read and decode the configuration file at `path`, of any declared version of `T`, in any
+/// enabled format.
+///
+/// The format named by the extension of `path` is tried first, followed by the format sniffed
+/// from its content (see [`Format::sniff`]), and then every other enabled format (in the order
+/// of [`Format::ALL`]).
+///
+/// ## Errors
+///
+/// Fails if the file can't be read, or can't be decoded in any enabled format, in which case the
+/// error is that of the first format tried.
+pub fn load<T, P>(path: P) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+    P: AsRef<Path>,
+{
+    load_with::<T, P>(path, UnsupportedVersion::Reject)
+}
+
+/// Read and decode the configuration file at `path` as [`load`] does, handling a file wrapped
+/// in the canonical envelope with an undeclared version (e.g., written by a newer release)
+/// according to `policy`.
+///
+/// ## Errors
+///
+/// Fails if the file can't be read, or can't be decoded in any enabled format, in which case the
+/// error is that of the first format tried.
+pub fn load_with<T, P>(path: P, policy: UnsupportedVersion) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let s = std::fs::read_to_string(path).map_err(Error::Io)?;
+    let hint = path
+        .extension()
+        .and_then(|extension| Format::from_extension(extension.to_str()?));
+    from_str_with::<T>(&s, hint, policy)
+}
+
+/// Decode a configuration file's content, of any declared version of `T`, in any enabled format,
+/// as [`load`] does, trying the format `hint` (e.g., named by its extension) first.
+///
+/// ## Errors
+///
+/// Fails if `s` can't be decoded in any enabled format, in which case the error is that of the
+/// first format tried.
+pub fn from_str<T>(s: &str, hint: Option<Format>) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+{
+    from_str_with::<T>(s, hint, UnsupportedVersion::Reject)
+}
+
+/// Decode a configuration file's content as [`from_str`] does, handling content wrapped in the
+/// canonical envelope with an undeclared version according to `policy`.
+///
+/// ## Errors
+///
+/// Fails if `s` can't be decoded in any enabled format, in which case the error is that of the
+/// first format tried.
+pub fn from_str_with<T>(
+    s: &str,
+    hint: Option<Format>,
+    policy: UnsupportedVersion,
+) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+{
+    detect::<T>(s, hint, policy).map(|(tagged, _)| tagged)
+}
+
+/// How [`write`] replaces a configuration file.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct WriteOptions {
+    /// Keep a timestamped backup of the file replaced, if any, alongside it.
+    pub backup: bool,
+    /// Write to a temporary file alongside the file, then rename it over the file, so that the
+    /// file is never left half-written.
+    pub atomic: bool,
+}
+
+/// The paths written when writing a configuration file (see [`write`]).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Written {
+    /// The file written.
+    pub output: PathBuf,
+    /// The backup of the file replaced, if one was replaced and [`WriteOptions::backup`] was set.
+    pub backup: Option<PathBuf>,
+}
+
+/// Write `contents` (e.g., a configuration migrated to the latest version and encoded) to the
+/// file at `path`, backing up the file replaced and writing atomically as `options` ask,
+/// returning the paths written.
+///
+/// ## Errors
+///
+/// Fails if the file can't be written, or if the file replaced can't be backed up (in which case
+/// nothing is written).
+pub fn write<P, C>(path: P, contents: C, options: WriteOptions) -> Result<Written, Error>
+where
+    P: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    let path = path.as_ref();
+    let backup = if options.backup && path.exists() {
+        Some(backup(path).map_err(Error::Backup)?)
+    } else {
+        None
+    };
+
+    if options.atomic {
+        write_atomic(path, contents.as_ref()).map_err(Error::Write)?;
+    } else {
+        fs::write(path, contents).map_err(Error::Write)?;
+    }
+
+    Ok(Written {
+        output: path.to_path_buf(),
+        backup,
+    })
+}
+
+/// Write `contents` to a temporary file alongside `output`, then rename it over `output`, which
+/// replaces it atomically on the platforms supported by `std`.
+fn write_atomic(output: &Path, contents: &[u8]) -> io::Result<()> {
+    // Numbered by a process-wide counter as well as the process, so that concurrent writes (or
+    // the leftovers of a crashed process with the same ID) never share a temporary file
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let (temp, mut file) = create_new(|_| {
+        let mut name = OsString::from(".");
+        name.push(output.file_name().unwrap_or_default());
+        name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        output.with_file_name(name)
+    })?;
+
+    let written = file.write_all(contents).and_then(|()| {
+        // The file replaced keeps its permissions
+        if let Ok(metadata) = fs::metadata(output) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()
+    });
+    drop(file);
+    let renamed = written.and_then(|()| fs::rename(&temp, output));
+    if renamed.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    renamed
+}
+
+/// Copy `path` to a backup taken now, e.g., `settings.toml.20240601T120000Z.bak`, numbered (e.g.,
+/// `settings.toml.20240601T120000Z.1.bak`) if one was already taken within the same second,
+/// returning the path of the backup.
+///
+/// The name of the backup is reserved by creating it exclusively before copying, so that
+/// concurrent conversions never overwrite each other's backups.
+fn backup(path: &Path) -> io::Result<PathBuf> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let stamp = timestamp(now);
+
+    let (backup, file) = create_new(|n| {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        if n == 0 {
+            name.push(format!(".{stamp}.bak"));
+        } else {
+            name.push(format!(".{stamp}.{n}.bak"));
+        }
+        path.with_file_name(name)
+    })?;
+    drop(file);
+
+    if let Err(err) = fs::copy(path, &backup) {
+        let _ = fs::remove_file(&backup);
+        return Err(err);
+    }
+    Ok(backup)
+}
+
+/// Create the first of the paths `candidate(0)`, `candidate(1)`, ... which doesn't already exist,
+/// failing rather than opening a file created concurrently.
+fn create_new(mut candidate: impl FnMut(usize) -> PathBuf) -> io::Result<(PathBuf, File)> {
+    let mut n = 0;
+    loop {
+        let path = candidate(n);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// The UTC date and time `secs` seconds after the Unix epoch, in the basic ISO 8601 format (e.g.,
+/// `20240601T120000Z`), which sorts chronologically and is valid in file names on any platform.
+fn timestamp(secs: u64) -> String {
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+
+    // The civil date of a number of days since the epoch, after Howard Hinnant's
+    // `civil_from_days`, counting eras of 400 years from 0000-03-01
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + yoe + u64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    )
+}
+
+/// Decode a configuration file's content as [`from_str_with`] does, along with the format it was
+/// decoded in.
+pub(crate) fn detect<T>(
+    s: &str,
+    hint: Option<Format>,
+    policy: UnsupportedVersion,
+) -> Result<(AnyVersion<T>, Format), Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+{
+    let mut candidates: Vec<Format> = Vec::with_capacity(Format::ALL.len());
+    let detected = hint.into_iter().chain(Format::sniff(s));
+    for format in detected.chain(Format::ALL) {
+        if format.is_enabled() && !candidates.contains(&format) {
+            candidates.push(format);
+        }
+    }
+
+    match candidates.split_first() {
+        Some((first, rest)) => first
+            .decode_with::<T>(s, policy)
+            .map(|tagged| (tagged, *first))
+            .or_else(|error| {
+                rest.iter()
+                    .find_map(|format| Some((format.decode_with::<T>(s, policy).ok()?, *format)))
+                    .ok_or(error)
+            }),
+        // At least one format is enabled for this module to be compiled
+        None => Err(Error::Disabled(Format::ALL[0])),
+    }
+}
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+fn decode_enveloped<T, C>(
+    codec: C,
+    s: &str,
+    policy: UnsupportedVersion,
+) -> Result<AnyVersion<T>, BoxedError>
+where
+    T: Versioned,
+    C: Codec + Copy,
+    C::Error: std::error::Error + Send + Sync + 'static,
+    for<'de, 'c> AnyVersion<T>:
+        Dispatch<T, Decode<'de, 'c, C>> + Dispatch<T, Decode<'de, 'c, EnvelopeData<C>>>,
+{
+    // Strings may be written with escapes in either format, so the version is always owned
+    #[derive(Deserialize)]
+    struct Envelope {
+        version: String,
+        #[allow(dead_code)]
+        data: IgnoredAny,
+    }
+
+    let bytes = s.as_bytes();
+    if let Ok(envelope) = codec.decode::<Envelope>(bytes) {
+        let data = EnvelopeData(codec);
+        return match decode_version_with::<T, _>(&data, &envelope.version, bytes, policy) {
+            Some(decoded) => decoded.map_err(Into::into),
+            None => Err(alloc::format!("undeclared version {}", envelope.version).into()),
+        };
+    }
+
+    T::VERSIONS
+        .iter()
+        .rev()
+        .find_map(|version| decode_version::<T, _>(&codec, version, bytes)?.ok())
+        .ok_or_else(|| "payload does not match any declared version".into())
+}
+
+macro_rules! format_bound {
+    ($(#[$attr:meta])* $feature:literal, $name:ident, $codec:ty, $($enveloped:ty)?) => {
+        $(#[$attr])*
+        #[cfg(feature = $feature)]
+        #[doc(hidden)]
+        pub trait $name<T>:
+            for<'de, 'c> Dispatch<T, Decode<'de, 'c, $codec>>
+            $(+ for<'de, 'c> Dispatch<T, Decode<'de, 'c, $enveloped>>)?
+        where
+            T: Versioned,
+        {
+        }
+
+        #[cfg(feature = $feature)]
+        impl<T, X> $name<T> for X
+        where
+            T: Versioned,
+            X: for<'de, 'c> Dispatch<T, Decode<'de, 'c, $codec>>
+                $(+ for<'de, 'c> Dispatch<T, Decode<'de, 'c, $enveloped>>)?,
+        {
+        }
+
+        $(#[$attr])*
+        #[cfg(not(feature = $feature))]
+        #[doc(hidden)]
+        pub trait $name<T> {}
+
+        #[cfg(not(feature = $feature))]
+        impl<T, X> $name<T> for X {}
+    };
+}
+
+format_bound!(
+    /// Decodable as JSON, if the feature `json` is enabled.
+    "json",
+    DecodableJson,
+    crate::codec::Json,
+);
+format_bound!(
+    /// Decodable as JSON5, if the feature `json5` is enabled.
+    "json5",
+    DecodableJson5,
+    crate::codec::Json5,
+    crate::codec::EnvelopeData<crate::codec::Json5>
+);
+format_bound!(
+    /// Decodable as RON, if the feature `ron` is enabled.
+    "ron",
+    DecodableRon,
+    crate::codec::Ron,
+    crate::codec::EnvelopeData<crate::codec::Ron>
+);
+format_bound!(
+    /// Decodable as TOML, if the feature `toml` is enabled.
+    "toml",
+    DecodableToml,
+    crate::codec::Toml,
+    crate::codec::EnvelopeData<crate::codec::Toml>
+);
+format_bound!(
+    /// Decodable as YAML, if the feature `yaml` is enabled.
+    "yaml",
+    DecodableYaml,
+    crate::codec::Yaml,
+    crate::codec::EnvelopeData<crate::codec::Yaml>
+);
+
+/// Implemented by the version-tagged representation of a [`versioned`](crate::versioned)
+/// data-structure whose declared versions can all be deserialized, so that configuration files
+/// of any version can be decoded in every enabled format.
+pub trait Decodable<T>:
+    DecodableJson<T> + DecodableJson5<T> + DecodableRon<T> + DecodableToml<T> + DecodableYaml<T>
+where
+    T: Versioned,
+{
+}
+
+impl<T, X> Decodable<T> for X
+where
+    T: Versioned,
+    X: DecodableJson<T> + DecodableJson5<T> + DecodableRon<T> + DecodableToml<T> + DecodableYaml<T>,
+{
+}
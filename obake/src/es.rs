@@ -0,0 +1,151 @@
+//! Upcasting of versioned events for event-sourcing frameworks.
+//!
+//! Event stores persist events for as long as an aggregate lives, so events written by every
+//! historical release of an application must remain readable. An [`Upcaster`] converts a stored
+//! event payload of any declared version into the latest version of its event type, and is
+//! designed to be plugged into `cqrs-es`/`eventstore`-style frameworks as a trait object.
+//!
+//! Requires the features `alloc` and `serde`.
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use obake::codec::Json;
+//! use obake::es::{CodecUpcaster, Upcaster};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Deserialize)]
+//! struct Deposited {
+//!     #[obake(cfg("0.1.0"))]
+//!     amount: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     cents: u64,
+//! }
+//!
+//! impl From<Deposited!["0.1.0"]> for Deposited!["0.2.0"] {
+//!     fn from(from: Deposited!["0.1.0"]) -> Self {
+//!         Self { cents: u64::from(from.amount) * 100 }
+//!     }
+//! }
+//!
+//! let upcaster: Box<dyn Upcaster<Deposited, Error = _>> =
+//!     Box::new(CodecUpcaster::<Deposited, _>::new(Json));
+//!
+//! assert!(upcaster.can_upcast("0.1.0"));
+//! assert_eq!(
+//!     upcaster.upcast("0.1.0", br#"{"amount":3}"#).unwrap(),
+//!     Deposited { cents: 300 },
+//! );
+//! # }
+//! ```
+
+use alloc::string::{String, ToString};
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::codec::{decode_version_with, Codec, Decode, UnsupportedVersion};
+use crate::{
+    try_upgrade_rendered, AnyVersion, Declared, Dispatch, StepError, TryUpgrade, Versioned,
+};
+
+/// Converts stored event payloads of any declared version of `T` into `T`.
+pub trait Upcaster<T> {
+    /// The error produced when upcasting fails.
+    type Error;
+
+    /// Whether payloads tagged with `version` can be upcast.
+    fn can_upcast(&self, version: &str) -> bool;
+
+    /// Decode `payload`, which was stored at `version`, and migrate it to `T`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `version` is not a declared version of `T` or if `payload` cannot be decoded
+    /// as that version.
+    fn upcast(&self, version: &str, payload: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// Errors produced by a [`CodecUpcaster`].
+#[derive(Debug)]
+pub enum Error<C> {
+    /// The event was stored at a version that is not declared.
+    UnknownVersion(String),
+    /// The [`Codec`] failed.
+    Codec(C),
+    /// The event was decoded, but failed to migrate to the latest version (see
+    /// [`TryUpgrade`]).
+    Migrate(StepError<String>),
+}
+
+impl<C> fmt::Display for Error<C>
+where
+    C: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVersion(version) => {
+                write!(f, "cannot upcast undeclared version {version}")
+            }
+            Self::Codec(err) => write!(f, "codec error: {err}"),
+            Self::Migrate(err) => write!(f, "cannot upcast event: {err}"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl<C> crate::error::Error for Error<C> where C: fmt::Debug + fmt::Display {}
+
+/// An [`Upcaster`] decoding payloads of every declared version of `T` with the codec `C`.
+pub struct CodecUpcaster<T, C> {
+    codec: C,
+    unsupported_version: UnsupportedVersion,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, C> CodecUpcaster<T, C> {
+    /// Create an upcaster decoding payloads using `codec`, which rejects events stored at
+    /// undeclared versions.
+    #[must_use]
+    pub fn new(codec: C) -> Self {
+        Self {
+            codec,
+            unsupported_version: UnsupportedVersion::Reject,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the [`UnsupportedVersion`] policy for events stored at undeclared versions.
+    #[must_use]
+    pub fn with_unsupported_version(mut self, policy: UnsupportedVersion) -> Self {
+        self.unsupported_version = policy;
+        self
+    }
+}
+
+impl<T, C> Upcaster<T> for CodecUpcaster<T, C>
+where
+    T: Versioned,
+    C: Codec,
+    AnyVersion<T>: Dispatch<T, Declared>,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    type Error = Error<C::Error>;
+
+    fn can_upcast(&self, version: &str) -> bool {
+        self.unsupported_version
+            .resolve::<T>(version)
+            .is_some_and(|version| AnyVersion::<T>::dispatch(version, Declared).is_some())
+    }
+
+    fn upcast(&self, version: &str, payload: &[u8]) -> Result<T, Self::Error> {
+        let tagged =
+            decode_version_with::<T, C>(&self.codec, version, payload, self.unsupported_version)
+                .ok_or_else(|| Error::UnknownVersion(version.to_string()))?
+                .map_err(Error::Codec)?;
+        try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate)
+    }
+}
@@ -0,0 +1,164 @@
+//! Bulk export of values of a [`versioned`](crate::versioned) data-structure marked
+//! `#[obake(downgrade)]` at a historical version, for consumers which haven't moved on from it
+//! (e.g., a partner integration still reading an old schema).
+//!
+//! [`downgrade_all`] downgrades each value to the requested version and encodes it with a
+//! [`Codec`], reporting for each value whether the downgrade was lossy (i.e., whether upgrading
+//! the exported payload again wouldn't reproduce the value), so that lossy exports can be
+//! reviewed (or withheld) before they're sent.
+//!
+//! Requires the features `alloc` and `serde`.
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use obake::codec::Json;
+//! use obake::export;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(downgrade)]
+//! #[derive(Clone, PartialEq, serde::Serialize)]
+//! struct Foo {
+//!     bar: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     baz: Option<u32>,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(from: Foo!["0.1.0"]) -> Self {
+//!         Self { bar: from.bar, baz: None }
+//!     }
+//! }
+//!
+//! impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+//!     fn from(from: Foo!["0.2.0"]) -> Self {
+//!         Self { bar: from.bar }
+//!     }
+//! }
+//!
+//! let values = [Foo { bar: 1, baz: None }, Foo { bar: 2, baz: Some(3) }];
+//! let export = export::downgrade_all(&Json, &values, "0.1.0").unwrap();
+//! assert_eq!(export.items[0].payload, br#"{"bar":1}"#);
+//! assert_eq!(export.lossy().collect::<Vec<_>>(), [1]);
+//! # }
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use crate::codec::{Codec, Encode};
+use crate::{Accept, AnyVersion, Downgrade, TryUpgrade, Versioned};
+
+/// A value exported by [`downgrade_all`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Exported {
+    /// The value, downgraded and encoded.
+    pub payload: Vec<u8>,
+    /// Whether the downgrade lost information, i.e., whether upgrading the payload again would
+    /// not reproduce the original value.
+    pub lossy: bool,
+}
+
+/// The values exported by [`downgrade_all`], in the order they were given.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Export {
+    /// The version the values were exported at.
+    pub version: &'static str,
+    /// Each value exported.
+    pub items: Vec<Exported>,
+}
+
+impl Export {
+    /// The indices of the values whose downgrade was lossy.
+    pub fn lossy(&self) -> impl Iterator<Item = usize> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.lossy)
+            .map(|(index, _)| index)
+    }
+
+    /// The payloads of the values, dropping the report of their lossiness.
+    #[must_use]
+    pub fn into_payloads(self) -> Vec<Vec<u8>> {
+        self.items.into_iter().map(|item| item.payload).collect()
+    }
+}
+
+/// Errors produced by [`downgrade_all`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error<E> {
+    /// The requested version is not declared.
+    UnknownVersion(String),
+    /// A value couldn't be encoded at the requested version.
+    Encode {
+        /// The index of the value.
+        index: usize,
+        /// The error produced by encoding it.
+        error: E,
+    },
+}
+
+impl<E> fmt::Display for Error<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVersion(version) => {
+                write!(f, "cannot export as undeclared version {version}")
+            }
+            Self::Encode { index, error } => {
+                write!(f, "failed to encode value at index {index}: {error}")
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl<E> crate::error::Error for Error<E> where E: fmt::Debug + fmt::Display {}
+
+/// Downgrade each of `values` to the declared version whose semantic version number is
+/// `version` and encode it with `codec`, reporting whether each downgrade was lossy.
+///
+/// ## Errors
+///
+/// Fails if `version` is not declared, or on the first value which can't be encoded.
+pub fn downgrade_all<'a, T, C>(
+    codec: &C,
+    values: impl IntoIterator<Item = &'a T>,
+    version: &str,
+) -> Result<Export, Error<C::Error>>
+where
+    T: Versioned + Clone + PartialEq + 'a,
+    C: Codec,
+    for<'c> AnyVersion<T>: Downgrade<T> + TryUpgrade<T> + Accept<T, Encode<'c, C>>,
+{
+    let unknown = || Error::UnknownVersion(version.to_string());
+    let version = *T::VERSIONS
+        .iter()
+        .find(|declared| **declared == version)
+        .ok_or_else(unknown)?;
+
+    let items = values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let tagged = AnyVersion::<T>::downgrade(value.clone(), version).ok_or_else(unknown)?;
+            let payload = tagged
+                .accept(Encode::new(codec))
+                .map_err(|error| Error::Encode { index, error })?;
+            // A value which fails to migrate back can't be recovered from its export either
+            let lossy = tagged
+                .try_upgrade()
+                .map_or(true, |round_trip| round_trip != *value);
+
+            Ok(Exported { payload, lossy })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Export { version, items })
+}
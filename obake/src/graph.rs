@@ -0,0 +1,237 @@
+//! Introspection of the migrations between the versions of a
+//! [`versioned`](crate::versioned) data-structure.
+//!
+//! [`Migrations::migration_graph`](crate::Migrations::migration_graph) describes each declared
+//! version as a node of a [`MigrationGraph`], and each conversion between two versions as an
+//! [`Edge`]: the migrations from each version to the next, shortcuts generated by
+//! `#[obake(dense_migrations)]` and downgrades generated by `#[obake(downgrade)]`.
+//! [`MigrationGraph::hops`] counts the migrations old data passes through on its way to a newer
+//! version, and [`MigrationGraph::to_dot`] renders the graph for visualisation with
+//! [Graphviz](https://graphviz.org).
+//!
+//! [`MigrationGraph::hops`] and [`MigrationGraph::to_dot`] require the feature `alloc`, and
+//! implementations of `serde::Serialize` (e.g., for rendering the graph as JSON) require the
+//! feature `serde`.
+//!
+//! ```
+//! use obake::graph::EdgeKind;
+//! use obake::Migrations;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(version("0.3.0"))]
+//! #[obake(downgrade)]
+//! #[derive(Clone)]
+//! struct Foo {}
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(_: Foo!["0.1.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//! #
+//! # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+//! #     fn from(_: Foo!["0.2.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//! #
+//! # impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+//! #     fn from(_: Foo!["0.3.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//! #
+//! # impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+//! #     fn from(_: Foo!["0.2.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//!
+//! let graph = Foo::migration_graph();
+//!
+//! assert_eq!(graph.versions, &["0.1.0", "0.2.0", "0.3.0"]);
+//! assert_eq!(graph.edges_of_kind(EdgeKind::Upgrade).count(), 2);
+//! assert_eq!(graph.edges_of_kind(EdgeKind::Downgrade).count(), 2);
+//! # #[cfg(feature = "alloc")]
+//! assert_eq!(graph.hops("0.1.0", "0.3.0"), Some(2));
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+#[cfg(feature = "alloc")]
+use core::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// The kind of conversion an [`Edge`] describes.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum EdgeKind {
+    /// A migration from one version to the next.
+    Upgrade,
+    /// A conversion from one version to a later version skipping the versions in between,
+    /// generated by `#[obake(dense_migrations)]`.
+    Shortcut,
+    /// A conversion from one version to the previous one, generated by `#[obake(downgrade)]`.
+    Downgrade,
+}
+
+impl EdgeKind {
+    /// The name of this kind of edge (i.e., `upgrade`, `shortcut` or `downgrade`).
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Upgrade => "upgrade",
+            Self::Shortcut => "shortcut",
+            Self::Downgrade => "downgrade",
+        }
+    }
+}
+
+/// A conversion between two versions of a data-structure.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Edge {
+    /// The version converted from.
+    pub from: &'static str,
+    /// The version converted to.
+    pub to: &'static str,
+    /// The kind of conversion.
+    pub kind: EdgeKind,
+}
+
+/// The versions of a data-structure and the conversions between them.
+///
+/// ## Note
+///
+/// Not intended to be constructed by hand, use
+/// [`Migrations::migration_graph`](crate::Migrations::migration_graph) to obtain it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct MigrationGraph {
+    /// The name of the data-structure.
+    pub name: &'static str,
+    /// Each declared version, in the order of [`Versioned::VERSIONS`](crate::Versioned::VERSIONS).
+    pub versions: &'static [&'static str],
+    /// Each conversion between two declared versions.
+    pub edges: &'static [Edge],
+}
+
+impl MigrationGraph {
+    /// The edges of a particular kind.
+    pub fn edges_of_kind(&self, kind: EdgeKind) -> impl Iterator<Item = &'static Edge> {
+        self.edges.iter().filter(move |edge| edge.kind == kind)
+    }
+
+    /// The edges leaving a version.
+    pub fn edges_from<'a>(&self, version: &'a str) -> impl Iterator<Item = &'static Edge> + 'a {
+        self.edges.iter().filter(move |edge| edge.from == version)
+    }
+
+    /// The fewest conversions needed to convert a value of one version into another, or `None`
+    /// if either version is undeclared or there is no sequence of conversions between them.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn hops(&self, from: &str, to: &str) -> Option<usize> {
+        let index = |version: &str| self.versions.iter().position(|v| *v == version);
+        let (from, to) = (index(from)?, index(to)?);
+
+        let mut distances = vec![None; self.versions.len()];
+        let mut queue = VecDeque::new();
+        distances[from] = Some(0);
+        queue.push_back(from);
+
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[current]?;
+            if current == to {
+                return Some(distance);
+            }
+
+            for edge in self.edges_from(self.versions[current]) {
+                let Some(next) = index(edge.to) else {
+                    continue;
+                };
+
+                if distances[next].is_none() {
+                    distances[next] = Some(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Render this graph in the [DOT](https://graphviz.org/doc/info/lang.html) language.
+    ///
+    /// Upgrades are drawn as solid edges, shortcuts as dashed edges and downgrades as dotted
+    /// edges.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // Writing to a `String` never fails
+        let _ = writeln!(dot, "digraph {:?} {{", self.name);
+        for version in self.versions {
+            let _ = writeln!(dot, "    {version:?};");
+        }
+        for edge in self.edges {
+            let style = match edge.kind {
+                EdgeKind::Upgrade => "solid",
+                EdgeKind::Shortcut => "dashed",
+                EdgeKind::Downgrade => "dotted",
+            };
+            let _ = writeln!(dot, "    {:?} -> {:?} [style={style}];", edge.from, edge.to);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for EdgeKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let index = match self {
+            Self::Upgrade => 0,
+            Self::Shortcut => 1,
+            Self::Downgrade => 2,
+        };
+        serializer.serialize_unit_variant("EdgeKind", index, self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Edge {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Edge", 3)?;
+        state.serialize_field("from", self.from)?;
+        state.serialize_field("to", self.to)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MigrationGraph {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("MigrationGraph", 3)?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("versions", self.versions)?;
+        state.serialize_field("edges", self.edges)?;
+        state.end()
+    }
+}
@@ -0,0 +1,83 @@
+//! Support code for the [`async_graphql`] types generated by `#[obake(async_graphql)]`.
+//!
+//! Marking a [`versioned`](crate::versioned) `struct` `Foo` with `#[obake(async_graphql)]`
+//! generates the following, so that GraphQL APIs can keep accepting old payload shapes:
+//!
+//! - `SimpleObject` and `InputObject` implementations for every declared version, named e.g.
+//!   `FooV0_1_0` and `FooV0_1_0Input` respectively.
+//! - A scalar `AnyFoo`, wrapping `Foo`, which accepts a JSON value of any declared version of
+//!   `Foo` (see [`json`](crate::json) for the accepted encodings) and resolves it to the latest
+//!   version.
+//!
+//! The crate using `#[obake(async_graphql)]` must depend on [`async_graphql`], every version of
+//! `Foo` must implement `Deserialize`, and `Foo` must implement [`Serialize`].
+//!
+//! Requires the feature `async-graphql`.
+
+use alloc::string::String;
+
+use core::fmt;
+
+use async_graphql::Value;
+use serde::Serialize;
+
+use crate::codec::{Decode, Json};
+use crate::{try_upgrade_rendered, AnyVersion, Dispatch, StepError, TryUpgrade, Versioned};
+
+/// Errors produced when parsing a GraphQL value (see [`parse`]).
+#[derive(Debug)]
+pub enum Error {
+    /// The value could not be decoded as any declared version.
+    Decode(crate::json::Error),
+    /// The value was decoded, but failed to migrate to the latest version (see [`TryUpgrade`]).
+    Migrate(StepError<String>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(err) => err.fmt(f),
+            Self::Migrate(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(err) => Some(err),
+            Self::Migrate(err) => Some(err),
+        }
+    }
+}
+
+/// Decode a GraphQL value of any declared version of `T` and migrate it to the latest version.
+///
+/// ## Errors
+///
+/// Fails if `value` cannot be decoded as any declared version of `T`, or if it fails to migrate.
+pub fn parse<T>(value: Value) -> Result<T, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    let json = value
+        .into_json()
+        .and_then(|json| serde_json::to_vec(&json))
+        .map_err(|err| Error::Decode(crate::json::Error::Json(err)))?;
+
+    let tagged = crate::json::from_slice::<T>(&json).map_err(Error::Decode)?;
+    try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate)
+}
+
+/// Encode the latest version of a data-structure as a GraphQL value.
+///
+/// Values that cannot be represented in GraphQL are encoded as `null`.
+pub fn to_value<T>(latest: &T) -> Value
+where
+    T: Serialize,
+{
+    async_graphql::to_value(latest).unwrap_or(Value::Null)
+}
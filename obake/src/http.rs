@@ -0,0 +1,159 @@
+//! Framework-agnostic version negotiation for HTTP APIs.
+//!
+//! Clients request a particular version of a response with either the `Accept-Version` or the
+//! `X-Api-Version` header, which may contain a comma-separated list of versions in order of
+//! preference. [`negotiate`] selects the first of these declared by a data-structure (or the
+//! latest version when no version is requested) and, for data-structures marked with
+//! `#[obake(downgrade)]`, [`respond`] encodes a response at the negotiated version in the
+//! canonical JSON envelope (see [`json`](crate::json)).
+//!
+//! Requires the feature `alloc` ([`respond`] and [`error_body`] require the feature `json`).
+//!
+//! ```
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! struct Foo {}
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(_: Foo!["0.1.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//!
+//! assert_eq!(obake::http::negotiate::<Foo>(None), Ok("0.2.0"));
+//! assert_eq!(obake::http::negotiate::<Foo>(Some("0.3.0, 0.1.0")), Ok("0.1.0"));
+//! assert!(obake::http::negotiate::<Foo>(Some("0.3.0")).is_err());
+//! ```
+
+use alloc::string::{String, ToString};
+#[cfg(feature = "json")]
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use crate::Versioned;
+#[cfg(feature = "json")]
+use crate::{json::ToEnvelope, Accept, AnyVersion, Downgrade};
+
+/// The preferred header used by clients to request a version.
+pub const ACCEPT_VERSION: &str = "Accept-Version";
+
+/// An alternative header used by clients to request a version.
+pub const X_API_VERSION: &str = "X-Api-Version";
+
+/// All headers which may be used to request a version, in order of precedence.
+pub const VERSION_HEADERS: &[&str] = &[ACCEPT_VERSION, X_API_VERSION];
+
+/// Find the version requested by a client, given a function looking up request headers by
+/// name.
+///
+/// ```
+/// let headers = [("X-Api-Version", "0.1.0")];
+/// let requested = obake::http::requested_version(|name| {
+///     headers
+///         .iter()
+///         .find(|(header, _)| header.eq_ignore_ascii_case(name))
+///         .map(|(_, value)| *value)
+/// });
+/// assert_eq!(requested, Some("0.1.0"));
+/// ```
+pub fn requested_version<'a, F>(header: F) -> Option<&'a str>
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    VERSION_HEADERS.iter().find_map(|name| header(name))
+}
+
+/// An error indicating that none of the versions requested by a client have been declared.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnsupportedVersion {
+    /// The versions requested by the client.
+    pub requested: String,
+    /// The semantic version numbers of all declared versions.
+    pub supported: &'static [&'static str],
+}
+
+impl fmt::Display for UnsupportedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported version {} (supported versions: {})",
+            self.requested,
+            self.supported.join(", "),
+        )
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl crate::error::Error for UnsupportedVersion {}
+
+/// Select the version of `T` to respond with, given the value of a client's version header.
+///
+/// If no version was requested, the latest version is selected. Otherwise, the header is
+/// treated as a comma-separated list of versions in order of preference, and the first declared
+/// version is selected.
+///
+/// ## Errors
+///
+/// Fails if a version was requested, but none of the requested versions have been declared.
+pub fn negotiate<T>(requested: Option<&str>) -> Result<&'static str, UnsupportedVersion>
+where
+    T: Versioned,
+{
+    let Some(requested) = requested else {
+        return Ok(T::VERSIONS[T::VERSIONS.len() - 1]);
+    };
+
+    requested
+        .split(',')
+        .map(str::trim)
+        .find_map(|version| T::VERSIONS.iter().find(|declared| **declared == version))
+        .copied()
+        .ok_or_else(|| UnsupportedVersion {
+            requested: requested.to_string(),
+            supported: T::VERSIONS,
+        })
+}
+
+/// Encode `latest` in the canonical JSON envelope at the version negotiated for a client's
+/// version header.
+///
+/// Requires the feature `json`.
+///
+/// ## Errors
+///
+/// Fails if negotiation fails. Serialization failures are reported as `Ok(Err(...))`, as they
+/// are typically not the client's fault.
+#[cfg(feature = "json")]
+pub fn respond<T>(
+    latest: T,
+    requested: Option<&str>,
+) -> Result<serde_json::Result<Vec<u8>>, UnsupportedVersion>
+where
+    T: Versioned,
+    AnyVersion<T>: Downgrade<T> + Accept<T, ToEnvelope>,
+{
+    let version = negotiate::<T>(requested)?;
+    let tagged = AnyVersion::<T>::downgrade(latest, version)
+        .unwrap_or_else(|| unreachable!("negotiated versions are always declared"));
+    Ok(crate::json::to_vec::<T>(&tagged))
+}
+
+/// The JSON body of an error response, naming the supported versions.
+///
+/// ```json
+/// {
+///     "error": "...",
+///     "supported_versions": ["0.1.0", "0.2.0"]
+/// }
+/// ```
+///
+/// Requires the feature `json`.
+#[cfg(feature = "json")]
+pub fn error_body(error: &dyn fmt::Display, supported: &[&str]) -> serde_json::Value {
+    serde_json::json!({
+        "error": error.to_string(),
+        "supported_versions": supported,
+    })
+}
@@ -0,0 +1,470 @@
+//! [Cap'n Proto](https://capnproto.org) and [FlatBuffers](https://flatbuffers.dev) schemas for
+//! every declared version of a [`versioned`](crate::versioned) data-structure, generated by
+//! `#[obake(idl)]`.
+//!
+//! Marking a data-structure with `#[obake(idl)]` implements [`IdlType`] for every declared
+//! version. Each version can then be rendered as a standalone schema (named after the
+//! data-structure, e.g. `Foo`) with [`render`], including the definitions of any other
+//! `#[obake(idl)]` types it refers to, or every version can be written to a directory at once
+//! with [`write_schemas`] (e.g., from a build script, into `OUT_DIR`), as `foo_v0_1_0.capnp`,
+//! `foo_v0_1_0.fbs`, etc.
+//!
+//! `struct`s are rendered as Cap'n Proto `struct`s and FlatBuffers `table`s. `enum`s are
+//! rendered as Cap'n Proto `struct`s holding an unnamed `union` (with a `group` for every
+//! variant with fields) and as a FlatBuffers `union` of one `table` per variant, wrapped in a
+//! `table` holding a single field, `value`.
+//!
+//! Neither schema language can express an optional scalar in every position, so `Option<T>` is
+//! rendered as `T`.
+//!
+//! Requires the feature `idl`.
+//!
+//! ```
+//! use obake::idl::{render, Format};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(idl)]
+//! struct Foo {
+//!     bar_baz: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     qux: Vec<String>,
+//! }
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(from: Foo!["0.1.0"]) -> Self {
+//! #         Self { bar_baz: from.bar_baz, qux: Vec::new() }
+//! #     }
+//! # }
+//!
+//! assert!(render::<Foo, Foo!["0.2.0"]>(Format::Capnp).contains(
+//!     "struct Foo {\n  barBaz @0 :UInt32;\n  qux @1 :List(Text);\n}\n",
+//! ));
+//! assert!(render::<Foo, Foo!["0.1.0"]>(Format::Flatbuffers).contains(
+//!     "table Foo {\n  bar_baz:uint;\n}\n\nroot_type Foo;\n",
+//! ));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{AnyVersion, Dispatch, VersionOf, VersionVisitor, Versioned, VisitVersion};
+
+/// A schema language.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Format {
+    /// [Cap'n Proto](https://capnproto.org) (`.capnp`).
+    Capnp,
+    /// [FlatBuffers](https://flatbuffers.dev) (`.fbs`).
+    Flatbuffers,
+}
+
+impl Format {
+    /// The conventional file extension of schemas in this language.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Capnp => "capnp",
+            Self::Flatbuffers => "fbs",
+        }
+    }
+}
+
+/// The name of a type in a schema.
+pub type TypeName = String;
+
+/// Types that can be described in a schema.
+///
+/// Implemented for primitive types, `String`, `Vec<T>` and `Option<T>`, and by `#[obake(idl)]`
+/// for every declared version of a data-structure.
+pub trait IdlType {
+    /// The name of this type in a schema written in `format`.
+    fn idl_type(format: Format) -> TypeName;
+
+    /// Add the definitions this type depends on to `schema`.
+    fn idl_items(format: Format, schema: &mut Schema) {
+        let _ = (format, schema);
+    }
+}
+
+macro_rules! impl_idl_type {
+    ($($ty:ty => ($capnp:literal, $fbs:literal)),* $(,)?) => {
+        $(
+            impl IdlType for $ty {
+                fn idl_type(format: Format) -> TypeName {
+                    match format {
+                        Format::Capnp => $capnp.to_string(),
+                        Format::Flatbuffers => $fbs.to_string(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_idl_type! {
+    bool => ("Bool", "bool"),
+    i8 => ("Int8", "byte"),
+    i16 => ("Int16", "short"),
+    i32 => ("Int32", "int"),
+    i64 => ("Int64", "long"),
+    u8 => ("UInt8", "ubyte"),
+    u16 => ("UInt16", "ushort"),
+    u32 => ("UInt32", "uint"),
+    u64 => ("UInt64", "ulong"),
+    f32 => ("Float32", "float"),
+    f64 => ("Float64", "double"),
+    String => ("Text", "string"),
+}
+
+impl<T> IdlType for Vec<T>
+where
+    T: IdlType,
+{
+    fn idl_type(format: Format) -> TypeName {
+        let inner = T::idl_type(format);
+        match format {
+            Format::Capnp if inner == "UInt8" => "Data".to_string(),
+            Format::Capnp => alloc::format!("List({inner})"),
+            Format::Flatbuffers => alloc::format!("[{inner}]"),
+        }
+    }
+
+    fn idl_items(format: Format, schema: &mut Schema) {
+        T::idl_items(format, schema);
+    }
+}
+
+impl<T> IdlType for Option<T>
+where
+    T: IdlType,
+{
+    fn idl_type(format: Format) -> TypeName {
+        T::idl_type(format)
+    }
+
+    fn idl_items(format: Format, schema: &mut Schema) {
+        T::idl_items(format, schema);
+    }
+}
+
+/// A field of a [`Item`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Field {
+    /// The name of the field, as declared in Rust.
+    pub name: &'static str,
+    /// The type of the field.
+    pub ty: TypeName,
+}
+
+impl Field {
+    /// A field named `name` of type `T`.
+    #[must_use]
+    pub fn of<T>(name: &'static str, format: Format) -> Self
+    where
+        T: IdlType,
+    {
+        Self {
+            name,
+            ty: T::idl_type(format),
+        }
+    }
+}
+
+/// A variant of an `enum` [`Item`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Variant {
+    /// The name of the variant, as declared in Rust.
+    pub name: &'static str,
+    /// The fields of the variant.
+    pub fields: Vec<Field>,
+}
+
+impl Variant {
+    /// A variant named `name` with `fields`.
+    #[must_use]
+    pub fn new(name: &'static str, fields: impl IntoIterator<Item = Field>) -> Self {
+        Self {
+            name,
+            fields: fields.into_iter().collect(),
+        }
+    }
+}
+
+/// The shape of an [`Item`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ItemKind {
+    /// A `struct` with named fields.
+    Struct(Vec<Field>),
+    /// An `enum`.
+    Enum(Vec<Variant>),
+}
+
+/// A definition in a schema.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Item {
+    /// The name of the definition.
+    pub name: &'static str,
+    /// The shape of the definition.
+    pub kind: ItemKind,
+}
+
+impl Item {
+    /// A `struct` named `name` with `fields`.
+    #[must_use]
+    pub fn structure(name: &'static str, fields: impl IntoIterator<Item = Field>) -> Self {
+        Self {
+            name,
+            kind: ItemKind::Struct(fields.into_iter().collect()),
+        }
+    }
+
+    /// An `enum` named `name` with `variants`.
+    #[must_use]
+    pub fn enumeration(name: &'static str, variants: impl IntoIterator<Item = Variant>) -> Self {
+        Self {
+            name,
+            kind: ItemKind::Enum(variants.into_iter().collect()),
+        }
+    }
+}
+
+/// The definitions making up a schema, in order.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Schema {
+    items: Vec<Item>,
+}
+
+impl Schema {
+    /// Whether a definition named `name` has already been added.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.items.iter().any(|item| item.name == name)
+    }
+
+    /// Add a definition.
+    pub fn push(&mut self, item: Item) {
+        self.items.push(item);
+    }
+
+    /// The definitions making up this schema, in order.
+    #[must_use]
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+}
+
+/// Render the schema of the version `V` of `T` in `format`.
+#[must_use]
+pub fn render<T, V>(format: Format) -> String
+where
+    T: Versioned,
+    V: VersionOf<T> + IdlType,
+{
+    let mut schema = Schema::default();
+    V::idl_items(format, &mut schema);
+    let root = V::idl_type(format);
+
+    let mut out = String::new();
+    match format {
+        Format::Capnp => {
+            let _ = writeln!(
+                out,
+                "# `{root}` version {}, generated by obake.",
+                V::VERSION
+            );
+            let _ = writeln!(out, "@{:#x};", capnp_id(&root, V::VERSION));
+            for item in schema.items() {
+                out.push('\n');
+                render_capnp(&mut out, item);
+            }
+        }
+        Format::Flatbuffers => {
+            let _ = writeln!(
+                out,
+                "// `{root}` version {}, generated by obake.",
+                V::VERSION
+            );
+            for item in schema.items() {
+                out.push('\n');
+                render_fbs(&mut out, item);
+            }
+            let _ = writeln!(out, "\nroot_type {root};");
+        }
+    }
+
+    out
+}
+
+/// A stable Cap'n Proto file ID for the schema of version `version` of `root`.
+fn capnp_id(root: &str, version: &str) -> u64 {
+    // FNV-1a, with the top bit set as required of Cap'n Proto IDs
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in root.bytes().chain(Some(b'@')).chain(version.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash | 1 << 63
+}
+
+/// Convert a Rust identifier to the camel case required of Cap'n Proto field names.
+fn camel_case(ident: &str) -> String {
+    let mut camel = String::with_capacity(ident.len());
+    let mut upper = false;
+    for (i, c) in ident.chars().enumerate() {
+        if c == '_' {
+            upper = i != 0;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else if camel.is_empty() {
+            camel.extend(c.to_lowercase());
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+fn render_capnp(out: &mut String, item: &Item) {
+    let _ = writeln!(out, "struct {} {{", item.name);
+    let mut ordinal = 0;
+    match &item.kind {
+        ItemKind::Struct(fields) => {
+            for field in fields {
+                render_capnp_field(out, "  ", field, &mut ordinal);
+            }
+        }
+        ItemKind::Enum(variants) => {
+            out.push_str("  union {\n");
+            for variant in variants {
+                let name = camel_case(variant.name);
+                if variant.fields.is_empty() {
+                    let _ = writeln!(out, "    {name} @{ordinal} :Void;");
+                    ordinal += 1;
+                } else {
+                    let _ = writeln!(out, "    {name} :group {{");
+                    for field in &variant.fields {
+                        render_capnp_field(out, "      ", field, &mut ordinal);
+                    }
+                    out.push_str("    }\n");
+                }
+            }
+            out.push_str("  }\n");
+        }
+    }
+    out.push_str("}\n");
+}
+
+fn render_capnp_field(out: &mut String, indent: &str, field: &Field, ordinal: &mut usize) {
+    let name = camel_case(field.name);
+    let _ = writeln!(out, "{indent}{name} @{ordinal} :{};", field.ty);
+    *ordinal += 1;
+}
+
+fn render_fbs(out: &mut String, item: &Item) {
+    match &item.kind {
+        ItemKind::Struct(fields) => render_fbs_table(out, item.name, fields),
+        ItemKind::Enum(variants) => {
+            for variant in variants {
+                render_fbs_table(
+                    out,
+                    &alloc::format!("{}{}", item.name, variant.name),
+                    &variant.fields,
+                );
+                out.push('\n');
+            }
+            let members: Vec<_> = variants
+                .iter()
+                .map(|variant| alloc::format!("{}{}", item.name, variant.name))
+                .collect();
+            let _ = writeln!(
+                out,
+                "union {}Variant {{ {} }}",
+                item.name,
+                members.join(", ")
+            );
+            let _ = writeln!(out, "\ntable {0} {{\n  value:{0}Variant;\n}}", item.name);
+        }
+    }
+}
+
+fn render_fbs_table(out: &mut String, name: &str, fields: &[Field]) {
+    let _ = writeln!(out, "table {name} {{");
+    for field in fields {
+        let _ = writeln!(out, "  {}:{};", field.name, field.ty);
+    }
+    out.push_str("}\n");
+}
+
+/// The [`VersionVisitor`] used by [`write_schemas`] to render the schema of a particular
+/// version.
+#[derive(Copy, Clone, Debug)]
+pub struct Render {
+    format: Format,
+}
+
+impl<T> VersionVisitor<T> for Render
+where
+    T: Versioned,
+{
+    type Output = (TypeName, String);
+}
+
+impl<T, V> VisitVersion<T, V> for Render
+where
+    T: Versioned,
+    V: VersionOf<T> + IdlType,
+{
+    fn visit(self) -> Self::Output {
+        (V::idl_type(self.format), render::<T, V>(self.format))
+    }
+}
+
+/// Write the Cap'n Proto and FlatBuffers schemas of every declared version of `T` to `dir`,
+/// named after the data-structure and the version (e.g., `foo_v0_1_0.capnp`).
+///
+/// Intended to be called from build scripts, with `dir` set to `OUT_DIR`.
+///
+/// ## Errors
+///
+/// Fails if any schema cannot be written.
+pub fn write_schemas<T>(dir: impl AsRef<std::path::Path>) -> std::io::Result<()>
+where
+    T: Versioned,
+    AnyVersion<T>: Dispatch<T, Render>,
+{
+    for format in [Format::Capnp, Format::Flatbuffers] {
+        for version in T::VERSIONS {
+            let Some((root, schema)) = AnyVersion::<T>::dispatch(version, Render { format }) else {
+                continue;
+            };
+
+            let stem =
+                alloc::format!("{}_v{}", snake_case(&root), version).replace(['.', '-', '+'], "_");
+            let path = dir
+                .as_ref()
+                .join(alloc::format!("{stem}.{}", format.extension()));
+            std::fs::write(path, schema)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn snake_case(ident: &str) -> String {
+    let mut snake = String::with_capacity(ident.len());
+    for (i, c) in ident.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
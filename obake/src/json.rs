@@ -0,0 +1,714 @@
+//! JSON encodings of [`versioned`](crate::versioned) data-structures.
+//!
+//! Values are exchanged in a canonical envelope naming the version of the payload:
+//!
+//! ```json
+//! { "version": "0.1.0", "data": { ... } }
+//! ```
+//!
+//! Values are encoded in this envelope by [`to_vec`]. When decoding, payloads which aren't wrapped in an envelope are also accepted: each declared
+//! version is tried in turn, from newest to oldest, and the first to successfully deserialize
+//! is used.
+//!
+//! Requires the feature `json`.
+//!
+//! ```
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg("0.1.0"))]
+//!     bar: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     baz: u64,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(from: Foo!["0.1.0"]) -> Self {
+//!         Self { baz: from.bar.into() }
+//!     }
+//! }
+//!
+//! let tagged = obake::json::from_slice::<Foo>(br#"{"version":"0.1.0","data":{"bar":1}}"#);
+//! assert_eq!(Foo::from(tagged.unwrap()), Foo { baz: 1 });
+//!
+//! let untagged = obake::json::from_slice::<Foo>(br#"{"bar":2}"#);
+//! assert_eq!(Foo::from(untagged.unwrap()), Foo { baz: 2 });
+//!
+//! let encoded = obake::json::to_vec::<Foo>(&Foo { baz: 3 }.into()).unwrap();
+//! assert_eq!(encoded, br#"{"version":"0.2.0","data":{"baz":3}}"#);
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+
+use crate::codec::{decode_version, decode_version_with, Decode, Json, UnsupportedVersion};
+use crate::{
+    Accept, AnyVersion, Declared, Dispatch, OnUnrecognized, VersionOf, VersionTagged,
+    VersionVisitor, Versioned, VisitValue,
+};
+
+/// Errors produced when decoding JSON.
+#[derive(Debug)]
+pub enum Error {
+    /// The payload was tagged with a version that is not declared.
+    UnknownVersion(String),
+    /// The payload was tagged with a declared version, but could not be deserialized as that
+    /// version.
+    Json(serde_json::Error),
+    /// The payload was untagged and could not be deserialized as any declared version.
+    Unrecognized,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVersion(version) => write!(f, "undeclared version {version}"),
+            Self::Json(err) => write!(f, "malformed payload: {err}"),
+            Self::Unrecognized => write!(f, "payload does not match any declared version"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EnvelopeRef<'a, V> {
+    version: &'static str,
+    data: &'a V,
+}
+
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(borrow)]
+    version: Cow<'a, str>,
+    #[serde(borrow)]
+    data: &'a RawValue,
+}
+
+/// Decode a JSON payload of any declared version of `T`, either wrapped in the canonical
+/// envelope or untagged.
+///
+/// ## Errors
+///
+/// Fails if the payload is tagged with an undeclared version, if a tagged payload cannot be
+/// deserialized as its version, or if an untagged payload cannot be deserialized as any
+/// version.
+pub fn from_slice<T>(bytes: &[u8]) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+{
+    from_slice_with::<T>(bytes, UnsupportedVersion::Reject)
+}
+
+/// Decode a JSON payload as [`from_slice`] does, handling a payload tagged with an undeclared
+/// version according to `policy`.
+///
+/// ```
+/// use obake::codec::UnsupportedVersion;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// let payload = br#"{"version":"0.2.0","data":{"bar":1,"baz":2}}"#;
+/// assert!(obake::json::from_slice::<Foo>(payload).is_err());
+///
+/// let x = obake::json::from_slice_with::<Foo>(payload, UnsupportedVersion::BestEffortLatest);
+/// assert_eq!(Foo::from(x.unwrap()), Foo { bar: 1 });
+/// ```
+///
+/// ## Errors
+///
+/// Fails as [`from_slice`] does, unless `policy` selects a declared version to decode a payload
+/// tagged with an undeclared version as.
+pub fn from_slice_with<T>(bytes: &[u8], policy: UnsupportedVersion) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+{
+    if let Ok(envelope) = serde_json::from_slice::<Envelope<'_>>(bytes) {
+        let data = envelope.data.get().as_bytes();
+        return decode_version_with::<T, _>(&Json, &envelope.version, data, policy)
+            .ok_or_else(|| Error::UnknownVersion(envelope.version.to_string()))?
+            .map_err(Error::Json);
+    }
+
+    T::VERSIONS
+        .iter()
+        .rev()
+        .find_map(|version| decode_version::<T, _>(&Json, version, bytes)?.ok())
+        .ok_or(Error::Unrecognized)
+}
+
+/// Decode a JSON payload of any declared version of `T` as [`from_slice`] does, passing the
+/// payload to the function named by `#[obake(on_unrecognized = ...)]` if that fails.
+///
+/// ```
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(on_unrecognized = recover)]
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// // Some legacy files were written with a trailing comma
+/// fn recover(payload: &[u8]) -> Option<obake::AnyVersion<Foo>> {
+///     let payload = std::str::from_utf8(payload).ok()?.replace(",}", "}");
+///     obake::json::from_slice::<Foo>(payload.as_bytes()).ok()
+/// }
+///
+/// let x = obake::json::from_slice_or_recover::<Foo>(br#"{"bar":1,}"#).unwrap();
+/// assert_eq!(Foo::from(x), Foo { bar: 1 });
+/// assert!(obake::json::from_slice_or_recover::<Foo>(b"{}").is_err());
+/// ```
+///
+/// ## Errors
+///
+/// Fails with the error produced by [`from_slice`] if the function fails to recover a value.
+pub fn from_slice_or_recover<T>(bytes: &[u8]) -> Result<AnyVersion<T>, Error>
+where
+    T: OnUnrecognized,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+{
+    from_slice::<T>(bytes).or_else(|err| T::on_unrecognized(bytes).ok_or(err))
+}
+
+/// The [`VersionVisitor`] used by [`to_vec`] to encode a value in the canonical envelope.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ToEnvelope;
+
+impl<T> VersionVisitor<T> for ToEnvelope
+where
+    T: Versioned,
+{
+    type Output = serde_json::Result<Vec<u8>>;
+}
+
+impl<T, V> VisitValue<T, V> for ToEnvelope
+where
+    T: Versioned,
+    V: VersionOf<T> + Serialize,
+{
+    fn visit_value(self, value: &V) -> Self::Output {
+        serde_json::to_vec(&EnvelopeRef {
+            version: V::VERSION,
+            data: value,
+        })
+    }
+}
+
+/// Encode a value of any declared version of `T` as JSON, wrapped in the canonical envelope.
+///
+/// ## Errors
+///
+/// Fails if the value cannot be serialized.
+pub fn to_vec<T>(tagged: &AnyVersion<T>) -> serde_json::Result<Vec<u8>>
+where
+    T: Versioned,
+    AnyVersion<T>: Accept<T, ToEnvelope>,
+{
+    tagged.accept(ToEnvelope)
+}
+
+/// Encode a value of any declared version of `T` as a JSON string, wrapped in the canonical
+/// envelope.
+///
+/// This is how version-tagged enums marked `#[obake(display)]` implement `Display`, which
+/// (along with `FromStr`, decoding as [`from_slice`] does) makes for easy logging, piping
+/// through command-line tools and comparing in tests.
+///
+/// ```
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(display)]
+/// #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// let tagged: obake::AnyVersion<Foo> = Foo { bar: 1 }.into();
+/// assert_eq!(tagged.to_string(), r#"{"version":"0.1.0","data":{"bar":1}}"#);
+///
+/// let parsed: obake::AnyVersion<Foo> = tagged.to_string().parse().unwrap();
+/// assert_eq!(Foo::from(parsed), Foo { bar: 1 });
+/// ```
+///
+/// ## Errors
+///
+/// Fails if the value cannot be serialized.
+pub fn to_string<T>(tagged: &AnyVersion<T>) -> serde_json::Result<String>
+where
+    T: Versioned,
+    AnyVersion<T>: Accept<T, ToEnvelope>,
+{
+    // `serde_json` only ever emits valid UTF-8, so this never replaces anything
+    to_vec::<T>(tagged).map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// A value of any declared version of `T` in the canonical envelope, of which only the version
+/// has been deserialized.
+///
+/// The payload is kept as raw JSON until [`RawVersioned::parse`] is called, so routing on the
+/// version (or forwarding the envelope unchanged, as it serializes back to the same envelope)
+/// doesn't pay the cost of deserializing the payload.
+///
+/// ```
+/// # use obake::json::RawVersioned;
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { bar: from.bar }
+/// #     }
+/// # }
+/// #
+/// let raw: RawVersioned<Foo> =
+///     RawVersioned::from_slice(br#"{"version":"0.1.0","data":{"bar":1}}"#).unwrap();
+/// assert_eq!(raw.version(), "0.1.0");
+/// assert_eq!(raw.data().get(), r#"{"bar":1}"#);
+///
+/// let foo: Foo = raw.parse().unwrap().into();
+/// assert_eq!(foo, Foo { bar: 1 });
+/// ```
+pub struct RawVersioned<T> {
+    version: String,
+    data: Box<RawValue>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Deserialize)]
+struct OwnedEnvelope {
+    version: String,
+    data: Box<RawValue>,
+}
+
+impl<T> RawVersioned<T> {
+    /// Deserialize the version of a payload in the canonical envelope, deferring deserialization
+    /// of the payload itself.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `bytes` is not a JSON payload in the canonical envelope.
+    pub fn from_slice(bytes: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+
+    /// The semantic version number the payload is tagged with.
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The payload, as raw JSON.
+    #[must_use]
+    pub fn data(&self) -> &RawValue {
+        &self.data
+    }
+
+    /// Deserialize the payload as the version it is tagged with.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the payload is tagged with an undeclared version, or cannot be deserialized as
+    /// the version it is tagged with.
+    pub fn parse(self) -> Result<AnyVersion<T>, Error>
+    where
+        T: Versioned,
+        for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    {
+        self.parse_with(UnsupportedVersion::Reject)
+    }
+
+    /// Deserialize the payload as [`RawVersioned::parse`] does, handling a payload tagged with
+    /// an undeclared version according to `policy`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the payload is tagged with an undeclared version rejected by `policy`, or
+    /// cannot be deserialized as the selected version.
+    pub fn parse_with(self, policy: UnsupportedVersion) -> Result<AnyVersion<T>, Error>
+    where
+        T: Versioned,
+        for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    {
+        decode_version_with::<T, _>(&Json, &self.version, self.data.get().as_bytes(), policy)
+            .ok_or(Error::UnknownVersion(self.version))?
+            .map_err(Error::Json)
+    }
+}
+
+impl<T> Clone for RawVersioned<T> {
+    fn clone(&self) -> Self {
+        Self {
+            version: self.version.clone(),
+            data: self.data.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for RawVersioned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawVersioned")
+            .field("version", &self.version)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RawVersioned<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let OwnedEnvelope { version, data } = OwnedEnvelope::deserialize(deserializer)?;
+        Ok(Self {
+            version,
+            data,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Serialize for RawVersioned<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Envelope<'a> {
+            version: &'a str,
+            data: &'a RawValue,
+        }
+
+        Envelope {
+            version: &self.version,
+            data: &self.data,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// A value of any declared version of `T` in the canonical envelope, or a payload of an
+/// undeclared (e.g., newer) version, captured without being deserialized.
+///
+/// Deserializing a `ForwardCompatible<T>` doesn't fail when it encounters a version it doesn't
+/// know, so long-lived processes can tolerate payloads written by newer releases, and serializing
+/// it again passes such payloads through untouched.
+///
+/// ```
+/// # use obake::json::ForwardCompatible;
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// let known: ForwardCompatible<Foo> =
+///     serde_json::from_str(r#"{"version":"0.1.0","data":{"bar":1}}"#).unwrap();
+/// assert_eq!(known.version(), "0.1.0");
+/// assert_eq!(known.known().map(Foo::from), Some(Foo { bar: 1 }));
+///
+/// let json = r#"{"version":"0.2.0","data":{"bar":1,"baz":2}}"#;
+/// let unknown: ForwardCompatible<Foo> = serde_json::from_str(json).unwrap();
+/// assert_eq!(unknown.version(), "0.2.0");
+/// assert_eq!(serde_json::to_string(&unknown).unwrap(), json);
+/// assert!(unknown.known().is_none());
+/// ```
+pub enum ForwardCompatible<T>
+where
+    T: Versioned,
+{
+    /// A payload of a declared version.
+    Known(AnyVersion<T>),
+    /// A payload of an undeclared version.
+    Unknown(RawVersioned<T>),
+}
+
+impl<T> ForwardCompatible<T>
+where
+    T: Versioned,
+{
+    /// Decode a JSON payload in the canonical envelope, capturing payloads of undeclared
+    /// versions rather than failing.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `bytes` is not a JSON payload in the canonical envelope, or if it is tagged with
+    /// a declared version but cannot be deserialized as that version.
+    pub fn from_slice(bytes: &[u8]) -> serde_json::Result<Self>
+    where
+        AnyVersion<T>: Dispatch<T, Declared>,
+        for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    {
+        serde_json::from_slice(bytes)
+    }
+
+    /// The semantic version number the payload is tagged with.
+    #[must_use]
+    pub fn version(&self) -> &str {
+        match self {
+            Self::Known(tagged) => tagged.version_str(),
+            Self::Unknown(raw) => raw.version(),
+        }
+    }
+
+    /// The payload, if it is of a declared version.
+    #[must_use]
+    pub fn known(self) -> Option<AnyVersion<T>> {
+        match self {
+            Self::Known(tagged) => Some(tagged),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl<T> fmt::Debug for ForwardCompatible<T>
+where
+    T: Versioned,
+    AnyVersion<T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Known(tagged) => f.debug_tuple("Known").field(tagged).finish(),
+            Self::Unknown(raw) => f.debug_tuple("Unknown").field(raw).finish(),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ForwardCompatible<T>
+where
+    T: Versioned,
+    AnyVersion<T>: Dispatch<T, Declared>,
+    for<'a, 'c> AnyVersion<T>: Dispatch<T, Decode<'a, 'c, Json>>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = RawVersioned::<T>::deserialize(deserializer)?;
+        if AnyVersion::<T>::dispatch(raw.version(), Declared).is_none() {
+            return Ok(Self::Unknown(raw));
+        }
+
+        raw.parse()
+            .map(Self::Known)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl<T> Serialize for ForwardCompatible<T>
+where
+    T: Versioned,
+    AnyVersion<T>: Accept<T, ToEnvelope>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Known(tagged) => {
+                let json = to_vec::<T>(tagged).map_err(serde::ser::Error::custom)?;
+                let json = String::from_utf8(json).map_err(serde::ser::Error::custom)?;
+                RawValue::from_string(json)
+                    .map_err(serde::ser::Error::custom)?
+                    .serialize(serializer)
+            }
+            Self::Unknown(raw) => raw.serialize(serializer),
+        }
+    }
+}
+
+/// An edit to the fields of a JSON payload, made by [`upgrade_value`] to migrate it from one
+/// version to the next.
+#[derive(Copy, Clone, Debug)]
+pub enum FieldEdit {
+    /// The field is renamed.
+    Rename {
+        /// The name of the field in the version migrated from.
+        from: &'static str,
+        /// The name of the field in the version migrated to.
+        to: &'static str,
+    },
+    /// The field is removed.
+    Drop(&'static str),
+    /// The field is added, set to its default unless the payload has it already.
+    Add {
+        /// The name of the field in the version migrated to.
+        name: &'static str,
+        /// The default of the field (or that given by `#[obake(migrate_default = ...)]`),
+        /// serialized.
+        default: fn() -> serde_json::Result<Value>,
+    },
+}
+
+/// The edits to the fields of a JSON payload migrating it between consecutive versions (see
+/// [`UpgradeValue`]).
+#[derive(Copy, Clone, Debug)]
+pub struct ValueStep {
+    /// The version migrated from.
+    pub from: &'static str,
+    /// The version migrated to.
+    pub to: &'static str,
+    /// The edits made to the fields of the payload.
+    pub edits: &'static [FieldEdit],
+}
+
+impl ValueStep {
+    fn apply(&self, fields: &mut Map<String, Value>) -> serde_json::Result<()> {
+        // Renamed fields are taken out before any is put back, so that fields can swap names
+        let mut renamed = Vec::new();
+        for edit in self.edits {
+            match *edit {
+                FieldEdit::Rename { from, to } => {
+                    if let Some(value) = fields.remove(from) {
+                        renamed.push((to, value));
+                    }
+                }
+                FieldEdit::Drop(name) => {
+                    fields.remove(name);
+                }
+                FieldEdit::Add { .. } => {}
+            }
+        }
+        for (to, value) in renamed {
+            fields.insert(to.to_string(), value);
+        }
+
+        for edit in self.edits {
+            if let FieldEdit::Add { name, default } = *edit {
+                if !fields.contains_key(name) {
+                    fields.insert(name.to_string(), default()?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Automatically implemented for data-structures marked `#[obake(json_upgrade)]`, describing
+/// how their JSON payloads change between versions, for [`upgrade_value`].
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`](crate::versioned) to derive it.
+pub trait UpgradeValue: Versioned {
+    /// The edits migrating payloads between each pair of consecutive versions, ordered from
+    /// oldest to newest.
+    const STEPS: &'static [ValueStep];
+}
+
+/// Migrate a JSON payload of any declared version of `T`, wrapped in the canonical envelope, to
+/// the latest version without deserializing it, by renaming, dropping and adding its fields as
+/// the versions of `T` do.
+///
+/// Fields that `T` doesn't declare (e.g., flattened fields) are left untouched, as are the values
+/// of the fields which are kept (including nested versioned data-structures), so that tools can
+/// transform documents they only partially model. Values migrated in this way are only as
+/// faithful as the versions' fields: any logic in a hand-written migration is skipped.
+///
+/// Requires the data-structure to be marked `#[obake(json_upgrade)]`.
+///
+/// ```
+/// use serde_json::json;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(json_upgrade)]
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Foo {
+///     #[obake(cfg_attr("<0.2", serde(rename = "userName")))]
+///     name: String,
+///     #[obake(cfg("<0.2"))]
+///     legacy: bool,
+///     #[obake(cfg(">=0.2"))]
+///     tags: Vec<String>,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(from: Foo!["0.1.0"]) -> Self {
+///         Self { name: from.name, tags: Vec::new() }
+///     }
+/// }
+///
+/// let value = json!({
+///     "version": "0.1.0",
+///     "data": { "userName": "Obake", "legacy": true, "note": "kept" },
+/// });
+/// assert_eq!(
+///     obake::json::upgrade_value::<Foo>(value).unwrap(),
+///     json!({
+///         "version": "0.2.0",
+///         "data": { "name": "Obake", "tags": [], "note": "kept" },
+///     }),
+/// );
+/// ```
+///
+/// ## Errors
+///
+/// Fails if `value` isn't wrapped in the canonical envelope, if it is tagged with an undeclared
+/// version, if its payload isn't an object, or if the default of an added field can't be
+/// serialized.
+pub fn upgrade_value<T>(value: Value) -> Result<Value, Error>
+where
+    T: UpgradeValue,
+{
+    #[derive(Deserialize)]
+    struct Envelope {
+        version: String,
+        data: Value,
+    }
+
+    let Envelope { version, data } = serde_json::from_value(value).map_err(Error::Json)?;
+    if !T::VERSIONS.contains(&version.as_str()) {
+        return Err(Error::UnknownVersion(version));
+    }
+    let Value::Object(mut fields) = data else {
+        return Err(Error::Json(serde::de::Error::custom(
+            "expected the payload to be an object",
+        )));
+    };
+
+    let mut current = version.as_str();
+    while let Some(step) = T::STEPS.iter().find(|step| step.from == current) {
+        step.apply(&mut fields).map_err(Error::Json)?;
+        current = step.to;
+    }
+
+    let mut envelope = Map::new();
+    envelope.insert("version".to_string(), Value::String(current.to_string()));
+    envelope.insert("data".to_string(), Value::Object(fields));
+    Ok(Value::Object(envelope))
+}
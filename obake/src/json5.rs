@@ -0,0 +1,159 @@
+//! JSON5 encodings of [`versioned`](crate::versioned) data-structures, for hand-edited
+//! configuration files.
+//!
+//! As with `obake::json`, values are exchanged in an envelope naming the version of the payload,
+//! which can be written with any JSON5 syntax (e.g., unquoted keys, comments and trailing
+//! commas):
+//!
+//! ```json5
+//! {
+//!     // Bumped by hand after each migration
+//!     version: '0.1.0',
+//!     data: { bar: 1 },
+//! }
+//! ```
+//!
+//! Values are encoded in this envelope by [`to_string`]. When decoding, payloads which aren't
+//! wrapped in an envelope are also accepted: each declared version is tried in turn, from newest
+//! to oldest, and the first to successfully deserialize is used.
+//!
+//! Requires the feature `json5`.
+//!
+//! ```
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg("0.1.0"))]
+//!     bar: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     baz: u64,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(from: Foo!["0.1.0"]) -> Self {
+//!         Self { baz: from.bar.into() }
+//!     }
+//! }
+//!
+//! let tagged = obake::json5::from_str::<Foo>("{ version: '0.1.0', data: { bar: 1 } }");
+//! assert_eq!(Foo::from(tagged.unwrap()), Foo { baz: 1 });
+//!
+//! let untagged = obake::json5::from_str::<Foo>("{ bar: 2, /* legacy */ }");
+//! assert_eq!(Foo::from(untagged.unwrap()), Foo { baz: 2 });
+//!
+//! let encoded = obake::json5::to_string::<Foo>(&Foo { baz: 3 }.into()).unwrap();
+//! assert_eq!(encoded, r#"{"version":"0.2.0","data":{"baz":3}}"#);
+//! ```
+
+use alloc::string::String;
+
+use core::fmt;
+
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use crate::codec::{
+    decode_version, decode_version_with, Codec, Decode, EnvelopeData, Json5, ToEnvelope,
+    UnsupportedVersion,
+};
+use crate::{Accept, AnyVersion, Dispatch, Versioned};
+
+/// Errors produced when decoding JSON5.
+#[derive(Debug)]
+pub enum Error {
+    /// The payload was tagged with a version that is not declared.
+    UnknownVersion(String),
+    /// The payload was tagged with a declared version, but could not be deserialized as that
+    /// version.
+    Json5(json5::Error),
+    /// The payload was untagged and could not be deserialized as any declared version.
+    Unrecognized,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVersion(version) => write!(f, "undeclared version {version}"),
+            Self::Json5(err) => write!(f, "malformed payload: {err}"),
+            Self::Unrecognized => write!(f, "payload does not match any declared version"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json5(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// JSON5 strings can't be borrowed (they may be written with either quote and contain escapes),
+// so the version is always owned
+#[derive(Deserialize)]
+struct Envelope {
+    version: String,
+    #[allow(dead_code)]
+    data: IgnoredAny,
+}
+
+/// Decode a JSON5 payload of any declared version of `T`, either wrapped in the canonical
+/// envelope or untagged.
+///
+/// ## Errors
+///
+/// Fails if the payload is tagged with an undeclared version, if a tagged payload cannot be
+/// deserialized as its version, or if an untagged payload cannot be deserialized as any
+/// version.
+pub fn from_str<T>(s: &str) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>:
+        Dispatch<T, Decode<'de, 'c, Json5>> + Dispatch<T, Decode<'de, 'c, EnvelopeData<Json5>>>,
+{
+    from_str_with::<T>(s, UnsupportedVersion::Reject)
+}
+
+/// Decode a JSON5 payload as [`from_str`] does, handling a payload tagged with an undeclared
+/// version according to `policy`.
+///
+/// ## Errors
+///
+/// Fails as [`from_str`] does, unless `policy` selects a declared version to decode a payload
+/// tagged with an undeclared version as.
+pub fn from_str_with<T>(s: &str, policy: UnsupportedVersion) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>:
+        Dispatch<T, Decode<'de, 'c, Json5>> + Dispatch<T, Decode<'de, 'c, EnvelopeData<Json5>>>,
+{
+    if let Ok(envelope) = Json5.decode::<Envelope>(s.as_bytes()) {
+        let data = EnvelopeData(Json5);
+        return decode_version_with::<T, _>(&data, &envelope.version, s.as_bytes(), policy)
+            .ok_or(Error::UnknownVersion(envelope.version))?
+            .map_err(Error::Json5);
+    }
+
+    T::VERSIONS
+        .iter()
+        .rev()
+        .find_map(|version| decode_version::<T, _>(&Json5, version, s.as_bytes())?.ok())
+        .ok_or(Error::Unrecognized)
+}
+
+/// Encode a value of any declared version of `T` as JSON5, wrapped in the canonical envelope.
+///
+/// ## Errors
+///
+/// Fails if the value cannot be serialized.
+pub fn to_string<T>(tagged: &AnyVersion<T>) -> json5::Result<String>
+where
+    T: Versioned,
+    for<'c> AnyVersion<T>: Accept<T, ToEnvelope<'c, Json5>>,
+{
+    let bytes = tagged.accept(ToEnvelope(&Json5))?;
+    String::from_utf8(bytes).map_err(serde::ser::Error::custom)
+}
@@ -50,29 +50,456 @@
 //!
 //! ## Other Features
 //!
+//! - `#[derive(obake::Obake)]`: declares a versioned data-structure from a definition with a
+//!   derive, for predictable composition with other attribute macros.
+//! - `obake::versioned_items! { ... }`: declares several versioned data-structures sharing
+//!   attributes (e.g., a list of versions) declared once at the top of the block.
+//! - `#[obake::extend_versions(base = ...)]`: declares newer versions of a versioned
+//!   data-structure declared elsewhere (e.g., by another crate), along with an enum holding
+//!   any version of either.
+//! - `obake::any_of!(Foo, ["0.1.0" | "0.2.0"] as legacy)`: a pattern matching any of several
+//!   versions, binding the fields they have in common, so that they can share a `match` arm.
+//! - `#[obake(cfg_attr(...))]`: applies attributes to data-structures, fields and variants only in
+//!   the versions matching a semantic version constraint (e.g.,
+//!   `#[obake(cfg_attr("<0.2", serde(default)))]`).
 //! - `#[obake(inherit)]`: allows nesting of versioned data-structures.
+//! - `#[obake(since(...))]` and `#[obake(until(...))]`: limit an inherited field to a range of
+//!   versions, wrapping it in `Option` in the others.
+//! - `#[obake(skip)]`: keeps runtime-only fields (e.g., caches) out of every version, adding
+//!   them only to the type named after the data-structure once migrated.
+//! - `#[obake(getter)]`: generates an accessor on [`AnyVersion`] borrowing a field from the
+//!   version held, if that version has it (e.g., `fn bar(&self) -> Option<&u32>`).
+//! - `#[obake(downgrade)]`: allows conversion of the latest version into older versions.
+//! - `#[obake(repr_c)]`: lays out every version and the version-tagged enum for use across a
+//!   C ABI.
+//! - `#[obake(dense_migrations)]`: allows conversion between any two versions in a single
+//!   call.
+//! - `#[obake(alias_identical)]`: reuses the previous version's type for versions which are
+//!   identical to it, rather than generating a new type.
+//! - `#[obake(tag = ...)]`: numbers versions with a compact integer tag.
+//! - `#[obake(note("0.2.0", "..."))]`: adds notes to the generated history of a version.
+//! - `#[obake(released("0.2.0", "2023-06-01"))]`: records the release date of a version, in
+//!   the generated history and for lookup with [`Releases`].
+//! - `#[obake(deprecated("0.1.0", "..."))]`: warns (with the given note) wherever a version
+//!   slated for removal is named with the `Foo!` macro.
+//! - `#[obake(stable("0.2.0"))]` and `#[obake(stability)]`: keeps a historical version stable
+//!   rather than frozen in the [`StabilityMap`] read by semver checkers, and heads the
+//!   documentation of each version with its stability.
+//! - `#[obake(version_default("0.1.0", path))]`: implements `Default` for a version with a
+//!   constructor of its own, rather than deriving the same `Default` for every version.
+//! - `#[obake(assert_fields("0.1.0", [...]))]`: fails to compile if a version doesn't have
+//!   exactly the listed fields (or variants), guarding historical versions during refactors.
+//! - `#[obake(fallible("0.2.0", error = E))]`: migrates to some versions with `TryFrom`, upgrading
+//!   with [`TryUpgrade`] and reporting the versions either side of the failing migration.
+//! - `#[obake(auto_migrate)]`: generates migrations which move unchanged fields into the next
+//!   version, rather than cloning them, and convert fields (or variant payloads) redeclared
+//!   with a new type.
+//! - `#[obake(migrate_default = ...)]`: sets fields without a `Default` (e.g., `Box<dyn Trait>`)
+//!   when they're added by a generated migration.
+//! - `#[obake(dto_modules)]`: groups each version of a data-structure in a module per version
+//!   (e.g., `v0_1_0::Foo`), matching versioned API routes.
+//! - `#[obake(auto_downgrade_subset)]`: rebuilds older versions from the latest version when
+//!   their fields are a subset of its fields (e.g., for test fixtures).
+//! - `#[obake(cfg_when(feature = "legacy", "<0.3"))]`: compiles the oldest versions only when
+//!   a `cfg` predicate holds, treating them as undeclared otherwise.
+//! - `#[obake(scheme = ...)]`: declares versions as integers (`integer`), calendar versions
+//!   (`calver`) or in a scheme of your own, rather than as semantic version numbers.
+//! - `#[obake(public_enum)]`: documents the version-tagged enum and names its variants after
+//!   their versions (e.g., `VersionedFoo::V0_1_0`), for matching on as a stable API.
+//! - `#[obake(no_macro)]`: skips declaring the `Foo!` macro for a data-structure, whose versions
+//!   can instead be named with `obake::version_of!(Foo, "0.1.0")` from any module.
+//! - `#[obake(stable_hash)]`: hashes values of any version as the latest version they migrate
+//!   to, so that stores holding mixed versions can deduplicate them without migrating them.
+//! - `#[obake(rules(...))]`: checks policies on how a data-structure may evolve (e.g., no field
+//!   removed without a major bump), failing compilation or warning when they're broken.
+//! - `#[obake(transparent)]`: lays out (and encodes) versions with a single field as that field,
+//!   preserving the wire format of versions from before a bare value grew into a structure.
+//! - `#[obake(lockfile = "obake.lock")]`: records the schema of each version in a lockfile,
+//!   failing compilation if a historical version changes without the lockfile being
+//!   regenerated (with `OBAKE_UPDATE_LOCKFILE=1`).
+//! - `#[obake(no_alloc)]`: rejects any attribute whose generated code would require `alloc`,
+//!   optionally bounding the size of the version-tagged enum at compile time.
+//! - `#[obake(boxed)]`: boxes each version held by the version-tagged enum, so that its size
+//!   doesn't grow with the largest version.
+//!     - Note: requires the feature `alloc`.
+//! - `#[obake(test_values("0.1.0", path))]`: names a function returning example values of a
+//!   version, shared by a generated migration test, benchmarks, snapshots, fuzz seeds and the
+//!   documentation of the version.
+//!     - Note: requires the feature `alloc`.
+//! - `#[obake(defmt)]`: formats the version of the version-tagged enum with `defmt`, as an
+//!   interned string, for logging from embedded targets without allocating.
+//!     - Note: requires the feature `defmt`.
+//! - `#[obake(secret)]`: zeroizes the copies of a field (e.g., a password) held by each version
+//!   when they're dropped, such as by migrations.
+//!     - Note: requires the feature `zeroize` (and `alloc` for secrets such as `String`).
+//! - `#[obake(debug_expansion = "...")]`: writes the formatted expansion of the data-structure
+//!   to a file, for debugging its interactions with other macros.
+//!     - Note: requires the feature `debug-expansion`.
 //! - `#[obake(derive(...))]`: allows derive attributes to be applied to generated `enum`s.
+//! - `#[obake(derive_bounds(...))]`: adds where-bounds to generated `enum`s, which derives
+//!   applied to them carry over to their implementations.
 //! - `#[obake(serde(...))]`: allows [`serde`](https://serde.rs) attributes to be applied to
 //!   generated `enum`s.
 //!     - Note: requires the feature `serde`.
+//! - `#[obake(serde(tag_as_index))]`: serializes generated `enum`s with the index of their
+//!   version rather than its name, for compact binary formats (e.g., `bincode` or `postcard`).
+//!     - Note: requires the feature `serde`.
+//! - `#[obake(serde(tag_case = "kebab"))]`: tags versions in a case matching the conventions of
+//!   existing data (e.g., `foo-v0-1-0` or `FOO_V0_1_0`), rather than as `Foo_v0_1_0`.
+//!     - Note: requires the feature `serde`.
+//! - `#[obake(serde(deny_unknown_on_latest))]`: rejects unknown fields when deserializing the
+//!   latest version, while tolerating the since-removed fields of older payloads (with
+//!   per-version overrides, e.g., `#[obake(serde(allow_unknown_fields("0.3.0")))]`).
+//!     - Note: requires the feature `serde`.
+//! - `#[obake(on_unrecognized = ...)]`: invokes a function with payloads that can't be decoded
+//!   as any declared version, to attempt recovery or record them.
+//!     - Note: requires the feature `serde`.
+//! - `#[obake(external(...))]`: delegates the (de)serialization of fields whose types come from
+//!   other crates to a shim module per version (e.g., `chrono` in older versions and `time` in
+//!   newer ones), as in `#[obake(external("<0.2", serde_shim = chrono_shim))]`.
+//!     - Note: requires the feature `serde`.
+//! - `#[obake(display)]`: implements `Display` and `FromStr` for the version-tagged enum using
+//!   the canonical JSON envelope, for logging, piping through command-line tools and test
+//!   assertions.
+//!     - Note: requires the feature `json`.
+//! - `#[obake(json_upgrade)]`: migrates JSON payloads to the latest version without deserializing
+//!   them, renaming, dropping and adding fields as the versions do, for tools editing documents
+//!   they only partially model.
+//!     - Note: requires the feature `json`.
+//! - `#[obake(wasm)]`: generates [`wasm-bindgen`](https://rustwasm.github.io/wasm-bindgen/)
+//!   bindings for upgrading JSON payloads in the browser.
+//!     - Note: requires the feature `wasm`.
+//! - `#[obake(ts)]`: generates [`ts-rs`](https://docs.rs/ts-rs) TypeScript definitions for
+//!   every declared version and the version-tagged envelope.
+//!     - Note: requires the feature `ts-rs`.
+//! - `#[obake(specta)]`: derives [`specta`](https://docs.rs/specta) types for every declared
+//!   version and the version-tagged enum (e.g., for use in Tauri commands).
+//!     - Note: requires the feature `specta`.
+//! - `#[obake(pyo3)]`: generates [`pyo3`](https://pyo3.rs) classes for every declared version
+//!   and an `upgrade` function for migrating Python data.
+//!     - Note: requires the feature `pyo3`.
+//! - `#[obake(abi_stable)]`: derives [`abi_stable`](https://docs.rs/abi_stable) `StableAbi`
+//!   for every declared version and generates an FFI-safe table of upgrade functions.
+//!     - Note: requires the feature `abi_stable`.
+//! - `#[obake(uniffi)]`: generates [`UniFFI`](https://mozilla.github.io/uniffi-rs/) records
+//!   for every declared version and exports an upgrade function to Kotlin and Swift.
+//!     - Note: requires the feature `uniffi`.
+//! - `#[obake(utoipa)]`: generates [`utoipa`](https://docs.rs/utoipa) schemas for
+//!   every declared version and the version-tagged envelope.
+//!     - Note: requires the feature `utoipa`.
+//! - `#[obake(async_graphql)]`: generates [`async-graphql`](https://docs.rs/async-graphql)
+//!   objects for every declared version and a scalar accepting any version.
+//!     - Note: requires the feature `async-graphql`.
+//! - `#[obake(tabular)]`: lists the columns of every declared version of a `struct` (and
+//!   describes them as [Arrow](https://arrow.apache.org) schemas), for reading tabular files of
+//!   any version.
+//!     - Note: requires the feature `csv` or `arrow`.
+//! - `#[obake(idl)]`: describes every declared version for rendering as a Cap'n Proto or
+//!   FlatBuffers schema.
+//!     - Note: requires the feature `idl`.
+//! - `#[obake(fake)]`: derives [`fake`](https://docs.rs/fake) `Dummy` for every declared
+//!   version and the version-tagged enum, for generating random payloads of any version (e.g.,
+//!   to load-test migrations).
+//!     - Note: requires the feature `fake`.
+//! - `#[obake(register)]`: registers the data-structure with `obake::registry`, by its name or
+//!   the name given (e.g., `#[obake(register = "billing.Invoice")]`), for decoding payloads
+//!   given only that name and their version.
+//!     - Note: requires the feature `registry`.
+//!
+//! ## Runtime Support
+//!
+//! - `obake::actix`: an `actix-web` extractor accepting any declared version of a JSON request
+//!   body, and a responder downgrading responses to the version requested by the client.
+//!     - Note: requires the feature `actix-web`.
+//! - `obake::axum`: an `axum` extractor accepting any declared version of a JSON request body.
+//!     - Note: requires the feature `axum`.
+//! - `obake::bench`: benchmarks of decoding and migrating payloads of each declared version,
+//!   for use with a benchmarking framework such as `criterion`.
+//!     - Note: requires the features `alloc` and `serde`.
+//! - `obake::cli`: the engine of a command migrating files of any declared version (in any
+//!   enabled format) to the latest version, reporting the migrations they pass through, and
+//!   optionally backing up the files it replaces and writing them atomically.
+//!     - Note: requires at least one of the features `json`, `json5`, `ron`, `toml` or `yaml`.
+//! - `obake::codec`: decoding payloads of any declared version given its version number, and
+//!   framing payloads with their version by a pluggable `Envelope` (e.g., a legacy header),
+//!   with an `UnsupportedVersion` policy for payloads tagged with an undeclared version.
+//!     - Note: requires the features `alloc` and `serde` (and `json`, `ron`, `json5`, `toml` or
+//!       `yaml` for the corresponding codecs).
+//! - `obake::collections`: migrating maps (and `Vec`s) whose values were each written at a
+//!   different version, reporting the keys of values which fail to migrate (or deserialize).
+//!     - Note: requires the feature `alloc` (and `serde` for deserializing).
+//! - `obake::config`: loading configuration files of any declared version, detecting whether
+//!   they're written in JSON, JSON5, RON, TOML or YAML by their extension or content.
+//!     - Note: requires at least one of the features `json`, `json5`, `ron`, `toml` or `yaml`.
+//! - `obake::es`: upcasting of stored events for event-sourcing frameworks.
+//!     - Note: requires the features `alloc` and `serde`.
+//! - `obake::export`: exporting values in bulk at a historical version (with
+//!   `#[obake(downgrade)]`), encoded with any codec, reporting which values lost information.
+//!     - Note: requires the features `alloc` and `serde`.
+//! - `obake::graph`: the versions of a data-structure and the migrations, shortcuts and
+//!   downgrades between them, for counting migration hops or rendering as DOT (or JSON).
+//!     - Note: counting hops and rendering as DOT require the feature `alloc`, serializing
+//!       requires the feature `serde`.
+//! - `obake::graphql`: resolving GraphQL values of any declared version using
+//!   `#[obake(async_graphql)]` scalars.
+//!     - Note: requires the feature `async-graphql`.
+//! - `obake::http`: framework-agnostic negotiation of the version of an HTTP response.
+//!     - Note: requires the feature `alloc` (and `json` for encoding responses).
+//! - `obake::idl`: rendering Cap'n Proto and FlatBuffers schemas for every declared version
+//!   (e.g., from a build script).
+//!     - Note: requires the feature `idl`.
+//! - `obake::json`: decoding JSON payloads of any declared version, tagged or untagged, eagerly
+//!   or (with `RawVersioned`) on demand, and passing payloads of newer versions through untouched
+//!   (with `ForwardCompatible`), and migrating payloads without deserializing them (with
+//!   `upgrade_value`).
+//!     - Note: requires the feature `json`.
+//! - `obake::json5`: decoding hand-edited JSON5 configuration files of any declared version,
+//!   tagged or untagged.
+//!     - Note: requires the feature `json5`.
+//! - `obake::openapi`: OpenAPI schemas for every declared version using `#[obake(utoipa)]`.
+//!     - Note: requires the feature `utoipa`.
+//! - `obake::pin`: pinning the versions accepted from each tenant (or environment) to a maximum
+//!   version, migrating older data only as far as the pin.
+//! - `obake::planner`: migrating values of several data-structures nested in each other by
+//!   `#[obake(inherit)]`, those nested more deeply first, all or nothing.
+//!     - Note: requires the feature `alloc`.
+//! - `obake::python`: migrating Python data using `#[obake(pyo3)]` bindings.
+//!     - Note: requires the feature `pyo3`.
+//! - `obake::registry`: decoding payloads of data-structures marked `#[obake(register)]` given
+//!   only the name of their data-structure and their version as strings, for message brokers and
+//!   plugin hosts.
+//!     - Note: requires the feature `registry`.
+//! - `obake::ron`: decoding RON configuration files (or game data) of any declared version,
+//!   tagged, named after the type of their version or untagged.
+//!     - Note: requires the feature `ron`.
+//! - `obake::save`: save files framed with a magic number, version and checksum, migrated to
+//!   the latest version on load.
+//!     - Note: requires the features `std` and `serde`.
+//! - `obake::schema`: JSON Schemas for every declared version.
+//!     - Note: requires the feature `schemars`.
+//! - `obake::schema_registry`: publishing every declared version to a Confluent Schema
+//!   Registry.
+//!     - Note: requires the feature `schema-registry`.
+//! - `obake::scheme`: the schemes versions can be declared in (semantic version numbers,
+//!   integers, calendar versions or a scheme of your own), and comparing versions under them.
+//! - `obake::store`: key-value storage with upgrade-on-read, backed by `sled` or `redb`.
+//!     - Note: requires the features `alloc` and `serde` (and `sled` or `redb` for the
+//!       corresponding backends).
+//! - `obake::tabular`: detecting the version of CSV headers and Arrow (or Parquet) schemas, and
+//!   reading CSV rows of any declared version.
+//!     - Note: requires the feature `csv` or `arrow`.
+//! - `obake::telemetry`: counting the payloads decoded (and values migrated) at each version
+//!   with the `metrics` facade, to see how much traffic in old formats remains.
+//!     - Note: requires the feature `metrics`.
+//! - `obake::testing`: checking that the example values of each version migrate, and encoding
+//!   them at their own versions for snapshot tests or fuzzing seeds.
+//!     - Note: requires the feature `alloc` (and `serde` for encoding).
+//! - `obake::txn`: migrating several related values (e.g., documents sharing a schema upgrade)
+//!   together, returning all of them migrated or the error of every one which failed.
+//!     - Note: requires the feature `alloc`.
+//! - `obake::validate`: validating any declared version against the `validator` rules of that
+//!   version.
+//!     - Note: requires the feature `validator`.
+//! - `obake::watch`: live-reloading configuration files of any declared version, migrating,
+//!   validating and atomically swapping in each change, and notifying subscribers of it.
+//!     - Note: requires the feature `notify` and at least one of the features `json`, `json5`,
+//!       `ron`, `toml` or `yaml`.
+//!
+//! ## `no_std`
+//!
+//! Without the feature `std`, the error types of the runtime (e.g., [`StepError`]) only
+//! implement `Display`. On toolchains with `core::error::Error` (Rust 1.81 or later), the
+//! feature `error_in_core` implements it for each of them without requiring `std`, so that
+//! embedded targets can handle them as generic errors (e.g., walking their sources) as they
+//! would with `std`.
 //!
 //! ## Limitations
 //!
 //! - Cannot be applied to tuple `struct`s (or `enum` variants with unnamed fields).
-//! - Cannot be applied to items with generic parameters.
+//! - Cannot be applied to items with generic (or lifetime) parameters, so fields can't borrow
+//!   from the input they're deserialized from (e.g., with `#[serde(borrow)]`).
 
 #![no_std]
 #![forbid(unsafe_code)]
 #![deny(clippy::all, clippy::pedantic)]
 #![deny(missing_docs, unused_imports)]
 
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+// `std::error::Error` re-exports `core::error::Error` on toolchains which have it, so error types
+// implementing the latter are errors to `std` users too
+#[cfg(feature = "error_in_core")]
+use core::error;
+#[cfg(all(feature = "std", not(feature = "error_in_core")))]
+use std::error;
+
+#[cfg(feature = "actix-web")]
+pub mod actix;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub mod bench;
+
+#[cfg(any(
+    feature = "json",
+    feature = "json5",
+    feature = "ron",
+    feature = "toml",
+    feature = "yaml"
+))]
+pub mod cli;
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub mod codec;
+
+#[cfg(feature = "alloc")]
+pub mod collections;
+
+#[cfg(any(
+    feature = "json",
+    feature = "json5",
+    feature = "ron",
+    feature = "toml",
+    feature = "yaml"
+))]
+pub mod config;
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub mod es;
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub mod export;
+
+pub mod graph;
+
+#[cfg(feature = "async-graphql")]
+pub mod graphql;
+
+#[cfg(feature = "alloc")]
+pub mod http;
+
+#[cfg(feature = "idl")]
+pub mod idl;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "json5")]
+pub mod json5;
+
+#[cfg(feature = "utoipa")]
+pub mod openapi;
+
+pub mod pin;
+
+#[cfg(feature = "alloc")]
+pub mod planner;
+
+#[cfg(feature = "pyo3")]
+pub mod python;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
+#[cfg(feature = "ron")]
+pub mod ron;
+
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod save;
+
+#[cfg(feature = "schemars")]
+pub mod schema;
+
+#[cfg(feature = "schema-registry")]
+pub mod schema_registry;
+
+pub mod scheme;
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub mod store;
+
+#[cfg(any(feature = "csv", feature = "arrow"))]
+pub mod tabular;
+
+#[cfg(feature = "metrics")]
+pub mod telemetry;
+
+#[cfg(feature = "alloc")]
+pub mod testing;
+
+#[cfg(feature = "alloc")]
+pub mod txn;
+
+#[cfg(feature = "validator")]
+pub mod validate;
+
+#[cfg(all(
+    feature = "notify",
+    any(
+        feature = "json",
+        feature = "json5",
+        feature = "ron",
+        feature = "toml",
+        feature = "yaml"
+    )
+))]
+pub mod watch;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "ts-rs")]
+#[doc(hidden)]
+pub use ts_rs;
+
+#[cfg(feature = "specta")]
+#[doc(hidden)]
+pub use specta;
+
+#[cfg(feature = "pyo3")]
+#[doc(hidden)]
+pub use pyo3;
+
+#[cfg(feature = "utoipa")]
+#[doc(hidden)]
+pub use utoipa;
+
+#[cfg(feature = "arrow")]
+#[doc(hidden)]
+pub use arrow_schema;
+
+#[cfg(feature = "zeroize")]
+#[doc(hidden)]
+pub use zeroize;
+
+#[cfg(feature = "fake")]
+#[doc(hidden)]
+pub use fake;
+
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub use inventory;
+
 /// The core macro of the library. Used to declare versioned data-structures.
 ///
+/// Every version converts into the latest version with `From`, so that a value of any concrete
+/// version can be upgraded with `.into()` without first wrapping it in [`AnyVersion`]. The
+/// version preceding the latest converts by its own migration, and older versions by upgrading
+/// through the version-tagged enum (unless the data-structure is marked
+/// `#[obake(fallible(...))]`, whose versions are upgraded with [`TryUpgrade`] instead).
+///
 /// ### Supported attributes:
 ///
 /// - `#[obake(version("x.y.z"))]` - Declares a possible version of the data-structure.
 /// - `#[obake(cfg(...))]` - Specifies a semantic version constraints for a particular field or
-///    variant.
+///   variant.
 ///   - `cfg` can contain any number of comma-separated semantic version constraints (e.g.,
 ///     `#[obake(cfg(">=0.3, <=0.1"))]`).
 ///   - A field or variant marked with a `cfg` attribute will only appear in a particular version
@@ -81,104 +508,2501 @@
 ///   - In the presence of multiple `cfg` attributes, any matching `cfg` will result in a match
 ///     (i.e., while comman-seperated constraints are treated as a conjunctively, multiple `cfg`
 ///     attributes are treated as a disjunctively).
+/// - `#[obake(cfg_attr("...", ...))]` - Apply an attribute to a particular field or variant
+///   only in the versions satisfying a semantic version constraint (e.g.,
+///   `#[obake(cfg_attr("<0.2", validate(range(max = 10))))]`), so that legacy versions can keep
+///   their legacy validation or serialization rules. Applied to the data-structure itself, the
+///   attribute is applied to the matching versions as a whole (e.g.,
+///   `#[obake(cfg_attr("<0.2", serde(rename_all = "camelCase")))]`), but not to the
+///   version-tagged enum.
 /// - `#[obake(derive(...))]` - Apply a derive to the version-tagged enum generated for the
-///    data-structre.
+///   data-structre.
+/// - `#[obake(derive_bounds(...))]` - Add a where-clause of the given predicates (e.g.,
+///   `#[obake(derive_bounds(for<'a> Foo_v0_1_0: Arbitrary<'a>))]`) to the version-tagged enum
+///   generated for the data-structure. Derives applied to the enum copy its where-clause into
+///   the implementations they generate, so this stands in for the bounds they would otherwise
+///   infer, much like `#[serde(bound = "...")]`.
 /// - `#[obake(serde(...))]` - Apply a [serde] attribute to the version-tagged enum generated
 ///   for the data-structre.
 ///   - Note: requires the feature `serde`.
+/// - `#[obake(serde(tag_as_index))]` - Implement `Serialize` and `Deserialize` for the
+///   version-tagged enum generated for the data-structure (in place of deriving them), encoding
+///   it as a tuple of the index of its version in [`Versioned::VERSIONS`] (as a `u32`) and the
+///   value of that version. Deserializing rejects indices of undeclared versions. The indices
+///   are stable so long as new versions are only ever declared after existing ones.
+///   - Note: requires the feature `serde`.
+/// - `#[obake(serde(tag_case = "..."))]` - Rename each variant of the version-tagged enum after
+///   the data-structure and its version in the given case, where the case is one of `snake`
+///   (e.g., `foo_v0_1_0`), `screaming_snake` (`FOO_V0_1_0`), `kebab` (`foo-v0-1-0`) or
+///   `screaming_kebab` (`FOO-V0-1-0`). The name of the data-structure is split into words at
+///   changes of case (e.g., `HttpConfig` becomes `http-config-v0-1-0`). Cannot be combined with
+///   `#[obake(serde(tag_as_index))]`.
+///   - Note: requires the feature `serde`.
+/// - `#[obake(serde(deny_unknown_on_latest))]` - Apply `#[serde(deny_unknown_fields)]` to the
+///   latest version only, so that new payloads are validated strictly while old payloads, which
+///   often still contain fields removed since, are tolerated. The versions matching a requirement
+///   can be held to it with `#[obake(serde(deny_unknown_fields("...")))]`, or exempted from it
+///   (overriding both) with `#[obake(serde(allow_unknown_fields("...")))]`.
+///   - Note: requires the feature `serde`.
+/// - `#[obake(on_unrecognized = path)]` - Implement [`OnUnrecognized`] for the data-structure,
+///   invoking the function `path` (of type `fn(&[u8]) -> Option<AnyVersion<Self>>`) with the raw
+///   payload whenever it can't be decoded as any declared version (see
+///   `obake::json::from_slice_or_recover`).
+///   - Note: requires the feature `serde`.
+/// - `#[obake(external(serde_shim = path))]` - Apply `#[serde(with = "path")]` to a field in
+///   every version (or, as `#[obake(external("req", serde_shim = path))]`, only in those
+///   matching the requirement `req`), so that a type from another crate (which may not
+///   implement `serde`'s traits, or whose encoding changed between releases) is (de)serialized
+///   by the module `path`. At most one shim may match each version, and fields without a
+///   matching shim are (de)serialized as usual.
+///   - Note: requires the feature `serde`.
+/// - `#[obake(display)]` - Implement `Display` for the version-tagged enum, writing it in the
+///   canonical `{ "version": ..., "data": ... }` envelope (see `obake::json::to_string`), and
+///   `FromStr`, accepting any declared version as `obake::json::from_slice` does. Every version
+///   must implement `serde`'s `Serialize` and `Deserialize`.
+///   - Note: requires the feature `json`.
+/// - `#[obake(json_upgrade)]` - Implement `obake::json::UpgradeValue` for a `struct`, listing
+///   the fields renamed (as deserialized by `serde`), dropped and added by each version, so that
+///   `obake::json::upgrade_value` can migrate payloads of any version without deserializing
+///   them. Added fields are set to their `Default` (or to their `#[obake(migrate_default = ...)]`),
+///   which must implement `serde`'s `Serialize`.
+///   - Note: requires the feature `json`.
+/// - `#[obake(downgrade)]` - Implement [`Downgrade`] for the version-tagged enum generated for
+///   the data-structure, converting the latest version into older versions using `From`
+///   implementations from each version to the one preceding it. With the feature `serde`, this
+///   also provides [`EncodeAs`] for serializing the latest version at any older version.
+/// - `#[obake(repr_c)]` - Mark every version of the data-structure `#[repr(C)]` and the
+///   version-tagged enum `#[repr(C, u32)]`, with a stable tag packing the major, minor and patch
+///   components of each version into 12, 10 and 10 bits respectively. Also generates a
+///   `#[repr(u32)]` tag enum (e.g., `FooVersionTag`) and an `extern "C"` upgrade entry point
+///   (e.g., `upgrade_foo`), exported under its name prefixed by the name of the crate declaring
+///   it (e.g., `my_crate_upgrade_foo`), so that crates declaring data-structures of the same
+///   name can be linked together. Data-structures of the same name in a single crate can't both
+///   be marked `#[obake(repr_c)]`, as their symbols would collide. Cannot be combined with
+///   `#[obake(fallible(...))]`, as the entry point can't report a failing migration.
+/// - `#[obake(dense_migrations)]` - Implement `From` for every pair of an older and a newer
+///   version of the data-structure (e.g., `From<Foo!["0.1.0"]> for Foo!["0.3.0"]`), composed
+///   from the `From` implementations between consecutive versions, so that old values can be
+///   migrated directly without going through the version-tagged enum.
+/// - `#[obake(alias_identical)]` - Declare versions whose fields (or variants) are
+///   token-identical to those of the preceding version as type aliases of it (e.g.,
+///   `type Foo_v0_2_1 = Foo_v0_2_0;`), so that no migration between them needs to be written.
+///   The version-tagged enum still has a variant for every version, but as the types are the
+///   same, generic code (e.g., `VersionOf::VERSION` or decoding with `obake::codec`) treats a
+///   value of an aliased version as the first of the identical versions.
+/// - `#[obake(tag = u8)]` - Number the declared versions of the data-structure from oldest to
+///   newest, giving the version-tagged enum those explicit discriminants (as a `#[repr(u8)]`
+///   enum) and implementing [`TagIndex`] for it. The tag can be any of `u8`, `u16` or `u32`.
+///   Cannot be combined with `#[obake(repr_c)]`.
+/// - `#[obake(note("0.2.0", "..."))]` - Add a note to the entry for a version in the history
+///   generated for the data-structure. The documentation of the data-structure (i.e., of the
+///   alias for its latest version) includes a table of the fields (or variants) present in each
+///   version (see [`Coverage`]) and a history listing those added and removed by each version.
+///   Renaming a field appears in the history as its removal and the addition of a new field, so
+///   notes are useful for recording renames and changes in meaning.
+/// - `#[obake(released("0.2.0", "2023-06-01"))]` - Record the date (as `YYYY-MM-DD`) on which a
+///   version was released, shown alongside it in the generated history and listed by
+///   [`Releases`] (e.g., `Foo::released_at("0.2.0")`). Each declared version can be given at most
+///   one date, and newer versions can't be released before older ones.
+/// - `#[obake(deprecated("0.1.0", "..."))]` - Deprecate a version other than the latest, so that
+///   naming it with the `Foo!` macro (e.g., `Foo!["0.1.0"]`) raises a `deprecated` warning with
+///   the optional note. Migrations from a deprecated version still name it, and so need
+///   `#[allow(deprecated)]` until the version is removed.
+/// - `#[obake(stable("0.2.0"))]` - Mark a version other than the latest as stable, rather than
+///   frozen, in the [`StabilityMap`] implemented for the data-structure (e.g., a version still
+///   maintained alongside the latest). The latest version is always stable, versions marked
+///   `#[obake(deprecated(...))]` are deprecated (and can't also be stable), and the rest are
+///   frozen.
+/// - `#[obake(stability)]` - Head the documentation of each version with a badge giving its
+///   stability (in the manner of `#[doc(cfg(...))]`), tagged with a `data-obake-stability`
+///   attribute for tooling reading the generated documentation.
+/// - `#[obake(version_default("0.1.0", path))]` - Implement `Default` for a version by calling
+///   the function at `path`, dropping `Default` from the item's derives for that version only.
+///   Fields added by an `#[obake(auto_migrate(...))]` migration into that version (without an
+///   `#[obake(migrate_default = ...)]`) are filled in from its default.
+/// - `#[obake(assert_fields("0.1.0", [...]))]` - Pin the fields (or variants) of a version to
+///   exactly those listed (e.g., `#[obake(assert_fields("0.1.0", [bar, baz]))]`), in any order.
+///   Any change to the `cfg` attributes that adds a field to (or removes one from) that version
+///   is a compile error, as it would change how stored values of that version are decoded.
+/// - `#[obake(fallible("0.2.0", error = E))]` - Migrate to the versions matching the requirement
+///   with a `TryFrom` implementation (whose error converts `Into` `E`) rather than `From`, and
+///   implement [`TryUpgrade`] for the version-tagged representation, whose errors are
+///   [`StepError`]s naming the versions either side of the failing migration, and [`TryMigrate`]
+///   for it and every version. `Into` the latest version (as required by [`VersionTagged`]) and
+///   [`Migrate`] still panic if a migration fails, so `E` must implement `Display`, and
+///   [`Upgrade`] isn't implemented. The helpers upgrading decoded values (e.g., the extractors,
+///   stores, collections and configuration loaders) upgrade with [`TryUpgrade`] and return the
+///   failure as an error instead. Without a requirement, every migration is fallible. Can't be combined with `#[obake(dense_migrations)]`, or with
+///   `#[obake(auto_migrate)]` for the same versions.
+/// - `#[obake(auto_migrate)]` - Implement `From` between consecutive versions of a `struct`,
+///   moving each field shared by both versions into the newer one (converting `#[obake(inherit)]`
+///   fields with `Into`) and setting fields new to it with `Default::default()`. A field
+///   redeclared with a new type from some version (e.g., `#[obake(cfg("<0.3"))] x: u32` and
+///   `#[obake(cfg(">=0.3"))] x: u64`) is converted with `Into` instead. For an `enum`, each
+///   variant is migrated to the variant of the same name in the newer version, whose payload is
+///   converted with `Into` if the variant is redeclared in the same way (e.g., `X(u32)` becoming
+///   `X(u64)`), so that its `serde` tag is unchanged; variants removed by the newer version
+///   must be migrated by hand. Fields are only ever moved, so large fields (e.g., `Vec<u8>`)
+///   are never cloned at any step of a migration. Takes an optional semantic version
+///   constraint (e.g., `#[obake(auto_migrate("<0.3"))]`) restricting the versions migrated to,
+///   so that migrations to other versions can be written by hand.
+/// - `#[obake(migrate_default = ...)]` - Marks a field of a `struct` with
+///   `#[obake(auto_migrate)]` as being set to the given expression, rather than
+///   `Default::default()`, by generated migrations to versions adding it (e.g.,
+///   `#[obake(migrate_default = Box::new(NoopPlugin))]` for a `Box<dyn Plugin>` field). Takes an
+///   optional semantic version constraint (e.g., `#[obake(migrate_default("0.2", ...))]`)
+///   restricting the versions it applies to, with the first matching attribute being used.
+/// - `#[obake(dto_modules)]` - Declare a module for each version of the data-structure, named
+///   after the version (e.g., `v0_1_0`), re-exporting that version under the data-structure's
+///   own name (e.g., `v0_1_0::Foo` for `Foo!["0.1.0"]`). Modules are shared by every
+///   data-structure marked with `dto_modules` declared in the same [`versioned_items`] block (so
+///   `v0_1_0` holds version 0.1.0 of each of them), but data-structures declared separately
+///   can't share a module. Only private, `pub(crate)` and `pub` data-structures are supported.
+/// - `#[obake(auto_downgrade_subset)]` - Implement [`FromLatest`] for each version of a
+///   `struct` whose fields are all present in the latest version, moving each of its fields out
+///   of the latest version (rebuilding `#[obake(inherit)]` fields with [`FromLatest`] in turn,
+///   so their data-structures need this attribute too). Versions with fields the latest version
+///   lacks are skipped.
+/// - `#[obake(cfg_when(feature = "legacy", "<0.3"))]` - Compile the versions of the
+///   data-structure matching a semantic version constraint (their definitions, variants of the
+///   version-tagged enum and generated migrations) only when a `cfg` predicate holds. Otherwise,
+///   those versions are not declared at all, so decoding them fails as it would for any
+///   undeclared version (e.g., with `obake::json::Error::UnknownVersion`). Only the oldest
+///   versions can be excluded, so that every version compiled can still be migrated to the
+///   latest, and hand-written migrations from excluded versions need the same `#[cfg(...)]`.
+///   Cannot be combined with `#[obake(repr_c)]`, `#[obake(tag = ...)]`,
+///   `#[obake(alias_identical)]` or the attributes generating bindings for other libraries.
+/// - `#[obake(scheme = ...)]` - Declare the versions of the data-structure (and write version
+///   constraints) in a scheme other than semantic versioning: `integer` (e.g., `"3"`), `calver`
+///   (e.g., `"2024.01.15"`) or the path of a type implementing [`scheme::VersionScheme`], whose
+///   versions may be any strings declared from oldest to newest. Generated names are taken from
+///   the versions as written (e.g., `Foo_v3`). See [`scheme`] for the syntax of constraints.
+/// - `#[obake(public_enum)]` - Document the version-tagged enum generated for the
+///   data-structure (and every version), rather than hiding it, and name each of its variants
+///   after the version it holds (e.g., `VersionedFoo::V0_1_0(Foo_v0_1_0)`), so that matching on
+///   it directly is part of the stable API of the crate declaring it. Variants are held in the
+///   order versions are declared, which must be from oldest to newest. The names are given by
+///   `#[obake(public_enum(variants = "Version{version}"))]`, where `{version}` is the version as
+///   it appears in generated names (by default, `V{version}`). As the names of the variants
+///   change, so do those used by derives such as `serde`'s.
+/// - `#[obake(no_macro)]` - Don't declare the `macro_rules!` macro named after the
+///   data-structure (e.g., `Foo!["0.1.0"]`), such as when it would collide with another macro of
+///   the same name. Versions can still be named with [`version_of!`] (e.g.,
+///   `obake::version_of!(Foo, "0.1.0")`), which also works across modules.
+/// - `#[obake(stable_hash)]` - Implement [`StableHash`] for the version-tagged enum and the latest
+///   version, hashing older versions as the latest version they migrate to (so that an old and
+///   a new encoding of the same logical value hash identically). Every version must implement
+///   `Clone`, and the latest version `Hash`. Cannot be combined with `#[obake(skip)]`.
+/// - `#[obake(rules(...))]` - Check the data-structure's versions against policies on how it may
+///   evolve, grouped by whether breaking them fails compilation (`deny(...)`) or raises a
+///   warning (`warn(...)`, reported as the use of a deprecated item), e.g.,
+///   `#[obake(rules(deny(no_removal_without_major), warn(max_chain = 5)))]`. The rules are:
+///     - `no_removal_without_major` - A field (or variant) may only be removed by a breaking
+///       version bump under semantic versioning (e.g., `1.2.0` to `2.0.0`, or `0.1.3` to
+///       `0.2.0`).
+///     - `new_fields_have_defaults` - A field added after the first version must declare a
+///       default for the version it's added in, with `#[obake(migrate_default = ...)]` or
+///       `#[serde(default)]`.
+///     - `max_chain = N` - At most `N` migrations may separate the oldest and latest versions.
+/// - `#[obake(transparent)]` - Mark each version of a `struct` with exactly one field
+///   `#[repr(transparent)]`, and `#[serde(transparent)]` if it derives `Serialize` or
+///   `Deserialize`, so that it's laid out and encoded as its field alone. Versions with more (or
+///   no) fields are unaffected, so a value which began as a bare value (e.g., a `u16` port) keeps
+///   its wire format in the versions from before it grew other fields. Cannot be combined with
+///   `#[obake(repr_c)]`.
+/// - `#[obake(lockfile = "obake.lock")]` - Record the schema of each version (the name and type
+///   of each field, or the name and payload of each variant, along with a fingerprint of them)
+///   in the given lockfile, relative to the manifest directory of the crate being compiled, and
+///   fail to compile if any version but the latest no longer matches its recorded schema,
+///   guarding frozen formats against accidental edits. The lockfile is created (and new versions
+///   recorded in it) as the data-structure is expanded, so should be checked in. After
+///   intentionally changing a recorded version, rebuild with the environment variable
+///   `OBAKE_UPDATE_LOCKFILE` set to regenerate it (e.g., `OBAKE_UPDATE_LOCKFILE=1 cargo check`,
+///   after touching the file declaring the data-structure, as changes to the environment don't
+///   trigger recompilation). The versions are recorded under the name of the data-structure,
+///   which can't be qualified by its module when expanded, so data-structures of the same name
+///   (e.g., in different modules) sharing a lockfile must each be given a distinct key with
+///   `#[obake(lockfile("obake.lock", key = "config::Settings"))]`, or they'd overwrite each
+///   other's entries.
+/// - `#[obake(no_alloc)]` - Guarantee that nothing generated for the data-structure requires
+///   `alloc`, rejecting attributes such as `#[obake(boxed)]` or those generating bindings for
+///   other libraries, and implement [`NoAlloc`] for it. Inherited fields must be of
+///   data-structures marked `#[obake(no_alloc)]` too. With `#[obake(no_alloc(max_size = 64))]`,
+///   compilation also fails if the version-tagged enum is larger than the given number of bytes.
+///   See [`assert_no_alloc`] for asserting the guarantee from elsewhere (e.g., in CI).
+/// - `#[obake(boxed)]` - Store each version in the version-tagged enum generated for the
+///   data-structure behind a `Box` (e.g., `VersionedFoo::Foo_v0_1_0(Box<Foo_v0_1_0>)`), so that
+///   `AnyVersion<Foo>` stays small however large its versions are. Cannot be combined with
+///   `#[obake(repr_c)]`.
+///   - Note: requires the feature `alloc`.
+/// - `#[obake(test_values("0.1.0", path))]` - Implement [`TestValues`] for the data-structure,
+///   gathering the values returned by the function at `path` (e.g., a `Vec<Foo!["0.1.0"]>`) for
+///   each version given one, and link the function from the documentation of its version. A
+///   `#[test]` migrating every value to the latest version is also generated, in a `#[cfg(test)]`
+///   module named after the data-structure (e.g., `http_config_test_values::migrate` for
+///   `HTTPConfig`), so the data-structure must be declared at module level. As the [`TestValues`]
+///   implementation is compiled in every build, the functions must be too (i.e., not
+///   `#[cfg(test)]`), though they may live behind a feature enabled by tests and benchmarks.
+///   - Note: requires the feature `alloc`.
+/// - `#[obake(debug_expansion = "target/obake")]` - Write the code generated for the
+///   data-structure, formatted, to a file named after it in the given directory (e.g.,
+///   `target/obake/Foo.rs`), relative to the manifest directory of the crate being compiled.
+///   The file is rewritten whenever the data-structure is expanded.
+///   - Note: requires the feature `debug-expansion`.
+/// - `#[obake(wasm)]` - Generate [wasm-bindgen] bindings for upgrading JSON payloads of any
+///   declared version (see `obake::wasm`).
+///   - Note: requires the feature `wasm`.
+/// - `#[obake(ts)]` - Derive [ts-rs] `TS` for every version of the data-structure (exported as
+///   e.g. `FooV0_1_0`) and for the version-tagged enum (exported as `VersionedFoo`, in the
+///   canonical `{ "version": ..., "data": ... }` form used by `obake::json`).
+///   - Note: requires the feature `ts-rs`.
+/// - `#[obake(specta)]` - Derive [specta] `Type` for every version of the data-structure
+///   (named as e.g. `FooV0_1_0`) and for the version-tagged enum (named as `VersionedFoo`),
+///   following any serde attributes so that `AnyVersion<Foo>` can be used in Tauri commands.
+///   - Note: requires the feature `specta`.
+/// - `#[obake(pyo3)]` - Generate [pyo3] classes for every version of the data-structure and a
+///   function registering them, along with an `upgrade` function, with a Python module (see
+///   `obake::python`).
+///   - Note: requires the feature `pyo3`.
+/// - `#[obake(abi_stable)]` - Implies `#[obake(repr_c)]`, derives [abi_stable] `StableAbi` for
+///   every version of the data-structure and the version-tagged enum, and generates an
+///   FFI-safe table of functions migrating each version to the latest version (e.g.,
+///   `FooUpgradeTable::TABLE`). The crate using `#[obake(abi_stable)]` must depend on
+///   [abi_stable].
+///   - Note: requires the feature `abi_stable`.
+/// - `#[obake(uniffi)]` - Derive [UniFFI] `Record` (or `Enum`) for every version of the
+///   data-structure and `Enum` for the version-tagged enum, and export an upgrade function
+///   (e.g., `upgrade_foo`) migrating any declared version to the latest version. The crate
+///   using `#[obake(uniffi)]` must depend on [UniFFI] and call `uniffi::setup_scaffolding!()`.
+///   Cannot be combined with `#[obake(repr_c)]`.
+///   - Note: requires the feature `uniffi`.
+/// - `#[obake(utoipa)]` - Derive [utoipa] `ToSchema` for every version of the data-structure
+///   (named as e.g. `FooV0_1_0`) and implement it for the version-tagged enum, describing the
+///   canonical JSON envelope as a `oneOf` discriminated by `version` (see `obake::openapi`).
+///   The crate using `#[obake(utoipa)]` must depend on [utoipa].
+///   - Note: requires the feature `utoipa`.
+/// - `#[obake(async_graphql)]` - Derive [async-graphql] `SimpleObject` and `InputObject` for
+///   every version of a `struct` (named as e.g. `FooV0_1_0` and `FooV0_1_0Input`), and
+///   generate a scalar (e.g., `AnyFoo`) accepting any declared version and resolving it to the
+///   latest version (see `obake::graphql`). The crate using `#[obake(async_graphql)]` must
+///   depend on [async-graphql].
+///   - Note: requires the feature `async-graphql`.
+/// - `#[obake(tabular)]` - Implement `obake::tabular::Columns` for every version of a `struct`,
+///   listing its fields in order, and with the feature `arrow`, `obake::tabular::ArrowSchema`,
+///   describing them as an Arrow schema (see `obake::tabular`).
+///   - Note: requires the feature `csv` or `arrow`.
+/// - `#[obake(idl)]` - Implement `obake::idl::IdlType` for every version of the data-structure,
+///   so that it can be rendered as a standalone Cap'n Proto or FlatBuffers schema (see
+///   `obake::idl`).
+///   - Note: requires the feature `idl`.
+/// - `#[obake(defmt)]` - Implement [`FormatVersion`] for the version-tagged enum, so that its
+///   version can be logged compactly with [defmt] (see [`defmt_version`]). The version-tagged
+///   enum itself can be formatted with `#[obake(derive(defmt::Format))]`. The crate using
+///   `#[obake(defmt)]` must depend on [defmt].
+///   - Note: requires the feature `defmt`.
+/// - `#[obake(fake)]` - Derive [fake] `Dummy<Faker>` for every version of the data-structure
+///   and for the version-tagged enum, which picks one of the declared versions at random, so
+///   that `Faker.fake::<AnyVersion<Foo>>()` produces plausible historical payloads. Fields can
+///   be given realistic values with `fake`'s own `#[dummy(...)]` attributes (e.g.,
+///   `#[dummy(faker = "1..100")]`), which are applied to every version.
+///   - Note: requires the feature `fake`.
+/// - `#[obake(register)]` - Submit the data-structure to `obake::registry` (with [inventory]),
+///   by its name or by the name given with `#[obake(register = "billing.Invoice")]`, so that
+///   JSON payloads of any declared version can be decoded given only that name and their
+///   version, migrated to the latest version. Every version must implement `Deserialize`.
+///   Names must be unique within the program.
+///   - Note: requires the feature `registry`.
 /// - `#[obake(inherit)]` - Marks a field as having an inherited version (i.e., given a field of
 ///   type `Bar`, when marked with `inherit`, this field will be expanded to a field of type
-///   `Bar![{version}]` in every version).
+///   `Bar![{version}]` in every version). Can also be applied to the unnamed fields of `enum`
+///   variants (e.g., `Settings(#[obake(inherit)] NetConfig)`).
+/// - `#[obake(since("version"))]` and `#[obake(until("version"))]` - Mark a field with
+///   `#[obake(inherit)]` as existing only from (and including) or up to (and including) the named
+///   version of the data-structure, for nested data-structures introduced (or retired) part way
+///   through its history. In the versions outside of that range, the field is wrapped in
+///   `Option`, holding the version of the nested data-structure it was introduced in (or retired
+///   after). With `#[obake(auto_migrate)]`, migrating into the range unwraps the field (falling
+///   back to `#[obake(migrate_default = ...)]`, or else `Default::default()`, for `None`), and
+///   migrating out of it wraps the field in `Some`.
+/// - `#[obake(skip)]` - Marks a field of a `struct` as runtime-only (e.g., a cache or a
+///   handle), so that it's left out of every version (and so the serialized form of each). The
+///   type named after the data-structure (e.g., `Foo`) is then a `struct` of its own, holding
+///   the fields of the latest version (e.g., `Foo_v0_2_0`) along with the skipped fields, which
+///   are set with `Default::default()` (or the expression given by
+///   `#[obake(skip = Cache::new())]`) whenever a value is migrated to it. Migrations to the
+///   latest version are written without the skipped fields. Cannot be combined with versioning
+///   attributes on the same field, with `#[obake(repr_c)]`, or with the attributes generating
+///   bindings for other libraries.
+/// - `#[obake(getter)]` - Generates a method on the version-tagged enum, named after a field of
+///   a `struct` and with the field's visibility, returning `Some` reference to the field if the
+///   version held has it, and `None` otherwise (e.g., `fn bar(&self) -> Option<&u32>`). Only
+///   one declaration of a field may be marked, and versions declaring the field with a
+///   different type return `None`. Cannot be combined with `#[obake(inherit)]` or
+///   `#[obake(skip)]`.
+/// - `#[obake(secret)]` - Marks a field as holding a secret (e.g., a password), declaring it as
+///   [zeroize] `Zeroizing<T>` in every version, so that the copy held by each version is
+///   zeroized when that version is dropped (e.g., once migrated to the next version), rather
+///   than left in memory. Values are moved (not cloned) between versions, but bytes held inline
+///   by the field (e.g., `[u8; 32]`) can still be copied by moves, so secrets are best held on
+///   the heap (e.g., `String` or `Vec<u8>`). Cannot be combined with `#[obake(inherit)]`.
+///   - Note: requires the feature `zeroize` (and `alloc` for secrets such as `String`).
 ///
 /// [serde]: https://serde.rs
+/// [wasm-bindgen]: https://rustwasm.github.io/wasm-bindgen/
+/// [ts-rs]: https://docs.rs/ts-rs
+/// [specta]: https://docs.rs/specta
+/// [pyo3]: https://pyo3.rs
+/// [abi_stable]: https://docs.rs/abi_stable
+/// [UniFFI]: https://mozilla.github.io/uniffi-rs/
+/// [utoipa]: https://docs.rs/utoipa
+/// [async-graphql]: https://docs.rs/async-graphql
+/// [zeroize]: https://docs.rs/zeroize
+/// [defmt]: https://defmt.ferrous-systems.com
+/// [fake]: https://docs.rs/fake
+/// [inventory]: https://docs.rs/inventory
 // TODO(@doctorn) document generated types and trait implementations
 pub use obake_macros::versioned;
 
-/// Automatically implemented for the latest version of a versioned data-structure.
+/// Declare several versioned data-structures at once, sharing attributes between them.
 ///
-/// ## Note
+/// Inner attributes at the top of the block (e.g., `#![obake(version("0.1.0"))]`) are applied
+/// to every data-structure declared in the block, before its own attributes. Each
+/// data-structure is otherwise declared exactly as with [`versioned`], and supports the same
+/// attributes.
 ///
-/// Not intended to be hand-implemented, use [`versioned`] to derive it.
-pub trait Versioned: Sized {
-    /// The associated type, `Versioned`, points to the version-tagged representation of this
-    /// data-structure.
-    type Versioned: VersionTagged<Self>;
-}
+/// ```
+/// obake::versioned_items! {
+///     #![obake(version("0.1.0"))]
+///     #![obake(version("0.2.0"))]
+///     #![derive(Debug, PartialEq)]
+///
+///     struct Foo {
+///         #[obake(cfg(">=0.2"))]
+///         bar: Bar,
+///     }
+///
+///     #[obake(derive(Debug))]
+///     enum Bar {
+///         X,
+///         #[obake(cfg(">=0.2"))]
+///         Y,
+///     }
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(_: Foo!["0.1.0"]) -> Self {
+///         Self { bar: Bar::Y }
+///     }
+/// }
+///
+/// impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+///     fn from(_: Bar!["0.1.0"]) -> Self {
+///         Self::X
+///     }
+/// }
+///
+/// let foo: Foo = obake::AnyVersion::<Foo>::from(Foo_v0_1_0 {}).into();
+/// assert_eq!(foo, Foo { bar: Bar::Y });
+/// ```
+pub use obake_macros::versioned_items;
 
-/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
-/// data-structure.
+/// Name a particular version of a [`versioned`] data-structure, by a path to the
+/// data-structure and the version as declared (e.g., `obake::version_of!(foo::Foo, "0.1.0")`).
 ///
-/// ## Note
+/// Unlike the `Foo!` macro generated alongside each data-structure, this can name versions of
+/// data-structures in other modules, and can't collide with other macros. Versions named this
+/// way can be used anywhere their generated names could be, except in a `struct` expression.
 ///
-/// Not intended to be hand-implemented, use [`versioned`] to derive it.
-pub trait VersionTagged<T>: From<T> + Into<T> {
-    /// The semantic version number corresponding to the tag of a particular instance.
-    fn version_str(&self) -> &'static str;
+/// ```
+/// mod foo {
+///     #[obake::versioned]
+///     #[obake(version("0.1.0"))]
+///     #[obake(version("0.2.0"))]
+///     #[obake(no_macro)]
+///     #[derive(Debug, PartialEq)]
+///     pub struct Foo {
+///         #[obake(cfg(">=0.2"))]
+///         pub bar: u32,
+///     }
+/// }
+///
+/// impl From<obake::version_of!(foo::Foo, "0.1.0")> for obake::version_of!(foo::Foo, "0.2.0") {
+///     fn from(_: obake::version_of!(foo::Foo, "0.1.0")) -> Self {
+///         Self { bar: 42 }
+///     }
+/// }
+///
+/// let foo: foo::Foo = obake::AnyVersion::<foo::Foo>::from(foo::Foo_v0_1_0 {}).into();
+/// assert_eq!(foo, foo::Foo { bar: 42 });
+/// ```
+pub use obake_macros::version_of;
+
+/// A pattern matching any of a set of versions of a [`versioned`] data-structure held by its
+/// version-tagged enum, binding the fields present (with the same type) in every matched
+/// version, by reference, under their own names.
+///
+/// `obake::any_of!(Foo, ["0.1.0" | "0.2.0"] as legacy)` also binds `legacy` to a reference to
+/// the matched value, so that several versions can share a single arm (e.g., for handling every
+/// pre-1.0 format the same way) rather than repeating it for each version.
+///
+/// The pattern is built by the `Foo!` macro declared alongside the data-structure, which must be
+/// in scope (i.e., the data-structure can't be marked `#[obake(no_macro)]`). The versions of
+/// `enum`s, and of data-structures marked `#[obake(boxed)]`, have no fields bound.
+///
+/// ```
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("1.0.0"))]
+/// #[derive(Debug, PartialEq)]
+/// struct Foo {
+///     name: String,
+///     #[obake(cfg("<1.0"))]
+///     legacy_id: u32,
+///     #[obake(cfg("0.2.0"))]
+///     extra: bool,
+/// }
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { name: from.name, legacy_id: from.legacy_id, extra: false }
+/// #     }
+/// # }
+/// #
+/// # impl From<Foo!["0.2.0"]> for Foo!["1.0.0"] {
+/// #     fn from(from: Foo!["0.2.0"]) -> Self {
+/// #         Self { name: from.name }
+/// #     }
+/// # }
+///
+/// fn describe(value: obake::AnyVersion<Foo>) -> String {
+///     if let obake::any_of!(Foo, ["0.1.0" | "0.2.0"] as legacy) = value {
+///         use obake::VersionTagged;
+///         format!("{} #{} (from {})", name, legacy_id, legacy.version_str())
+///     } else {
+///         Foo::from(value).name
+///     }
+/// }
+///
+/// let old: Foo!["0.1.0"] = Foo_v0_1_0 { name: "old".into(), legacy_id: 7 };
+/// assert_eq!(describe(old.into()), "old #7 (from 0.1.0)");
+/// assert_eq!(describe(Foo { name: "new".into() }.into()), "new");
+/// ```
+#[macro_export]
+macro_rules! any_of {
+    ($item:ident, [$($version:tt)|+] as $binding:ident $(,)?) => {
+        $item!(@any_of $binding [] $($version)|+)
+    };
 }
 
-/// Short-hand for referring to the version-tagged representation of a [`versioned`] data-structre.
-pub type AnyVersion<T> = <T as Versioned>::Versioned;
+#[doc(hidden)]
+pub use obake_macros::__any_of_pattern;
 
-/// Automatically implemented for all declared versions of a versioned data-structure.
+/// Declare newer versions of a [`versioned`] data-structure declared elsewhere (e.g., a
+/// configuration type provided by another crate), as `#[obake::extend_versions(base = ...)]`.
+///
+/// The item is declared exactly as with [`versioned`], except that its versions continue those
+/// of `base`, migrating from the latest version of `base` (with a `From` implementation) into
+/// the oldest version of the item. Alongside the usual expansion, this generates an enum named
+/// after the item (e.g., `ExtendedFoo`) holding any version of either `base` or the item, which
+/// implements `Into` the latest version of the item (see [`Extends`]). Any derives declared with
+/// `#[obake(derive(...))]` are also applied to this enum.
+///
+/// ```
+/// use obake::VersionTagged;
+///
+/// mod upstream {
+///     #[obake::versioned]
+///     #[obake(version("0.1.0"))]
+///     #[obake(version("0.2.0"))]
+///     pub struct Config {
+///         #[obake(cfg(">=0.2"))]
+///         pub retries: u32,
+///     }
+///
+///     impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+///         fn from(_: Config!["0.1.0"]) -> Self {
+///             Self { retries: 3 }
+///         }
+///     }
+/// }
+///
+/// #[obake::extend_versions(base = upstream::Config)]
+/// #[obake(version("0.3.0"))]
+/// #[obake(version("0.4.0"))]
+/// #[derive(Debug, PartialEq)]
+/// struct Config {
+///     retries: u32,
+///     #[obake(cfg(">=0.4"))]
+///     timeout: u32,
+/// }
+///
+/// impl From<upstream::Config> for Config!["0.3.0"] {
+///     fn from(from: upstream::Config) -> Self {
+///         Self { retries: from.retries }
+///     }
+/// }
+///
+/// impl From<Config!["0.3.0"]> for Config!["0.4.0"] {
+///     fn from(from: Config!["0.3.0"]) -> Self {
+///         Self { retries: from.retries, timeout: 30 }
+///     }
+/// }
+///
+/// let x = ExtendedConfig::from(obake::AnyVersion::<upstream::Config>::from(
+///     upstream::Config_v0_1_0 {},
+/// ));
+/// assert_eq!(x.version_str(), "0.1.0");
+///
+/// let config: Config = x.into();
+/// assert_eq!(config, Config { retries: 3, timeout: 30 });
+/// ```
+pub use obake_macros::extend_versions;
+
+/// Declare a versioned data-structure using a derive, rather than [`versioned`].
+///
+/// The item deriving `Obake` is a definition of the data-structure, written exactly as with
+/// [`versioned`], from which every version is generated in the same way. As a derive can't
+/// replace the item it's applied to, the generated data-structure is named with an
+/// `#[obake(name = ...)]` attribute, and the definition itself is left in place (though it
+/// needn't ever be used).
+///
+/// As with any derive, attributes preceding `#[derive(obake::Obake)]` aren't visible to it,
+/// so only attributes following it are applied to the generated versions. This makes the order
+/// in which `obake` and other attribute macros (e.g., `#[pyclass]`) are expanded explicit.
+///
+/// ```
+/// #[allow(dead_code)]
+/// #[derive(obake::Obake)]
+/// #[obake(name = Foo)]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[derive(Debug, PartialEq)]
+/// struct FooDefinition {
+///     #[obake(cfg(">=0.2"))]
+///     bar: u32,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(_: Foo!["0.1.0"]) -> Self {
+///         Self { bar: 42 }
+///     }
+/// }
+///
+/// let foo: Foo = obake::AnyVersion::<Foo>::from(Foo_v0_1_0 {}).into();
+/// assert_eq!(foo, Foo { bar: 42 });
+/// ```
+pub use obake_macros::Obake;
+
+/// Automatically implemented for the latest version of a versioned data-structure.
 ///
 /// ## Note
 ///
 /// Not intended to be hand-implemented, use [`versioned`] to derive it.
-pub trait VersionOf<T>: Into<AnyVersion<T>>
-where
-    T: Versioned,
-{
-    /// The semantic version number of this version.
-    const VERSION: &'static str;
+pub trait Versioned: Sized {
+    /// The associated type, `Versioned`, points to the version-tagged representation of this
+    /// data-structure.
+    type Versioned: VersionTagged<Self>;
 
-    /// Trys to convert the version-tagged representation of `T` into this particular version.
+    /// The scheme the declared versions are written in (see [`scheme`]), which is
+    /// [`scheme::Semver`] unless set with `#[obake(scheme = ...)]`.
+    type Scheme: scheme::VersionScheme;
+
+    /// The semantic version numbers of all declared versions, ordered from oldest to newest.
     ///
-    /// ## Errors
+    /// ```
+    /// use obake::Versioned;
     ///
-    /// If `tagged.version_str() != Self::VERSION`, this conversion will fail and report a
-    /// corresponding [`VersionMismatch`].
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// struct Foo {}
+    /// #
+    /// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    /// #     fn from(_: Foo!["0.1.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
     ///
+    /// assert_eq!(Foo::VERSIONS, &["0.1.0", "0.2.0"]);
     /// ```
-    /// use obake::VersionOf;
+    const VERSIONS: &'static [&'static str];
+
+    /// The newest declared version also supported by a peer, given the versions the peer
+    /// supports (see [`negotiate`]).
+    ///
+    /// Paired with [`Downgrade`], this is enough to encode messages at a version both ends of a
+    /// connection understand.
+    ///
+    /// ```
+    /// use obake::{Downgrade, VersionTagged, Versioned};
     ///
     /// #[obake::versioned]
     /// #[obake(version("0.1.0"))]
     /// #[obake(version("0.2.0"))]
-    /// # #[derive(PartialEq, Eq, Debug)]
+    /// #[obake(version("0.3.0"))]
+    /// #[obake(downgrade)]
     /// struct Foo {}
-    ///
+    /// #
     /// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
     /// #     fn from(_: Foo!["0.1.0"]) -> Self {
     /// #         Self {}
     /// #     }
     /// # }
+    /// #
+    /// # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    /// #     fn from(_: Foo!["0.2.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
+    /// #
+    /// # impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    /// #     fn from(_: Foo!["0.3.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
+    /// #
+    /// # impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    /// #     fn from(_: Foo!["0.2.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
     ///
-    /// let x: obake::AnyVersion<Foo> = (Foo {}).into();
-    /// assert_eq!(
-    ///     <Foo!["0.1.0"]>::try_from_versioned(x),
-    ///     Err(obake::VersionMismatch {
-    ///         expected: "0.1.0",
-    ///         found: "0.2.0",
-    ///     }),
-    /// );
+    /// let version = Foo::negotiate_with(&["0.1.0", "0.2.0", "1.0.0"]).unwrap();
+    /// assert_eq!(version, "0.2.0");
     ///
-    /// let x: obake::AnyVersion<Foo> = (Foo {}).into();
-    /// assert_eq!(
-    ///     <Foo!["0.2.0"]>::try_from_versioned(x),
-    ///     Ok(Foo {}),
-    /// );
+    /// let message = obake::AnyVersion::<Foo>::downgrade(Foo {}, version).unwrap();
+    /// assert_eq!(message.version_str(), "0.2.0");
+    ///
+    /// assert_eq!(Foo::negotiate_with(&["1.0.0"]), None);
     /// ```
-    fn try_from_versioned(tagged: AnyVersion<T>) -> Result<Self, VersionMismatch>;
+    #[must_use]
+    fn negotiate_with(peer_versions: &[&str]) -> Option<&'static str> {
+        negotiate(Self::VERSIONS, peer_versions)
+    }
 }
 
-/// A struct representing a mismatch of versions.
+/// The newest of `ours` also present in `theirs`, or `None` if there is no version in common.
 ///
-/// Such a mismatch can occur when trying to convert a version-tagged representation of a piece
-/// of data into a particular version.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub struct VersionMismatch {
-    /// The expected version.
-    pub expected: &'static str,
-    /// The version found.
-    pub found: &'static str,
+/// `ours` is expected to be ordered from oldest to newest, as in [`Versioned::VERSIONS`], while
+/// `theirs` may be in any order.
+///
+/// ```
+/// assert_eq!(
+///     obake::negotiate(&["0.1.0", "0.2.0", "0.3.0"], &["0.3.1", "0.2.0", "0.1.0"]),
+///     Some("0.2.0"),
+/// );
+/// assert_eq!(obake::negotiate(&["0.1.0"], &["0.2.0"]), None);
+/// ```
+#[must_use]
+pub fn negotiate<'a>(ours: &[&'a str], theirs: &[&str]) -> Option<&'a str> {
+    ours.iter()
+        .rev()
+        .copied()
+        .find(|version| theirs.contains(version))
+}
+
+/// Automatically implemented for all [`versioned`] data-structures, recording which of their
+/// fields (or variants) are present in which declared versions.
+///
+/// The same information is rendered as a table in the documentation of the data-structure.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::Coverage;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// struct Foo {
+///     bar: u32,
+///     #[obake(cfg(">=0.2"))]
+///     baz: u32,
+/// }
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { bar: from.bar, baz: 0 }
+/// #     }
+/// # }
+///
+/// assert_eq!(
+///     Foo::COVERAGE,
+///     &[("bar", &[true, true][..]), ("baz", &[false, true][..])],
+/// );
+/// ```
+pub trait Coverage: Versioned {
+    /// Each field (or variant) declared in any version, paired with whether it is present in
+    /// each declared version, in the order of [`Versioned::VERSIONS`].
+    const COVERAGE: &'static [(&'static str, &'static [bool])];
+}
+
+/// Automatically implemented for all [`versioned`] data-structures, recording the dates on which
+/// their versions were released (as set with `#[obake(released(...))]`).
+///
+/// The same dates appear in the history in the documentation of the data-structure.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::Releases;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("0.3.0"))]
+/// #[obake(released("0.1.0", "2023-01-10"))]
+/// #[obake(released("0.2.0", "2023-06-01"))]
+/// struct Foo {}
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+/// #     fn from(_: Foo!["0.2.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+///
+/// assert_eq!(Foo::released_at("0.2.0"), Some("2023-06-01"));
+/// assert_eq!(Foo::released_at("0.3.0"), None);
+/// ```
+pub trait Releases: Versioned {
+    /// Each declared version with a release date, paired with that date (as `YYYY-MM-DD`), in
+    /// the order of [`Versioned::VERSIONS`].
+    const RELEASES: &'static [(&'static str, &'static str)];
+
+    /// The release date (as `YYYY-MM-DD`) of the declared version whose semantic version number
+    /// is `version`, or `None` if it hasn't been given one (or hasn't been declared).
+    #[must_use]
+    fn released_at(version: &str) -> Option<&'static str> {
+        Self::RELEASES
+            .iter()
+            .find(|(released, _)| *released == version)
+            .map(|(_, date)| *date)
+    }
+}
+
+/// The stability of a version of a [`versioned`] data-structure, telling semver checkers (and
+/// readers) which versions may still change.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Stability {
+    /// The latest version, or a historical version marked `#[obake(stable(...))]`, which may
+    /// still evolve.
+    Stable,
+    /// A historical version, which must not change, as data written at it relies on its schema.
+    Frozen,
+    /// A historical version marked `#[obake(deprecated(...))]`, kept only to migrate data
+    /// written at it until it's removed.
+    Deprecated,
+}
+
+impl Stability {
+    /// The name of this stability (i.e., `stable`, `frozen` or `deprecated`).
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Frozen => "frozen",
+            Self::Deprecated => "deprecated",
+        }
+    }
+}
+
+impl core::fmt::Display for Stability {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Stability {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let index = match self {
+            Self::Stable => 0,
+            Self::Frozen => 1,
+            Self::Deprecated => 2,
+        };
+        serializer.serialize_unit_variant("Stability", index, self.as_str())
+    }
+}
+
+/// Automatically implemented for all [`versioned`] data-structures, mapping each declared
+/// version to its [`Stability`], for tooling (e.g., semver checkers) to treat frozen historical
+/// versions differently from the evolving latest version.
+///
+/// The latest version is stable, versions marked `#[obake(deprecated(...))]` are deprecated and
+/// the rest are frozen, unless marked `#[obake(stable(...))]`. With `#[obake(stability)]`, the
+/// documentation of each version is headed by its stability too.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{Stability, StabilityMap};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("0.3.0"))]
+/// #[obake(version("0.4.0"))]
+/// #[obake(deprecated("0.1.0"))]
+/// #[obake(stable("0.3.0"))]
+/// struct Foo {}
+/// #
+/// # #[allow(deprecated)]
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+/// #     fn from(_: Foo!["0.2.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// # impl From<Foo!["0.3.0"]> for Foo!["0.4.0"] {
+/// #     fn from(_: Foo!["0.3.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+///
+/// assert_eq!(Foo::stability("0.1.0"), Some(Stability::Deprecated));
+/// assert_eq!(Foo::stability("0.2.0"), Some(Stability::Frozen));
+/// assert_eq!(Foo::stability("0.3.0"), Some(Stability::Stable));
+/// assert_eq!(Foo::stability("0.4.0"), Some(Stability::Stable));
+/// ```
+pub trait StabilityMap: Versioned {
+    /// Each declared version paired with its stability, in the order of
+    /// [`Versioned::VERSIONS`].
+    const STABILITY: &'static [(&'static str, Stability)];
+
+    /// The stability of the declared version whose semantic version number is `version`, or
+    /// `None` if it hasn't been declared.
+    #[must_use]
+    fn stability(version: &str) -> Option<Stability> {
+        Self::STABILITY
+            .iter()
+            .find(|(declared, _)| *declared == version)
+            .map(|(_, stability)| *stability)
+    }
+}
+
+/// A data-structure inherited by a field (of any version) of a [`versioned`] data-structure,
+/// listed by [`Inherits`].
+#[derive(Copy, Clone, Debug)]
+pub struct Inherited {
+    /// The name of the inherited data-structure, as written in the field's type.
+    pub name: &'static str,
+    /// The [`TypeId`](core::any::TypeId) of the inherited data-structure (i.e., of its latest
+    /// version).
+    pub type_id: fn() -> core::any::TypeId,
+    /// The data-structures inherited by the inherited data-structure in turn.
+    pub inherited: &'static [Inherited],
+}
+
+/// Automatically implemented for all [`versioned`] data-structures, listing the data-structures
+/// nested in them by fields marked `#[obake(inherit)]`, e.g., for ordering the migrations of
+/// values of several data-structures so that nested data-structures migrate first (see
+/// `obake::planner`).
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::Inherits;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// struct Bar {}
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// struct Foo {
+///     #[obake(inherit)]
+///     bar: Bar,
+/// }
+///
+/// assert_eq!(Foo::INHERITED.len(), 1);
+/// assert_eq!(Foo::INHERITED[0].name, "Bar");
+/// assert_eq!((Bar::depth(), Foo::depth()), (0, 1));
+/// assert!(Foo::inherits::<Bar>() && !Bar::inherits::<Foo>());
+/// ```
+pub trait Inherits: Versioned {
+    /// Each data-structure inherited by a field of any version, once each, in the order their
+    /// fields are declared.
+    const INHERITED: &'static [Inherited];
+
+    /// How many levels of data-structures are nested in this one through inherited fields (or
+    /// `0` if it has none).
+    #[must_use]
+    fn depth() -> usize {
+        fn depth(inherited: &[Inherited]) -> usize {
+            inherited
+                .iter()
+                .map(|inherited| depth(inherited.inherited) + 1)
+                .max()
+                .unwrap_or_default()
+        }
+
+        depth(Self::INHERITED)
+    }
+
+    /// Whether `U` is nested in this data-structure through inherited fields, directly or by
+    /// the data-structures it inherits.
+    #[must_use]
+    fn inherits<U>() -> bool
+    where
+        U: 'static,
+    {
+        fn inherits(inherited: &[Inherited], type_id: core::any::TypeId) -> bool {
+            inherited.iter().any(|inherited| {
+                (inherited.type_id)() == type_id || inherits(inherited.inherited, type_id)
+            })
+        }
+
+        inherits(Self::INHERITED, core::any::TypeId::of::<U>())
+    }
+}
+
+/// Automatically implemented for every version of a [`versioned`] data-structure, listing the
+/// names of its fields (or variants), e.g., for checking payloads against the exact schema of a
+/// historical version without deserializing them.
+///
+/// Names are those deserialized by [serde], following any `rename`, `rename_all`, `skip` and
+/// `skip_deserializing` attributes (including those applied with `#[obake(cfg_attr(...))]`).
+/// Fields marked `#[serde(flatten)]` have no name of their own, so are not listed.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::FieldNames;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[derive(serde::Deserialize)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Foo {
+///     user_id: u32,
+///     #[obake(cfg(">=0.2"))]
+///     #[serde(rename = "name")]
+///     display_name: String,
+/// }
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { user_id: from.user_id, display_name: String::new() }
+/// #     }
+/// # }
+///
+/// assert_eq!(<Foo!["0.1.0"]>::FIELD_NAMES, &["userId"]);
+/// assert_eq!(<Foo!["0.2.0"]>::FIELD_NAMES, &["userId", "name"]);
+/// ```
+pub trait FieldNames {
+    /// The names of the fields (or variants) of this version, in declaration order.
+    const FIELD_NAMES: &'static [&'static str];
+}
+
+/// Automatically implemented for [`versioned`] data-structures marked
+/// `#[obake(test_values(...))]`, gathering the example values curated for each version, so that
+/// tests, benchmarks (see [`bench`]), snapshots and fuzz seeds (see [`testing`]) share them.
+///
+/// Each `#[obake(test_values("0.1.0", path))]` names a function returning values of that version
+/// (e.g., a `Vec<Foo!["0.1.0"]>`), which is also linked from the documentation of the version.
+/// A test migrating every value to the latest version is generated alongside.
+///
+/// The implementation calls each function outside of tests too (e.g., for benchmarks or from other
+/// crates), so the functions must not be `#[cfg(test)]`.
+///
+/// Requires the feature `alloc`.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{TestValues, VersionTagged};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(test_values("0.1.0", legacy_foos))]
+/// #[obake(test_values("0.2.0", foos))]
+/// #[derive(Debug, PartialEq)]
+/// struct Foo {
+///     #[obake(cfg(">=0.2"))]
+///     bar: u32,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(_: Foo!["0.1.0"]) -> Self {
+///         Self { bar: 0 }
+///     }
+/// }
+///
+/// fn legacy_foos() -> Vec<Foo!["0.1.0"]> {
+///     vec![Foo_v0_1_0 {}]
+/// }
+///
+/// fn foos() -> Vec<Foo!["0.2.0"]> {
+///     vec![Foo { bar: 0 }, Foo { bar: u32::MAX }]
+/// }
+///
+/// let versions: Vec<_> = Foo::test_values().iter().map(|value| value.version_str()).collect();
+/// assert_eq!(versions, ["0.1.0", "0.2.0", "0.2.0"]);
+/// ```
+#[cfg(feature = "alloc")]
+pub trait TestValues: Versioned {
+    /// The example values of every version given them, ordered from oldest version to newest.
+    fn test_values() -> alloc::vec::Vec<AnyVersion<Self>>;
+}
+
+/// Automatically implemented for all declared [`versioned`] data-structures, describing the
+/// conversions between their versions (see [`graph`]).
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::graph::{Edge, EdgeKind};
+/// use obake::Migrations;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// struct Foo {}
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+///
+/// let graph = Foo::migration_graph();
+///
+/// assert_eq!(graph.name, "Foo");
+/// assert_eq!(
+///     graph.edges,
+///     &[Edge { from: "0.1.0", to: "0.2.0", kind: EdgeKind::Upgrade }],
+/// );
+/// ```
+pub trait Migrations: Versioned {
+    /// The graph of declared versions and the conversions between them.
+    fn migration_graph() -> graph::MigrationGraph;
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
+/// data-structure.
+///
+/// The version-tagged encoding converts into the latest version with `Into`. For a
+/// data-structure marked `#[obake(fallible(...))]`, `Into` panics if a migration fails, so use
+/// [`TryUpgrade`] (or [`TryMigrate`]) to report the failure instead.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+pub trait VersionTagged<T>: From<T> + Into<T> {
+    /// The semantic version number corresponding to the tag of a particular instance.
+    fn version_str(&self) -> &'static str;
+
+    /// Migrate this instance to the latest version, then transform it with `f`.
+    ///
+    /// ```
+    /// use obake::VersionTagged;
+    ///
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// struct Foo {
+    ///     #[obake(cfg(">=0.2"))]
+    ///     bar: u32,
+    /// }
+    ///
+    /// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    ///     fn from(_: Foo!["0.1.0"]) -> Self {
+    ///         Self { bar: 42 }
+    ///     }
+    /// }
+    ///
+    /// let stored: Vec<obake::AnyVersion<Foo>> =
+    ///     vec![Foo_v0_1_0 {}.into(), Foo { bar: 7 }.into()];
+    ///
+    /// let mut outdated = 0;
+    /// let bars: Vec<u32> = stored
+    ///     .into_iter()
+    ///     .map(|x| {
+    ///         x.inspect_version(|version| outdated += usize::from(version != "0.2.0"))
+    ///             .map_latest(|foo| foo.bar)
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(bars, &[42, 7]);
+    /// assert_eq!(outdated, 1);
+    /// ```
+    fn map_latest<U, F>(self, f: F) -> U
+    where
+        F: FnOnce(T) -> U,
+    {
+        f(self.into())
+    }
+
+    /// Migrate this instance to the latest version, then transform it with the fallible `f`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `f` fails.
+    ///
+    /// ```
+    /// use obake::VersionTagged;
+    ///
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// struct Foo {
+    ///     #[obake(cfg(">=0.2"))]
+    ///     bar: u32,
+    /// }
+    ///
+    /// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    ///     fn from(_: Foo!["0.1.0"]) -> Self {
+    ///         Self { bar: 0 }
+    ///     }
+    /// }
+    ///
+    /// let positive = |foo: Foo| if foo.bar > 0 { Ok(foo.bar) } else { Err("zero") };
+    ///
+    /// let x: obake::AnyVersion<Foo> = Foo { bar: 7 }.into();
+    /// assert_eq!(x.and_then_latest(positive), Ok(7));
+    ///
+    /// let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {}.into();
+    /// assert_eq!(x.and_then_latest(positive), Err("zero"));
+    /// ```
+    fn and_then_latest<U, E, F>(self, f: F) -> Result<U, E>
+    where
+        F: FnOnce(T) -> Result<U, E>,
+    {
+        f(self.into())
+    }
+
+    /// Call `f` with the semantic version number of this instance, then return it unchanged
+    /// (e.g., for counting outdated values part way through a chain of combinators).
+    #[must_use]
+    fn inspect_version<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&'static str),
+    {
+        f(self.version_str());
+        self
+    }
+}
+
+/// Short-hand for referring to the version-tagged representation of a [`versioned`] data-structre.
+pub type AnyVersion<T> = <T as Versioned>::Versioned;
+
+/// Automatically implemented for all declared versions of a versioned data-structure.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+pub trait VersionOf<T>: Into<AnyVersion<T>>
+where
+    T: Versioned,
+{
+    /// The semantic version number of this version.
+    const VERSION: &'static str;
+
+    /// The position of this version among the declared versions, counting from zero (see
+    /// [`Dispatch::dispatch_index`]).
+    ///
+    /// Versions excluded by `#[obake(cfg_when(...))]` are counted too, so that the index of a
+    /// version (e.g., as written by a binary format tagging payloads with it) is the same
+    /// whichever versions are compiled. When none are excluded, this is the position of the
+    /// version in [`Versioned::VERSIONS`], but otherwise it's offset by the number of versions
+    /// excluded.
+    ///
+    /// ```
+    /// use obake::{VersionOf, Versioned};
+    ///
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// struct Foo {}
+    /// #
+    /// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    /// #     fn from(_: Foo!["0.1.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
+    ///
+    /// assert_eq!(<Foo!["0.1.0"]>::INDEX, 0);
+    /// assert_eq!(Foo::VERSIONS[<Foo!["0.2.0"]>::INDEX], "0.2.0");
+    /// ```
+    const INDEX: usize;
+
+    /// Trys to convert the version-tagged representation of `T` into this particular version.
+    ///
+    /// ## Errors
+    ///
+    /// If `tagged.version_str() != Self::VERSION`, this conversion will fail and report a
+    /// corresponding [`VersionMismatch`].
+    ///
+    /// ```
+    /// use obake::VersionOf;
+    ///
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// # #[derive(PartialEq, Eq, Debug)]
+    /// struct Foo {}
+    ///
+    /// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    /// #     fn from(_: Foo!["0.1.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
+    ///
+    /// let x: obake::AnyVersion<Foo> = (Foo {}).into();
+    /// assert_eq!(
+    ///     <Foo!["0.1.0"]>::try_from_versioned(x),
+    ///     Err(obake::VersionMismatch {
+    ///         expected: "0.1.0",
+    ///         found: "0.2.0",
+    ///     }),
+    /// );
+    ///
+    /// let x: obake::AnyVersion<Foo> = (Foo {}).into();
+    /// assert_eq!(
+    ///     <Foo!["0.2.0"]>::try_from_versioned(x),
+    ///     Ok(Foo {}),
+    /// );
+    /// ```
+    fn try_from_versioned(tagged: AnyVersion<T>) -> Result<Self, VersionMismatch>;
+}
+
+/// Automatically implemented for data-structures declared with [`extend_versions`], whose
+/// versions continue those of a [`versioned`] data-structure declared elsewhere.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`extend_versions`] to derive it.
+pub trait Extends: Versioned {
+    /// The data-structure whose versions are extended.
+    type Base: Versioned;
+
+    /// The version-tagged representation of any version of either `Base` or this data-structure.
+    type Extended: VersionTagged<Self> + From<AnyVersion<Self::Base>> + From<AnyVersion<Self>>;
+}
+
+/// Short-hand for referring to the representation of any version of a data-structure declared
+/// with [`extend_versions`], including those of the data-structure it extends.
+pub type AnyExtended<T> = <T as Extends>::Extended;
+
+/// A visitor over the declared versions of a [`versioned`] data-structure.
+///
+/// Visitors are driven by [`Dispatch`], which selects the version matching a runtime version
+/// string and hands it to the corresponding [`VisitVersion`] implementation.
+pub trait VersionVisitor<T>
+where
+    T: Versioned,
+{
+    /// The result of visiting a version.
+    type Output;
+}
+
+/// Implemented by [`VersionVisitor`]s able to handle the particular version `V` of `T`.
+pub trait VisitVersion<T, V>: VersionVisitor<T>
+where
+    T: Versioned,
+    V: VersionOf<T>,
+{
+    /// Visit the version `V`.
+    fn visit(self) -> Self::Output;
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
+/// data-structure for every [`VersionVisitor`] able to visit all of its declared versions.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{Dispatch, VersionOf, VersionVisitor, VisitVersion};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// struct Foo {}
+///
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// struct Echo;
+///
+/// impl VersionVisitor<Foo> for Echo {
+///     type Output = &'static str;
+/// }
+///
+/// impl<V: VersionOf<Foo>> VisitVersion<Foo, V> for Echo {
+///     fn visit(self) -> &'static str {
+///         V::VERSION
+///     }
+/// }
+///
+/// assert_eq!(obake::AnyVersion::<Foo>::dispatch("0.1.0", Echo), Some("0.1.0"));
+/// assert_eq!(obake::AnyVersion::<Foo>::dispatch("0.3.0", Echo), None);
+/// assert_eq!(obake::AnyVersion::<Foo>::dispatch_index(1, Echo), Some("0.2.0"));
+/// assert_eq!(obake::AnyVersion::<Foo>::dispatch_index(2, Echo), None);
+/// ```
+pub trait Dispatch<T, Vis>: VersionTagged<T>
+where
+    T: Versioned,
+    Vis: VersionVisitor<T>,
+{
+    /// Visit the declared version whose semantic version number is `version`, or return `None`
+    /// if no such version has been declared.
+    fn dispatch(version: &str, visitor: Vis) -> Option<Vis::Output>;
+
+    /// Visit the declared version at `index` (see [`VersionOf::INDEX`]), or return `None` if
+    /// `index` is out of bounds or the version there is excluded by `#[obake(cfg_when(...))]`.
+    ///
+    /// The generated implementation looks the version up in a table of functions, in constant
+    /// time, rather than comparing version numbers, for binary formats tagging payloads with an
+    /// integer. The default implementation looks the version up in [`Versioned::VERSIONS`].
+    fn dispatch_index(index: usize, visitor: Vis) -> Option<Vis::Output> {
+        Self::dispatch(T::VERSIONS.get(index)?, visitor)
+    }
+}
+
+/// Implemented by [`VersionVisitor`]s able to handle values of the particular version `V` of
+/// `T`.
+pub trait VisitValue<T, V>: VersionVisitor<T>
+where
+    T: Versioned,
+    V: VersionOf<T>,
+{
+    /// Visit a value of the version `V`.
+    fn visit_value(self, value: &V) -> Self::Output;
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
+/// data-structure for every [`VersionVisitor`] able to visit values of all of its declared
+/// versions.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{Accept, VersionOf, VersionVisitor, VisitValue};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// struct Foo {
+///     bar: u32,
+/// }
+///
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { bar: from.bar }
+/// #     }
+/// # }
+/// #
+/// struct Bar;
+///
+/// impl VersionVisitor<Foo> for Bar {
+///     type Output = u32;
+/// }
+///
+/// impl VisitValue<Foo, Foo!["0.1.0"]> for Bar {
+///     fn visit_value(self, value: &Foo!["0.1.0"]) -> u32 {
+///         value.bar
+///     }
+/// }
+///
+/// impl VisitValue<Foo, Foo!["0.2.0"]> for Bar {
+///     fn visit_value(self, value: &Foo!["0.2.0"]) -> u32 {
+///         value.bar
+///     }
+/// }
+///
+/// let x: obake::AnyVersion<Foo> = (Foo { bar: 42 }).into();
+/// assert_eq!(x.accept(Bar), 42);
+/// ```
+pub trait Accept<T, Vis>: VersionTagged<T>
+where
+    T: Versioned,
+    Vis: VersionVisitor<T>,
+{
+    /// Visit the value held by this instance.
+    fn accept(&self, visitor: Vis) -> Vis::Output;
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
+/// data-structure marked with `#[obake(downgrade)]`.
+///
+/// Downgrades are performed by converting the latest version into each preceding version in
+/// turn, so require `From` implementations from each declared version to the one before it.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{Downgrade, VersionTagged};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(downgrade)]
+/// struct Foo {}
+///
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+///     fn from(_: Foo!["0.2.0"]) -> Self {
+///         Self {}
+///     }
+/// }
+///
+/// let x = obake::AnyVersion::<Foo>::downgrade(Foo {}, "0.1.0").unwrap();
+/// assert_eq!(x.version_str(), "0.1.0");
+/// assert!(obake::AnyVersion::<Foo>::downgrade(Foo {}, "0.3.0").is_none());
+/// ```
+pub trait Downgrade<T>: VersionTagged<T>
+where
+    T: Versioned,
+{
+    /// Convert `latest` into the declared version of `T` whose semantic version number is
+    /// `version`, or return `None` if no such version has been declared.
+    fn downgrade(latest: T, version: &str) -> Option<Self>;
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
+/// data-structure, allowing partial upgrades (i.e., migrating a version-tagged value to a
+/// version other than the latest).
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{Upgrade, VersionTagged};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("0.3.0"))]
+/// struct Foo {}
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+/// #     fn from(_: Foo!["0.2.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+///
+/// let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {}.into();
+/// let y = x.upgrade("0.2.0").unwrap();
+/// assert_eq!(y.version_str(), "0.2.0");
+/// assert!(y.upgrade("0.1.0").is_none());
+/// ```
+pub trait Upgrade<T>: VersionTagged<T>
+where
+    T: Versioned,
+{
+    /// Migrate this instance to the declared version of `T` whose semantic version number is
+    /// `version`, or return `None` if no such version has been declared or it precedes the
+    /// version of this instance.
+    fn upgrade(self, version: &str) -> Option<Self>;
+
+    /// Migrate this instance to the latest version one migration at a time, passing the
+    /// [`Progress`] of each migration to `progress` once it completes (e.g., to drive a progress
+    /// bar while converting a large save file).
+    ///
+    /// Requires the feature `std`.
+    ///
+    /// ```
+    /// use obake::{Progress, Upgrade};
+    ///
+    /// #[obake::versioned]
+    /// #[obake(version("0.1.0"))]
+    /// #[obake(version("0.2.0"))]
+    /// #[obake(version("0.3.0"))]
+    /// #[derive(Debug, PartialEq)]
+    /// struct Foo {}
+    /// #
+    /// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    /// #     fn from(_: Foo!["0.1.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
+    /// #
+    /// # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    /// #     fn from(_: Foo!["0.2.0"]) -> Self {
+    /// #         Self {}
+    /// #     }
+    /// # }
+    ///
+    /// let mut hops = Vec::new();
+    /// let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {}.into();
+    /// let foo = x.upgrade_with_progress(|progress: Progress| {
+    ///     hops.push((progress.step, progress.steps, progress.from, progress.to));
+    /// });
+    ///
+    /// assert_eq!(foo, Foo {});
+    /// assert_eq!(hops, [(0, 2, "0.1.0", "0.2.0"), (1, 2, "0.2.0", "0.3.0")]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn upgrade_with_progress<F>(mut self, mut progress: F) -> T
+    where
+        Self: Sized,
+        F: FnMut(Progress),
+    {
+        let versions = T::VERSIONS;
+        let current = self.version_str();
+        let Some(start) = versions.iter().position(|version| *version == current) else {
+            return self.into();
+        };
+
+        let steps = versions.len() - start - 1;
+        for (step, to) in versions[start + 1..].iter().enumerate() {
+            let from = self.version_str();
+            let started = std::time::Instant::now();
+            self = match self.upgrade(to) {
+                Some(upgraded) => upgraded,
+                // Only undeclared (or preceding) versions fail to upgrade
+                None => unreachable!("version {} is declared after version {}", to, from),
+            };
+
+            progress(Progress {
+                step,
+                steps,
+                from,
+                to,
+                elapsed: started.elapsed(),
+            });
+        }
+
+        self.into()
+    }
+}
+
+/// The progress of a migration to the latest version, reported after each step by
+/// [`Upgrade::upgrade_with_progress`].
+///
+/// Requires the feature `std`.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Progress {
+    /// The index of the migration that completed, counting from zero.
+    pub step: usize,
+    /// The number of migrations needed to reach the latest version.
+    pub steps: usize,
+    /// The version migrated from.
+    pub from: &'static str,
+    /// The version migrated to.
+    pub to: &'static str,
+    /// The time the migration took.
+    pub elapsed: core::time::Duration,
+}
+
+/// Automatically implemented for the version-tagged representation of a [`versioned`]
+/// data-structure marked with `#[obake(fallible(...))]`, whose migrations to some versions are
+/// `TryFrom` implementations which can fail. Other data-structures implement it with an
+/// `Infallible` error, so they can be migrated alongside fallible ones.
+///
+/// Errors name the versions either side of the failing migration, so that failures part way
+/// through a long chain of migrations can be traced to the step responsible.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use obake::{StepError, TryUpgrade, VersionTagged};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("0.3.0"))]
+/// #[obake(fallible("0.2.0", error = &'static str))]
+/// #[obake(derive(Clone))]
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Foo {
+///     #[obake(cfg("0.1.0"))]
+///     address: String,
+///     #[obake(cfg(">=0.2"))]
+///     port: u16,
+/// }
+///
+/// impl TryFrom<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     type Error = &'static str;
+///
+///     fn try_from(from: Foo!["0.1.0"]) -> Result<Self, Self::Error> {
+///         let port = from.address.parse().map_err(|_| "invalid port")?;
+///         Ok(Self { port })
+///     }
+/// }
+///
+/// impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+///     fn from(from: Foo!["0.2.0"]) -> Self {
+///         Self { port: from.port }
+///     }
+/// }
+///
+/// let x: obake::AnyVersion<Foo> = Foo_v0_1_0 { address: "80".into() }.into();
+/// assert_eq!(x.try_upgrade(), Ok(Foo { port: 80 }));
+///
+/// let x: obake::AnyVersion<Foo> = Foo_v0_1_0 { address: "http".into() }.into();
+/// let error = x
+///     .try_upgrade_inspect(|_, before| assert_eq!(before.version_str(), "0.1.0"))
+///     .unwrap_err();
+/// assert_eq!(
+///     error,
+///     StepError {
+///         from: "0.1.0",
+///         to: "0.2.0",
+///         error: "invalid port",
+///     },
+/// );
+/// ```
+pub trait TryUpgrade<T>: VersionTagged<T>
+where
+    T: Versioned,
+{
+    /// The error produced by the fallible migrations, set with `#[obake(fallible(...))]`.
+    type Error;
+
+    /// Migrate this instance to the latest version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails with the error of the first migration to fail, along with the versions it migrates
+    /// between.
+    fn try_upgrade(self) -> Result<T, StepError<Self::Error>> {
+        self.try_upgrade_with(|_| None, |_, _| {})
+    }
+
+    /// Migrate this instance to the latest version, passing any error to `inspect` along with
+    /// the partially migrated value the failing migration was applied to.
+    ///
+    /// The partially migrated value is cloned before each fallible migration, so that it can
+    /// still be inspected once the migration consumes it.
+    ///
+    /// ## Errors
+    ///
+    /// Fails with the error of the first migration to fail, along with the versions it migrates
+    /// between.
+    fn try_upgrade_inspect<F>(self, inspect: F) -> Result<T, StepError<Self::Error>>
+    where
+        Self: Clone,
+        F: FnOnce(&StepError<Self::Error>, &Self),
+    {
+        self.try_upgrade_with(|before| Some(before.clone()), inspect)
+    }
+
+    /// Migrate this instance to the latest version, taking a `snapshot` of the partially
+    /// migrated value before each fallible migration to pass to `inspect` if it fails.
+    #[doc(hidden)]
+    fn try_upgrade_with<S, F>(self, snapshot: S, inspect: F) -> Result<T, StepError<Self::Error>>
+    where
+        S: FnMut(&Self) -> Option<Self>,
+        F: FnOnce(&StepError<Self::Error>, &Self);
+}
+
+/// An error produced by a failing migration (see [`TryUpgrade`]).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct StepError<E> {
+    /// The version migrated from.
+    pub from: &'static str,
+    /// The version migrated to.
+    pub to: &'static str,
+    /// The error produced by the migration.
+    pub error: E,
+}
+
+impl<E> core::fmt::Display for StepError<E>
+where
+    E: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "migration from version {} to version {} failed: {}",
+            self.from, self.to, self.error,
+        )
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl<E> crate::error::Error for StepError<E> where E: core::fmt::Debug + core::fmt::Display {}
+
+/// Migrate `tagged` to the latest version with [`TryUpgrade`], rendering the error of a failing
+/// migration with its `Display` implementation, so that helpers generic over data-structures
+/// with different error types can report it.
+#[cfg(feature = "alloc")]
+pub(crate) fn try_upgrade_rendered<T>(
+    tagged: AnyVersion<T>,
+) -> Result<T, StepError<alloc::string::String>>
+where
+    T: Versioned,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: core::fmt::Display,
+{
+    use alloc::string::ToString;
+
+    tagged.try_upgrade().map_err(|error| StepError {
+        from: error.from,
+        to: error.to,
+        error: error.error.to_string(),
+    })
+}
+
+/// Automatically implemented for the version-tagged representation of a [`versioned`]
+/// data-structure and every declared version of it, migrating any of them to the latest version.
+///
+/// This lets generic code accept anything which can be migrated to a particular data-structure
+/// (e.g., `impl Migrate<Latest = Foo>`), whichever data-structure that is.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::Migrate;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[derive(Debug, PartialEq)]
+/// struct Foo {
+///     #[obake(cfg(">=0.2"))]
+///     bar: u32,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(_: Foo!["0.1.0"]) -> Self {
+///         Self { bar: 42 }
+///     }
+/// }
+///
+/// fn store<T>(value: impl Migrate<Latest = T>) -> T {
+///     value.migrate()
+/// }
+///
+/// assert_eq!(store(Foo_v0_1_0 {}), Foo { bar: 42 });
+/// assert_eq!(store(obake::AnyVersion::<Foo>::from(Foo { bar: 1 })), Foo { bar: 1 });
+/// ```
+pub trait Migrate {
+    /// The latest version of the data-structure.
+    type Latest;
+
+    /// Migrate this instance to the latest version.
+    fn migrate(self) -> Self::Latest;
+}
+
+/// Automatically implemented for the version-tagged representation of a [`versioned`]
+/// data-structure and every declared version of it, migrating any of them to the latest version
+/// with [`TryUpgrade`].
+///
+/// Unlike [`Migrate`], which panics when a migration of a data-structure marked
+/// `#[obake(fallible(...))]` fails, this lets generic code accept anything which can be migrated
+/// to a particular data-structure and report a failing migration.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use obake::TryMigrate;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(fallible(error = &'static str))]
+/// #[derive(Debug, PartialEq)]
+/// struct Foo {
+///     #[obake(cfg("0.1.0"))]
+///     bar: i32,
+///     #[obake(cfg(">=0.2"))]
+///     bar: u32,
+/// }
+///
+/// impl TryFrom<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     type Error = &'static str;
+///
+///     fn try_from(from: Foo!["0.1.0"]) -> Result<Self, Self::Error> {
+///         let bar = u32::try_from(from.bar).map_err(|_| "negative")?;
+///         Ok(Self { bar })
+///     }
+/// }
+///
+/// fn store<T>(value: impl TryMigrate<Latest = T>) -> Option<T> {
+///     value.try_migrate().ok()
+/// }
+///
+/// assert_eq!(store(Foo_v0_1_0 { bar: 1 }), Some(Foo { bar: 1 }));
+/// assert_eq!(store(Foo_v0_1_0 { bar: -1 }), None);
+/// ```
+pub trait TryMigrate {
+    /// The latest version of the data-structure.
+    type Latest: Versioned;
+
+    /// The error produced by the fallible migrations (see [`TryUpgrade::Error`]).
+    type Error;
+
+    /// Migrate this instance to the latest version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails with the error of the first migration to fail, along with the versions it migrates
+    /// between.
+    fn try_migrate(self) -> Result<Self::Latest, StepError<Self::Error>>;
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a [`versioned`]
+/// data-structure marked with `#[obake(tag = ...)]`, numbering its declared versions from oldest
+/// to newest (i.e., by their position in [`Versioned::VERSIONS`]).
+///
+/// These indices are stable so long as new versions are only ever declared after existing ones,
+/// and make for a compact version tag in binary protocols.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::{Declared, TagIndex};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(tag = u8)]
+/// struct Foo {}
+///
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.0"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+/// #
+/// let x: obake::AnyVersion<Foo> = (Foo {}).into();
+/// assert_eq!(x.tag_index(), 1);
+///
+/// assert_eq!(obake::AnyVersion::<Foo>::version_of_tag_index(0), Some("0.1.0"));
+/// assert!(obake::AnyVersion::<Foo>::from_tag_index(1, Declared).is_some());
+/// assert!(obake::AnyVersion::<Foo>::from_tag_index(2, Declared).is_none());
+/// ```
+pub trait TagIndex<T>: VersionTagged<T>
+where
+    T: Versioned,
+{
+    /// The integer type of the tag.
+    type Index: Copy + core::convert::TryInto<usize>;
+
+    /// The tag of the version of this instance.
+    fn tag_index(&self) -> Self::Index;
+
+    /// The semantic version number of the declared version tagged with `index`, or `None` if
+    /// no such version has been declared.
+    #[must_use]
+    fn version_of_tag_index(index: Self::Index) -> Option<&'static str> {
+        T::VERSIONS
+            .get(core::convert::TryInto::try_into(index).ok()?)
+            .copied()
+    }
+
+    /// Visit the declared version tagged with `index`, or return `None` if no such version has
+    /// been declared.
+    fn from_tag_index<Vis>(index: Self::Index, visitor: Vis) -> Option<Vis::Output>
+    where
+        Self: Dispatch<T, Vis>,
+        Vis: VersionVisitor<T>,
+    {
+        Self::dispatch_index(core::convert::TryInto::try_into(index).ok()?, visitor)
+    }
+}
+
+/// A [`VersionVisitor`] serializing the value of a particular version with a [`serde`]
+/// `Serializer`, used by [`EncodeAs`].
+///
+/// Requires the feature `serde`.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug)]
+pub struct SerializeWith<S> {
+    serializer: S,
+}
+
+#[cfg(feature = "serde")]
+impl<S> SerializeWith<S> {
+    /// Create a visitor serializing values with `serializer`.
+    #[must_use]
+    pub fn new(serializer: S) -> Self {
+        Self { serializer }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, S> VersionVisitor<T> for SerializeWith<S>
+where
+    T: Versioned,
+    S: serde::Serializer,
+{
+    type Output = Result<S::Ok, S::Error>;
+}
+
+#[cfg(feature = "serde")]
+impl<T, V, S> VisitValue<T, V> for SerializeWith<S>
+where
+    T: Versioned,
+    V: VersionOf<T> + serde::Serialize,
+    S: serde::Serializer,
+{
+    fn visit_value(self, value: &V) -> Self::Output {
+        value.serialize(self.serializer)
+    }
+}
+
+/// The output of [`EncodeAs::encode_as`].
+///
+/// Requires the feature `serde`.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Encoded<O> {
+    /// The output of the serializer.
+    pub output: O,
+    /// Whether the downgrade lost information, i.e., whether upgrading the encoded version
+    /// again would not reproduce the original value.
+    pub lossy: bool,
+}
+
+/// Errors produced by [`EncodeAs::encode_as`].
+///
+/// Requires the feature `serde`.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EncodeAsError<E> {
+    /// The requested version is not declared.
+    UnknownVersion,
+    /// The serializer failed.
+    Serialize(E),
+}
+
+#[cfg(feature = "serde")]
+impl<E> core::fmt::Display for EncodeAsError<E>
+where
+    E: core::fmt::Display,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownVersion => write!(f, "cannot encode as an undeclared version"),
+            Self::Serialize(err) => write!(f, "serialization error: {err}"),
+        }
+    }
+}
+
+#[cfg(all(feature = "serde", any(feature = "std", feature = "error_in_core")))]
+impl<E> crate::error::Error for EncodeAsError<E> where E: core::fmt::Debug + core::fmt::Display {}
+
+/// Automatically implemented for the latest version of every [`versioned`] data-structure
+/// marked with `#[obake(downgrade)]`, allowing values to be encoded at older versions (e.g., for
+/// older peers during a rolling deployment).
+///
+/// Requires the feature `serde`.
+///
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use obake::EncodeAs;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(downgrade)]
+/// #[derive(Clone, PartialEq, serde::Serialize)]
+/// struct Foo {
+///     bar: u32,
+///     #[obake(cfg(">=0.2"))]
+///     baz: u32,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(from: Foo!["0.1.0"]) -> Self {
+///         Self { bar: from.bar, baz: 0 }
+///     }
+/// }
+///
+/// impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+///     fn from(from: Foo!["0.2.0"]) -> Self {
+///         Self { bar: from.bar }
+///     }
+/// }
+///
+/// let mut out = Vec::new();
+/// let encoded = Foo { bar: 1, baz: 2 }
+///     .encode_as("0.1.0", &mut serde_json::Serializer::new(&mut out))
+///     .unwrap();
+/// assert_eq!(out, br#"{"bar":1}"#);
+/// assert!(encoded.lossy);
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub trait EncodeAs: Versioned + Clone + PartialEq
+where
+    AnyVersion<Self>: Downgrade<Self> + TryUpgrade<Self>,
+{
+    /// Downgrade this value to the declared version whose semantic version number is `version`
+    /// and serialize that version with `serializer`, reporting whether the downgrade was lossy.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `version` is not declared, or if the serializer fails.
+    fn encode_as<S>(
+        &self,
+        version: &str,
+        serializer: S,
+    ) -> Result<Encoded<S::Ok>, EncodeAsError<S::Error>>
+    where
+        S: serde::Serializer,
+        AnyVersion<Self>: Accept<Self, SerializeWith<S>>,
+    {
+        let tagged = AnyVersion::<Self>::downgrade(self.clone(), version)
+            .ok_or(EncodeAsError::UnknownVersion)?;
+        let output = tagged
+            .accept(SerializeWith::new(serializer))
+            .map_err(EncodeAsError::Serialize)?;
+        // A value which fails to migrate back can't be recovered from its encoding either
+        let lossy = tagged
+            .try_upgrade()
+            .map_or(true, |round_trip| round_trip != *self);
+
+        Ok(Encoded { output, lossy })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> EncodeAs for T
+where
+    T: Versioned + Clone + PartialEq,
+    AnyVersion<T>: Downgrade<T> + TryUpgrade<T>,
+{
+}
+
+/// Automatically implemented for the latest version of every [`versioned`] data-structure
+/// marked with `#[obake(on_unrecognized = ...)]`, naming a function invoked with the raw payload
+/// when it can't be decoded as any declared version (e.g., by `obake::json::from_slice_or_recover`).
+///
+/// The function may attempt to recover a value from the payload (returning `Some`), or record
+/// the payload for later inspection (returning `None`, in which case decoding still fails).
+///
+/// Requires the feature `serde`.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::OnUnrecognized;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(on_unrecognized = recover)]
+/// struct Foo {}
+///
+/// fn recover(payload: &[u8]) -> Option<obake::AnyVersion<Foo>> {
+///     (payload == b"legacy").then(|| Foo {}.into())
+/// }
+///
+/// assert!(Foo::on_unrecognized(b"legacy").is_some());
+/// assert!(Foo::on_unrecognized(b"garbage").is_none());
+/// ```
+#[cfg(feature = "serde")]
+pub trait OnUnrecognized: Versioned {
+    /// Attempt to recover a value from a payload which can't be decoded as any declared
+    /// version.
+    fn on_unrecognized(payload: &[u8]) -> Option<AnyVersion<Self>>;
+}
+
+/// Automatically implemented for each version of a [`versioned`] `struct` marked with
+/// `#[obake(auto_downgrade_subset)]` whose fields are all present in the latest version,
+/// rebuilding it from the latest version by dropping the fields it lacks.
+///
+/// Unlike [`Downgrade`], this needs no hand-written conversions, so is useful for building
+/// fixtures of historical versions in tests.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+///
+/// ```
+/// use obake::FromLatest;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(auto_downgrade_subset)]
+/// struct Foo {
+///     bar: u32,
+///     #[obake(cfg(">=0.2"))]
+///     baz: String,
+/// }
+/// #
+/// # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+/// #     fn from(from: Foo!["0.1.0"]) -> Self {
+/// #         Self { bar: from.bar, baz: String::new() }
+/// #     }
+/// # }
+///
+/// let fixture = <Foo!["0.1.0"]>::from_latest(Foo {
+///     bar: 42,
+///     baz: "baz".to_owned(),
+/// });
+/// assert_eq!(fixture.bar, 42);
+/// ```
+pub trait FromLatest<T>
+where
+    T: Versioned,
+{
+    /// Rebuild this version from `latest`.
+    fn from_latest(latest: T) -> Self;
+}
+
+/// Automatically implemented for the version-tagged representations of [`versioned`]
+/// data-structures marked with `#[obake(stable_hash)]` (and for their latest versions), hashing
+/// every version as the latest version it migrates to.
+///
+/// An old and a new encoding of the same logical value therefore hash identically, so stores
+/// holding values of mixed versions can deduplicate (or bucket) them without migrating them
+/// first. Hashes are only as stable as the [`Hasher`](core::hash::Hasher) used, so those
+/// persisted or compared across processes should use a hasher with a fixed output (e.g.,
+/// FNV), rather than the randomly keyed `std::collections::hash_map::DefaultHasher`.
+///
+/// ```
+/// use core::hash::Hasher;
+/// use obake::{AnyVersion, StableHash};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(stable_hash)]
+/// #[derive(Clone, Hash)]
+/// struct Foo {
+///     #[obake(cfg("0.1.0"))]
+///     bar: u32,
+///     #[obake(cfg(">=0.2"))]
+///     baz: u64,
+/// }
+///
+/// impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+///     fn from(from: Foo!["0.1.0"]) -> Self {
+///         Self { baz: from.bar.into() }
+///     }
+/// }
+///
+/// fn hash(value: &impl StableHash) -> u64 {
+///     let mut hasher = std::collections::hash_map::DefaultHasher::new();
+///     value.stable_hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let old: AnyVersion<Foo> = Foo_v0_1_0 { bar: 1 }.into();
+/// let new: AnyVersion<Foo> = Foo { baz: 1 }.into();
+/// assert_eq!(hash(&old), hash(&new));
+/// assert_eq!(hash(&old), hash(&Foo { baz: 1 }));
+/// ```
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+pub trait StableHash {
+    /// Feed the latest version of `self` into `state`.
+    fn stable_hash<H>(&self, state: &mut H)
+    where
+        H: core::hash::Hasher;
+}
+
+/// Automatically implemented for [`versioned`] data-structures marked with
+/// `#[obake(no_alloc)]`, none of whose generated code requires `alloc`.
+///
+/// The errors returned by the generated code (e.g., [`TagMismatch`] and [`pin::PinError`]) only
+/// borrow version numbers, so they can be used without `alloc` too.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+pub trait NoAlloc: Versioned {}
+
+/// Fails to compile unless `T` is marked with `#[obake(no_alloc)]`, so that builds for targets
+/// without `alloc` can't be broken by a change to a data-structure far from them.
+///
+/// ```
+/// #[obake::versioned]
+/// #[obake(no_alloc(max_size = 8))]
+/// #[obake(version("0.1.0"))]
+/// struct Config {
+///     retries: u8,
+/// }
+///
+/// const _: () = obake::assert_no_alloc::<Config>();
+/// ```
+pub const fn assert_no_alloc<T>()
+where
+    T: NoAlloc,
+{
+}
+
+/// Automatically implemented for the version-tagged representations of [`versioned`]
+/// data-structures marked with `#[obake(defmt)]`, formatting just their version with
+/// [defmt](https://defmt.ferrous-systems.com) (see [`defmt_version`]).
+///
+/// Requires the feature `defmt`.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`] to derive it.
+#[cfg(feature = "defmt")]
+pub trait FormatVersion {
+    /// Format the version of `self` (e.g., `0.2.0`) as an interned string.
+    fn format_version(&self, fmt: defmt::Formatter<'_>);
+}
+
+/// Formats just the version of the value it wraps with `defmt`, as returned by
+/// [`defmt_version`].
+#[cfg(feature = "defmt")]
+#[derive(Copy, Clone, Debug)]
+pub struct DefmtVersion<'a, V>(&'a V);
+
+#[cfg(feature = "defmt")]
+impl<V> defmt::Format for DefmtVersion<'_, V>
+where
+    V: FormatVersion,
+{
+    fn format(&self, fmt: defmt::Formatter<'_>) {
+        self.0.format_version(fmt);
+    }
+}
+
+/// Format just the version of `value` with `defmt`, without allocating.
+///
+/// The version is written as an interned string, so only its index is sent over the wire (e.g.,
+/// over RTT).
+///
+/// Requires the feature `defmt`.
+///
+/// ```ignore
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(defmt)]
+/// #[obake(derive(defmt::Format))]
+/// #[derive(defmt::Format)]
+/// struct Config {
+///     baud: u32,
+/// }
+///
+/// let config: obake::AnyVersion<Config> = load_config();
+/// defmt::info!("loaded config v{}", obake::defmt_version(&config));
+/// ```
+#[cfg(feature = "defmt")]
+#[must_use]
+pub fn defmt_version<V>(value: &V) -> DefmtVersion<'_, V>
+where
+    V: FormatVersion,
+{
+    DefmtVersion(value)
+}
+
+/// A [`VersionVisitor`] which visits any version, used to check whether a version has been
+/// declared.
+///
+/// ```
+/// use obake::{Declared, Dispatch};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// struct Foo {}
+///
+/// assert!(obake::AnyVersion::<Foo>::dispatch("0.1.0", Declared).is_some());
+/// assert!(obake::AnyVersion::<Foo>::dispatch("0.2.0", Declared).is_none());
+/// ```
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Declared;
+
+impl<T> VersionVisitor<T> for Declared
+where
+    T: Versioned,
+{
+    type Output = ();
+}
+
+impl<T, V> VisitVersion<T, V> for Declared
+where
+    T: Versioned,
+    V: VersionOf<T>,
+{
+    fn visit(self) {}
+}
+
+/// A struct representing a mismatch of versions.
+///
+/// Such a mismatch can occur when trying to convert a version-tagged representation of a piece
+/// of data into a particular version.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct VersionMismatch {
+    /// The expected version.
+    pub expected: &'static str,
+    /// The version found.
+    pub found: &'static str,
+}
+
+/// An error indicating that a value can't be tagged with a version, as the value is of a type
+/// declared for other versions.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TagMismatch<'a> {
+    /// The version the value was to be tagged with.
+    pub version: &'a str,
+    /// The versions the type of the value is declared for.
+    pub expected: &'static [&'static str],
+}
+
+/// Tag `value` with the declared version of `T` whose semantic version number is `version`,
+/// for when the version of a value is only known at runtime (e.g., read from a database column
+/// alongside it).
+///
+/// This is a generic form of the `TryFrom<(&str, V)>` implementations generated for the
+/// version-tagged representation of `T` for each of its versions `V`. These can only fail when
+/// `V` isn't the version named by `version`, so are most useful when `V` is shared by several
+/// versions (see `#[obake(alias_identical)]`).
+///
+/// ## Errors
+///
+/// Fails if `V` is not the type of the version named by `version`.
+///
+/// ```
+/// use obake::{TagMismatch, VersionTagged};
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.1.1"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(alias_identical)]
+/// struct Foo {
+///     #[obake(cfg(">=0.2"))]
+///     bar: u32,
+/// }
+/// #
+/// # impl From<Foo!["0.1.1"]> for Foo!["0.2.0"] {
+/// #     fn from(_: Foo!["0.1.1"]) -> Self {
+/// #         Self { bar: 0 }
+/// #     }
+/// # }
+///
+/// let x = obake::tag_as::<Foo, _>("0.1.1", Foo_v0_1_0 {}).unwrap();
+/// assert_eq!(x.version_str(), "0.1.1");
+///
+/// assert_eq!(
+///     obake::tag_as::<Foo, _>("0.2.0", Foo_v0_1_0 {}).err(),
+///     Some(TagMismatch {
+///         version: "0.2.0",
+///         expected: &["0.1.0", "0.1.1"],
+///     }),
+/// );
+/// ```
+pub fn tag_as<'a, T, V>(version: &'a str, value: V) -> Result<AnyVersion<T>, TagMismatch<'a>>
+where
+    T: Versioned,
+    V: VersionOf<T>,
+    AnyVersion<T>: core::convert::TryFrom<(&'a str, V), Error = TagMismatch<'a>>,
+{
+    core::convert::TryFrom::try_from((version, value))
+}
+
+/// Support for code generated by [`versioned`], not public API.
+#[doc(hidden)]
+pub mod __private {
+    use core::marker::PhantomData;
+
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "json")]
+    pub use serde_json;
+
+    /// Checks for a migration from `F` to `T`.
+    ///
+    /// When `T: From<F>`, the inherent items below are selected. Otherwise, the fallbacks
+    /// provided by [`MissingMigration`] are, so that generated code still compiles and the
+    /// missing migration is instead reported by a failed assertion on [`Migration::EXISTS`],
+    /// spanned on the relevant `#[obake(version(...))]` attribute.
+    pub struct Migration<F, T>(PhantomData<fn(F) -> T>);
+
+    impl<F, T> Migration<F, T>
+    where
+        T: From<F>,
+    {
+        pub const EXISTS: bool = true;
+
+        #[inline]
+        #[must_use]
+        pub fn migrate(from: F) -> T {
+            from.into()
+        }
+    }
+
+    pub trait MissingMigration<F, T> {
+        const EXISTS: bool = false;
+
+        #[must_use]
+        fn migrate(_: F) -> T {
+            unreachable!("missing migrations are rejected at compile time")
+        }
+    }
+
+    impl<F, T> MissingMigration<F, T> for Migration<F, T> {}
+
+    /// Rebuilds an older version of an inherited field from the version held by the latest
+    /// version of its parent, by way of the latest version of the field's own data-structure.
+    #[inline]
+    #[must_use]
+    pub fn from_latest_inherited<T, F, V>(from: F) -> V
+    where
+        T: crate::Versioned + From<crate::AnyVersion<T>>,
+        F: crate::VersionOf<T>,
+        V: crate::FromLatest<T>,
+    {
+        V::from_latest(T::from(from.into()))
+    }
 }
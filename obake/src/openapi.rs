@@ -0,0 +1,143 @@
+//! OpenAPI schemas for every declared version of a [`versioned`](crate::versioned)
+//! data-structure, generated by `#[obake(utoipa)]`.
+//!
+//! Marking a data-structure `Foo` with `#[obake(utoipa)]` derives [`utoipa::ToSchema`] for every
+//! declared version, with version-suffixed schema names (e.g., `FooV0_1_0`), and implements
+//! [`ToSchema`] for `AnyVersion<Foo>` (named `VersionedFoo`). The latter describes the canonical
+//! `{ "version": ..., "data": ... }` envelope accepted by [`json`](crate::json) as a `oneOf`
+//! discriminated by `version`, and registers the schema of every version as a dependency.
+//!
+//! The crate using `#[obake(utoipa)]` must depend on [`utoipa`].
+//!
+//! Requires the feature `utoipa`.
+//!
+//! ```
+//! use utoipa::ToSchema;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(utoipa)]
+//! struct Foo {
+//!     #[obake(cfg(">=0.2"))]
+//!     bar: u32,
+//! }
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(_: Foo!["0.1.0"]) -> Self {
+//! #         Self { bar: 0 }
+//! #     }
+//! # }
+//!
+//! let mut schemas = Vec::new();
+//! obake::AnyVersion::<Foo>::schemas(&mut schemas);
+//!
+//! let names: Vec<_> = schemas.iter().map(|(name, _)| name.as_str()).collect();
+//! assert_eq!(names, ["FooV0_1_0", "FooV0_2_0"]);
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use utoipa::openapi::schema::{Discriminator, ObjectBuilder, OneOfBuilder, Ref, Schema, Type};
+use utoipa::openapi::RefOr;
+use utoipa::ToSchema;
+
+use crate::{AnyVersion, Dispatch, VersionOf, VersionVisitor, Versioned, VisitVersion};
+
+/// The [`VersionVisitor`] used by [`envelope_schema`] to find the schema name of a particular
+/// version.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SchemaName;
+
+impl<T> VersionVisitor<T> for SchemaName
+where
+    T: Versioned,
+{
+    type Output = Cow<'static, str>;
+}
+
+impl<T, V> VisitVersion<T, V> for SchemaName
+where
+    T: Versioned,
+    V: VersionOf<T> + ToSchema,
+{
+    fn visit(self) -> Cow<'static, str> {
+        V::name()
+    }
+}
+
+/// The [`VersionVisitor`] used by [`version_schemas`] to collect the schema of a particular
+/// version, along with the schemas it depends on.
+pub struct Schemas<'a> {
+    schemas: &'a mut Vec<(String, RefOr<Schema>)>,
+}
+
+impl<'a> Schemas<'a> {
+    /// Create a visitor collecting schemas into `schemas`.
+    #[must_use]
+    pub fn new(schemas: &'a mut Vec<(String, RefOr<Schema>)>) -> Self {
+        Self { schemas }
+    }
+}
+
+impl<T> VersionVisitor<T> for Schemas<'_>
+where
+    T: Versioned,
+{
+    type Output = ();
+}
+
+impl<T, V> VisitVersion<T, V> for Schemas<'_>
+where
+    T: Versioned,
+    V: VersionOf<T> + ToSchema,
+{
+    fn visit(self) {
+        self.schemas.push((V::name().into_owned(), V::schema()));
+        V::schemas(self.schemas);
+    }
+}
+
+/// The schema of the canonical `{ "version": ..., "data": ... }` envelope of `T`: a `oneOf`
+/// over every declared version, discriminated by `version`.
+#[must_use]
+pub fn envelope_schema<T>() -> RefOr<Schema>
+where
+    T: Versioned,
+    AnyVersion<T>: Dispatch<T, SchemaName>,
+{
+    let variants = T::VERSIONS.iter().filter_map(|&version| {
+        let name = AnyVersion::<T>::dispatch(version, SchemaName)?;
+        Some(
+            ObjectBuilder::new()
+                .property(
+                    "version",
+                    ObjectBuilder::new()
+                        .schema_type(Type::String)
+                        .enum_values(Some([version])),
+                )
+                .required("version")
+                .property("data", Ref::from_schema_name(name))
+                .required("data"),
+        )
+    });
+
+    variants
+        .fold(OneOfBuilder::new(), OneOfBuilder::item)
+        .discriminator(Some(Discriminator::new("version")))
+        .into()
+}
+
+/// Collect the schemas of every declared version of `T`, along with the schemas they depend on,
+/// into `schemas`.
+pub fn version_schemas<T>(schemas: &mut Vec<(String, RefOr<Schema>)>)
+where
+    T: Versioned,
+    for<'a> AnyVersion<T>: Dispatch<T, Schemas<'a>>,
+{
+    for version in T::VERSIONS {
+        AnyVersion::<T>::dispatch(version, Schemas::new(schemas));
+    }
+}
@@ -0,0 +1,156 @@
+//! Pinning the versions of a [`versioned`](crate::versioned) data-structure accepted from a
+//! tenant (or environment) to a maximum version.
+//!
+//! During a staged rollout, a [`Pinned`] holds the newest version a particular tenant has been
+//! moved onto. [`Pinned::accept`] rejects data of any newer version with a [`PinError`], and
+//! migrates data of older versions only as far as the pin (see [`Upgrade`]), so that it can be
+//! handled the same way as data of the pinned version. Keeping a [`Pinned`] for each tenant
+//! (e.g., in a map keyed by tenant) lets each be moved onto a new version independently.
+//!
+//! ```
+//! use obake::pin::{PinError, Pinned};
+//! use obake::VersionTagged;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(version("0.3.0"))]
+//! struct Foo {}
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(_: Foo!["0.1.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//! #
+//! # impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+//! #     fn from(_: Foo!["0.2.0"]) -> Self {
+//! #         Self {}
+//! #     }
+//! # }
+//!
+//! let pinned = Pinned::<Foo>::new("0.2.0").unwrap();
+//!
+//! let x = pinned.accept(Foo_v0_1_0 {}.into()).unwrap();
+//! assert_eq!(x.version_str(), "0.2.0");
+//!
+//! assert_eq!(
+//!     pinned.accept(Foo {}.into()).err(),
+//!     Some(PinError {
+//!         version: "0.3.0",
+//!         pin: "0.2.0",
+//!     }),
+//! );
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{AnyVersion, Upgrade, VersionTagged, Versioned};
+
+/// The maximum version of `T` accepted from a particular tenant (or environment).
+pub struct Pinned<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Pinned<T>
+where
+    T: Versioned,
+{
+    /// Pin to the declared version of `T` whose semantic version number is `version`, or return
+    /// `None` if no such version has been declared.
+    #[must_use]
+    pub fn new(version: &str) -> Option<Self> {
+        let index = T::VERSIONS.iter().position(|x| *x == version)?;
+
+        Some(Self {
+            index,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Pin to the latest version of `T`, accepting data of any declared version.
+    #[must_use]
+    pub fn latest() -> Self {
+        Self {
+            index: T::VERSIONS.len() - 1,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The semantic version number of the pinned version.
+    #[must_use]
+    pub fn version(&self) -> &'static str {
+        T::VERSIONS[self.index]
+    }
+
+    /// Whether data of the version whose semantic version number is `version` is accepted (i.e.,
+    /// it has been declared and is no newer than the pinned version).
+    #[must_use]
+    pub fn allows(&self, version: &str) -> bool {
+        T::VERSIONS[..=self.index].contains(&version)
+    }
+
+    /// Migrate `value` to the pinned version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if `value` is of a version newer than the pinned version.
+    pub fn accept(&self, value: AnyVersion<T>) -> Result<AnyVersion<T>, PinError>
+    where
+        AnyVersion<T>: Upgrade<T>,
+    {
+        let version = value.version_str();
+        let pin = self.version();
+
+        value.upgrade(pin).ok_or(PinError { version, pin })
+    }
+}
+
+impl<T> Clone for Pinned<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Pinned<T> {}
+
+impl<T> PartialEq for Pinned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Pinned<T> {}
+
+impl<T> fmt::Debug for Pinned<T>
+where
+    T: Versioned,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pinned").field(&self.version()).finish()
+    }
+}
+
+/// An error indicating that data of a version newer than the pinned version was rejected.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PinError {
+    /// The version of the rejected data.
+    pub version: &'static str,
+    /// The pinned version.
+    pub pin: &'static str,
+}
+
+impl fmt::Display for PinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "version {} is newer than the pinned version {}",
+            self.version, self.pin,
+        )
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl crate::error::Error for PinError {}
@@ -0,0 +1,209 @@
+//! Migrating values of several [`versioned`](crate::versioned) data-structures, some nested in
+//! others by fields marked `#[obake(inherit)]`, in an order respecting their nesting.
+//!
+//! When values of nested data-structures are stored apart from those of the data-structures
+//! nesting them (e.g., as separate documents), the migrations of the outer values may rely on
+//! the inner values having already been migrated. A [`Planner`] is given each value along with
+//! a key naming it, and migrates the values of data-structures nested in others (as listed by
+//! [`Inherits`]) before the values of the data-structures nesting them, whatever order they were
+//! given in, so that the order needn't be maintained by hand as data-structures are nested.
+//!
+//! As with `obake::txn`, the values are migrated all or nothing: either every value migrates, or
+//! the planner stops at the first value which fails, without migrating the values which follow
+//! it, and returns its error.
+//!
+//! Requires the feature `alloc`.
+//!
+//! ```
+//! use obake::planner::Planner;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq)]
+//! struct Address {
+//!     #[obake(cfg(">=0.2"))]
+//!     country: String,
+//! }
+//!
+//! impl From<Address!["0.1.0"]> for Address!["0.2.0"] {
+//!     fn from(_: Address!["0.1.0"]) -> Self {
+//!         Self { country: "GB".to_owned() }
+//!     }
+//! }
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq)]
+//! struct Customer {
+//!     #[obake(inherit)]
+//!     address: Address,
+//! }
+//!
+//! impl From<Customer!["0.1.0"]> for Customer!["0.2.0"] {
+//!     fn from(from: Customer!["0.1.0"]) -> Self {
+//!         Self { address: from.address.into() }
+//!     }
+//! }
+//!
+//! let mut planner = Planner::new();
+//! planner
+//!     .add("customer", Customer_v0_1_0 { address: Address_v0_1_0 {} })
+//!     .add("billing address", Address_v0_1_0 {});
+//! assert_eq!(planner.order().collect::<Vec<_>>(), [&"billing address", &"customer"]);
+//!
+//! let mut migrated = planner.execute().unwrap();
+//! let address: Address = migrated.take(&"billing address").unwrap();
+//! assert_eq!(address.country, "GB");
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::any::Any;
+use core::fmt;
+
+use crate::collections::KeyedError;
+use crate::{try_upgrade_rendered, AnyVersion, Inherits, StepError, TryMigrate, TryUpgrade};
+
+/// The error of the value which failed to migrate (see [`Planner::execute`]), named by its key
+/// and rendered with its `Display` implementation (as the values may be of data-structures with
+/// different error types).
+pub type Error<K> = KeyedError<K, StepError<String>>;
+
+type Migration = Box<dyn FnOnce() -> Result<Box<dyn Any>, StepError<String>>>;
+
+struct Step<K> {
+    key: K,
+    depth: usize,
+    migrate: Migration,
+}
+
+/// Values of several data-structures, each named by a key of type `K`, to be migrated to their
+/// latest versions in an order respecting their nesting.
+pub struct Planner<K> {
+    steps: Vec<Step<K>>,
+}
+
+impl<K> Planner<K> {
+    /// Create a planner without any values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Add a value of any declared version of a data-structure (or its version-tagged
+    /// representation), named by `key`.
+    pub fn add<V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        V: TryMigrate + Into<AnyVersion<V::Latest>>,
+        V::Latest: Inherits + 'static,
+        AnyVersion<V::Latest>: TryUpgrade<V::Latest> + 'static,
+        <AnyVersion<V::Latest> as TryUpgrade<V::Latest>>::Error: fmt::Display,
+    {
+        let tagged: AnyVersion<V::Latest> = value.into();
+        let migrate = move || {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::record_upgraded::<V::Latest>(&tagged);
+            try_upgrade_rendered::<V::Latest>(tagged).map(|value| Box::new(value) as Box<dyn Any>)
+        };
+
+        self.steps.push(Step {
+            key,
+            depth: V::Latest::depth(),
+            migrate: Box::new(migrate),
+        });
+        self
+    }
+
+    /// The keys of the values, in the order they'll be migrated: the values of data-structures
+    /// nested more deeply first, and otherwise in the order they were added.
+    pub fn order(&self) -> impl Iterator<Item = &K> + '_ {
+        let mut order: Vec<_> = self.steps.iter().collect();
+        order.sort_by_key(|step| step.depth);
+        order.into_iter().map(|step| &step.key)
+    }
+
+    /// Migrate every value to the latest version of its data-structure, in the order given by
+    /// [`Planner::order`].
+    ///
+    /// ## Errors
+    ///
+    /// Stops at the first value which fails to migrate, without migrating the values which
+    /// follow it, and returns its error, so that no migrated value is returned unless all of them
+    /// succeed.
+    pub fn execute(mut self) -> Result<Migrated<K>, Error<K>> {
+        self.steps.sort_by_key(|step| step.depth);
+
+        let mut values = Vec::with_capacity(self.steps.len());
+        for step in self.steps {
+            match (step.migrate)() {
+                Ok(value) => values.push((step.key, value)),
+                Err(error) => {
+                    return Err(KeyedError {
+                        key: step.key,
+                        error,
+                    })
+                }
+            }
+        }
+        Ok(Migrated { values })
+    }
+}
+
+impl<K> Default for Planner<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> fmt::Debug for Planner<K>
+where
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Planner")
+            .field("order", &self.order().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The values migrated by [`Planner::execute`], each at the latest version of its
+/// data-structure.
+pub struct Migrated<K> {
+    values: Vec<(K, Box<dyn Any>)>,
+}
+
+impl<K> Migrated<K> {
+    /// The keys of the values not yet taken, in the order they were migrated.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.values.iter().map(|(key, _)| key)
+    }
+
+    /// Take the value named by `key`, or `None` if there's no such value (or it isn't a `T`).
+    pub fn take<T>(&mut self, key: &K) -> Option<T>
+    where
+        T: 'static,
+        K: PartialEq,
+    {
+        let index = self
+            .values
+            .iter()
+            .position(|(other, value)| other == key && value.is::<T>())?;
+        let (_, value) = self.values.remove(index);
+        value.downcast().ok().map(|value| *value)
+    }
+}
+
+impl<K> fmt::Debug for Migrated<K>
+where
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migrated")
+            .field("keys", &self.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,63 @@
+//! Support code for the [`pyo3`] bindings generated by `#[obake(pyo3)]`.
+//!
+//! Marking a [`versioned`](crate::versioned) data-structure `Foo` with `#[obake(pyo3)]`
+//! generates the following, so that Python code can reuse the migrations defined in Rust:
+//!
+//! - A `#[pyclass]` for every declared version, exposed to Python as e.g. `FooV0_1_0`. The
+//!   fields of `struct`s are readable and writable from Python.
+//! - `register_foo(module)`, which adds every version class and a function
+//!   `upgrade(data: dict, version: str) -> FooVx_y_z` to `module`, migrating `data`, stored at
+//!   `version`, to the latest version.
+//!
+//! As every type registers a function named `upgrade`, each type should be registered in its
+//! own (sub)module.
+//!
+//! Requires the feature `pyo3`.
+//!
+//! ```ignore
+//! #[pyo3::pymodule]
+//! fn documents(module: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     let config = PyModule::new(module.py(), "config")?;
+//!     register_config(&config)?;
+//!     module.add_submodule(&config)
+//! }
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::codec::{decode_version, Decode, Json};
+use crate::{AnyVersion, Dispatch, TryUpgrade, Versioned};
+
+/// Decode `data`, a Python object of the declared version `version` of `T`, and migrate it to
+/// the latest version.
+///
+/// `data` is converted using Python's `json` module, so must be JSON-serializable.
+///
+/// ## Errors
+///
+/// Fails with a `ValueError` if `version` is not a declared version of `T`, if `data` cannot be
+/// decoded as that version, or if it fails to migrate.
+pub fn upgrade<T>(data: &Bound<'_, PyAny>, version: &str) -> PyResult<T>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: core::fmt::Display,
+{
+    let json: String = data
+        .py()
+        .import("json")?
+        .call_method1("dumps", (data,))?
+        .extract()?;
+
+    let tagged = decode_version::<T, _>(&Json, version, json.as_bytes())
+        .ok_or_else(|| PyValueError::new_err(format!("unknown version {version}")))?
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    tagged
+        .try_upgrade()
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
@@ -0,0 +1,209 @@
+//! A process-wide registry of [`versioned`](crate::versioned) data-structures, decoding payloads
+//! given only the name of their data-structure and their version as strings (e.g., from the
+//! headers of a message), for message brokers and plugin hosts which can't name every type they
+//! carry.
+//!
+//! Data-structures marked `#[obake(register)]` (or `#[obake(register = "...")]`, naming them
+//! explicitly) are collected with [`inventory`](https://docs.rs/inventory) wherever they're
+//! declared in the program. [`decode`] decodes a JSON payload of any registered version and
+//! migrates it to the latest version, boxed as [`Any`] to be downcast by its consumer.
+//!
+//! Requires the feature `registry`.
+//!
+//! ```
+//! use obake::registry;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(register = "billing.Invoice")]
+//! #[derive(Debug, PartialEq, serde::Deserialize)]
+//! struct Invoice {
+//!     #[obake(cfg("0.1.0"))]
+//!     cents: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     amount: u64,
+//! }
+//!
+//! impl From<Invoice!["0.1.0"]> for Invoice!["0.2.0"] {
+//!     fn from(from: Invoice!["0.1.0"]) -> Self {
+//!         Self { amount: from.cents.into() }
+//!     }
+//! }
+//!
+//! let decoded = registry::decode("billing.Invoice", "0.1.0", br#"{"cents":120}"#).unwrap();
+//! assert_eq!(decoded.downcast_ref::<Invoice>(), Some(&Invoice { amount: 120 }));
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+
+use core::any::Any;
+use core::fmt;
+
+use crate::codec::{Decode, Json};
+use crate::{try_upgrade_rendered, AnyVersion, Dispatch, StepError, TryUpgrade, Versioned};
+
+type DecodeFn = fn(&'static str, &str, &[u8]) -> Result<Box<dyn Any>, Error>;
+
+/// A data-structure marked `#[obake(register)]`.
+pub struct Registration {
+    type_name: &'static str,
+    versions: &'static [&'static str],
+    decode: DecodeFn,
+}
+
+inventory::collect!(Registration);
+
+impl Registration {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(
+        type_name: &'static str,
+        versions: &'static [&'static str],
+        decode: DecodeFn,
+    ) -> Self {
+        Self {
+            type_name,
+            versions,
+            decode,
+        }
+    }
+
+    /// The name the data-structure is registered by.
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The declared versions of the data-structure, ordered from oldest to newest.
+    #[must_use]
+    pub fn versions(&self) -> &'static [&'static str] {
+        self.versions
+    }
+
+    /// Decode a JSON payload of `version` of this data-structure, migrated to the latest
+    /// version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the data-structure declares no such version, if `bytes` isn't a valid encoding
+    /// of it, or if it fails to migrate.
+    pub fn decode(&self, version: &str, bytes: &[u8]) -> Result<Box<dyn Any>, Error> {
+        (self.decode)(self.type_name, version, bytes)
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration")
+            .field("type_name", &self.type_name)
+            .field("versions", &self.versions)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Errors produced when decoding payloads by the name of their data-structure.
+#[derive(Debug)]
+pub enum Error {
+    /// No data-structure is registered by the name given.
+    UnknownType(String),
+    /// The data-structure declares no such version.
+    UnknownVersion {
+        /// The name the data-structure is registered by.
+        type_name: &'static str,
+        /// The version requested.
+        version: String,
+    },
+    /// The payload isn't a valid encoding of the version requested.
+    Decode {
+        /// The name the data-structure is registered by.
+        type_name: &'static str,
+        /// The error produced by decoding it.
+        error: serde_json::Error,
+    },
+    /// The payload was decoded, but failed to migrate to the latest version (see
+    /// [`TryUpgrade`]).
+    Migrate {
+        /// The name the data-structure is registered by.
+        type_name: &'static str,
+        /// The error of the migration which failed, rendered with its `Display` implementation.
+        error: StepError<String>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownType(type_name) => {
+                write!(f, "no data-structure registered as `{type_name}`")
+            }
+            Self::UnknownVersion { type_name, version } => {
+                write!(f, "`{type_name}` declares no version {version}")
+            }
+            Self::Decode { type_name, error } => {
+                write!(f, "malformed `{type_name}` payload: {error}")
+            }
+            Self::Migrate { type_name, error } => {
+                write!(f, "`{type_name}` payload failed to migrate: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode { error, .. } => Some(error),
+            Self::Migrate { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Every registered data-structure, in no particular order.
+pub fn registrations() -> impl Iterator<Item = &'static Registration> {
+    inventory::iter::<Registration>.into_iter()
+}
+
+/// The data-structure registered as `type_name`, if any.
+#[must_use]
+pub fn lookup(type_name: &str) -> Option<&'static Registration> {
+    registrations().find(|registration| registration.type_name == type_name)
+}
+
+/// Decode a JSON payload of `version` of the data-structure registered as `type_name`, migrated
+/// to the latest version (e.g., `Foo`, to be recovered with `downcast::<Foo>()`).
+///
+/// ## Errors
+///
+/// Fails if no data-structure is registered as `type_name`, if it declares no such version, if
+/// `bytes` isn't a valid encoding of it, or if it fails to migrate.
+pub fn decode(type_name: &str, version: &str, bytes: &[u8]) -> Result<Box<dyn Any>, Error> {
+    lookup(type_name)
+        .ok_or_else(|| Error::UnknownType(type_name.to_string()))?
+        .decode(version, bytes)
+}
+
+#[doc(hidden)]
+pub fn decode_latest<T>(
+    type_name: &'static str,
+    version: &str,
+    bytes: &[u8],
+) -> Result<Box<dyn Any>, Error>
+where
+    T: Versioned + 'static,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    let tagged = crate::codec::decode_version::<T, _>(&Json, version, bytes)
+        .ok_or_else(|| Error::UnknownVersion {
+            type_name,
+            version: version.to_string(),
+        })?
+        .map_err(|error| Error::Decode { type_name, error })?;
+    let latest =
+        try_upgrade_rendered::<T>(tagged).map_err(|error| Error::Migrate { type_name, error })?;
+    Ok(Box::new(latest))
+}
@@ -0,0 +1,159 @@
+//! RON encodings of [`versioned`](crate::versioned) data-structures, for hand-edited
+//! configuration files and game data.
+//!
+//! As with `obake::json`, values are exchanged in an envelope naming the version of the payload:
+//!
+//! ```ron
+//! (version: "0.1.0", data: (bar: 1))
+//! ```
+//!
+//! Values are encoded in this envelope by [`to_string`]. When decoding, payloads which aren't
+//! wrapped in an envelope are also accepted, and each declared version is tried in turn, from
+//! newest to oldest. RON can name the `struct` a payload holds (as written with
+//! [`PrettyConfig::struct_names`](ron::ser::PrettyConfig::struct_names)), as in
+//! `Foo_v0_1_0(bar: 1)`, and a named payload is only ever decoded as the version of that name.
+//!
+//! Requires the feature `ron`.
+//!
+//! ```
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg("0.1.0"))]
+//!     bar: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     baz: u64,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(from: Foo!["0.1.0"]) -> Self {
+//!         Self { baz: from.bar.into() }
+//!     }
+//! }
+//!
+//! let tagged = obake::ron::from_str::<Foo>(r#"(version: "0.1.0", data: (bar: 1))"#);
+//! assert_eq!(Foo::from(tagged.unwrap()), Foo { baz: 1 });
+//!
+//! let untagged = obake::ron::from_str::<Foo>("(bar: 2)");
+//! assert_eq!(Foo::from(untagged.unwrap()), Foo { baz: 2 });
+//!
+//! let named = obake::ron::from_str::<Foo>("Foo_v0_1_0(bar: 3)");
+//! assert_eq!(Foo::from(named.unwrap()), Foo { baz: 3 });
+//!
+//! let encoded = obake::ron::to_string::<Foo>(&Foo { baz: 4 }.into()).unwrap();
+//! assert_eq!(encoded, r#"(version:"0.2.0",data:(baz:4))"#);
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+
+use core::fmt;
+
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+use crate::codec::{
+    decode_version, decode_version_with, Codec, Decode, EnvelopeData, Ron, ToEnvelope,
+    UnsupportedVersion,
+};
+use crate::{Accept, AnyVersion, Dispatch, Versioned};
+
+/// Errors produced when decoding RON.
+#[derive(Debug)]
+pub enum Error {
+    /// The payload was tagged with a version that is not declared.
+    UnknownVersion(String),
+    /// The payload was tagged with a declared version, but could not be deserialized as that
+    /// version.
+    Ron(ron::Error),
+    /// The payload was untagged and could not be deserialized as any declared version.
+    Unrecognized,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVersion(version) => write!(f, "undeclared version {version}"),
+            Self::Ron(err) => write!(f, "malformed payload: {err}"),
+            Self::Unrecognized => write!(f, "payload does not match any declared version"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Ron(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(borrow)]
+    version: Cow<'a, str>,
+    #[allow(dead_code)]
+    data: IgnoredAny,
+}
+
+/// Decode a RON payload of any declared version of `T`, either wrapped in the canonical
+/// envelope or untagged.
+///
+/// ## Errors
+///
+/// Fails if the payload is tagged with an undeclared version, if a tagged payload cannot be
+/// deserialized as its version, or if an untagged payload cannot be deserialized as any
+/// version.
+pub fn from_str<T>(s: &str) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>:
+        Dispatch<T, Decode<'de, 'c, Ron>> + Dispatch<T, Decode<'de, 'c, EnvelopeData<Ron>>>,
+{
+    from_str_with::<T>(s, UnsupportedVersion::Reject)
+}
+
+/// Decode a RON payload as [`from_str`] does, handling a payload tagged with an undeclared
+/// version according to `policy`.
+///
+/// ## Errors
+///
+/// Fails as [`from_str`] does, unless `policy` selects a declared version to decode a payload
+/// tagged with an undeclared version as.
+pub fn from_str_with<T>(s: &str, policy: UnsupportedVersion) -> Result<AnyVersion<T>, Error>
+where
+    T: Versioned,
+    for<'de, 'c> AnyVersion<T>:
+        Dispatch<T, Decode<'de, 'c, Ron>> + Dispatch<T, Decode<'de, 'c, EnvelopeData<Ron>>>,
+{
+    if let Ok(envelope) = Ron.decode::<Envelope<'_>>(s.as_bytes()) {
+        let data = EnvelopeData(Ron);
+        return decode_version_with::<T, _>(&data, &envelope.version, s.as_bytes(), policy)
+            .ok_or_else(|| Error::UnknownVersion(envelope.version.to_string()))?
+            .map_err(Error::Ron);
+    }
+
+    // A payload naming its `struct` fails to deserialize as any other version
+    T::VERSIONS
+        .iter()
+        .rev()
+        .find_map(|version| decode_version::<T, _>(&Ron, version, s.as_bytes())?.ok())
+        .ok_or(Error::Unrecognized)
+}
+
+/// Encode a value of any declared version of `T` as RON, wrapped in the canonical envelope.
+///
+/// ## Errors
+///
+/// Fails if the value cannot be serialized.
+pub fn to_string<T>(tagged: &AnyVersion<T>) -> ron::Result<String>
+where
+    T: Versioned,
+    for<'c> AnyVersion<T>: Accept<T, ToEnvelope<'c, Ron>>,
+{
+    let bytes = tagged.accept(ToEnvelope(&Ron))?;
+    String::from_utf8(bytes).map_err(|err| err.utf8_error().into())
+}
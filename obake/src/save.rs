@@ -0,0 +1,295 @@
+//! Save files holding any declared version of a [`versioned`](crate::versioned) data-structure.
+//!
+//! A save file frames the encoding of a value with a magic number ([`MAGIC`]), the name of the
+//! [`Codec`] used to encode it, the semantic version number it was encoded at, and a CRC-32
+//! checksum of the encoding:
+//!
+//! | Bytes | Contents |
+//! |-------|----------|
+//! | 8 | [`MAGIC`] |
+//! | 1 + _n_ | The length of [`Codec::NAME`], followed by the name itself |
+//! | 1 + _m_ | The length of the version, followed by the version itself |
+//! | 4 | The CRC-32 of the payload (little-endian) |
+//! | _rest_ | The payload, encoded with the codec |
+//!
+//! Values are always saved at the latest version. When loading, the payload is checked against
+//! its checksum, decoded as the version it was saved at, and migrated to the latest version.
+//!
+//! Requires the features `std` and `serde`.
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use obake::codec::Json;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg(">=0.2"))]
+//!     bar: u32,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(_: Foo!["0.1.0"]) -> Self {
+//!         Self { bar: 42 }
+//!     }
+//! }
+//!
+//! let path = std::env::temp_dir().join("obake-save-doctest.sav");
+//! obake::save::write_save(&path, &Foo { bar: 7 }, &Json).unwrap();
+//! assert_eq!(obake::save::read_save::<Foo, _>(&path, &Json).unwrap(), Foo { bar: 7 });
+//! # std::fs::remove_file(&path).unwrap();
+//! # }
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::codec::{decode_version_with, Codec, Decode, UnsupportedVersion};
+use crate::{
+    try_upgrade_rendered, AnyVersion, Dispatch, StepError, TryUpgrade, VersionOf, Versioned,
+};
+
+/// The magic number at the start of every save file.
+pub const MAGIC: [u8; 8] = *b"\x89OBAKE\r\n";
+
+/// Errors produced when saving or loading.
+#[derive(Debug)]
+pub enum Error<C> {
+    /// The file could not be read or written.
+    Io(io::Error),
+    /// The [`Codec`] failed.
+    Codec(C),
+    /// The file does not begin with [`MAGIC`].
+    BadMagic,
+    /// The header of the file is malformed (e.g., truncated).
+    Malformed,
+    /// The file was saved with a different codec.
+    CodecMismatch(String),
+    /// The file was saved at a version that is not declared.
+    UnknownVersion(String),
+    /// The payload does not match its checksum.
+    ChecksumMismatch {
+        /// The checksum recorded in the header.
+        expected: u32,
+        /// The checksum of the payload.
+        found: u32,
+    },
+    /// The payload was decoded, but failed to migrate to the latest version (see
+    /// [`TryUpgrade`]).
+    Migrate(StepError<String>),
+    /// The name of the codec or the latest version is too long to be recorded in the header
+    /// (which allows at most 255 bytes).
+    TooLong(String),
+}
+
+impl<C> fmt::Display for Error<C>
+where
+    C: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Codec(err) => write!(f, "codec error: {err}"),
+            Self::BadMagic => write!(f, "not a save file"),
+            Self::Malformed => write!(f, "save file has a malformed header"),
+            Self::CodecMismatch(codec) => write!(f, "save file was encoded with codec {codec}"),
+            Self::UnknownVersion(version) => {
+                write!(f, "save file has undeclared version {version}")
+            }
+            Self::ChecksumMismatch { expected, found } => write!(
+                f,
+                "save file is corrupt (expected checksum {expected:#010x}, found {found:#010x})"
+            ),
+            Self::Migrate(err) => write!(f, "save file failed to migrate: {err}"),
+            Self::TooLong(s) => write!(f, "`{s}` is too long to be recorded in a save file"),
+        }
+    }
+}
+
+impl<C> std::error::Error for Error<C> where C: fmt::Debug + fmt::Display {}
+
+impl<C> From<io::Error> for Error<C> {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The CRC-32 (IEEE 802.3) checksum of `bytes`.
+#[must_use]
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Encode `latest` as a save file using `codec`.
+///
+/// ## Errors
+///
+/// Fails if `latest` cannot be encoded, or if [`Codec::NAME`] or the latest version is longer
+/// than 255 bytes.
+pub fn encode<T, C>(latest: &T, codec: &C) -> Result<Vec<u8>, Error<C::Error>>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+    C: Codec,
+{
+    let payload = codec.encode(latest).map_err(Error::Codec)?;
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + payload.len() + 32);
+    bytes.extend_from_slice(&MAGIC);
+    push_str(&mut bytes, C::NAME)?;
+    push_str(&mut bytes, T::VERSION)?;
+    bytes.extend_from_slice(&crc32(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+
+    Ok(bytes)
+}
+
+fn push_str<C>(bytes: &mut Vec<u8>, s: &str) -> Result<(), Error<C>> {
+    let len = u8::try_from(s.len()).map_err(|_| Error::TooLong(s.to_string()))?;
+    bytes.push(len);
+    bytes.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+/// Decode a save file of any declared version of `T`, encoded with `codec`, and migrate it to
+/// the latest version.
+///
+/// ## Errors
+///
+/// Fails if `bytes` is not a well-formed save file encoded with `codec`, if it was saved at an
+/// undeclared version, if its payload is corrupt, or if it fails to migrate.
+pub fn decode<T, C>(bytes: &[u8], codec: &C) -> Result<T, Error<C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    decode_with(bytes, codec, UnsupportedVersion::Reject)
+}
+
+/// Decode a save file as [`decode`] does, handling a save file written at an undeclared version
+/// (e.g., by a newer release) according to `policy`.
+///
+/// ## Errors
+///
+/// Fails as [`decode`] does, unless `policy` selects a declared version to decode a save file
+/// written at an undeclared version as.
+pub fn decode_with<T, C>(
+    bytes: &[u8],
+    codec: &C,
+    policy: UnsupportedVersion,
+) -> Result<T, Error<C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    let rest = bytes.strip_prefix(&MAGIC[..]).ok_or(Error::BadMagic)?;
+    let (codec_name, rest) = split_str(rest)?;
+    if codec_name != C::NAME {
+        return Err(Error::CodecMismatch(codec_name.to_string()));
+    }
+
+    let (version, rest) = split_str(rest)?;
+    if rest.len() < 4 {
+        return Err(Error::Malformed);
+    }
+    let (checksum, payload) = rest.split_at(4);
+    let expected = u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    let found = crc32(payload);
+    if expected != found {
+        return Err(Error::ChecksumMismatch { expected, found });
+    }
+
+    let tagged = decode_version_with::<T, C>(codec, version, payload, policy)
+        .ok_or_else(|| Error::UnknownVersion(version.to_string()))?
+        .map_err(Error::Codec)?;
+    try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate)
+}
+
+fn split_str<C>(bytes: &[u8]) -> Result<(&str, &[u8]), Error<C>> {
+    let (&len, rest) = bytes.split_first().ok_or(Error::Malformed)?;
+    if rest.len() < usize::from(len) {
+        return Err(Error::Malformed);
+    }
+
+    let (s, rest) = rest.split_at(usize::from(len));
+    let s = core::str::from_utf8(s).map_err(|_| Error::Malformed)?;
+    Ok((s, rest))
+}
+
+/// Save `latest` to the file at `path` using `codec`, replacing any existing file.
+///
+/// ## Errors
+///
+/// Fails if `latest` cannot be encoded, or if the file cannot be written.
+pub fn write_save<T, C>(
+    path: impl AsRef<Path>,
+    latest: &T,
+    codec: &C,
+) -> Result<(), Error<C::Error>>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+    C: Codec,
+{
+    std::fs::write(path, encode(latest, codec)?)?;
+    Ok(())
+}
+
+/// Load the save file at `path`, decoding it with `codec` and migrating it to the latest
+/// version.
+///
+/// ## Errors
+///
+/// Fails if the file cannot be read, or cannot be decoded (see [`decode`]).
+pub fn read_save<T, C>(path: impl AsRef<Path>, codec: &C) -> Result<T, Error<C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    decode(&std::fs::read(path)?, codec)
+}
+
+/// Load the save file at `path` as [`read_save`] does, handling a save file written at an
+/// undeclared version according to `policy` (see [`decode_with`]).
+///
+/// ## Errors
+///
+/// Fails if the file cannot be read, or cannot be decoded (see [`decode_with`]).
+pub fn read_save_with<T, C>(
+    path: impl AsRef<Path>,
+    codec: &C,
+    policy: UnsupportedVersion,
+) -> Result<T, Error<C::Error>>
+where
+    T: Versioned,
+    C: Codec,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    decode_with(&std::fs::read(path)?, codec, policy)
+}
@@ -0,0 +1,83 @@
+//! JSON Schemas for every declared version of a [`versioned`](crate::versioned)
+//! data-structure.
+//!
+//! Schemas are produced by [`schemars`], so every version must implement
+//! [`JsonSchema`](schemars::JsonSchema) (e.g., by applying `#[derive(schemars::JsonSchema)]` to
+//! the versioned item).
+//!
+//! Requires the feature `schemars`.
+//!
+//! ```
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(schemars::JsonSchema)]
+//! struct Foo {
+//!     #[obake(cfg(">=0.2"))]
+//!     bar: u32,
+//! }
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(_: Foo!["0.1.0"]) -> Self {
+//! #         Self { bar: 0 }
+//! #     }
+//! # }
+//!
+//! let schemas = obake::schema::json_schemas::<Foo>();
+//! assert_eq!(schemas.len(), 2);
+//! assert_eq!(schemas[0].0, "0.1.0");
+//! assert!(schemas[0].1.schema.object.as_ref().unwrap().properties.is_empty());
+//! assert!(schemas[1].1.schema.object.as_ref().unwrap().properties.contains_key("bar"));
+//! ```
+
+use alloc::vec::Vec;
+
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+
+use crate::{AnyVersion, Dispatch, VersionOf, VersionVisitor, Versioned, VisitVersion};
+
+/// The [`VersionVisitor`] used by [`json_schema`] to generate the schema of a particular version.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SchemaFor;
+
+impl<T> VersionVisitor<T> for SchemaFor
+where
+    T: Versioned,
+{
+    type Output = RootSchema;
+}
+
+impl<T, V> VisitVersion<T, V> for SchemaFor
+where
+    T: Versioned,
+    V: VersionOf<T> + JsonSchema,
+{
+    fn visit(self) -> RootSchema {
+        schemars::schema_for!(V)
+    }
+}
+
+/// The JSON Schema of the declared version of `T` whose semantic version number is `version`,
+/// or `None` if no such version has been declared.
+#[must_use]
+pub fn json_schema<T>(version: &str) -> Option<RootSchema>
+where
+    T: Versioned,
+    AnyVersion<T>: Dispatch<T, SchemaFor>,
+{
+    AnyVersion::<T>::dispatch(version, SchemaFor)
+}
+
+/// The JSON Schemas of every declared version of `T`, ordered from oldest to newest.
+#[must_use]
+pub fn json_schemas<T>() -> Vec<(&'static str, RootSchema)>
+where
+    T: Versioned,
+    AnyVersion<T>: Dispatch<T, SchemaFor>,
+{
+    T::VERSIONS
+        .iter()
+        .filter_map(|&version| json_schema::<T>(version).map(|schema| (version, schema)))
+        .collect()
+}
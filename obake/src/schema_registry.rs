@@ -0,0 +1,189 @@
+//! Publishing the JSON Schemas of a [`versioned`](crate::versioned) data-structure to a
+//! [Confluent Schema Registry](https://docs.confluent.io/platform/current/schema-registry/).
+//!
+//! [`SchemaRegistry::register`] registers every declared version, from oldest to newest, as a
+//! schema under a subject, and [`SchemaRegistry::is_compatible`] checks the latest version
+//! against the compatibility rules configured for a subject, so that CI can fail when a new
+//! version would be rejected by the registry.
+//!
+//! HTTP is delegated to a user-provided [`Transport`], so any async HTTP client can be used.
+//!
+//! Requires the feature `schema-registry`.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{json_schemas, SchemaFor};
+use crate::{AnyVersion, Dispatch, Versioned};
+
+/// An async HTTP client used to talk to a schema registry.
+pub trait Transport {
+    /// The error produced by failed requests.
+    type Error;
+
+    /// Send a `POST` request with a JSON `body` to `path` (relative to the registry's base URL)
+    /// using the content type `application/vnd.schemaregistry.v1+json`, returning the body of
+    /// the response.
+    ///
+    /// ## Errors
+    ///
+    /// Implementations should fail if the request fails or the registry responds with a
+    /// non-success status code.
+    fn post(&self, path: &str, body: Vec<u8>)
+        -> impl Future<Output = Result<Vec<u8>, Self::Error>>;
+}
+
+/// Errors produced by a [`SchemaRegistry`].
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The [`Transport`] failed.
+    Transport(E),
+    /// A schema or response could not be (de)serialized.
+    Json(serde_json::Error),
+}
+
+impl<E> fmt::Display for Error<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "schema registry request failed: {err}"),
+            Self::Json(err) => write!(f, "malformed schema registry payload: {err}"),
+        }
+    }
+}
+
+impl<E> std::error::Error for Error<E> where E: fmt::Debug + fmt::Display {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaRequest {
+    schema_type: &'static str,
+    schema: String,
+}
+
+#[derive(Deserialize)]
+struct RegisterResponse {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct CompatibilityResponse {
+    is_compatible: bool,
+}
+
+/// `subject` percent-encoded as a segment of a URL path, so that subjects containing `/`, `?`,
+/// `#`, spaces or non-ASCII characters address the subject rather than another resource.
+fn path_segment(subject: &str) -> String {
+    let mut segment = String::with_capacity(subject.len());
+    for &byte in subject.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            segment.push(char::from(byte));
+        } else {
+            const HEX: &[u8; 16] = b"0123456789ABCDEF";
+            segment.push('%');
+            segment.push(char::from(HEX[usize::from(byte >> 4)]));
+            segment.push(char::from(HEX[usize::from(byte & 0xF)]));
+        }
+    }
+    segment
+}
+
+/// A client for a Confluent Schema Registry.
+pub struct SchemaRegistry<Tr> {
+    transport: Tr,
+}
+
+impl<Tr> SchemaRegistry<Tr>
+where
+    Tr: Transport,
+{
+    /// Create a client sending requests with `transport`.
+    #[must_use]
+    pub fn new(transport: Tr) -> Self {
+        Self { transport }
+    }
+
+    /// Consume the client, returning its transport.
+    #[must_use]
+    pub fn into_transport(self) -> Tr {
+        self.transport
+    }
+
+    async fn post<R>(&self, path: &str, schema: &impl Serialize) -> Result<R, Error<Tr::Error>>
+    where
+        R: for<'de> Deserialize<'de>,
+    {
+        let body = serde_json::to_vec(&SchemaRequest {
+            schema_type: "JSON",
+            schema: serde_json::to_string(schema).map_err(Error::Json)?,
+        })
+        .map_err(Error::Json)?;
+        let response = self
+            .transport
+            .post(path, body)
+            .await
+            .map_err(Error::Transport)?;
+        serde_json::from_slice(&response).map_err(Error::Json)
+    }
+
+    /// Register every declared version of `T` under `subject`, from oldest to newest, returning
+    /// the schema identifier assigned to each version. `subject` is percent-encoded in the path
+    /// of each request.
+    ///
+    /// Versions which have already been registered are assigned their existing identifiers by
+    /// the registry.
+    ///
+    /// ## Errors
+    ///
+    /// Fails on the first version the registry rejects (e.g., because it is incompatible with
+    /// the previously registered schema).
+    pub async fn register<T>(
+        &self,
+        subject: &str,
+    ) -> Result<Vec<(&'static str, u64)>, Error<Tr::Error>>
+    where
+        T: Versioned,
+        AnyVersion<T>: Dispatch<T, SchemaFor>,
+    {
+        let path = format!("/subjects/{}/versions", path_segment(subject));
+        let mut ids = Vec::new();
+
+        for (version, schema) in json_schemas::<T>() {
+            let response: RegisterResponse = self.post(&path, &schema).await?;
+            ids.push((version, response.id));
+        }
+
+        Ok(ids)
+    }
+
+    /// Check whether the latest version of `T` is compatible with the latest schema registered
+    /// under `subject`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the registry cannot be queried (e.g., because `subject` does not exist).
+    pub async fn is_compatible<T>(&self, subject: &str) -> Result<bool, Error<Tr::Error>>
+    where
+        T: Versioned,
+        AnyVersion<T>: Dispatch<T, SchemaFor>,
+    {
+        let Some((_, schema)) = json_schemas::<T>().pop() else {
+            return Ok(true);
+        };
+
+        let path = format!(
+            "/compatibility/subjects/{}/versions/latest",
+            path_segment(subject)
+        );
+        let response: CompatibilityResponse = self.post(&path, &schema).await?;
+        Ok(response.is_compatible)
+    }
+}
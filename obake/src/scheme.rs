@@ -0,0 +1,247 @@
+//! Schemes for the version numbers of [`versioned`](crate::versioned) data-structures.
+//!
+//! By default, versions are declared as semantic version numbers ([`Semver`]). Marking a
+//! data-structure with `#[obake(scheme = integer)]` declares its versions as integers instead
+//! ([`Integer`], e.g., `"3"`), and `#[obake(scheme = calver)]` as dates of the form
+//! `YYYY.MM.DD` ([`Calver`], e.g., `"2024.01.15"`). Names and documentation are generated from
+//! the versions as written (e.g., `Foo_v3` or `Foo_v2024_01_15`).
+//!
+//! Any other path names a [`VersionScheme`] of your own, whose versions may be any strings. As
+//! the scheme can't be consulted at compile time, these versions must be declared from oldest to
+//! newest (which [`is_ordered`] can be used to test).
+//!
+//! Outside of semantic versioning, version requirements (e.g., in `#[obake(cfg(...))]`) are
+//! comma-separated lists of versions which must all be matched, each prefixed by one of `=`, `>`,
+//! `>=`, `<` or `<=` (where a missing operator means `=`), or `*`.
+//!
+//! ```
+//! use obake::Versioned;
+//!
+//! #[obake::versioned]
+//! #[obake(scheme = integer)]
+//! #[obake(version("1"))]
+//! #[obake(version("2"))]
+//! struct Foo {
+//!     #[obake(cfg(">=2"))]
+//!     bar: u32,
+//! }
+//! #
+//! # impl From<Foo!["1"]> for Foo!["2"] {
+//! #     fn from(_: Foo!["1"]) -> Self {
+//! #         Self { bar: 0 }
+//! #     }
+//! # }
+//!
+//! let _ = Foo_v1 {};
+//! assert_eq!(Foo::VERSIONS, &["1", "2"]);
+//! assert!(obake::scheme::is_ordered::<Foo>());
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::Versioned;
+
+/// A scheme for version numbers, ordering the versions written in it.
+pub trait VersionScheme {
+    /// Compare the versions `a` and `b`, or return `None` if either isn't a valid version in this
+    /// scheme.
+    fn compare(a: &str, b: &str) -> Option<Ordering>;
+}
+
+/// Semantic version numbers (e.g., `"0.1.0"`), ordered by precedence, so versions differing only
+/// in build metadata are equal.
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use obake::scheme::{Semver, VersionScheme};
+///
+/// assert_eq!(Semver::compare("0.2.0", "0.10.0"), Some(Ordering::Less));
+/// assert_eq!(Semver::compare("1.0.0-rc.1", "1.0.0"), Some(Ordering::Less));
+/// assert_eq!(Semver::compare("1.0.0+a", "1.0.0+b"), Some(Ordering::Equal));
+/// assert_eq!(Semver::compare("1.0", "1.0.0"), None);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Semver;
+
+/// Integer version numbers (e.g., `"3"`).
+#[derive(Copy, Clone, Debug)]
+pub struct Integer;
+
+/// Calendar versions of the form `YYYY.MM.DD` (e.g., `"2024.01.15"`).
+#[derive(Copy, Clone, Debug)]
+pub struct Calver;
+
+/// Parse a non-negative integer without leading zeros.
+fn parse_numeric(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) || s.len() > 1 && s.starts_with('0') {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+/// Whether `s` is a dot-separated list of non-empty alphanumeric (or `-`) identifiers.
+fn is_identifiers(s: &str) -> bool {
+    s.split('.')
+        .all(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'))
+}
+
+/// Whether `s` is a valid pre-release, whose numeric identifiers can't have leading zeros either.
+fn is_pre_release(s: &str) -> bool {
+    is_identifiers(s)
+        && s.split('.')
+            .filter(|id| id.bytes().all(|b| b.is_ascii_digit()))
+            .all(|id| parse_numeric(id).is_some())
+}
+
+/// The major, minor and patch components and the pre-release of a semantic version number.
+fn parse_semver(version: &str) -> Option<([u64; 3], Option<&str>)> {
+    let version = match version.split_once('+') {
+        Some((version, build)) if is_identifiers(build) => version,
+        Some(_) => return None,
+        None => version,
+    };
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) if is_pre_release(pre) => (core, Some(pre)),
+        Some(_) => return None,
+        None => (version, None),
+    };
+
+    let mut parts = core.split('.');
+    let mut numbers = [0; 3];
+    for number in &mut numbers {
+        *number = parse_numeric(parts.next()?)?;
+    }
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((numbers, pre))
+}
+
+fn compare_pre(a: Option<&str>, b: Option<&str>) -> Ordering {
+    let (a, b) = match (a, b) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        (Some(a), Some(b)) => (a, b),
+    };
+
+    let (mut a, mut b) = (a.split('.'), b.split('.'));
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (parse_numeric(a), parse_numeric(b)) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => a.cmp(b),
+            },
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+impl VersionScheme for Semver {
+    fn compare(a: &str, b: &str) -> Option<Ordering> {
+        let (a, a_pre) = parse_semver(a)?;
+        let (b, b_pre) = parse_semver(b)?;
+
+        Some(a.cmp(&b).then_with(|| compare_pre(a_pre, b_pre)))
+    }
+}
+
+impl VersionScheme for Integer {
+    fn compare(a: &str, b: &str) -> Option<Ordering> {
+        Some(parse_numeric(a)?.cmp(&parse_numeric(b)?))
+    }
+}
+
+fn parse_calver(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.');
+    let mut date = [0; 3];
+    for (component, len) in date.iter_mut().zip([4, 2, 2].iter()) {
+        let part = parts.next().filter(|part| part.len() == *len)?;
+        if !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        *component = part.parse().ok()?;
+    }
+
+    let [y, m, d] = date;
+    if parts.next().is_some() || !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+
+    Some((y, m, d))
+}
+
+impl VersionScheme for Calver {
+    fn compare(a: &str, b: &str) -> Option<Ordering> {
+        Some(parse_calver(a)?.cmp(&parse_calver(b)?))
+    }
+}
+
+/// Compare the versions `a` and `b` of `T` under its scheme, or return `None` if either isn't a
+/// valid version in that scheme.
+///
+/// Neither `a` nor `b` need be declared, so this can be used, e.g., to tell data from a newer
+/// version of an application apart from data which is simply malformed.
+#[must_use]
+pub fn compare<T>(a: &str, b: &str) -> Option<Ordering>
+where
+    T: Versioned,
+{
+    <T::Scheme as VersionScheme>::compare(a, b)
+}
+
+/// Whether the declared versions of `T` are valid and strictly ordered from oldest to newest
+/// under its scheme.
+///
+/// This always holds for the built-in schemes, but versions in a scheme of your own are ordered
+/// as declared, so this is worth checking in a test.
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use obake::scheme::VersionScheme;
+///
+/// /// Release channels, from least to most stable.
+/// struct Channel;
+///
+/// impl VersionScheme for Channel {
+///     fn compare(a: &str, b: &str) -> Option<Ordering> {
+///         let rank = |x| ["alpha", "beta", "stable"].iter().position(|y| *y == x);
+///         Some(rank(a)?.cmp(&rank(b)?))
+///     }
+/// }
+///
+/// #[obake::versioned]
+/// #[obake(scheme = Channel)]
+/// #[obake(version("alpha"))]
+/// #[obake(version("beta"))]
+/// struct Foo {}
+/// #
+/// # impl From<Foo!["alpha"]> for Foo!["beta"] {
+/// #     fn from(_: Foo!["alpha"]) -> Self {
+/// #         Self {}
+/// #     }
+/// # }
+///
+/// assert!(obake::scheme::is_ordered::<Foo>());
+/// ```
+#[must_use]
+pub fn is_ordered<T>() -> bool
+where
+    T: Versioned,
+{
+    T::VERSIONS
+        .windows(2)
+        .all(|pair| compare::<T>(pair[0], pair[1]) == Some(Ordering::Less))
+}
@@ -0,0 +1,438 @@
+//! Key-value storage of [`versioned`](crate::versioned) data-structures with upgrade-on-read.
+//!
+//! A [`VersionedTree`] stores values alongside the semantic version number they were written
+//! with. Reading a value decodes whichever version was stored and migrates it to the latest
+//! version, optionally writing the upgraded encoding back (see [`WriteBack`]). Records written by
+//! older releases of an application can also be upgraded in bulk with
+//! [`VersionedTree::compact`].
+//!
+//! Storage is provided by a [`Backend`]. Implementations are provided for
+//! `RefCell<BTreeMap<Vec<u8>, Vec<u8>>>` (useful for testing), [`sled::Tree`] (requires the
+//! feature `sled`) and [`RedbBackend`] (requires the feature `redb`).
+//!
+//! Requires the features `alloc` and `serde`.
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use std::cell::RefCell;
+//! use std::collections::BTreeMap;
+//!
+//! use obake::codec::Json;
+//! use obake::store::VersionedTree;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg(">=0.2"))]
+//!     bar: u32,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(_: Foo!["0.1.0"]) -> Self {
+//!         Self { bar: 42 }
+//!     }
+//! }
+//!
+//! let tree = VersionedTree::<Foo, _, _>::new(RefCell::new(BTreeMap::new()), Json);
+//! tree.insert(b"x", &Foo { bar: 0 }).unwrap();
+//! assert_eq!(tree.get(b"x").unwrap(), Some(Foo { bar: 0 }));
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::Serialize;
+
+use crate::codec::{
+    decode_framed_with, encode_framed, Codec, Decode, Envelope, FrameError, UnsupportedVersion,
+    VersionPrefixed,
+};
+use crate::{
+    try_upgrade_rendered, AnyVersion, Dispatch, StepError, TryUpgrade, VersionOf, VersionTagged,
+    Versioned,
+};
+
+/// A byte-oriented key-value store used as the storage for a [`VersionedTree`].
+pub trait Backend {
+    /// The error produced by failed storage operations.
+    type Error;
+
+    /// Fetch the value stored under `key`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the underlying storage cannot be read.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Store `value` under `key`, replacing any existing value.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the underlying storage cannot be written.
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), Self::Error>;
+
+    /// Remove the value stored under `key`, if any.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the underlying storage cannot be written.
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error>;
+
+    /// List all keys currently in the store.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the underlying storage cannot be read.
+    fn keys(&self) -> Result<Vec<Vec<u8>>, Self::Error>;
+}
+
+impl Backend for RefCell<BTreeMap<Vec<u8>, Vec<u8>>> {
+    type Error = Infallible;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.borrow().get(key).cloned())
+    }
+
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
+        self.borrow_mut().insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        self.borrow_mut().remove(key);
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.borrow().keys().cloned().collect())
+    }
+}
+
+#[cfg(feature = "sled")]
+impl Backend for sled::Tree {
+    type Error = sled::Error;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(sled::Tree::get(self, key)?.map(|value| value.to_vec()))
+    }
+
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
+        sled::Tree::insert(self, key, value).map(|_| ())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        sled::Tree::remove(self, key).map(|_| ())
+    }
+
+    fn keys(&self) -> Result<Vec<Vec<u8>>, Self::Error> {
+        self.iter()
+            .keys()
+            .map(|key| key.map(|key| key.to_vec()))
+            .collect()
+    }
+}
+
+/// A [`Backend`] storing values in a single table of a [`redb::Database`].
+///
+/// Requires the feature `redb`.
+#[cfg(feature = "redb")]
+pub struct RedbBackend {
+    db: redb::Database,
+    table: redb::TableDefinition<'static, &'static [u8], &'static [u8]>,
+}
+
+#[cfg(feature = "redb")]
+impl RedbBackend {
+    /// Use the table named `table` in `db` as storage.
+    #[must_use]
+    pub fn new(db: redb::Database, table: &'static str) -> Self {
+        Self {
+            db,
+            table: redb::TableDefinition::new(table),
+        }
+    }
+
+    /// The underlying database.
+    #[must_use]
+    pub fn database(&self) -> &redb::Database {
+        &self.db
+    }
+}
+
+#[cfg(feature = "redb")]
+#[allow(clippy::result_large_err)]
+impl Backend for RedbBackend {
+    type Error = redb::Error;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let txn = self.db.begin_read()?;
+        let table = match txn.open_table(self.table) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(table.get(key)?.map(|value| value.value().to_vec()))
+    }
+
+    fn insert(&self, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
+        let txn = self.db.begin_write()?;
+        txn.open_table(self.table)?.insert(key, value)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), Self::Error> {
+        let txn = self.db.begin_write()?;
+        txn.open_table(self.table)?.remove(key)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<Vec<u8>>, Self::Error> {
+        use redb::ReadableTable;
+
+        let txn = self.db.begin_read()?;
+        let table = match txn.open_table(self.table) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        table
+            .iter()?
+            .map(|entry| Ok(entry?.0.value().to_vec()))
+            .collect()
+    }
+}
+
+/// Controls whether reading an outdated record from a [`VersionedTree`] also stores the
+/// upgraded encoding.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WriteBack {
+    /// Outdated records are only upgraded in memory (or by [`VersionedTree::compact`]).
+    Never,
+    /// Outdated records are re-encoded at the latest version the first time they are read.
+    Lazy,
+}
+
+/// Errors produced by a [`VersionedTree`].
+#[derive(Debug)]
+pub enum Error<B, C> {
+    /// The [`Backend`] failed.
+    Backend(B),
+    /// The [`Codec`] failed.
+    Codec(C),
+    /// A stored record did not begin with a well-formed version header.
+    Corrupt,
+    /// A stored record was written at a version that is not declared.
+    UnknownVersion(String),
+    /// A value could not be stored as the [`Envelope`] can't encode its version.
+    Unframeable(String),
+    /// A stored record failed to migrate to the latest version (see [`TryUpgrade`]).
+    Migrate(StepError<String>),
+}
+
+impl<B, C> fmt::Display for Error<B, C>
+where
+    B: fmt::Display,
+    C: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Backend(err) => write!(f, "storage error: {err}"),
+            Self::Codec(err) => write!(f, "codec error: {err}"),
+            Self::Corrupt => write!(f, "stored record has a malformed version header"),
+            Self::UnknownVersion(version) => {
+                write!(f, "stored record has undeclared version {version}")
+            }
+            Self::Unframeable(version) => write!(f, "version {version} can't be stored"),
+            Self::Migrate(err) => write!(f, "stored record failed to migrate: {err}"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "error_in_core"))]
+impl<B, C> crate::error::Error for Error<B, C>
+where
+    B: fmt::Debug + fmt::Display,
+    C: fmt::Debug + fmt::Display,
+{
+}
+
+fn frame_error<B, C>(err: FrameError<C>) -> Error<B, C> {
+    match err {
+        FrameError::Codec(err) => Error::Codec(err),
+        FrameError::BadMagic | FrameError::Malformed => Error::Corrupt,
+        FrameError::UnknownVersion(version) => Error::UnknownVersion(version),
+        FrameError::Unframeable(version) => Error::Unframeable(version),
+    }
+}
+
+/// A key-value store of values of the versioned data-structure `T`.
+///
+/// Each record is framed by the [`Envelope`] `E` with the semantic version number it was encoded
+/// at, followed by its encoding under the codec `C`. By default, records are framed by
+/// [`VersionPrefixed`], and a tree can be opened over records framed by a legacy format with
+/// [`VersionedTree::with_envelope`].
+pub struct VersionedTree<T, B, C, E = VersionPrefixed> {
+    backend: B,
+    codec: C,
+    envelope: E,
+    write_back: WriteBack,
+    unsupported_version: UnsupportedVersion,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, B, C> VersionedTree<T, B, C> {
+    /// Create a tree stored in `backend` using `codec`, which never writes back upgraded
+    /// records on read and rejects records written at undeclared versions.
+    #[must_use]
+    pub fn new(backend: B, codec: C) -> Self {
+        Self {
+            backend,
+            codec,
+            envelope: VersionPrefixed,
+            write_back: WriteBack::Never,
+            unsupported_version: UnsupportedVersion::Reject,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, B, C, E> VersionedTree<T, B, C, E> {
+    /// Set the [`WriteBack`] policy of this tree.
+    #[must_use]
+    pub fn with_write_back(mut self, write_back: WriteBack) -> Self {
+        self.write_back = write_back;
+        self
+    }
+
+    /// Set the [`UnsupportedVersion`] policy for records written at undeclared versions (e.g.,
+    /// by a newer release sharing the same storage).
+    #[must_use]
+    pub fn with_unsupported_version(mut self, policy: UnsupportedVersion) -> Self {
+        self.unsupported_version = policy;
+        self
+    }
+
+    /// Set the [`Envelope`] framing the records of this tree.
+    #[must_use]
+    pub fn with_envelope<F>(self, envelope: F) -> VersionedTree<T, B, C, F> {
+        VersionedTree {
+            backend: self.backend,
+            codec: self.codec,
+            envelope,
+            write_back: self.write_back,
+            unsupported_version: self.unsupported_version,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The underlying storage.
+    #[must_use]
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Consume the tree, returning the underlying storage.
+    #[must_use]
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+}
+
+impl<T, B, C, E> VersionedTree<T, B, C, E>
+where
+    T: Versioned + VersionOf<T> + Serialize,
+    B: Backend,
+    C: Codec,
+    E: Envelope,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, C>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error<B::Error, C::Error>> {
+        encode_framed(&self.envelope, &self.codec, value).map_err(frame_error)
+    }
+
+    fn decode(&self, record: &[u8]) -> Result<(T, bool), Error<B::Error, C::Error>> {
+        let policy = self.unsupported_version;
+        let (tagged, _) =
+            decode_framed_with::<T, C, E>(&self.envelope, &self.codec, record, policy)
+                .map_err(frame_error)?;
+        let outdated = tagged.version_str() != <T as VersionOf<T>>::VERSION;
+        let value = try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate)?;
+        Ok((value, outdated))
+    }
+
+    /// Fetch and upgrade the value stored under `key`.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the backend fails, if the stored record is malformed or was written at an
+    /// undeclared version, or if the stored payload cannot be decoded or migrated.
+    pub fn get(&self, key: &[u8]) -> Result<Option<T>, Error<B::Error, C::Error>> {
+        let Some(record) = self.backend.get(key).map_err(Error::Backend)? else {
+            return Ok(None);
+        };
+
+        let (value, outdated) = self.decode(&record)?;
+        if outdated && self.write_back == WriteBack::Lazy {
+            self.insert(key, &value)?;
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Store `value` under `key`, encoded at the latest version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the backend fails or if `value` cannot be encoded.
+    pub fn insert(&self, key: &[u8], value: &T) -> Result<(), Error<B::Error, C::Error>> {
+        let record = self.encode(value)?;
+        self.backend.insert(key, &record).map_err(Error::Backend)
+    }
+
+    /// Remove the value stored under `key`, if any.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the backend fails.
+    pub fn remove(&self, key: &[u8]) -> Result<(), Error<B::Error, C::Error>> {
+        self.backend.remove(key).map_err(Error::Backend)
+    }
+
+    /// Rewrite every record not encoded at the latest version, returning the number of records
+    /// upgraded.
+    ///
+    /// ## Errors
+    ///
+    /// Fails on the first record that cannot be read, decoded or rewritten. Records upgraded
+    /// before the failure remain upgraded.
+    pub fn compact(&self) -> Result<usize, Error<B::Error, C::Error>> {
+        let mut upgraded = 0;
+
+        for key in self.backend.keys().map_err(Error::Backend)? {
+            let Some(record) = self.backend.get(&key).map_err(Error::Backend)? else {
+                continue;
+            };
+
+            let (value, outdated) = self.decode(&record)?;
+            if outdated {
+                self.insert(&key, &value)?;
+                upgraded += 1;
+            }
+        }
+
+        Ok(upgraded)
+    }
+}
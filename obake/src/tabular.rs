@@ -0,0 +1,420 @@
+//! Reading tabular data (e.g., exported CSV or Parquet files) of any declared version of a
+//! [`versioned`](crate::versioned) `struct`.
+//!
+//! Marking a `struct` with `#[obake(tabular)]` implements [`Columns`] for every declared
+//! version, listing the columns (i.e., fields) of that version in order, and, with the feature
+//! `arrow`, [`ArrowSchema`] describing the same columns as an [Arrow](https://arrow.apache.org)
+//! schema. The version of a file can then be recovered from its header or schema alone, with
+//! [`detect_columns`] and [`detect_schema`], and with the feature `csv`, the rows of a CSV file
+//! can be read and migrated to the latest version with [`CsvReader`].
+//!
+//! Parquet files carry an Arrow schema, so the version of a Parquet file can be found by
+//! passing its Arrow schema to [`detect_schema`].
+//!
+//! Requires the feature `csv` or `arrow`.
+//!
+//! ```
+//! # #[cfg(feature = "csv")]
+//! # {
+//! use obake::tabular::{Columns, CsvReader};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(tabular)]
+//! #[derive(Debug, PartialEq, serde::Deserialize)]
+//! struct Foo {
+//!     bar: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     baz: String,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(from: Foo!["0.1.0"]) -> Self {
+//!         Self {
+//!             bar: from.bar,
+//!             baz: String::new(),
+//!         }
+//!     }
+//! }
+//!
+//! assert_eq!(<Foo!["0.1.0"]>::COLUMNS, &["bar"]);
+//! assert_eq!(<Foo!["0.2.0"]>::COLUMNS, &["bar", "baz"]);
+//!
+//! let mut reader = CsvReader::<Foo, _>::from_reader("bar\n1\n2\n".as_bytes()).unwrap();
+//! assert_eq!(reader.version(), "0.1.0");
+//!
+//! let rows: Result<Vec<_>, _> = reader.collect();
+//! assert_eq!(
+//!     rows.unwrap(),
+//!     [
+//!         Foo { bar: 1, baz: String::new() },
+//!         Foo { bar: 2, baz: String::new() },
+//!     ],
+//! );
+//! # }
+//! ```
+
+#[cfg(feature = "csv")]
+use core::fmt;
+#[cfg(feature = "csv")]
+use core::marker::PhantomData;
+
+#[cfg(feature = "arrow")]
+use alloc::sync::Arc;
+
+#[cfg(feature = "arrow")]
+use arrow_schema::{DataType, Field, Fields, Schema};
+#[cfg(feature = "csv")]
+use serde::de::DeserializeOwned;
+
+use crate::{AnyVersion, Dispatch, VersionOf, VersionVisitor, Versioned, VisitVersion};
+#[cfg(feature = "csv")]
+use crate::{StepError, TryUpgrade};
+
+/// Automatically implemented for every declared version of a `struct` marked with
+/// `#[obake(tabular)]`.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`](crate::versioned) to derive it.
+pub trait Columns {
+    /// The columns of this version, in order.
+    const COLUMNS: &'static [&'static str];
+}
+
+/// The [`VersionVisitor`] used by [`detect_columns`] to match a header against the columns of a
+/// particular version.
+#[derive(Copy, Clone, Debug)]
+pub struct MatchColumns<'a> {
+    columns: &'a [&'a str],
+}
+
+impl<T> VersionVisitor<T> for MatchColumns<'_>
+where
+    T: Versioned,
+{
+    type Output = bool;
+}
+
+impl<T, V> VisitVersion<T, V> for MatchColumns<'_>
+where
+    T: Versioned,
+    V: VersionOf<T> + Columns,
+{
+    fn visit(self) -> bool {
+        V::COLUMNS == self.columns
+    }
+}
+
+/// Find the newest declared version of `T` whose columns are exactly `columns`.
+#[must_use]
+pub fn detect_columns<T>(columns: &[&str]) -> Option<&'static str>
+where
+    T: Versioned,
+    for<'a> AnyVersion<T>: Dispatch<T, MatchColumns<'a>>,
+{
+    T::VERSIONS.iter().rev().copied().find(|version| {
+        AnyVersion::<T>::dispatch(version, MatchColumns { columns }).unwrap_or(false)
+    })
+}
+
+/// Types of columns that can be described by an Arrow [`DataType`].
+///
+/// Requires the feature `arrow`.
+#[cfg(feature = "arrow")]
+pub trait ArrowType {
+    /// Whether values of this type can be null.
+    const NULLABLE: bool = false;
+
+    /// The Arrow type of values of this type.
+    fn data_type() -> DataType;
+}
+
+#[cfg(feature = "arrow")]
+macro_rules! impl_arrow_type {
+    ($($ty:ty => $data_type:expr),* $(,)?) => {
+        $(
+            impl ArrowType for $ty {
+                fn data_type() -> DataType {
+                    $data_type
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "arrow")]
+impl_arrow_type! {
+    bool => DataType::Boolean,
+    i8 => DataType::Int8,
+    i16 => DataType::Int16,
+    i32 => DataType::Int32,
+    i64 => DataType::Int64,
+    u8 => DataType::UInt8,
+    u16 => DataType::UInt16,
+    u32 => DataType::UInt32,
+    u64 => DataType::UInt64,
+    f32 => DataType::Float32,
+    f64 => DataType::Float64,
+    alloc::string::String => DataType::Utf8,
+    alloc::vec::Vec<u8> => DataType::Binary,
+}
+
+#[cfg(feature = "arrow")]
+impl<T> ArrowType for Option<T>
+where
+    T: ArrowType,
+{
+    const NULLABLE: bool = true;
+
+    fn data_type() -> DataType {
+        T::data_type()
+    }
+}
+
+/// The Arrow [`Field`] describing a column named `name` holding values of type `T`.
+///
+/// Requires the feature `arrow`.
+#[cfg(feature = "arrow")]
+#[must_use]
+pub fn arrow_field<T>(name: &str) -> Field
+where
+    T: ArrowType,
+{
+    Field::new(name, T::data_type(), T::NULLABLE)
+}
+
+/// Automatically implemented for every declared version of a `struct` marked with
+/// `#[obake(tabular)]`.
+///
+/// Requires the feature `arrow`.
+///
+/// ## Note
+///
+/// Not intended to be hand-implemented, use [`versioned`](crate::versioned) to derive it.
+#[cfg(feature = "arrow")]
+pub trait ArrowSchema: Columns {
+    /// The Arrow fields describing the columns of this version, in order.
+    fn arrow_fields() -> Fields;
+
+    /// The Arrow schema describing the columns of this version.
+    #[must_use]
+    fn arrow_schema() -> Schema {
+        Schema::new(Self::arrow_fields())
+    }
+}
+
+/// The [`VersionVisitor`] used by [`detect_schema`] to match an Arrow schema against the
+/// columns of a particular version.
+///
+/// Requires the feature `arrow`.
+#[cfg(feature = "arrow")]
+#[derive(Copy, Clone, Debug)]
+pub struct MatchSchema<'a> {
+    fields: &'a Fields,
+}
+
+#[cfg(feature = "arrow")]
+impl<T> VersionVisitor<T> for MatchSchema<'_>
+where
+    T: Versioned,
+{
+    type Output = bool;
+}
+
+#[cfg(feature = "arrow")]
+impl<T, V> VisitVersion<T, V> for MatchSchema<'_>
+where
+    T: Versioned,
+    V: VersionOf<T> + ArrowSchema,
+{
+    fn visit(self) -> bool {
+        let expected = V::arrow_fields();
+        expected.len() == self.fields.len()
+            && expected
+                .iter()
+                .zip(self.fields.iter())
+                .all(|(a, b)| same_column(a, b))
+    }
+}
+
+#[cfg(feature = "arrow")]
+fn same_column(a: &Arc<Field>, b: &Arc<Field>) -> bool {
+    a.name() == b.name() && a.data_type() == b.data_type() && a.is_nullable() == b.is_nullable()
+}
+
+/// Find the newest declared version of `T` whose columns match the fields of `schema`, by name,
+/// type and nullability.
+///
+/// Requires the feature `arrow`.
+#[cfg(feature = "arrow")]
+#[must_use]
+pub fn detect_schema<T>(schema: &Schema) -> Option<&'static str>
+where
+    T: Versioned,
+    for<'a> AnyVersion<T>: Dispatch<T, MatchSchema<'a>>,
+{
+    let fields = schema.fields();
+    T::VERSIONS
+        .iter()
+        .rev()
+        .copied()
+        .find(|version| AnyVersion::<T>::dispatch(version, MatchSchema { fields }).unwrap_or(false))
+}
+
+/// Errors that can occur while reading a CSV file with a [`CsvReader`].
+///
+/// Requires the feature `csv`.
+#[cfg(feature = "csv")]
+#[derive(Debug)]
+pub enum Error {
+    /// The header of the file does not match the columns of any declared version.
+    Unrecognized,
+    /// The file could not be read, or a row could not be deserialized as the detected version.
+    Csv(csv::Error),
+    /// A row was deserialized, but failed to migrate to the latest version (see
+    /// [`TryUpgrade`]).
+    Migrate(StepError<alloc::string::String>),
+}
+
+#[cfg(feature = "csv")]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unrecognized => write!(f, "header does not match any declared version"),
+            Self::Csv(err) => write!(f, "{err}"),
+            Self::Migrate(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        Self::Csv(err)
+    }
+}
+
+/// The [`VersionVisitor`] used by [`CsvReader`] to deserialize a row as a particular version.
+///
+/// Requires the feature `csv`.
+#[cfg(feature = "csv")]
+#[derive(Copy, Clone, Debug)]
+pub struct DecodeRecord<'a> {
+    record: &'a csv::StringRecord,
+    headers: &'a csv::StringRecord,
+}
+
+#[cfg(feature = "csv")]
+impl<T> VersionVisitor<T> for DecodeRecord<'_>
+where
+    T: Versioned,
+{
+    type Output = csv::Result<AnyVersion<T>>;
+}
+
+#[cfg(feature = "csv")]
+impl<T, V> VisitVersion<T, V> for DecodeRecord<'_>
+where
+    T: Versioned,
+    V: VersionOf<T> + DeserializeOwned,
+{
+    fn visit(self) -> Self::Output {
+        self.record
+            .deserialize::<V>(Some(self.headers))
+            .map(Into::into)
+    }
+}
+
+/// Reads the rows of a CSV file of any declared version of `T`, detected from the header of the
+/// file, and migrates them to the latest version.
+///
+/// Requires the feature `csv`.
+#[cfg(feature = "csv")]
+pub struct CsvReader<T, R> {
+    reader: csv::Reader<R>,
+    headers: csv::StringRecord,
+    version: &'static str,
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "csv")]
+impl<T, R> CsvReader<T, R>
+where
+    T: Versioned,
+    R: std::io::Read,
+    for<'a> AnyVersion<T>: Dispatch<T, MatchColumns<'a>>,
+{
+    /// Read CSV data from `reader`, detecting its version from its header.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the header cannot be read, or does not match the columns of any declared
+    /// version.
+    pub fn from_reader(reader: R) -> Result<Self, Error> {
+        Self::new(csv::Reader::from_reader(reader))
+    }
+
+    /// Read CSV data from a configured [`csv::Reader`], detecting its version from its header.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the header cannot be read, or does not match the columns of any declared
+    /// version.
+    pub fn new(mut reader: csv::Reader<R>) -> Result<Self, Error> {
+        let headers = reader.headers()?.clone();
+        let columns: std::vec::Vec<_> = headers.iter().collect();
+        let version = detect_columns::<T>(&columns).ok_or(Error::Unrecognized)?;
+
+        Ok(Self {
+            reader,
+            headers,
+            version,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "csv")]
+impl<T, R> CsvReader<T, R> {
+    /// The declared version detected from the header of the file.
+    #[must_use]
+    pub fn version(&self) -> &'static str {
+        self.version
+    }
+}
+
+#[cfg(feature = "csv")]
+impl<T, R> Iterator for CsvReader<T, R>
+where
+    T: Versioned,
+    R: std::io::Read,
+    for<'a> AnyVersion<T>: Dispatch<T, DecodeRecord<'a>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = csv::StringRecord::new();
+        match self.reader.read_record(&mut record) {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => return Some(Err(err.into())),
+        }
+
+        let visitor = DecodeRecord {
+            record: &record,
+            headers: &self.headers,
+        };
+        let tagged = AnyVersion::<T>::dispatch(self.version, visitor)?;
+        Some(match tagged {
+            Ok(tagged) => crate::try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate),
+            Err(err) => Err(err.into()),
+        })
+    }
+}
@@ -0,0 +1,46 @@
+//! Counters of the versions of [`versioned`](crate::versioned) data-structures decoded and
+//! migrated at runtime, recorded through the [`metrics`](https://docs.rs/metrics) facade.
+//!
+//! Every payload decoded by the codec helpers (e.g., `obake::json::from_slice`, `obake::save`
+//! and `obake::store`) increments [`DECODED`], and every value the helpers of
+//! `obake::collections` migrate (or attempt to) increments [`UPGRADED`]. Both are labelled with
+//! the type (as [`core::any::type_name`] names it) and the version it was written at, so
+//! operators can see how much traffic in old formats remains before dropping support for them.
+//!
+//! Counters are only recorded once a recorder (e.g., a Prometheus exporter) is installed.
+//!
+//! Requires the feature `metrics`.
+
+use crate::{AnyVersion, VersionTagged, Versioned};
+
+/// The counter incremented for every payload decoded, labelled by [`TYPE`] and [`VERSION`].
+pub const DECODED: &str = "obake_decoded_total";
+
+/// The counter incremented for every value migrated (or attempted to be) to the latest version,
+/// labelled by [`TYPE`] and [`VERSION`].
+pub const UPGRADED: &str = "obake_upgraded_total";
+
+/// The label naming the versioned data-structure.
+pub const TYPE: &str = "type";
+
+/// The label naming the version a payload (or value) was written at.
+pub const VERSION: &str = "version";
+
+/// Count a payload of `T` decoded at `version`.
+pub(crate) fn record_decoded<T>(version: &'static str)
+where
+    T: Versioned,
+{
+    metrics::counter!(DECODED, TYPE => core::any::type_name::<T>(), VERSION => version)
+        .increment(1);
+}
+
+/// Count a value of `T` about to be migrated to the latest version.
+pub(crate) fn record_upgraded<T>(tagged: &AnyVersion<T>)
+where
+    T: Versioned,
+{
+    let version = tagged.version_str();
+    metrics::counter!(UPGRADED, TYPE => core::any::type_name::<T>(), VERSION => version)
+        .increment(1);
+}
@@ -0,0 +1,165 @@
+//! Reusing the example values curated for each version of a [`versioned`](crate::versioned)
+//! data-structure with `#[obake(test_values(...))]` (see [`TestValues`]).
+//!
+//! [`assert_migrate`] migrates every value to the latest version, as the test generated for the
+//! data-structure does. [`named`] names each value uniquely (e.g., `0.1.0-1` for the second value
+//! of version `0.1.0`), and [`snapshots`] encodes each value at its own version, for snapshot
+//! tests pinning the encodings of historical versions or as the seeds of a fuzzing corpus.
+//!
+//! Requires the feature `alloc` (and `serde` for [`snapshots`]).
+//!
+//! ```
+//! # #[cfg(feature = "json")] {
+//! use obake::codec::Json;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(test_values("0.1.0", legacy_foos))]
+//! #[obake(test_values("0.2.0", foos))]
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! struct Foo {
+//!     #[obake(cfg(">=0.2"))]
+//!     bar: u32,
+//! }
+//!
+//! impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//!     fn from(_: Foo!["0.1.0"]) -> Self {
+//!         Self { bar: 0 }
+//!     }
+//! }
+//!
+//! fn legacy_foos() -> Vec<Foo!["0.1.0"]> {
+//!     vec![Foo_v0_1_0 {}]
+//! }
+//!
+//! fn foos() -> Vec<Foo!["0.2.0"]> {
+//!     vec![Foo { bar: 1 }, Foo { bar: u32::MAX }]
+//! }
+//!
+//! obake::testing::assert_migrate::<Foo>();
+//!
+//! let snapshots = obake::testing::snapshots::<Foo, _>(&Json).unwrap();
+//! assert_eq!(snapshots[0].name, "0.1.0-0");
+//! assert_eq!(snapshots[0].payload, b"{}");
+//! assert_eq!(snapshots[2].name, "0.2.0-1");
+//! assert_eq!(snapshots[2].payload, br#"{"bar":4294967295}"#);
+//! # }
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use crate::codec::{Codec, Encode};
+#[cfg(feature = "serde")]
+use crate::Accept;
+use crate::{AnyVersion, TestValues, TryUpgrade, VersionTagged};
+
+/// Migrate every test value of `T` to the latest version.
+///
+/// ## Panics
+///
+/// Panics if any value fails to migrate, naming the value and the versions either side of the
+/// failing migration.
+#[track_caller]
+pub fn assert_migrate<T>()
+where
+    T: TestValues,
+    AnyVersion<T>: TryUpgrade<T>,
+{
+    for Named { name, value } in named::<T>() {
+        if let Err(err) = value.try_upgrade() {
+            panic!(
+                "test value {} failed to migrate from version {} to version {}",
+                name, err.from, err.to,
+            );
+        }
+    }
+}
+
+/// A test value, named uniquely among those of its data-structure by its version and its
+/// position among the values of that version (e.g., `0.1.0-1`).
+pub struct Named<T>
+where
+    T: TestValues,
+{
+    /// The name of the value.
+    pub name: String,
+    /// The value.
+    pub value: AnyVersion<T>,
+}
+
+impl<T> fmt::Debug for Named<T>
+where
+    T: TestValues,
+    AnyVersion<T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Named")
+            .field("name", &self.name)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// The test values of `T`, each named uniquely, ordered from oldest version to newest.
+#[must_use]
+pub fn named<T>() -> Vec<Named<T>>
+where
+    T: TestValues,
+{
+    let mut named: Vec<Named<T>> = Vec::new();
+    for value in T::test_values() {
+        let version = value.version_str();
+        let index = named
+            .iter()
+            .filter(|named| named.value.version_str() == version)
+            .count();
+        named.push(Named {
+            name: format!("{version}-{index}"),
+            value,
+        });
+    }
+    named
+}
+
+/// A test value encoded at its own version.
+#[cfg(feature = "serde")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Snapshot {
+    /// The name of the value (see [`Named`]), e.g., for a snapshot or seed file.
+    pub name: String,
+    /// The version the value was encoded at.
+    pub version: &'static str,
+    /// The encoded value.
+    pub payload: Vec<u8>,
+}
+
+/// Encode each test value of `T` at its own version with `codec`, ordered from oldest version to
+/// newest.
+///
+/// ## Errors
+///
+/// Fails if any value cannot be encoded.
+#[cfg(feature = "serde")]
+pub fn snapshots<T, C>(codec: &C) -> Result<Vec<Snapshot>, C::Error>
+where
+    T: TestValues,
+    C: Codec,
+    for<'c> AnyVersion<T>: Accept<T, Encode<'c, C>>,
+{
+    named::<T>()
+        .into_iter()
+        .map(|Named { name, value }| {
+            Ok(Snapshot {
+                name,
+                version: value.version_str(),
+                payload: value.accept(Encode::new(codec))?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+//! Migrating several related values of [`versioned`](crate::versioned) data-structures together,
+//! all or nothing.
+//!
+//! [`migrate_all!`] migrates each value to the latest version of its data-structure with
+//! [`TryUpgrade`] (which never fails for data-structures without fallible migrations), returning
+//! the migrated values as a tuple only once every one of them has migrated. Otherwise, it fails
+//! with the errors of every value which failed to migrate, each named by its binding. The values
+//! which did migrate are dropped, so a partially migrated set of documents can't escape to be
+//! stored (or acted upon) alongside ones still in an older schema.
+//!
+//! Requires the feature `alloc`.
+//!
+//! ```
+//! use core::convert::TryFrom;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, PartialEq)]
+//! struct Account {
+//!     #[obake(cfg(">=0.2"))]
+//!     active: bool,
+//! }
+//!
+//! impl From<Account!["0.1.0"]> for Account!["0.2.0"] {
+//!     fn from(_: Account!["0.1.0"]) -> Self {
+//!         Self { active: true }
+//!     }
+//! }
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(fallible(error = &'static str))]
+//! #[derive(Debug, PartialEq)]
+//! struct Profile {
+//!     #[obake(cfg("0.1.0"))]
+//!     age: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     age: u8,
+//! }
+//!
+//! impl TryFrom<Profile!["0.1.0"]> for Profile!["0.2.0"] {
+//!     type Error = &'static str;
+//!
+//!     fn try_from(from: Profile!["0.1.0"]) -> Result<Self, Self::Error> {
+//!         let age = from.age.parse().map_err(|_| "invalid age")?;
+//!         Ok(Self { age })
+//!     }
+//! }
+//!
+//! let account: obake::AnyVersion<Account> = Account_v0_1_0 {}.into();
+//! let profile: obake::AnyVersion<Profile> = Profile_v0_1_0 { age: "42".into() }.into();
+//! let (account, profile) = obake::txn::migrate_all!(
+//!     (account: obake::AnyVersion<Account>),
+//!     (profile: obake::AnyVersion<Profile>),
+//! )
+//! .unwrap();
+//! assert_eq!(account, Account { active: true });
+//! assert_eq!(profile, Profile { age: 42 });
+//!
+//! let account: obake::AnyVersion<Account> = Account_v0_1_0 {}.into();
+//! let profile: obake::AnyVersion<Profile> = Profile_v0_1_0 { age: "old".into() }.into();
+//! let errors = obake::txn::migrate_all!(
+//!     (account: obake::AnyVersion<Account>),
+//!     (profile: obake::AnyVersion<Profile>),
+//! )
+//! .unwrap_err();
+//! assert_eq!(errors.len(), 1);
+//! assert_eq!(errors[0].key, "profile");
+//! assert_eq!(errors[0].error.error, "invalid age");
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use crate::collections::KeyedError;
+use crate::{try_upgrade_rendered, AnyVersion, StepError, TryUpgrade, Versioned};
+
+/// The errors of the values which failed to migrate (see [`migrate_all!`]), in the order they
+/// were given, each named by its binding and rendered with its `Display` implementation (as the
+/// values may be of data-structures with different error types).
+pub type Errors = Vec<KeyedError<&'static str, StepError<String>>>;
+
+/// Migrate several values, each the version-tagged representation of a (possibly different)
+/// [`versioned`](crate::versioned) data-structure, to their latest versions, all or nothing.
+///
+/// Each value is given as `(binding: Type)`, where `binding` names a local of `Type` (e.g.,
+/// `obake::AnyVersion<Foo>`), and errors are keyed by the name of the binding. Evaluates to a
+/// `Result` of a tuple of the migrated values (in the order they were given) or the [`Errors`]
+/// of every value which failed.
+///
+/// Every value is migrated, in the order they were given, so that all failures are reported
+/// together, but no migrated value is returned unless all of them succeed. Errors of each
+/// data-structure's fallible migrations must implement `Display`.
+///
+/// See the [module documentation](self) for an example.
+pub use crate::__txn_migrate_all as migrate_all;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __txn_migrate_all {
+    ($(($binding:ident : $ty:ty)),+ $(,)?) => {{
+        let mut errors: $crate::txn::Errors = $crate::alloc::vec::Vec::new();
+        $(
+            let $binding = $crate::txn::__step::<<$ty as $crate::TryMigrate>::Latest>(
+                ::core::stringify!($binding),
+                $binding,
+                &mut errors,
+            );
+        )+
+        match ($($binding,)+) {
+            ($(::core::option::Option::Some($binding),)+) => {
+                ::core::result::Result::Ok(($($binding,)+))
+            }
+            _ => ::core::result::Result::Err(errors),
+        }
+    }};
+}
+
+/// Migrate a single value of [`migrate_all!`], pushing its error (if any) to `errors`, named by
+/// `key`.
+#[doc(hidden)]
+pub fn __step<T>(key: &'static str, tagged: AnyVersion<T>, errors: &mut Errors) -> Option<T>
+where
+    T: Versioned,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    #[cfg(feature = "metrics")]
+    crate::telemetry::record_upgraded::<T>(&tagged);
+    try_upgrade_rendered::<T>(tagged)
+        .map_err(|error| errors.push(KeyedError { key, error }))
+        .ok()
+}
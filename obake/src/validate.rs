@@ -0,0 +1,86 @@
+//! Validating data-structures against the rules of the version they were declared with.
+//!
+//! Validation rules typically change along with a data-structure, so legacy payloads should be
+//! validated against legacy rules, before they are migrated to the latest version. Rules for
+//! particular versions are declared with `#[obake(cfg_attr(...))]`, and the rules of the active
+//! version are checked with [`ValidateVersion::validate`].
+//!
+//! Requires the feature `validator`.
+//!
+//! ```
+//! use obake::validate::ValidateVersion;
+//! use validator::Validate;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Validate)]
+//! struct Foo {
+//!     #[obake(cfg_attr("0.1.0", validate(range(max = 10))))]
+//!     #[obake(cfg_attr(">=0.2", validate(range(max = 100))))]
+//!     bar: u32,
+//! }
+//! #
+//! # impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+//! #     fn from(from: Foo!["0.1.0"]) -> Self {
+//! #         Self { bar: from.bar }
+//! #     }
+//! # }
+//!
+//! let legacy: obake::AnyVersion<Foo> = Foo_v0_1_0 { bar: 50 }.into();
+//! assert!(legacy.validate().is_err());
+//!
+//! let latest: obake::AnyVersion<Foo> = (Foo { bar: 50 }).into();
+//! assert!(latest.validate().is_ok());
+//! ```
+
+use validator::{Validate, ValidationErrors};
+
+use crate::{Accept, VersionOf, VersionTagged, VersionVisitor, Versioned, VisitValue};
+
+/// The [`VersionVisitor`] used by [`ValidateVersion::validate`] to validate a value against the
+/// rules of its own version.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Validator;
+
+impl<T> VersionVisitor<T> for Validator
+where
+    T: Versioned,
+{
+    type Output = Result<(), ValidationErrors>;
+}
+
+impl<T, V> VisitValue<T, V> for Validator
+where
+    T: Versioned,
+    V: VersionOf<T> + Validate,
+{
+    fn visit_value(self, value: &V) -> Self::Output {
+        value.validate()
+    }
+}
+
+/// Automatically implemented by the generated version-tagged encoding of a
+/// [`versioned`](crate::versioned) data-structure when every declared version implements
+/// [`Validate`].
+pub trait ValidateVersion<T>: VersionTagged<T>
+where
+    T: Versioned,
+{
+    /// Validate the value held by this instance against the rules of its version.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the value violates the rules declared for its version.
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+impl<T, A> ValidateVersion<T> for A
+where
+    T: Versioned,
+    A: VersionTagged<T> + Accept<T, Validator>,
+{
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.accept(Validator)
+    }
+}
@@ -0,0 +1,65 @@
+//! Support code for the [`wasm_bindgen`] bindings generated by `#[obake(wasm)]`.
+//!
+//! Marking a [`versioned`](crate::versioned) data-structure `Foo` with `#[obake(wasm)]`
+//! generates the following bindings, so that migrations can be run in the browser:
+//!
+//! - `upgrade_foo(json: string): any`, which decodes a JSON payload of any declared version of
+//!   `Foo` (see [`json`](crate::json) for the accepted encodings) and returns the latest
+//!   version as a JavaScript object, throwing an `Error` if the payload cannot be decoded.
+//! - `foo_versions(): string[]`, which returns the declared versions of `Foo`, ordered from
+//!   oldest to newest.
+//! - A TypeScript type `FooVersion`, the union of all declared version strings.
+//!
+//! The functions are named after the words of the data-structure's name, so that acronyms stay
+//! whole (e.g., `upgrade_http_config` and `http_config_versions` for `HTTPConfig`).
+//!
+//! The crate using `#[obake(wasm)]` must depend on [`wasm_bindgen`].
+//!
+//! Requires the feature `wasm`.
+
+use alloc::string::ToString;
+
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+
+pub use js_sys::Array;
+
+use crate::codec::{Decode, Json};
+use crate::{AnyVersion, Dispatch, TryUpgrade, Versioned};
+
+fn js_error(err: &dyn core::fmt::Display) -> JsValue {
+    js_sys::Error::new(&err.to_string()).into()
+}
+
+/// Decode a JSON payload of any declared version of `T` and return the latest version as a
+/// JavaScript object.
+///
+/// ## Errors
+///
+/// Fails with a JavaScript `Error` if the payload cannot be decoded or migrated.
+pub fn upgrade<T>(json: &str) -> Result<JsValue, JsValue>
+where
+    T: Versioned + Serialize,
+    for<'de, 'c> AnyVersion<T>: Dispatch<T, Decode<'de, 'c, Json>>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: core::fmt::Display,
+{
+    let latest = crate::json::from_slice::<T>(json.as_bytes())
+        .map_err(|err| js_error(&err))?
+        .try_upgrade()
+        .map_err(|err| js_error(&err))?;
+    let json = serde_json::to_string(&latest).map_err(|err| js_error(&err))?;
+    js_sys::JSON::parse(&json)
+}
+
+/// The declared versions of `T` as a JavaScript array, ordered from oldest to newest.
+#[must_use]
+pub fn versions<T>() -> Array
+where
+    T: Versioned,
+{
+    T::VERSIONS
+        .iter()
+        .map(|version| JsValue::from_str(version))
+        .collect()
+}
@@ -0,0 +1,443 @@
+//! Live-reloading of configuration files of any declared version of a
+//! [`versioned`](crate::versioned) data-structure.
+//!
+//! A [`ConfigWatcher`] loads a configuration file (as [`config::load`] does), migrates it to the
+//! latest version and validates it, then watches it for changes with
+//! [`notify`](https://docs.rs/notify). Each change is reloaded in the background and, once
+//! migrated and validated, swapped in atomically, so that readers always see either the
+//! previous configuration or the new one in full. A change which fails to load (or validate)
+//! leaves the previous configuration in place. Files wrapped in the canonical envelope with an
+//! undeclared version are rejected, unless another policy is given to
+//! [`ConfigWatcher::validated_with`].
+//!
+//! Subscribers are told of each change, along with the versions the previous and new files
+//! were written at, so that they can react to (or log) a file being rewritten at a newer
+//! version.
+//!
+//! Requires the feature `notify` and at least one of the features `json`, `json5`, `ron`,
+//! `toml` or `yaml`, each enabling the corresponding format.
+//!
+//! ```no_run
+//! # #[cfg(feature = "toml")] {
+//! use obake::watch::ConfigWatcher;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Debug, serde::Deserialize)]
+//! struct Settings {
+//!     #[obake(cfg("0.1.0"))]
+//!     port: u16,
+//!     #[obake(cfg(">=0.2"))]
+//!     address: String,
+//! }
+//!
+//! impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+//!     fn from(from: Settings!["0.1.0"]) -> Self {
+//!         Self { address: format!("localhost:{}", from.port) }
+//!     }
+//! }
+//!
+//! let watcher = ConfigWatcher::<Settings>::validated("settings.toml", |settings: &Settings| {
+//!     if settings.address.is_empty() {
+//!         return Err("no address given");
+//!     }
+//!     Ok(())
+//! })
+//! .unwrap();
+//!
+//! watcher.on_change(|change| {
+//!     println!(
+//!         "reloaded settings ({} -> {}): {:?}",
+//!         change.previous_version, change.version, change.current,
+//!     );
+//! });
+//! watcher.on_error(|err| eprintln!("kept previous settings: {err}"));
+//!
+//! println!("listening on {}", watcher.current().address);
+//! # }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::sync::Arc;
+
+use core::{fmt, iter};
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use arc_swap::ArcSwap;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::codec::UnsupportedVersion;
+use crate::config::{self, Decodable};
+use crate::{try_upgrade_rendered, AnyVersion, StepError, TryUpgrade, VersionTagged, Versioned};
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Errors produced when loading (or watching) configuration files.
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be read or decoded.
+    Load(config::Error),
+    /// The file was decoded, but failed to migrate to the latest version (see [`TryUpgrade`]).
+    Migrate(StepError<String>),
+    /// The file was decoded, but its configuration was rejected by the validator.
+    Invalid(BoxedError),
+    /// The file could not be watched.
+    Watch(notify::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load(err) => err.fmt(f),
+            Self::Migrate(err) => write!(f, "failed to migrate configuration: {err}"),
+            Self::Invalid(err) => write!(f, "invalid configuration: {err}"),
+            Self::Watch(err) => write!(f, "failed to watch configuration: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Load(err) => Some(err),
+            Self::Migrate(err) => Some(err),
+            Self::Invalid(err) => Some(&**err),
+            Self::Watch(err) => Some(err),
+        }
+    }
+}
+
+/// A change of configuration, given to the subscribers of a [`ConfigWatcher`].
+#[derive(Debug)]
+pub struct Change<T> {
+    /// The configuration replaced.
+    pub previous: Arc<T>,
+    /// The version the replaced configuration was written at.
+    pub previous_version: &'static str,
+    /// The configuration swapped in.
+    pub current: Arc<T>,
+    /// The version the new configuration was written at (before being migrated).
+    pub version: &'static str,
+}
+
+type Validator<T> = Box<dyn Fn(&T) -> Result<(), BoxedError> + Send + Sync>;
+type Subscriber<A> = Arc<dyn Fn(&A) + Send + Sync>;
+/// Replaced (rather than pushed to) by each subscription, so that subscribers are called on a
+/// snapshot without holding the lock, and may themselves subscribe (or reload).
+type Subscribers<A> = Mutex<Arc<[Subscriber<A>]>>;
+
+/// The changes swapped in but not yet given to subscribers, in the order they were swapped in,
+/// and whether a reload is already giving them to subscribers.
+struct Notifications<T> {
+    pending: VecDeque<Change<T>>,
+    notifying: bool,
+}
+
+/// A configuration loaded, along with the version it was written at, swapped in together so that
+/// neither is ever seen alongside the other's predecessor.
+struct Loaded<T> {
+    config: Arc<T>,
+    version: &'static str,
+}
+
+/// The state shared between a [`ConfigWatcher`] and the thread notifying it of changes.
+struct Shared<T> {
+    path: PathBuf,
+    validate: Validator<T>,
+    policy: UnsupportedVersion,
+    current: ArcSwap<Loaded<T>>,
+    notifications: Mutex<Notifications<T>>,
+    on_change: Subscribers<Change<T>>,
+    on_error: Subscribers<Error>,
+}
+
+impl<T> Shared<T>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    fn reload(&self) -> Result<(), Error> {
+        let (latest, version) = load(&self.path, &self.validate, self.policy)?;
+        let current = Arc::new(latest);
+
+        // Queued in the same critical section as the swap, so that subscribers are notified of
+        // concurrent reloads in the order they were swapped in
+        {
+            let mut notifications = lock(&self.notifications);
+            let previous = self.current.swap(Arc::new(Loaded {
+                config: Arc::clone(&current),
+                version,
+            }));
+            notifications.pending.push_back(Change {
+                previous: Arc::clone(&previous.config),
+                previous_version: previous.version,
+                current,
+                version,
+            });
+
+            // The reload already notifying (possibly a subscriber's caller) notifies this change
+            // once it's done with those before it
+            if notifications.notifying {
+                return Ok(());
+            }
+            notifications.notifying = true;
+        }
+
+        self.notify();
+        Ok(())
+    }
+
+    /// Give each pending change to the subscribers, until none are left.
+    fn notify(&self) {
+        // A subscriber panicking leaves the remaining changes to be notified by the next reload
+        struct Unwinding<'a, T>(&'a Mutex<Notifications<T>>);
+
+        impl<T> Drop for Unwinding<'_, T> {
+            fn drop(&mut self) {
+                if std::thread::panicking() {
+                    lock(self.0).notifying = false;
+                }
+            }
+        }
+
+        let _unwinding = Unwinding(&self.notifications);
+        loop {
+            let change = {
+                let mut notifications = lock(&self.notifications);
+                let Some(change) = notifications.pending.pop_front() else {
+                    notifications.notifying = false;
+                    return;
+                };
+                change
+            };
+
+            for subscriber in snapshot(&self.on_change).iter() {
+                subscriber(&change);
+            }
+        }
+    }
+
+    fn report(&self, err: &Error) {
+        for subscriber in snapshot(&self.on_error).iter() {
+            subscriber(err);
+        }
+    }
+}
+
+/// Load the file at `path`, migrated to the latest version and validated, along with the version
+/// it was written at.
+fn load<T>(
+    path: &Path,
+    validate: &Validator<T>,
+    policy: UnsupportedVersion,
+) -> Result<(T, &'static str), Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Decodable<T>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    let tagged = config::load_with::<T, _>(path, policy).map_err(Error::Load)?;
+    let version = tagged.version_str();
+    let latest = try_upgrade_rendered::<T>(tagged).map_err(Error::Migrate)?;
+    validate(&latest).map_err(Error::Invalid)?;
+    Ok((latest, version))
+}
+
+// Subscribers panicking shouldn't stop the configuration from being reloaded
+fn lock<S>(mutex: &Mutex<S>) -> MutexGuard<'_, S> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn snapshot<A: ?Sized>(subscribers: &Subscribers<A>) -> Arc<[Subscriber<A>]> {
+    Arc::clone(&lock(subscribers))
+}
+
+fn subscribe<A: ?Sized>(subscribers: &Subscribers<A>, subscriber: Subscriber<A>) {
+    let mut subscribers = lock(subscribers);
+    *subscribers = subscribers
+        .iter()
+        .cloned()
+        .chain(iter::once(subscriber))
+        .collect();
+}
+
+/// A configuration file of any declared version of `T`, migrated to the latest version and
+/// reloaded whenever it changes.
+pub struct ConfigWatcher<T> {
+    shared: Arc<Shared<T>>,
+    // Stops watching the file once dropped
+    _watcher: RecommendedWatcher,
+}
+
+impl<T> ConfigWatcher<T>
+where
+    T: Versioned + Send + Sync + 'static,
+    AnyVersion<T>: Decodable<T>,
+    AnyVersion<T>: TryUpgrade<T>,
+    <AnyVersion<T> as TryUpgrade<T>>::Error: fmt::Display,
+{
+    /// Load the configuration file at `path` and watch it for changes.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the file can't be loaded, or if it can't be watched.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::validated(path, |_: &T| Ok::<_, BoxedError>(()))
+    }
+
+    /// Load the configuration file at `path` and watch it for changes, rejecting any
+    /// configuration (including the first) for which `validate` fails.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the file can't be loaded, if its configuration is rejected by `validate`, or if
+    /// it can't be watched.
+    pub fn validated<F, E>(path: impl AsRef<Path>, validate: F) -> Result<Self, Error>
+    where
+        F: Fn(&T) -> Result<(), E> + Send + Sync + 'static,
+        E: Into<BoxedError>,
+    {
+        Self::validated_with(path, validate, UnsupportedVersion::Reject)
+    }
+
+    /// Load the configuration file at `path` and watch it for changes as
+    /// [`ConfigWatcher::validated`] does, handling a file (or a change) wrapped in the canonical
+    /// envelope with an undeclared version (e.g., written by a newer release) according to
+    /// `policy` (see [`config::load_with`]).
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the file can't be loaded, if its configuration is rejected by `validate`, or if
+    /// it can't be watched.
+    pub fn validated_with<F, E>(
+        path: impl AsRef<Path>,
+        validate: F,
+        policy: UnsupportedVersion,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&T) -> Result<(), E> + Send + Sync + 'static,
+        E: Into<BoxedError>,
+    {
+        let path = path.as_ref().to_path_buf();
+        let validate: Validator<T> = Box::new(move |config| validate(config).map_err(Into::into));
+        let (latest, version) = load(&path, &validate, policy)?;
+        let shared = Arc::new(Shared {
+            path,
+            validate,
+            policy,
+            current: ArcSwap::from_pointee(Loaded {
+                config: Arc::new(latest),
+                version,
+            }),
+            notifications: Mutex::new(Notifications {
+                pending: VecDeque::new(),
+                notifying: false,
+            }),
+            on_change: Mutex::new(Arc::new([])),
+            on_error: Mutex::new(Arc::new([])),
+        });
+
+        // Editors often replace files rather than writing to them, which ends any watch on the
+        // file itself, so its directory is watched instead
+        let dir = match shared.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let file_name: Option<OsString> = shared.path.file_name().map(Into::into);
+        let handler = {
+            let shared = Arc::downgrade(&shared);
+            move |event: notify::Result<notify::Event>| {
+                let Some(shared) = shared.upgrade() else {
+                    return;
+                };
+
+                let event = match event {
+                    Ok(event) => event,
+                    Err(err) => return shared.report(&Error::Watch(err)),
+                };
+                let changed = (event.kind.is_create() || event.kind.is_modify())
+                    && event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name().map(Into::into) == file_name);
+                if changed {
+                    if let Err(err) = shared.reload() {
+                        shared.report(&err);
+                    }
+                }
+            }
+        };
+
+        let mut watcher = notify::recommended_watcher(handler).map_err(Error::Watch)?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(Error::Watch)?;
+
+        Ok(Self {
+            shared,
+            _watcher: watcher,
+        })
+    }
+
+    /// The latest configuration loaded.
+    #[must_use]
+    pub fn current(&self) -> Arc<T> {
+        Arc::clone(&self.shared.current.load().config)
+    }
+
+    /// The version the latest configuration loaded was written at (before being migrated).
+    #[must_use]
+    pub fn version(&self) -> &'static str {
+        self.shared.current.load().version
+    }
+
+    /// Call `subscriber` with each change of configuration, once it has been swapped in, in the
+    /// order the changes were swapped in.
+    ///
+    /// Subscribers are called on the thread reloading the file (usually the thread watching it),
+    /// so shouldn't block. They may subscribe or reload the file themselves, in which case the
+    /// change they reload is notified once every subscriber has been notified of the current one.
+    pub fn on_change(&self, subscriber: impl Fn(&Change<T>) + Send + Sync + 'static) {
+        subscribe(&self.shared.on_change, Arc::new(subscriber));
+    }
+
+    /// Call `subscriber` with each error produced when reloading the file (in which case the
+    /// previous configuration is kept), or when watching it.
+    pub fn on_error(&self, subscriber: impl Fn(&Error) + Send + Sync + 'static) {
+        subscribe(&self.shared.on_error, Arc::new(subscriber));
+    }
+
+    /// Reload the file now, rather than when it next changes (e.g., on `SIGHUP`), notifying
+    /// subscribers of the change.
+    ///
+    /// ## Errors
+    ///
+    /// Fails if the file can't be loaded, or if its configuration is rejected, in which case the
+    /// previous configuration is kept.
+    pub fn reload(&self) -> Result<(), Error> {
+        self.shared.reload()
+    }
+}
+
+impl<T> fmt::Debug for ConfigWatcher<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let current = self.shared.current.load();
+        f.debug_struct("ConfigWatcher")
+            .field("path", &self.shared.path)
+            .field("current", &current.config)
+            .field("version", &current.version)
+            .finish_non_exhaustive()
+    }
+}
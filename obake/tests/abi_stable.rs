@@ -0,0 +1,56 @@
+#![cfg(feature = "abi_stable")]
+
+use abi_stable::abi_stability::abi_checking::check_layout_compatibility;
+use abi_stable::StableAbi;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(abi_stable)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 1,
+        }
+    }
+}
+
+#[test]
+fn stable_layouts() {
+    assert!(check_layout_compatibility(<Foo!["0.1.0"]>::LAYOUT, <Foo!["0.1.0"]>::LAYOUT,).is_ok());
+    assert!(check_layout_compatibility(VersionedFoo::LAYOUT, VersionedFoo::LAYOUT).is_ok());
+    assert!(check_layout_compatibility(<Foo!["0.1.0"]>::LAYOUT, <Foo!["0.2.0"]>::LAYOUT,).is_err());
+}
+
+#[test]
+fn upgrade_table() {
+    let table = FooUpgradeTable::TABLE;
+    assert_eq!(
+        table
+            .versions
+            .iter()
+            .map(|version| version.as_str())
+            .collect::<Vec<_>>(),
+        ["0.1.0", "0.2.0"],
+    );
+
+    let expected = Foo {
+        field_0: 3,
+        field_1: 1,
+    };
+    assert_eq!((table.v0_1_0)(Foo_v0_1_0 { field_0: 3 }), expected);
+    assert_eq!((table.v0_2_0)(expected), expected);
+    assert_eq!(
+        (table.upgrade)(VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field_0: 3 })),
+        expected,
+    );
+    assert!(check_layout_compatibility(FooUpgradeTable::LAYOUT, FooUpgradeTable::LAYOUT).is_ok());
+}
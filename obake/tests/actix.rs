@@ -0,0 +1,119 @@
+#![cfg(feature = "actix-web")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use actix_web::{FromRequest, Responder};
+
+use obake::actix::{Downgraded, VersionedJson};
+use obake::http::{ACCEPT_VERSION, X_API_VERSION};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(downgrade)]
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("in-memory bodies never block"),
+    }
+}
+
+fn respond(req: TestRequest) -> (StatusCode, serde_json::Value) {
+    let latest = Foo {
+        field_0: 1,
+        field_1: "one".to_owned(),
+    };
+    let response = Downgraded(latest).respond_to(&req.to_http_request());
+    let status = response.status();
+    let body = block_on(actix_web::body::to_bytes(response.into_body())).unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn extract_old_versions() {
+    let (req, mut payload) = TestRequest::default()
+        .set_payload(r#"{"version":"0.1.0","data":{"field_0":1}}"#)
+        .to_http_parts();
+    let VersionedJson(value) =
+        block_on(VersionedJson::<Foo>::from_request(&req, &mut payload)).unwrap();
+    assert_eq!(
+        value,
+        Foo {
+            field_0: 1,
+            field_1: "default".to_owned(),
+        },
+    );
+}
+
+#[test]
+fn reject_unrecognized_payloads() {
+    let (req, mut payload) = TestRequest::default()
+        .set_payload(r#"{"field_1":"one"}"#)
+        .to_http_parts();
+    let rejection = block_on(VersionedJson::<Foo>::from_request(&req, &mut payload)).unwrap_err();
+    assert_eq!(rejection.supported_versions(), &["0.1.0", "0.2.0"]);
+    assert_eq!(
+        actix_web::ResponseError::status_code(&rejection),
+        StatusCode::BAD_REQUEST,
+    );
+}
+
+#[test]
+fn respond_with_requested_version() {
+    let (status, body) = respond(TestRequest::default());
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        serde_json::json!({"version": "0.2.0", "data": {"field_0": 1, "field_1": "one"}}),
+    );
+
+    let (status, body) = respond(TestRequest::default().insert_header((ACCEPT_VERSION, "0.1.0")));
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        serde_json::json!({"version": "0.1.0", "data": {"field_0": 1}}),
+    );
+
+    let (status, body) = respond(TestRequest::default().insert_header((X_API_VERSION, "0.1.0")));
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["version"], "0.1.0");
+
+    let (status, body) = respond(TestRequest::default().insert_header((ACCEPT_VERSION, "0.3.0")));
+    assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(
+        body["supported_versions"],
+        serde_json::json!(["0.1.0", "0.2.0"])
+    );
+}
@@ -0,0 +1,66 @@
+use obake::{VersionOf, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.2.1"))]
+#[obake(version("0.2.2"))]
+#[obake(version("0.3.0"))]
+#[obake(alias_identical)]
+#[obake(dense_migrations)]
+#[obake(derive(Clone))]
+#[derive(Clone, PartialEq, Debug)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2, <0.3"))]
+    field_1: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.2"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.2"]) -> Self {
+        Self {
+            field_0: from.field_0 + from.field_1,
+        }
+    }
+}
+
+#[test]
+fn identical_versions_are_aliased() {
+    let x = Foo_v0_2_0 {
+        field_0: 1,
+        field_1: 2,
+    };
+    let y: Foo!["0.2.2"] = x.clone();
+    assert_eq!(x, y);
+}
+
+#[test]
+fn aliased_versions_keep_their_tags() {
+    let x = VersionedFoo::Foo_v0_2_1(Foo_v0_2_0 {
+        field_0: 1,
+        field_1: 2,
+    });
+    assert_eq!(x.version_str(), "0.2.1");
+    assert_eq!(
+        <Foo!["0.2.1"]>::try_from_versioned(x.clone()),
+        Ok(Foo_v0_2_0 {
+            field_0: 1,
+            field_1: 2,
+        })
+    );
+    assert_eq!(Foo::from(x), Foo { field_0: 3 });
+}
+
+#[test]
+fn dense_migrations_skip_aliases() {
+    assert_eq!(Foo::from(Foo_v0_1_0 { field_0: 1 }), Foo { field_0: 2 });
+}
@@ -0,0 +1,90 @@
+#![allow(dead_code)]
+
+use obake::VersionTagged;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("1.0.0"))]
+#[obake(auto_migrate)]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    bar: u32,
+    #[obake(cfg("<0.3"))]
+    baz: u32,
+    #[obake(cfg(">=0.3"))]
+    baz: u64,
+    #[obake(cfg(">=0.2"))]
+    qux: bool,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+#[obake(public_enum)]
+#[derive(Debug, PartialEq)]
+enum Bar {
+    X,
+    #[obake(cfg(">=0.2"))]
+    Y,
+}
+
+fn pre_release(value: &obake::AnyVersion<Foo>) -> Option<(&'static str, u32, u32)> {
+    match value {
+        obake::any_of!(Foo, ["0.1.0" | "0.2.0"] as legacy) => {
+            Some((legacy.version_str(), *bar, *baz))
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn fields_common_to_the_matched_versions_are_bound() {
+    let old: Foo!["0.1.0"] = Foo_v0_1_0 { bar: 1, baz: 2 };
+    assert_eq!(pre_release(&old.into()), Some(("0.1.0", 1, 2)));
+
+    let old: Foo!["0.2.0"] = Foo_v0_2_0 {
+        bar: 3,
+        baz: 4,
+        qux: true,
+    };
+    assert_eq!(pre_release(&old.into()), Some(("0.2.0", 3, 4)));
+
+    let latest = Foo {
+        bar: 5,
+        baz: 6,
+        qux: false,
+    };
+    assert_eq!(pre_release(&latest.into()), None);
+}
+
+#[test]
+fn fields_retyped_between_the_matched_versions_are_not_bound() {
+    let value: obake::AnyVersion<Foo> = Foo_v0_3_0 {
+        bar: 1,
+        baz: 2,
+        qux: true,
+    }
+    .into();
+
+    // `baz` is a `u32` until 0.3.0, so only `bar` and `qux` are common to these versions
+    if let obake::any_of!(Foo, ["0.2.0" | "0.3.0" | "1.0.0"] as modern) = value {
+        let baz = "shadowed";
+        assert_eq!(
+            (modern.version_str(), *bar, *qux, baz),
+            ("0.3.0", 1, true, "shadowed")
+        );
+    } else {
+        panic!("0.3.0 wasn't matched");
+    }
+}
+
+#[test]
+fn versions_of_enums_are_matched() {
+    let old: Bar!["0.1.0"] = Bar_v0_1_0::X;
+    let value: obake::AnyVersion<Bar> = old.into();
+    assert!(matches!(value, obake::any_of!(Bar, ["0.1.0"] as _legacy)));
+    assert!(!matches!(value, obake::any_of!(Bar, ["0.2.0"] as _latest)));
+}
@@ -0,0 +1,46 @@
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(assert_fields("0.1.0", [field_0]))]
+#[obake(assert_fields("0.2.0", [r#type, field_0]))]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    r#type: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            r#type: String::new(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(assert_fields("0.1.0", [A]))]
+#[obake(assert_fields("0.2.0", [A, B]))]
+enum Bar {
+    A,
+    #[obake(cfg(">=0.2"))]
+    B,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        match from {
+            Bar_v0_1_0::A => Self::A,
+        }
+    }
+}
+
+#[test]
+fn assertions_hold() {
+    let _ = Foo_v0_1_0 { field_0: 0 };
+    let _ = Bar_v0_2_0::B;
+}
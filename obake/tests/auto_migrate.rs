@@ -0,0 +1,208 @@
+use std::rc::Rc;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate("<0.3"))]
+#[derive(PartialEq, Debug)]
+struct Foo {
+    blob: Vec<u8>,
+    shared: Rc<String>,
+    #[obake(cfg("<0.2"))]
+    removed: u32,
+    #[obake(cfg(">=0.2"))]
+    added: u32,
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            blob: from.blob,
+            shared: from.shared,
+            added: from.added + 1,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+#[derive(PartialEq, Debug)]
+struct Bar {
+    #[obake(inherit)]
+    foo: Foo,
+}
+
+#[test]
+fn auto_migrate_moves_fields() {
+    let blob = vec![0; 1 << 20];
+    let ptr = blob.as_ptr();
+    let shared = Rc::new(String::from("shared"));
+
+    let x: Foo!["0.1.0"] = Foo_v0_1_0 {
+        blob,
+        shared: Rc::clone(&shared),
+        removed: 1,
+    };
+    let y: Foo!["0.2.0"] = x.into();
+
+    assert_eq!(y.added, 0);
+    assert_eq!(y.blob.as_ptr(), ptr);
+    assert_eq!(Rc::strong_count(&shared), 2);
+
+    let z: Foo = VersionedFoo::from(y).into();
+
+    assert_eq!(z.added, 1);
+    assert_eq!(z.blob.as_ptr(), ptr);
+    assert_eq!(Rc::strong_count(&shared), 2);
+}
+
+#[test]
+fn auto_migrate_converts_inherited_fields() {
+    let x: Bar!["0.1.0"] = Bar_v0_1_0 {
+        foo: Foo_v0_1_0 {
+            blob: vec![1, 2, 3],
+            shared: Rc::new(String::new()),
+            removed: 1,
+        },
+    };
+    let y: Bar = x.into();
+
+    assert_eq!(y.foo.blob, vec![1, 2, 3]);
+    assert_eq!(y.foo.added, 0);
+}
+
+trait Plugin {
+    fn name(&self) -> &'static str;
+}
+
+struct Noop;
+
+impl Plugin for Noop {
+    fn name(&self) -> &'static str {
+        "noop"
+    }
+}
+
+struct Logger;
+
+impl Plugin for Logger {
+    fn name(&self) -> &'static str {
+        "logger"
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+struct Config {
+    #[obake(cfg(">=0.2"))]
+    #[obake(migrate_default = Box::new(Noop))]
+    plugin: Box<dyn Plugin>,
+    #[obake(cfg(">=0.2"))]
+    #[obake(migrate_default("0.2", vec![Box::new(Logger)]))]
+    #[obake(migrate_default = Vec::new())]
+    extras: Vec<Box<dyn Plugin>>,
+    #[obake(cfg(">=0.3"))]
+    retries: u32,
+}
+
+#[test]
+fn auto_migrate_uses_declared_defaults() {
+    let x: Config!["0.1.0"] = Config_v0_1_0 {};
+    let x: Config = VersionedConfig::from(x).into();
+
+    assert_eq!(x.plugin.name(), "noop");
+    assert_eq!(x.extras.len(), 1);
+    assert_eq!(x.extras[0].name(), "logger");
+    assert_eq!(x.retries, 0);
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[derive(PartialEq, Debug)]
+struct Retyped {
+    name: String,
+    #[obake(cfg("<0.3"))]
+    count: u32,
+    #[obake(cfg(">=0.3"))]
+    count: u64,
+}
+
+#[test]
+fn auto_migrate_converts_retyped_fields() {
+    let x: Retyped!["0.1.0"] = Retyped_v0_1_0 {
+        name: "x".to_owned(),
+        count: 7,
+    };
+    let x: Retyped = VersionedRetyped::from(x).into();
+
+    assert_eq!(
+        x,
+        Retyped {
+            name: "x".to_owned(),
+            count: 7,
+        }
+    );
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[derive(PartialEq, Debug)]
+enum Event {
+    Ping,
+    #[obake(cfg("<0.3"))]
+    Count(u32),
+    #[obake(cfg(">=0.3"))]
+    Count(u64),
+    Named {
+        id: u8,
+        #[obake(cfg(">=0.2"))]
+        label: String,
+    },
+    Nested(#[obake(inherit)] Foo),
+    #[obake(cfg(">=0.2"))]
+    Added,
+}
+
+#[test]
+fn auto_migrate_converts_retyped_variants() {
+    let x: Event = VersionedEvent::from(Event_v0_1_0::Count(3)).into();
+    assert_eq!(x, Event::Count(3));
+
+    let x: Event!["0.1.0"] = Event_v0_1_0::Ping;
+    let x: Event = VersionedEvent::from(x).into();
+    assert_eq!(x, Event::Ping);
+
+    let x: Event = VersionedEvent::from(Event_v0_2_0::Added).into();
+    assert_eq!(x, Event::Added);
+
+    let x: Event = VersionedEvent::from(Event_v0_1_0::Named { id: 1 }).into();
+    assert_eq!(
+        x,
+        Event::Named {
+            id: 1,
+            label: String::new(),
+        }
+    );
+
+    let foo = Foo_v0_1_0 {
+        blob: vec![1],
+        shared: Rc::new(String::new()),
+        removed: 1,
+    };
+    let Event::Nested(foo) = VersionedEvent::from(Event_v0_1_0::Nested(foo)).into() else {
+        panic!("expected `Event::Nested`");
+    };
+    assert_eq!(foo.added, 1);
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "axum")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use axum::body::Body;
+use axum::extract::{FromRequest, Request};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+use obake::axum::VersionedJson;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(PartialEq, Eq, Debug, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("in-memory bodies never block"),
+    }
+}
+
+fn extract(body: &'static str) -> Result<Foo, obake::axum::Rejection> {
+    let req = Request::new(Body::from(body));
+    block_on(VersionedJson::<Foo>::from_request(req, &())).map(|VersionedJson(value)| value)
+}
+
+#[test]
+fn accept_old_versions() {
+    assert_eq!(
+        extract(r#"{"version":"0.1.0","data":{"field_0":1}}"#).unwrap(),
+        Foo {
+            field_0: 1,
+            field_1: "default".to_owned(),
+        },
+    );
+    assert_eq!(
+        extract(r#"{"field_0":2,"field_1":"two"}"#).unwrap(),
+        Foo {
+            field_0: 2,
+            field_1: "two".to_owned(),
+        },
+    );
+}
+
+#[test]
+fn reject_unknown_versions() {
+    let rejection = extract(r#"{"version":"0.3.0","data":{}}"#).unwrap_err();
+    assert_eq!(rejection.supported_versions(), &["0.1.0", "0.2.0"]);
+
+    let response = rejection.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = block_on(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"], "undeclared version 0.3.0");
+    assert_eq!(
+        body["supported_versions"],
+        serde_json::json!(["0.1.0", "0.2.0"])
+    );
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = &'static str))]
+#[derive(PartialEq, Eq, Debug, serde::Deserialize)]
+struct Bar {
+    #[obake(cfg("0.1.0"))]
+    field_0: i32,
+    #[obake(cfg("0.2.0"))]
+    field_0: u32,
+}
+
+impl std::convert::TryFrom<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Bar!["0.1.0"]) -> Result<Self, Self::Error> {
+        u32::try_from(from.field_0)
+            .map(|field_0| Self { field_0 })
+            .map_err(|_| "negative")
+    }
+}
+
+#[test]
+fn reject_failing_migrations() {
+    let req = Request::new(Body::from(r#"{"version":"0.1.0","data":{"field_0":-1}}"#));
+    let rejection = block_on(VersionedJson::<Bar>::from_request(req, &()))
+        .map(|VersionedJson(value)| value)
+        .unwrap_err();
+    let err = rejection.migrate_error().unwrap();
+    assert_eq!(
+        (err.from, err.to, err.error.as_str()),
+        ("0.1.0", "0.2.0", "negative")
+    );
+
+    let response = rejection.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = block_on(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body["error"],
+        "migration from version 0.1.0 to version 0.2.0 failed: negative"
+    );
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "json")]
+
+use obake::codec::{Codec, Json};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+            field_1: from.field_1,
+        }
+    }
+}
+
+fn samples() -> Vec<obake::AnyVersion<Foo>> {
+    vec![
+        Foo_v0_1_0 { field_0: 1 }.into(),
+        Foo_v0_2_0 {
+            field_0: 1,
+            field_1: "x".to_owned(),
+        }
+        .into(),
+        Foo {
+            field_0: 1,
+            field_1: "x".to_owned(),
+        }
+        .into(),
+    ]
+}
+
+#[test]
+fn cases_cover_each_sample() {
+    let cases = obake::bench::cases::<Foo, _, _>(&Json, samples()).unwrap();
+
+    let names: Vec<_> = cases.iter().map(obake::bench::Case::name).collect();
+    assert_eq!(
+        names,
+        ["0.1.0 -> 0.3.0", "0.2.0 -> 0.3.0", "0.3.0 -> 0.3.0"]
+    );
+    assert_eq!(cases[0].version(), "0.1.0");
+    assert_eq!(cases[0].payload(), br#"{"field_0":1}"#);
+}
+
+#[test]
+fn cases_decode_and_migrate() {
+    let cases = obake::bench::cases::<Foo, _, _>(&Json, samples()).unwrap();
+
+    let results: Vec<_> = cases.iter().map(|case| case.run().unwrap()).collect();
+    assert_eq!(
+        results,
+        [
+            Foo {
+                field_0: 2,
+                field_1: "migrated".to_owned(),
+            },
+            Foo {
+                field_0: 2,
+                field_1: "x".to_owned(),
+            },
+            Foo {
+                field_0: 1,
+                field_1: "x".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn cases_are_rerunnable() {
+    let cases = obake::bench::cases::<Foo, _, _>(&Json, samples()).unwrap();
+
+    for case in &cases {
+        assert_eq!(case.run().unwrap(), case.run().unwrap());
+        assert!(Json.decode::<serde_json::Value>(case.payload()).is_ok());
+    }
+}
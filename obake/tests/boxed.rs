@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+
+use core::mem::size_of;
+
+use obake::{Downgrade, VersionOf, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(boxed)]
+#[obake(downgrade)]
+#[obake(derive(PartialEq, Debug))]
+#[derive(Clone, PartialEq, Debug)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: [u64; 512],
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: [1; 512],
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+            field_1: from.field_1,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_0: from.field_0 - 1,
+            field_1: from.field_1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[test]
+fn variants_are_boxed() {
+    assert_eq!(size_of::<VersionedFoo>(), 2 * size_of::<usize>());
+
+    let versioned = VersionedFoo::from(Foo_v0_1_0 { field_0: 1 });
+    assert!(matches!(&versioned, VersionedFoo::Foo_v0_1_0(x) if x.field_0 == 1));
+}
+
+#[test]
+fn upgrade() {
+    let versioned = VersionedFoo::from(Foo_v0_1_0 { field_0: 1 });
+    assert_eq!(
+        Foo::from(versioned),
+        Foo {
+            field_0: 2,
+            field_1: [1; 512],
+        }
+    );
+}
+
+#[test]
+fn downgrade() {
+    let foo = Foo {
+        field_0: 2,
+        field_1: [1; 512],
+    };
+    let versioned = VersionedFoo::downgrade(foo, "0.1.0").unwrap();
+    assert_eq!(versioned.version_str(), "0.1.0");
+    assert_eq!(
+        <Foo!["0.1.0"]>::try_from_versioned(versioned),
+        Ok(Foo_v0_1_0 { field_0: 1 })
+    );
+}
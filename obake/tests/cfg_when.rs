@@ -0,0 +1,146 @@
+use obake::graph::{Edge, EdgeKind};
+use obake::{Coverage, Declared, Dispatch, Downgrade, Migrations, VersionTagged, Versioned};
+
+// Tests are always compiled with `test`, so the versions before `0.2.0` are never compiled
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.1.1"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(cfg_when(not(test), "<0.2"))]
+#[obake(downgrade)]
+#[obake(derive(Debug, PartialEq))]
+#[obake(dense_migrations)]
+#[derive(Clone, Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    bar: u32,
+}
+
+#[cfg(not(test))]
+impl From<Foo!["0.1.0"]> for Foo!["0.1.1"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self {}
+    }
+}
+
+#[cfg(not(test))]
+impl From<Foo!["0.1.1"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.1"]) -> Self {
+        Self { bar: 0 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self { bar: from.bar }
+    }
+}
+
+#[cfg(not(test))]
+impl From<Foo!["0.2.0"]> for Foo!["0.1.1"] {
+    fn from(_: Foo!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+#[cfg(not(test))]
+impl From<Foo!["0.1.1"]> for Foo!["0.1.0"] {
+    fn from(_: Foo!["0.1.1"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self { bar: from.bar }
+    }
+}
+
+// Tests are always compiled with `test`, so every version is compiled
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(cfg_when(test, "<0.2"))]
+#[obake(downgrade)]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Clone, Debug, PartialEq)]
+struct Bar {
+    #[obake(cfg(">=0.2"))]
+    baz: u32,
+}
+
+#[cfg(test)]
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self { baz: 0 }
+    }
+}
+
+#[cfg(test)]
+impl From<Bar!["0.2.0"]> for Bar!["0.1.0"] {
+    fn from(_: Bar!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+#[test]
+fn excluded_versions_are_undeclared() {
+    assert_eq!(Foo::VERSIONS, &["0.2.0", "0.3.0"]);
+    assert!(obake::AnyVersion::<Foo>::dispatch("0.1.0", Declared).is_none());
+    assert!(obake::AnyVersion::<Foo>::dispatch("0.1.1", Declared).is_none());
+    assert!(obake::AnyVersion::<Foo>::dispatch("0.2.0", Declared).is_some());
+}
+
+#[test]
+fn included_versions_migrate() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_2_0 { bar: 42 }.into();
+    assert_eq!(x.version_str(), "0.2.0");
+    assert_eq!(Foo::from(x), Foo { bar: 42 });
+    assert_eq!(Foo::from(Foo_v0_2_0 { bar: 7 }), Foo { bar: 7 });
+}
+
+#[test]
+fn excluded_versions_cannot_be_downgraded_to() {
+    assert!(obake::AnyVersion::<Foo>::downgrade(Foo { bar: 42 }, "0.1.0").is_none());
+    assert_eq!(
+        obake::AnyVersion::<Foo>::downgrade(Foo { bar: 42 }, "0.2.0"),
+        Some(Foo_v0_2_0 { bar: 42 }.into()),
+    );
+}
+
+#[test]
+fn excluded_versions_are_not_covered() {
+    assert_eq!(Foo::COVERAGE, &[("bar", &[true, true][..])]);
+}
+
+#[test]
+fn excluded_versions_are_not_in_the_migration_graph() {
+    assert_eq!(
+        Foo::migration_graph().edges,
+        &[
+            Edge {
+                from: "0.2.0",
+                to: "0.3.0",
+                kind: EdgeKind::Upgrade,
+            },
+            Edge {
+                from: "0.3.0",
+                to: "0.2.0",
+                kind: EdgeKind::Downgrade,
+            },
+        ],
+    );
+}
+
+#[test]
+fn included_versions_are_declared() {
+    assert_eq!(Bar::VERSIONS, &["0.1.0", "0.2.0"]);
+
+    let x: obake::AnyVersion<Bar> = Bar_v0_1_0 {}.into();
+    assert_eq!(Bar::from(x), Bar { baz: 0 });
+    assert_eq!(
+        obake::AnyVersion::<Bar>::downgrade(Bar { baz: 42 }, "0.1.0"),
+        Some(Bar_v0_1_0 {}.into()),
+    );
+}
@@ -0,0 +1,247 @@
+#![cfg(all(feature = "json", feature = "toml", feature = "yaml"))]
+
+use std::path::{Path, PathBuf};
+
+use obake::cli::{self, Error, Options, Plan, Written};
+use obake::codec::UnsupportedVersion;
+use obake::config::Format;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Settings {
+    #[obake(cfg("<0.3"))]
+    port: u16,
+    #[obake(cfg(">=0.2"))]
+    verbose: bool,
+    #[obake(cfg(">=0.3"))]
+    address: String,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            port: from.port,
+            verbose: false,
+        }
+    }
+}
+
+impl From<Settings!["0.2.0"]> for Settings!["0.3.0"] {
+    fn from(from: Settings!["0.2.0"]) -> Self {
+        Self {
+            verbose: from.verbose,
+            address: format!("localhost:{}", from.port),
+        }
+    }
+}
+
+/// A directory of its own for the test `test`, emptied of files left by earlier runs.
+fn dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("obake-cli-test").join(test);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn files_are_converted_to_the_latest_version() {
+    let dir = dir("files_are_converted_to_the_latest_version");
+    let input = write(&dir, "convert.json", r#"{ "port": 80 }"#);
+    let output = dir.join("convert.yaml");
+    let plan = cli::convert::<Settings>(&input, &output, &Options::default()).unwrap();
+
+    assert_eq!(
+        plan,
+        Plan {
+            from: "0.1.0",
+            to: "0.3.0",
+            migrations: vec![("0.1.0", "0.2.0"), ("0.2.0", "0.3.0")],
+            input_format: Format::Json,
+            output_format: Format::Yaml,
+            written: Some(Written {
+                output: output.clone(),
+                backup: None,
+            }),
+        },
+    );
+    assert_eq!(plan.to_string(), "0.1.0 -> 0.2.0 -> 0.3.0 (JSON -> YAML)");
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "verbose: false\naddress: localhost:80\n",
+    );
+}
+
+#[test]
+fn files_are_converted_in_place() {
+    let dir = dir("files_are_converted_in_place");
+    let input = write(&dir, "in-place.json", r#"{ "port": 80, "verbose": true }"#);
+    let plan = cli::convert::<Settings>(&input, &input, &Options::default()).unwrap();
+    assert_eq!(plan.to_string(), "0.2.0 -> 0.3.0 (JSON)");
+    assert_eq!(
+        std::fs::read_to_string(&input).unwrap(),
+        "{\n  \"verbose\": true,\n  \"address\": \"localhost:80\"\n}\n",
+    );
+
+    let plan = cli::convert::<Settings>(&input, &input, &Options::default()).unwrap();
+    assert!(plan.is_noop());
+    assert_eq!(plan.to_string(), "0.3.0 (JSON)");
+}
+
+#[test]
+fn dry_runs_only_report_the_plan() {
+    let dir = dir("dry_runs_only_report_the_plan");
+    let input = write(&dir, "dry-run.conf", "port = 80");
+    let output = dir.join("dry-run.json");
+    let options = Options {
+        output_format: Some(Format::Yaml),
+        dry_run: true,
+        ..Options::default()
+    };
+    let plan = cli::convert::<Settings>(&input, &output, &options).unwrap();
+
+    assert_eq!(plan.to_string(), "0.1.0 -> 0.2.0 -> 0.3.0 (TOML -> YAML)");
+    assert_eq!(plan.written, None);
+    assert!(!output.exists());
+}
+
+#[test]
+fn replaced_files_can_be_backed_up() {
+    let dir = dir("replaced_files_can_be_backed_up");
+    let input = write(&dir, "backup.json", r#"{ "port": 80 }"#);
+    let options = Options {
+        backup: true,
+        atomic: true,
+        ..Options::default()
+    };
+    let plan = cli::convert::<Settings>(&input, &input, &options).unwrap();
+    let written = plan.written.unwrap();
+    assert_eq!(written.output, input);
+    assert!(std::fs::read_to_string(&input)
+        .unwrap()
+        .contains("localhost:80"));
+
+    let backup = written.backup.unwrap();
+    assert_eq!(backup.parent(), input.parent());
+    let name = backup.file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("backup.json.2") && name.ends_with("Z.bak"));
+    assert_eq!(
+        std::fs::read_to_string(&backup).unwrap(),
+        r#"{ "port": 80 }"#
+    );
+
+    // Backups taken within the same second are numbered rather than overwritten
+    let again = cli::convert::<Settings>(&input, &input, &options).unwrap();
+    let again = again.written.unwrap().backup.unwrap();
+    assert_ne!(again, backup);
+    assert!(backup.exists() && again.exists());
+}
+
+#[test]
+fn new_files_are_written_atomically_without_backups() {
+    let dir = dir("new_files_are_written_atomically_without_backups");
+    let input = write(&dir, "atomic.json", r#"{ "port": 80 }"#);
+    let output = dir.join("atomic-new.toml");
+    let options = Options {
+        backup: true,
+        atomic: true,
+        ..Options::default()
+    };
+    let plan = cli::convert::<Settings>(&input, &output, &options).unwrap();
+    assert_eq!(
+        plan.written,
+        Some(Written {
+            output: output.clone(),
+            backup: None,
+        }),
+    );
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "verbose = false\naddress = \"localhost:80\"\n",
+    );
+
+    let mut files = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(files, ["atomic-new.toml", "atomic.json"]);
+}
+
+#[test]
+fn outputs_can_be_enveloped() {
+    let dir = dir("outputs_can_be_enveloped");
+    let input = write(&dir, "envelope.yml", "port: 80\nverbose: true");
+    let output = dir.join("envelope.toml");
+    let options = Options {
+        envelope: true,
+        ..Options::default()
+    };
+    cli::convert::<Settings>(&input, &output, &options).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "version = \"0.3.0\"\n\n[data]\nverbose = true\naddress = \"localhost:80\"\n",
+    );
+    let (tagged, format) = cli::read::<Settings>(&output, None).unwrap();
+    assert_eq!(format, Format::Toml);
+    assert_eq!(cli::plan::<Settings>(&tagged, format, format).from, "0.3.0");
+}
+
+#[test]
+fn undeclared_versions_are_handled_by_policy() {
+    let dir = dir("undeclared_versions_are_handled_by_policy");
+    let input = write(
+        &dir,
+        "newer.json",
+        r#"{ "version": "0.4.0", "data": { "verbose": true, "address": "localhost:80" } }"#,
+    );
+    let output = dir.join("newer.toml");
+    assert!(matches!(
+        cli::convert::<Settings>(&input, &output, &Options::default()),
+        Err(Error::Read(_)),
+    ));
+
+    let options = Options {
+        unsupported_version: UnsupportedVersion::BestEffortLatest,
+        ..Options::default()
+    };
+    let plan = cli::convert::<Settings>(&input, &output, &options).unwrap();
+    assert_eq!(plan.to_string(), "0.3.0 (JSON -> TOML)");
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "verbose = true\naddress = \"localhost:80\"\n",
+    );
+
+    let (tagged, _) =
+        cli::read_with::<Settings>(&input, None, UnsupportedVersion::BestEffortLatest).unwrap();
+    assert_eq!(
+        cli::plan::<Settings>(&tagged, Format::Json, Format::Json).from,
+        "0.3.0"
+    );
+}
+
+#[test]
+fn unreadable_inputs_are_reported() {
+    let dir = dir("unreadable_inputs_are_reported");
+    let input = write(&dir, "broken.json", r#"{ "port": "eighty" }"#);
+    let err =
+        cli::convert::<Settings>(&input, dir.join("broken.toml"), &Options::default()).unwrap_err();
+    assert!(matches!(err, Error::Read(_)));
+    assert!(err.to_string().starts_with("malformed JSON configuration"));
+
+    let options = Options {
+        input_format: Some(Format::Toml),
+        ..Options::default()
+    };
+    let input = write(&dir, "explicit.json", r#"{ "port": 80 }"#);
+    assert!(cli::convert::<Settings>(&input, dir.join("explicit.toml"), &options).is_err());
+}
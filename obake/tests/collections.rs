@@ -0,0 +1,160 @@
+#![cfg(feature = "json")]
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+use obake::collections::{
+    try_upgrade_map, try_upgrade_vec, upgrade_map, upgrade_vec, KeyedError, VersionedHashMap,
+    VersionedMap, VersionedVec,
+};
+use obake::StepError;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(serde::Deserialize))]
+#[obake(serde(tag = "version", content = "data"))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.to_string(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = &'static str))]
+#[derive(Debug, PartialEq)]
+struct Bar {
+    #[obake(cfg("0.1.0"))]
+    field_0: i32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+}
+
+impl TryFrom<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Bar!["0.1.0"]) -> Result<Self, Self::Error> {
+        let field_1 = u32::try_from(from.field_0).map_err(|_| "negative")?;
+        Ok(Self { field_1 })
+    }
+}
+
+fn foo(field_1: &str) -> Foo {
+    Foo {
+        field_1: field_1.to_owned(),
+    }
+}
+
+#[test]
+fn maps_and_vecs_are_upgraded() {
+    let entries = vec![
+        (
+            "x",
+            obake::AnyVersion::<Foo>::from(Foo_v0_1_0 { field_0: 1 }),
+        ),
+        ("y", foo("y").into()),
+    ];
+    let map: BTreeMap<_, Foo> = upgrade_map(entries);
+    assert_eq!(map["x"], foo("1"));
+    assert_eq!(map["y"], foo("y"));
+
+    let values = upgrade_vec::<Foo>(vec![Foo_v0_1_0 { field_0: 2 }.into(), foo("z").into()]);
+    assert_eq!(values, vec![foo("2"), foo("z")]);
+}
+
+#[test]
+fn failing_values_are_reported_by_key() {
+    let entries = vec![
+        (
+            "x",
+            obake::AnyVersion::<Bar>::from(Bar_v0_1_0 { field_0: -1 }),
+        ),
+        ("y", Bar_v0_1_0 { field_0: 1 }.into()),
+        ("z", Bar_v0_1_0 { field_0: -2 }.into()),
+    ];
+    let errors = try_upgrade_map::<_, Bar, _, HashMap<_, _>>(entries).unwrap_err();
+    let step = StepError {
+        from: "0.1.0",
+        to: "0.2.0",
+        error: "negative",
+    };
+    assert_eq!(
+        errors,
+        vec![
+            KeyedError {
+                key: "x",
+                error: step
+            },
+            KeyedError {
+                key: "z",
+                error: step
+            },
+        ],
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "\"x\": migration from version 0.1.0 to version 0.2.0 failed: negative",
+    );
+
+    let values = vec![Bar_v0_1_0 { field_0: 3 }.into(), Bar { field_1: 4 }.into()];
+    assert_eq!(
+        try_upgrade_vec::<Bar>(values),
+        Ok(vec![Bar { field_1: 3 }, Bar { field_1: 4 }]),
+    );
+
+    let values = vec![Bar { field_1: 4 }.into(), Bar_v0_1_0 { field_0: -3 }.into()];
+    let errors = try_upgrade_vec::<Bar>(values).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].key, 1);
+}
+
+#[test]
+fn collections_are_deserialized_and_upgraded() {
+    let json = r#"{
+        "x": { "version": "Foo_v0_1_0", "data": { "field_0": 1 } },
+        "y": { "version": "Foo_v0_2_0", "data": { "field_1": "y" } }
+    }"#;
+    let VersionedMap(map) = serde_json::from_str::<VersionedMap<String, Foo>>(json).unwrap();
+    assert_eq!(map["x"], foo("1"));
+    assert_eq!(map["y"], foo("y"));
+
+    let VersionedHashMap(map) =
+        serde_json::from_str::<VersionedHashMap<String, Foo>>(json).unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["x"], foo("1"));
+
+    let json = r#"[
+        { "version": "Foo_v0_2_0", "data": { "field_1": "a" } },
+        { "version": "Foo_v0_1_0", "data": { "field_0": 2 } }
+    ]"#;
+    let VersionedVec(values) = serde_json::from_str::<VersionedVec<Foo>>(json).unwrap();
+    assert_eq!(values, vec![foo("a"), foo("2")]);
+}
+
+#[test]
+fn deserialization_errors_name_the_key() {
+    let json = r#"{
+        "x": { "version": "Foo_v0_1_0", "data": { "field_0": 1 } },
+        "y": { "version": "Foo_v0_2_0", "data": { "field_0": 2 } }
+    }"#;
+    let err = serde_json::from_str::<VersionedMap<String, Foo>>(json).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("invalid value for key \"y\": missing field `field_1`"));
+
+    let json = r#"[{ "version": "Foo_v0_2_0", "data": {} }]"#;
+    let err = serde_json::from_str::<VersionedVec<Foo>>(json).unwrap_err();
+    assert!(err.to_string().starts_with("invalid value at index 0"));
+}
@@ -0,0 +1,69 @@
+use obake::VersionTagged;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(PartialEq, Eq, Debug)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { field_0: 1 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+fn stored() -> Vec<obake::AnyVersion<Foo>> {
+    vec![
+        Foo_v0_1_0 {}.into(),
+        Foo_v0_2_0 { field_0: 4 }.into(),
+        Foo { field_0: 9 }.into(),
+    ]
+}
+
+#[test]
+fn map_latest_upgrades_first() {
+    let values: Vec<_> = stored()
+        .into_iter()
+        .map(|x| x.map_latest(|foo| foo.field_0))
+        .collect();
+    assert_eq!(values, &[2, 5, 9]);
+}
+
+#[test]
+fn and_then_latest_short_circuits() {
+    let even = |foo: Foo| {
+        if foo.field_0.is_multiple_of(2) {
+            Ok(foo)
+        } else {
+            Err(foo.field_0)
+        }
+    };
+    let values: Result<Vec<_>, _> = stored()
+        .into_iter()
+        .map(|x| x.and_then_latest(even))
+        .collect();
+    assert_eq!(values, Err(5));
+}
+
+#[test]
+fn inspect_version_sees_stored_version() {
+    let mut versions = Vec::new();
+    let values: Vec<Foo> = stored()
+        .into_iter()
+        .map(|x| x.inspect_version(|version| versions.push(version)).into())
+        .collect();
+    assert_eq!(versions, &["0.1.0", "0.2.0", "0.3.0"]);
+    assert_eq!(values.len(), 3);
+}
@@ -0,0 +1,210 @@
+#![cfg(all(
+    feature = "json",
+    feature = "json5",
+    feature = "ron",
+    feature = "toml",
+    feature = "yaml"
+))]
+
+use std::path::PathBuf;
+
+use obake::codec::UnsupportedVersion;
+use obake::config::{self, Error, Format};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Settings {
+    #[obake(cfg("0.1.0"))]
+    port: u16,
+    #[obake(cfg(">=0.2"))]
+    address: String,
+    #[obake(cfg(">=0.2"))]
+    verbose: bool,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            address: format!("localhost:{}", from.port),
+            verbose: false,
+        }
+    }
+}
+
+fn settings(address: &str, verbose: bool) -> Settings {
+    Settings {
+        address: address.to_owned(),
+        verbose,
+    }
+}
+
+fn write(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("obake-config-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn load(name: &str, contents: &str) -> Result<Settings, Error> {
+    config::load::<Settings, _>(write(name, contents)).map(Settings::from)
+}
+
+#[test]
+fn files_of_every_format_and_version_are_loaded() {
+    let old = settings("localhost:80", false);
+    assert_eq!(load("old.json", r#"{ "port": 80 }"#).unwrap(), old);
+    assert_eq!(load("old.json5", "{ port: 80, }").unwrap(), old);
+    assert_eq!(load("old.ron", "(port: 80)").unwrap(), old);
+    assert_eq!(load("old.toml", "port = 80").unwrap(), old);
+    assert_eq!(load("old.yml", "port: 80").unwrap(), old);
+
+    let new = settings("example.com:443", true);
+    let toml = "address = \"example.com:443\"\nverbose = true";
+    assert_eq!(load("new.toml", toml).unwrap(), new);
+    let yaml = "address: example.com:443\nverbose: true";
+    assert_eq!(load("new.yaml", yaml).unwrap(), new);
+}
+
+#[test]
+fn enveloped_payloads_are_decoded_as_their_version() {
+    let toml = "version = \"0.1.0\"\n\n[data]\nport = 8080";
+    assert_eq!(
+        load("enveloped.toml", toml).unwrap(),
+        settings("localhost:8080", false),
+    );
+
+    let yaml = "version: 0.2.0\ndata:\n  address: example.com\n  verbose: true";
+    assert_eq!(
+        load("enveloped.yaml", yaml).unwrap(),
+        settings("example.com", true),
+    );
+
+    let err = load("undeclared.toml", "version = \"0.3.0\"\n\n[data]\nport = 1").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Decode {
+            format: Format::Toml,
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "malformed TOML configuration: undeclared version 0.3.0",
+    );
+}
+
+#[test]
+fn undeclared_versions_are_handled_by_policy() {
+    let toml = "version = \"0.3.0\"\n\n[data]\naddress = \"example.com\"\nverbose = true";
+    let path = write("newer.toml", toml);
+    assert!(config::load::<Settings, _>(&path).is_err());
+
+    let loaded = config::load_with::<Settings, _>(&path, UnsupportedVersion::BestEffortLatest);
+    assert_eq!(
+        Settings::from(loaded.unwrap()),
+        settings("example.com", true)
+    );
+
+    let json = r#"{ "version": "0.1.9", "data": { "port": 80 } }"#;
+    let policy =
+        UnsupportedVersion::Callback(|version| version.starts_with("0.1.").then_some("0.1.0"));
+    assert!(config::from_str::<Settings>(json, None).is_err());
+    assert_eq!(
+        Settings::from(config::from_str_with::<Settings>(json, None, policy).unwrap()),
+        settings("localhost:80", false),
+    );
+    assert_eq!(
+        Settings::from(Format::Json.decode_with::<Settings>(json, policy).unwrap()),
+        settings("localhost:80", false),
+    );
+}
+
+#[test]
+fn formats_are_detected_without_a_matching_extension() {
+    let old = settings("localhost:80", false);
+    assert_eq!(load("misnamed.toml", r#"{ "port": 80 }"#).unwrap(), old);
+    assert_eq!(load("unnamed", "# legacy\nport = 80").unwrap(), old);
+    assert_eq!(
+        load("unnamed.conf", "Settings_v0_1_0(port: 80)").unwrap(),
+        old
+    );
+    assert_eq!(load("unnamed.cfg", "---\nport: 80").unwrap(), old);
+
+    let decoded = config::from_str::<Settings>("{ port: 80 }", Some(Format::Json)).unwrap();
+    assert_eq!(Settings::from(decoded), old);
+}
+
+#[test]
+fn unrecognized_files_report_the_most_likely_format() {
+    let err = load("broken.toml", "port = \"eighty\"").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Decode {
+            format: Format::Toml,
+            ..
+        }
+    ));
+
+    let err = load("broken", r#"{ "port": "eighty" }"#).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Decode {
+            format: Format::Json,
+            ..
+        }
+    ));
+
+    let err = config::load::<Settings, _>("/nonexistent/settings.toml")
+        .map(Settings::from)
+        .unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+}
+
+#[test]
+fn formats_are_sniffed_from_content() {
+    assert_eq!(Format::sniff("{\"port\": 80}"), Some(Format::Json));
+    assert_eq!(Format::sniff("[1, 2]"), Some(Format::Json));
+    assert_eq!(Format::sniff("// a comment\n(port: 80)"), Some(Format::Ron));
+    assert_eq!(
+        Format::sniff("#![enable(implicit_some)]\n(port: 80)"),
+        Some(Format::Ron)
+    );
+    assert_eq!(Format::sniff("Settings(port: 80)"), Some(Format::Ron));
+    assert_eq!(Format::sniff("[[servers]]\nport = 80"), Some(Format::Toml));
+    assert_eq!(Format::sniff("\"port\" = 80"), Some(Format::Toml));
+    assert_eq!(Format::sniff("# comment\nport: 80"), Some(Format::Yaml));
+    assert_eq!(Format::sniff("---"), Some(Format::Yaml));
+    assert_eq!(Format::sniff("80"), None);
+    assert_eq!(Format::sniff(""), None);
+
+    assert_eq!(Format::from_extension("YML"), Some(Format::Yaml));
+    assert_eq!(Format::from_extension("conf"), None);
+    assert!(Format::ALL.iter().all(|format| format.is_enabled()));
+}
+
+#[test]
+fn files_are_written_back_with_a_backup() {
+    let path = write("written.toml", "port = 80\n");
+    let options = config::WriteOptions {
+        backup: true,
+        atomic: true,
+    };
+
+    let written = config::write(&path, "address = \"localhost:80\"\n", options).unwrap();
+    assert_eq!(written.output, path);
+    let backup = written.backup.unwrap();
+    assert_eq!(std::fs::read_to_string(&backup).unwrap(), "port = 80\n");
+    std::fs::remove_file(backup).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "address = \"localhost:80\"\n"
+    );
+
+    // Nothing is backed up when nothing is replaced
+    std::fs::remove_file(&path).unwrap();
+    let written = config::write(&path, "port = 80\n", options).unwrap();
+    assert_eq!(written.backup, None);
+}
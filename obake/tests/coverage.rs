@@ -0,0 +1,80 @@
+#![allow(dead_code)]
+
+use obake::Coverage;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(note("0.2.0", "`field_1` is always `0` when migrated from `0.1.0`."))]
+#[obake(note("0.3.0", "`field_1` is folded into `field_0`."))]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg("0.2.0"))]
+    field_1: u32,
+    #[obake(cfg(">=0.2"))]
+    r#type: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 0,
+            r#type: 0,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            r#type: from.r#type,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+enum Bar {
+    X,
+    #[obake(cfg("<0.2"))]
+    Y,
+    #[obake(cfg(">=0.2"))]
+    Z(u32),
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        match from {
+            Bar_v0_1_0::X => Self::X,
+            Bar_v0_1_0::Y => Self::Z(0),
+        }
+    }
+}
+
+#[test]
+fn struct_coverage() {
+    assert_eq!(
+        Foo::COVERAGE,
+        &[
+            ("field_0", &[true, true, true][..]),
+            ("field_1", &[false, true, false][..]),
+            ("type", &[false, true, true][..]),
+        ]
+    );
+}
+
+#[test]
+fn enum_coverage() {
+    assert_eq!(
+        Bar::COVERAGE,
+        &[
+            ("X", &[true, true][..]),
+            ("Y", &[true, false][..]),
+            ("Z", &[false, true][..]),
+        ]
+    );
+}
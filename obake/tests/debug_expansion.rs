@@ -0,0 +1,34 @@
+#![cfg(feature = "debug-expansion")]
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(debug_expansion = "target/obake/debug_expansion")]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 0,
+        }
+    }
+}
+
+#[test]
+fn debug_expansion_is_written() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/target/obake/debug_expansion/Foo.rs"
+    );
+    let expansion = std::fs::read_to_string(path).unwrap();
+
+    assert!(expansion.contains("struct Foo_v0_1_0 {\n    field_0: u32,\n}"));
+    assert!(expansion.contains("struct Foo_v0_2_0 {\n    field_0: u32,\n    field_1: u32,\n}"));
+    assert!(expansion.contains("enum VersionedFoo {"));
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "defmt")]
+#![allow(unused_macros)]
+
+use obake::{AnyVersion, FormatVersion};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(defmt)]
+#[obake(derive(defmt::Format))]
+#[derive(defmt::Format)]
+struct Config {
+    baud: u32,
+    #[obake(cfg(">=0.2"))]
+    parity: bool,
+}
+
+impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+    fn from(from: Config!["0.1.0"]) -> Self {
+        Self {
+            baud: from.baud,
+            parity: false,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(version("1"))]
+#[obake(defmt)]
+enum Mode {
+    Idle,
+}
+
+fn assert_format<T: defmt::Format>(_: &T) {}
+
+fn assert_format_version<T: FormatVersion>(_: &T) {}
+
+// Nothing can be logged without a global logger, so these only check that the implementations
+// exist
+#[test]
+fn versions_can_be_formatted() {
+    let config: AnyVersion<Config> = Config_v0_1_0 { baud: 9600 }.into();
+    assert_format(&config);
+    assert_format(&obake::defmt_version(&config));
+
+    let mode: AnyVersion<Mode> = Mode::Idle.into();
+    assert_format_version(&mode);
+    assert_format(&obake::defmt_version(&mode));
+}
@@ -0,0 +1,66 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[obake(dense_migrations)]
+#[derive(PartialEq, Debug)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.3"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+            field_1: from.field_0.to_string(),
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.4.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+            field_1: from.field_1,
+        }
+    }
+}
+
+#[test]
+fn direct_conversions() {
+    let from = Foo_v0_1_0 { field_0: 0 };
+    assert_eq!(
+        <Foo!["0.3.0"]>::from(from),
+        Foo_v0_3_0 {
+            field_0: 2,
+            field_1: "1".to_owned(),
+        }
+    );
+
+    let from = Foo_v0_2_0 { field_0: 1 };
+    assert_eq!(
+        Foo::from(from),
+        Foo {
+            field_0: 3,
+            field_1: "1".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn direct_conversions_match_the_chain() {
+    let direct = Foo::from(Foo_v0_1_0 { field_0: 0 });
+    let chained = Foo::from(VersionedFoo::from(Foo_v0_1_0 { field_0: 0 }));
+    assert_eq!(direct, chained);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "json")]
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[obake(serde(deny_unknown_on_latest))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Foo {
+    #[obake(cfg("<0.3"))]
+    legacy: u32,
+    bar: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[obake(serde(deny_unknown_fields(">=0.2")))]
+#[obake(serde(allow_unknown_fields("0.3.0")))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Bar {
+    baz: u32,
+}
+
+#[test]
+fn only_the_latest_version_denies_unknown_fields() {
+    let json = r#"{ "legacy": 1, "bar": 2, "removed": 3 }"#;
+    assert!(serde_json::from_str::<Foo!["0.1.0"]>(json).is_ok());
+    assert!(serde_json::from_str::<Foo!["0.2.0"]>(json).is_ok());
+
+    let err = serde_json::from_str::<Foo!["0.3.0"]>(json).unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `legacy`"));
+    assert_eq!(
+        serde_json::from_str::<Foo!["0.3.0"]>(r#"{ "bar": 2 }"#).unwrap(),
+        Foo { bar: 2 },
+    );
+}
+
+#[test]
+fn strictness_is_overridden_per_version() {
+    let json = r#"{ "baz": 1, "removed": 2 }"#;
+    assert!(serde_json::from_str::<Bar!["0.1.0"]>(json).is_ok());
+    assert!(serde_json::from_str::<Bar!["0.2.0"]>(json).is_err());
+    assert_eq!(
+        serde_json::from_str::<Bar!["0.3.0"]>(json).unwrap(),
+        Bar { baz: 1 },
+    );
+}
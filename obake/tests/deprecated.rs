@@ -0,0 +1,49 @@
+#![deny(deprecated)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(deprecated("0.1.0", "migrate to 0.2.0 before the next release"))]
+#[obake(deprecated("0.2.0"))]
+#[derive(PartialEq, Eq, Debug)]
+struct Foo {
+    field_0: u32,
+}
+
+// Migrations from deprecated versions must be kept until they're removed
+#[allow(deprecated)]
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+#[test]
+fn deprecated_versions_still_migrate() {
+    #[allow(deprecated)]
+    let x: Foo!["0.1.0"] = Foo_v0_1_0 { field_0: 0 };
+    let x: Foo = obake::AnyVersion::<Foo>::from(x).into();
+    assert_eq!(x, Foo { field_0: 2 });
+}
+
+#[test]
+fn deprecated_arms_name_the_same_type() {
+    #[allow(deprecated)]
+    fn same(x: Foo_v0_2_0) -> Foo!["0.2.0"] {
+        x
+    }
+
+    assert_eq!(same(Foo_v0_2_0 { field_0: 1 }), Foo_v0_2_0 { field_0: 1 });
+}
@@ -0,0 +1,48 @@
+use obake::VersionTagged;
+
+trait Parse<'a>: Sized {
+    fn parse(input: &'a str) -> Option<Self>;
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Clone, Debug, PartialEq))]
+#[obake(derive_bounds(Foo_v0_1_0: Clone + PartialEq, Foo_v0_2_0: Clone))]
+// Bounds naming a lifetime of their own, as derives like `Arbitrary` need
+#[obake(derive_bounds(for<'a> Foo_v0_2_0: Parse<'a>))]
+#[derive(Clone, Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    bar: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { bar: 0 }
+    }
+}
+
+impl<'a> Parse<'a> for Foo {
+    fn parse(input: &'a str) -> Option<Self> {
+        Some(Self {
+            bar: input.parse().ok()?,
+        })
+    }
+}
+
+#[test]
+fn derives_with_bounds() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {}.into();
+    let y = x.clone();
+
+    assert_eq!(x, y);
+    assert_eq!(y.version_str(), "0.1.0");
+}
+
+#[test]
+fn higher_ranked_bounds() {
+    let x: obake::AnyVersion<Foo> = Foo::parse("7").unwrap().into();
+
+    assert_eq!(x, Foo { bar: 7 }.into());
+}
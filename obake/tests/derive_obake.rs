@@ -0,0 +1,68 @@
+#![allow(dead_code)]
+
+use obake::{VersionOf, Versioned};
+
+#[derive(obake::Obake)]
+#[obake(name = Foo)]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FooDefinition {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: Bar,
+}
+
+#[derive(obake::Obake)]
+#[obake(name = Bar)]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BarDefinition {
+    X,
+    #[obake(cfg(">=0.2"))]
+    Y(u32),
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: Bar::Y(from.field_0),
+        }
+    }
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        match from {
+            Bar_v0_1_0::X => Self::X,
+        }
+    }
+}
+
+#[test]
+fn derive_generates_versions() {
+    assert_eq!(Foo::VERSIONS, &["0.1.0", "0.2.0"]);
+    assert_eq!(<Foo!["0.1.0"]>::VERSION, "0.1.0");
+    assert_eq!(Bar::VERSIONS, &["0.1.0", "0.2.0"]);
+
+    let foo = Foo_v0_1_0 { field_0: 1 };
+    assert_eq!(foo.clone(), foo);
+}
+
+#[test]
+fn derive_migrates() {
+    let foo: Foo = obake::AnyVersion::<Foo>::from(Foo_v0_1_0 { field_0: 1 }).into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_0: 1,
+            field_1: Bar::Y(1),
+        }
+    );
+
+    let bar: Bar = obake::AnyVersion::<Bar>::from(Bar_v0_1_0::X).into();
+    assert_eq!(bar, Bar::X);
+}
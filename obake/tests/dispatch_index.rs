@@ -0,0 +1,118 @@
+use obake::{Dispatch, TagIndex, VersionOf, VersionVisitor, Versioned, VisitVersion};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(tag = u8)]
+#[cfg_attr(feature = "json", derive(serde::Deserialize))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    bar: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { bar: 0 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self { bar: from.bar }
+    }
+}
+
+// Tests are always compiled with `test`, so `0.1.0` is never compiled
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(cfg_when(not(test), "<0.2"))]
+struct Bar {}
+
+#[cfg(not(test))]
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.3.0"] {
+    fn from(_: Bar!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+struct Echo;
+
+impl<T: Versioned> VersionVisitor<T> for Echo {
+    type Output = (&'static str, usize);
+}
+
+impl<T: Versioned, V: VersionOf<T>> VisitVersion<T, V> for Echo {
+    fn visit(self) -> Self::Output {
+        (V::VERSION, V::INDEX)
+    }
+}
+
+#[test]
+fn versions_are_indexed_from_zero() {
+    assert_eq!(<Foo!["0.1.0"]>::INDEX, 0);
+    assert_eq!(<Foo!["0.2.0"]>::INDEX, 1);
+    assert_eq!(Foo::INDEX, 2);
+
+    for (index, version) in Foo::VERSIONS.iter().enumerate() {
+        assert_eq!(
+            obake::AnyVersion::<Foo>::dispatch_index(index, Echo),
+            Some((*version, index)),
+        );
+        assert_eq!(
+            obake::AnyVersion::<Foo>::dispatch(version, Echo),
+            Some((*version, index)),
+        );
+    }
+    assert_eq!(obake::AnyVersion::<Foo>::dispatch_index(3, Echo), None);
+}
+
+#[test]
+fn indices_count_excluded_versions() {
+    // The indices are the same whether or not the oldest versions are compiled
+    assert_eq!(Bar::VERSIONS, ["0.2.0", "0.3.0"]);
+    assert_eq!(<Bar!["0.2.0"]>::INDEX, 1);
+    assert_eq!(Bar::INDEX, 2);
+    assert_eq!(obake::AnyVersion::<Bar>::dispatch_index(0, Echo), None);
+    assert_eq!(
+        obake::AnyVersion::<Bar>::dispatch_index(1, Echo),
+        Some(("0.2.0", 1)),
+    );
+    assert_eq!(
+        obake::AnyVersion::<Bar>::dispatch_index(2, Echo),
+        Some(("0.3.0", 2)),
+    );
+    assert_eq!(obake::AnyVersion::<Bar>::dispatch_index(3, Echo), None);
+}
+
+#[test]
+fn tag_indices_are_dispatched_by_index() {
+    assert_eq!(
+        obake::AnyVersion::<Foo>::from_tag_index(1, Echo),
+        Some(("0.2.0", 1)),
+    );
+    assert_eq!(obake::AnyVersion::<Foo>::from_tag_index(7, Echo), None);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn payloads_are_decoded_by_index() {
+    use obake::codec::{decode_index, Json};
+
+    let decoded = decode_index::<Foo, _>(&Json, 1, br#"{"bar":5}"#);
+    assert_eq!(Foo::from(decoded.unwrap().unwrap()), Foo { bar: 5 });
+
+    let decoded = decode_index::<Foo, _>(&Json, 0, b"{}");
+    assert_eq!(Foo::from(decoded.unwrap().unwrap()), Foo { bar: 0 });
+
+    assert!(decode_index::<Foo, _>(&Json, 3, b"{}").is_none());
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "json")]
+
+use obake::AnyVersion;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(display)]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+#[test]
+fn display() {
+    let tagged: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 1 }.into();
+    assert_eq!(
+        tagged.to_string(),
+        r#"{"version":"0.1.0","data":{"field_0":1}}"#
+    );
+    assert_eq!(
+        format!("{tagged}"),
+        obake::json::to_string::<Foo>(&tagged).unwrap()
+    );
+}
+
+#[test]
+fn round_trip() {
+    let tagged: AnyVersion<Foo> = Foo {
+        field_0: 2,
+        field_1: "x".to_owned(),
+    }
+    .into();
+    assert_eq!(
+        tagged.to_string().parse::<AnyVersion<Foo>>().unwrap(),
+        tagged
+    );
+}
+
+#[test]
+fn from_str() {
+    let parsed: AnyVersion<Foo> = r#"{"version":"0.1.0","data":{"field_0":3}}"#.parse().unwrap();
+    assert_eq!(parsed, VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field_0: 3 }));
+    assert_eq!(Foo::from(parsed).field_1, "migrated");
+
+    assert!(matches!(
+        r#"{"version":"0.3.0","data":{}}"#.parse::<AnyVersion<Foo>>(),
+        Err(obake::json::Error::UnknownVersion(version)) if version == "0.3.0"
+    ));
+}
@@ -0,0 +1,80 @@
+#![allow(unused_macros)]
+
+mod api {
+    obake::versioned_items! {
+        #![obake(version("0.1.0"))]
+        #![obake(version("0.2.0"))]
+        #![obake(dto_modules)]
+
+        #[derive(Debug, PartialEq)]
+        pub struct Request {
+            pub id: u32,
+            #[obake(cfg(">=0.2"))]
+            pub filter: Option<String>,
+        }
+
+        #[derive(Debug, PartialEq)]
+        pub(crate) struct Response {
+            pub ok: bool,
+        }
+    }
+
+    impl From<Request!["0.1.0"]> for Request!["0.2.0"] {
+        fn from(from: Request!["0.1.0"]) -> Self {
+            Self {
+                id: from.id,
+                filter: None,
+            }
+        }
+    }
+
+    impl From<Response!["0.1.0"]> for Response!["0.2.0"] {
+        fn from(from: Response!["0.1.0"]) -> Self {
+            Self { ok: from.ok }
+        }
+    }
+}
+
+// Separately declared items can't share modules, so are declared in a module of their own
+mod internal {
+    #[obake::versioned]
+    #[obake(version("0.2.0"))]
+    #[obake(dto_modules)]
+    struct Private {}
+
+    pub fn private_v0_2_0() -> bool {
+        let _ = v0_2_0::Private {};
+        true
+    }
+}
+
+#[test]
+fn versions_are_grouped_by_module() {
+    let request = api::v0_1_0::Request { id: 1 };
+    let response = api::v0_1_0::Response { ok: true };
+    assert_eq!(request.id, 1);
+    assert!(response.ok);
+
+    let request = api::v0_2_0::Request {
+        id: 1,
+        filter: Some("x".to_owned()),
+    };
+    assert_eq!(request.filter.as_deref(), Some("x"));
+}
+
+#[test]
+fn modules_reexport_versioned_types() {
+    let request: api::v0_2_0::Request = api::Request {
+        id: 2,
+        filter: None,
+    };
+    let upgraded: api::Request =
+        obake::AnyVersion::<api::Request>::from(api::v0_1_0::Request { id: 2 }).into();
+
+    assert_eq!(upgraded, request);
+}
+
+#[test]
+fn private_items_are_reexported_to_their_module() {
+    assert!(internal::private_v0_2_0());
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "json")]
+
+use obake::{EncodeAs, EncodeAsError};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(downgrade)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: Option<String>,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: None,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+fn encode_as(
+    value: &Foo,
+    version: &str,
+) -> Result<(String, bool), EncodeAsError<serde_json::Error>> {
+    let mut out = Vec::new();
+    let encoded = value.encode_as(version, &mut serde_json::Serializer::new(&mut out))?;
+    Ok((String::from_utf8(out).unwrap(), encoded.lossy))
+}
+
+#[test]
+fn lossless_downgrades() {
+    let value = Foo {
+        field_0: 1,
+        field_1: None,
+    };
+
+    assert_eq!(
+        encode_as(&value, "0.1.0").unwrap(),
+        (r#"{"field_0":1}"#.to_owned(), false),
+    );
+    assert_eq!(
+        encode_as(&value, "0.2.0").unwrap(),
+        (r#"{"field_0":1,"field_1":null}"#.to_owned(), false),
+    );
+}
+
+#[test]
+fn lossy_downgrades() {
+    let value = Foo {
+        field_0: 1,
+        field_1: Some("x".to_owned()),
+    };
+
+    assert_eq!(
+        encode_as(&value, "0.1.0").unwrap(),
+        (r#"{"field_0":1}"#.to_owned(), true),
+    );
+}
+
+#[test]
+fn undeclared_versions() {
+    let value = Foo {
+        field_0: 1,
+        field_1: None,
+    };
+
+    assert!(matches!(
+        encode_as(&value, "0.3.0"),
+        Err(EncodeAsError::UnknownVersion)
+    ));
+}
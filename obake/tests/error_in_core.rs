@@ -0,0 +1,51 @@
+#![cfg(feature = "error_in_core")]
+
+use core::error::Error;
+
+use obake::pin::PinError;
+use obake::StepError;
+
+fn source_of(err: &(dyn Error + 'static)) -> Option<String> {
+    err.source().map(ToString::to_string)
+}
+
+#[test]
+fn errors_implement_the_core_error_trait() {
+    let err = StepError {
+        from: "0.1.0",
+        to: "0.2.0",
+        error: "not a byte",
+    };
+    assert_eq!(
+        (&err as &dyn Error).to_string(),
+        "migration from version 0.1.0 to version 0.2.0 failed: not a byte",
+    );
+    assert_eq!(source_of(&err), None);
+
+    let err = PinError {
+        version: "0.3.0",
+        pin: "0.2.0",
+    };
+    assert_eq!(
+        (&err as &dyn Error).to_string(),
+        "version 0.3.0 is newer than the pinned version 0.2.0",
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn keyed_errors_report_their_error_as_source() {
+    use obake::collections::KeyedError;
+
+    let err = KeyedError {
+        key: "a",
+        error: PinError {
+            version: "0.3.0",
+            pin: "0.2.0",
+        },
+    };
+    assert_eq!(
+        source_of(&err).as_deref(),
+        Some("version 0.3.0 is newer than the pinned version 0.2.0"),
+    );
+}
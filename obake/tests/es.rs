@@ -0,0 +1,56 @@
+#![cfg(feature = "json")]
+
+use obake::codec::Json;
+use obake::es::{CodecUpcaster, Error, Upcaster};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(PartialEq, Eq, Debug, serde::Deserialize)]
+struct Renamed {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    display_name: String,
+}
+
+impl From<Renamed!["0.1.0"]> for Renamed!["0.2.0"] {
+    fn from(from: Renamed!["0.1.0"]) -> Self {
+        Self {
+            display_name: from.name,
+        }
+    }
+}
+
+fn upcaster() -> Box<dyn Upcaster<Renamed, Error = Error<serde_json::Error>>> {
+    Box::new(CodecUpcaster::<Renamed, _>::new(Json))
+}
+
+#[test]
+fn upcast_every_version() {
+    let upcaster = upcaster();
+    let expected = Renamed {
+        display_name: "obake".to_owned(),
+    };
+
+    assert_eq!(
+        upcaster.upcast("0.1.0", br#"{"name":"obake"}"#).unwrap(),
+        expected,
+    );
+    assert_eq!(
+        upcaster
+            .upcast("0.2.0", br#"{"display_name":"obake"}"#)
+            .unwrap(),
+        expected,
+    );
+}
+
+#[test]
+fn upcast_unknown_version() {
+    let upcaster = upcaster();
+    assert!(!upcaster.can_upcast("0.3.0"));
+    assert!(matches!(
+        upcaster.upcast("0.3.0", b"{}"),
+        Err(Error::UnknownVersion(version)) if version == "0.3.0"
+    ));
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "json")]
+
+use obake::codec::Json;
+use obake::export::{self, Error, Exported};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(downgrade)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: Option<String>,
+    #[obake(cfg(">=0.3"))]
+    field_2: Vec<u32>,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: None,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1,
+            field_2: Vec::new(),
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+fn foo(field_1: Option<&str>, field_2: &[u32]) -> Foo {
+    Foo {
+        field_0: 1,
+        field_1: field_1.map(str::to_owned),
+        field_2: field_2.to_vec(),
+    }
+}
+
+#[test]
+fn values_are_exported_at_the_requested_version() {
+    let values = vec![foo(None, &[]), foo(Some("x"), &[]), foo(None, &[2])];
+    let export = export::downgrade_all(&Json, &values, "0.2.0").unwrap();
+
+    assert_eq!(export.version, "0.2.0");
+    assert_eq!(
+        export.items,
+        [
+            Exported {
+                payload: br#"{"field_0":1,"field_1":null}"#.to_vec(),
+                lossy: false,
+            },
+            Exported {
+                payload: br#"{"field_0":1,"field_1":"x"}"#.to_vec(),
+                lossy: false,
+            },
+            Exported {
+                payload: br#"{"field_0":1,"field_1":null}"#.to_vec(),
+                lossy: true,
+            },
+        ],
+    );
+    assert_eq!(export.lossy().collect::<Vec<_>>(), [2]);
+}
+
+#[test]
+fn payloads_can_be_taken_alone() {
+    let values = [foo(Some("x"), &[1]), foo(None, &[])];
+    let export = export::downgrade_all(&Json, &values, "0.1.0").unwrap();
+
+    assert_eq!(export.lossy().collect::<Vec<_>>(), [0]);
+    assert_eq!(
+        export.into_payloads(),
+        [br#"{"field_0":1}"#.to_vec(), br#"{"field_0":1}"#.to_vec()],
+    );
+}
+
+#[test]
+fn exporting_at_the_latest_version_is_lossless() {
+    let values = [foo(Some("x"), &[1])];
+    let export = export::downgrade_all(&Json, &values, "0.3.0").unwrap();
+
+    assert_eq!(export.lossy().count(), 0);
+}
+
+#[test]
+fn undeclared_versions_are_rejected() {
+    let err = export::downgrade_all(&Json, &[foo(None, &[])], "0.4.0").unwrap_err();
+    assert!(matches!(&err, Error::UnknownVersion(version) if version == "0.4.0"));
+    assert_eq!(err.to_string(), "cannot export as undeclared version 0.4.0");
+}
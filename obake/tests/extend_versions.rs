@@ -0,0 +1,104 @@
+use obake::{AnyExtended, Extends, VersionTagged};
+
+mod upstream {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(derive(Debug, PartialEq))]
+    #[derive(Debug, PartialEq)]
+    pub struct Config {
+        pub retries: u32,
+        #[obake(cfg(">=0.2"))]
+        pub verbose: bool,
+    }
+
+    impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+        fn from(from: Config!["0.1.0"]) -> Self {
+            Self {
+                retries: from.retries,
+                verbose: false,
+            }
+        }
+    }
+}
+
+#[obake::extend_versions(base = upstream::Config)]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq)]
+struct Config {
+    retries: u32,
+    verbose: bool,
+    #[obake(cfg(">=0.4"))]
+    timeout: u32,
+}
+
+impl From<upstream::Config> for Config!["0.3.0"] {
+    fn from(from: upstream::Config) -> Self {
+        Self {
+            retries: from.retries + 1,
+            verbose: from.verbose,
+        }
+    }
+}
+
+impl From<Config!["0.3.0"]> for Config!["0.4.0"] {
+    fn from(from: Config!["0.3.0"]) -> Self {
+        Self {
+            retries: from.retries,
+            verbose: from.verbose,
+            timeout: 30,
+        }
+    }
+}
+
+fn upgrade<T: Extends>(x: impl Into<AnyExtended<T>>) -> T {
+    x.into().into()
+}
+
+#[test]
+fn base_versions_upgrade_through_extension() {
+    let x = ExtendedConfig::from(obake::AnyVersion::<upstream::Config>::from(
+        upstream::Config_v0_1_0 { retries: 1 },
+    ));
+    assert_eq!(x.version_str(), "0.1.0");
+    assert_eq!(
+        upgrade::<Config>(x),
+        Config {
+            retries: 2,
+            verbose: false,
+            timeout: 30,
+        },
+    );
+}
+
+#[test]
+fn extension_versions_upgrade() {
+    let x = ExtendedConfig::from(obake::AnyVersion::<Config>::from(Config_v0_3_0 {
+        retries: 1,
+        verbose: true,
+    }));
+    assert_eq!(x.version_str(), "0.3.0");
+    assert_eq!(
+        upgrade::<Config>(x),
+        Config {
+            retries: 1,
+            verbose: true,
+            timeout: 30,
+        },
+    );
+}
+
+#[test]
+fn latest_is_extension() {
+    let latest = Config {
+        retries: 0,
+        verbose: false,
+        timeout: 0,
+    };
+    let x = ExtendedConfig::from(latest);
+    assert_eq!(x.version_str(), "0.4.0");
+    assert!(matches!(x, ExtendedConfig::Extension(_)));
+    assert_eq!(<Config as obake::Versioned>::VERSIONS, &["0.3.0", "0.4.0"],);
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "json")]
+#![allow(unused_macros)]
+
+/// Stands in for a type from another crate, which implements none of `serde`'s traits.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Stamp {
+    millis: u64,
+}
+
+/// Encodes a `Stamp` as whole seconds, as it was in older versions.
+mod secs_shim {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        stamp: &super::Stamp,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(stamp.millis / 1000)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<super::Stamp, D::Error> {
+        let secs = u64::deserialize(deserializer)?;
+        Ok(super::Stamp {
+            millis: secs * 1000,
+        })
+    }
+}
+
+/// Encodes a `Stamp` as milliseconds, as it is in newer versions.
+mod shims {
+    pub mod millis {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            stamp: &crate::Stamp,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_u64(stamp.millis)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<crate::Stamp, D::Error> {
+            Ok(crate::Stamp {
+                millis: u64::deserialize(deserializer)?,
+            })
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, PartialEq, serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Event {
+    #[obake(external("<0.2", serde_shim = secs_shim))]
+    #[obake(external(">=0.2", serde_shim = shims::millis))]
+    at: Stamp,
+    count: u32,
+}
+
+impl From<Event!["0.1.0"]> for Event!["0.2.0"] {
+    fn from(from: Event!["0.1.0"]) -> Self {
+        Self {
+            at: from.at,
+            count: from.count,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Deadline {
+    At(#[obake(external(serde_shim = secs_shim))] Stamp),
+    Never,
+}
+
+#[test]
+fn shims_apply_per_version() {
+    let old = Event_v0_1_0 {
+        at: Stamp { millis: 3000 },
+        count: 1,
+    };
+    let new = Event {
+        at: Stamp { millis: 3000 },
+        count: 1,
+    };
+
+    assert_eq!(
+        serde_json::to_string(&old).unwrap(),
+        r#"{"at":3,"count":1}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&new).unwrap(),
+        r#"{"at":3000,"count":1}"#,
+    );
+}
+
+#[test]
+fn shims_round_trip_through_migrations() {
+    let x: obake::AnyVersion<Event> =
+        serde_json::from_str(r#"{"Event_v0_1_0":{"at":4,"count":2}}"#).unwrap();
+    let x: Event = x.into();
+
+    assert_eq!(
+        x,
+        Event {
+            at: Stamp { millis: 4000 },
+            count: 2,
+        },
+    );
+}
+
+#[test]
+fn shims_apply_to_unnamed_fields() {
+    let x: Deadline = serde_json::from_str(r#"{"At":5}"#).unwrap();
+
+    assert_eq!(x, Deadline::At(Stamp { millis: 5000 }));
+    assert_eq!(serde_json::to_string(&x).unwrap(), r#"{"At":5}"#);
+}
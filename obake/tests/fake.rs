@@ -0,0 +1,51 @@
+#![cfg(feature = "fake")]
+
+use obake::fake::{Fake, Faker};
+use obake::{AnyVersion, VersionTagged, Versioned};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fake)]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[dummy(faker = "1..100")]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+#[test]
+fn every_version() {
+    let old: Foo!["0.1.0"] = Faker.fake();
+    assert!((1..100).contains(&old.field_0));
+
+    let new: Foo = Faker.fake();
+    assert!((1..100).contains(&new.field_0));
+}
+
+#[test]
+fn any_version() {
+    let mut seen = Vec::new();
+    for _ in 0..256 {
+        let tagged: AnyVersion<Foo> = Faker.fake();
+        let version = tagged.version_str();
+        if !seen.contains(&version) {
+            seen.push(version);
+        }
+
+        assert!((1..100).contains(&Foo::from(tagged).field_0));
+    }
+
+    seen.sort_unstable();
+    assert_eq!(seen, Foo::VERSIONS);
+}
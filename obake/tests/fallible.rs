@@ -0,0 +1,168 @@
+#![allow(dead_code, unused_macros)]
+
+use std::convert::TryFrom;
+
+use obake::{StepError, TryMigrate, TryUpgrade, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(fallible("0.3.0", error = String))]
+#[obake(derive(Clone, Debug, PartialEq))]
+#[derive(Clone, Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("<0.3"))]
+    field_0: String,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+    #[obake(cfg(">=0.3"))]
+    field_2: u8,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 1,
+        }
+    }
+}
+
+impl TryFrom<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Foo!["0.2.0"]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            field_1: from.field_1,
+            field_2: from.field_0.parse().map_err(|_| "not a byte")?,
+        })
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = &'static str))]
+#[derive(Clone, Debug, PartialEq)]
+enum Bar {
+    #[obake(cfg("0.1.0"))]
+    A(i32),
+    #[obake(cfg("0.2.0"))]
+    B(u32),
+}
+
+impl TryFrom<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Bar!["0.1.0"]) -> Result<Self, Self::Error> {
+        match from {
+            Bar_v0_1_0::A(x) => u32::try_from(x).map(Self::B).map_err(|_| "negative"),
+        }
+    }
+}
+
+#[test]
+fn try_upgrade_succeeds() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        field_0: "7".to_owned(),
+    }
+    .into();
+    assert_eq!(
+        x.try_upgrade(),
+        Ok(Foo {
+            field_1: 1,
+            field_2: 7,
+        }),
+    );
+
+    let x: obake::AnyVersion<Bar> = Bar_v0_1_0::A(3).into();
+    assert_eq!(x.try_upgrade(), Ok(Bar::B(3)));
+}
+
+#[test]
+fn try_upgrade_names_failing_step() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        field_0: "300".to_owned(),
+    }
+    .into();
+    assert_eq!(
+        x.try_upgrade(),
+        Err(StepError {
+            from: "0.2.0",
+            to: "0.3.0",
+            error: "not a byte".to_owned(),
+        }),
+    );
+
+    let x: obake::AnyVersion<Bar> = Bar_v0_1_0::A(-3).into();
+    let error = x.try_upgrade().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "migration from version 0.1.0 to version 0.2.0 failed: negative",
+    );
+}
+
+#[test]
+fn try_upgrade_inspect_borrows_partially_migrated_value() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        field_0: "x".to_owned(),
+    }
+    .into();
+    let mut inspected = None;
+    let error = x
+        .try_upgrade_inspect(|error, before| inspected = Some((error.clone(), before.clone())))
+        .unwrap_err();
+
+    let (inspected_error, before) = inspected.unwrap();
+    assert_eq!(inspected_error, error);
+    assert_eq!(before.version_str(), "0.2.0");
+    assert_eq!(
+        before,
+        VersionedFoo::Foo_v0_2_0(Foo_v0_2_0 {
+            field_0: "x".to_owned(),
+            field_1: 1,
+        }),
+    );
+}
+
+#[test]
+fn try_upgrade_inspect_is_not_called_on_success() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_2_0 {
+        field_0: "8".to_owned(),
+        field_1: 2,
+    }
+    .into();
+    let upgraded = x.try_upgrade_inspect(|_, _| panic!("inspected a successful upgrade"));
+    assert_eq!(
+        upgraded,
+        Ok(Foo {
+            field_1: 2,
+            field_2: 8,
+        }),
+    );
+}
+
+#[test]
+#[should_panic(expected = "migration from version 0.2.0 to version 0.3.0 failed: not a byte")]
+fn into_panics_on_failing_step() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        field_0: "x".to_owned(),
+    }
+    .into();
+    let _: Foo = x.into();
+}
+
+#[test]
+fn try_migrate_upgrades_any_version() {
+    let error = Foo_v0_1_0 {
+        field_0: "x".to_owned(),
+    }
+    .try_migrate()
+    .unwrap_err();
+    assert_eq!((error.from, error.to), ("0.2.0", "0.3.0"));
+
+    let x: obake::AnyVersion<Bar> = Bar_v0_1_0::A(3).into();
+    assert_eq!(x.try_migrate(), Ok(Bar::B(3)));
+    assert_eq!(Bar::B(4).try_migrate(), Ok(Bar::B(4)));
+}
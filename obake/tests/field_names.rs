@@ -0,0 +1,134 @@
+#![allow(dead_code)]
+
+use obake::FieldNames;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    r#type: u32,
+    #[obake(cfg("<0.3"))]
+    field_1: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            r#type: 0,
+            field_1: from.field_1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            r#type: from.r#type,
+        }
+    }
+}
+
+#[derive(Default, serde::Deserialize)]
+struct Extra {
+    extra: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+struct Bar {
+    user_id: u32,
+    #[serde(rename = "id")]
+    key: u32,
+    #[serde(rename(serialize = "out", deserialize = "in"))]
+    value: u32,
+    #[serde(skip)]
+    cache: u32,
+    #[serde(flatten)]
+    extra: Extra,
+    #[obake(cfg_attr("<0.2", serde(rename = "legacy")))]
+    current: u32,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        Self {
+            user_id: from.user_id,
+            key: from.key,
+            value: from.value,
+            cache: from.cache,
+            extra: from.extra,
+            current: from.current,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Baz {
+    HttpRequest,
+    #[serde(rename = "other")]
+    Response,
+    #[serde(skip_deserializing)]
+    Internal,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(alias_identical)]
+#[obake(cfg_attr("<0.2", serde(rename_all = "camelCase")))]
+#[derive(serde::Deserialize)]
+struct Qux {
+    user_id: u32,
+}
+
+impl From<Qux!["0.1.0"]> for Qux!["0.2.0"] {
+    fn from(from: Qux!["0.1.0"]) -> Self {
+        Self {
+            user_id: from.user_id,
+        }
+    }
+}
+
+#[test]
+fn struct_field_names() {
+    assert_eq!(<Foo!["0.1.0"]>::FIELD_NAMES, &["field_0", "field_1"]);
+    assert_eq!(
+        <Foo!["0.2.0"]>::FIELD_NAMES,
+        &["field_0", "type", "field_1"],
+    );
+    assert_eq!(<Foo!["0.3.0"]>::FIELD_NAMES, &["field_0", "type"]);
+}
+
+#[test]
+fn serde_field_names() {
+    assert_eq!(
+        <Bar!["0.1.0"]>::FIELD_NAMES,
+        &["USER-ID", "id", "in", "legacy"],
+    );
+    assert_eq!(
+        <Bar!["0.2.0"]>::FIELD_NAMES,
+        &["USER-ID", "id", "in", "CURRENT"],
+    );
+}
+
+#[test]
+fn conditional_container_field_names() {
+    assert_eq!(<Qux!["0.1.0"]>::FIELD_NAMES, &["userId"]);
+    assert_eq!(<Qux!["0.2.0"]>::FIELD_NAMES, &["user_id"]);
+}
+
+#[test]
+fn serde_variant_names() {
+    assert_eq!(<Baz!["0.1.0"]>::FIELD_NAMES, &["http_request", "other"]);
+}
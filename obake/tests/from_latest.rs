@@ -0,0 +1,116 @@
+#![allow(dead_code)]
+
+use obake::FromLatest;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_downgrade_subset)]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    bar: u32,
+    #[obake(cfg(">=0.2"))]
+    baz: String,
+    #[obake(cfg(">=0.3"))]
+    qux: Vec<u8>,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            bar: from.bar,
+            baz: String::new(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            bar: from.bar,
+            baz: from.baz,
+            qux: Vec::new(),
+        }
+    }
+}
+
+// `0.1.0` has a field that was since removed, so it can't be rebuilt from the latest version
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_downgrade_subset)]
+struct Bar {
+    #[obake(cfg("<0.2"))]
+    removed: u32,
+    #[obake(inherit)]
+    foo: Foo,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        Self {
+            foo: Foo_v0_1_0 { bar: from.removed }.into(),
+        }
+    }
+}
+
+// Inherited fields are rebuilt through the latest version of their own data-structure
+#[obake::versioned]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_downgrade_subset)]
+struct Baz {
+    #[obake(inherit)]
+    foo: Foo,
+}
+
+impl From<Baz!["0.2.0"]> for Baz!["0.3.0"] {
+    fn from(from: Baz!["0.2.0"]) -> Self {
+        Self {
+            foo: from.foo.into(),
+        }
+    }
+}
+
+fn latest() -> Foo {
+    Foo {
+        bar: 1,
+        baz: "baz".to_owned(),
+        qux: vec![2],
+    }
+}
+
+#[test]
+fn rebuild_older_versions() {
+    let x = <Foo!["0.1.0"]>::from_latest(latest());
+    assert_eq!(x.bar, 1);
+
+    let x = <Foo!["0.2.0"]>::from_latest(latest());
+    assert_eq!(x.bar, 1);
+    assert_eq!(x.baz, "baz");
+
+    assert_eq!(Foo::from_latest(latest()), latest());
+}
+
+#[test]
+fn rebuild_inherited_fields() {
+    let x = <Baz!["0.2.0"]>::from_latest(Baz { foo: latest() });
+    assert_eq!(x.foo.baz, "baz");
+
+    let x = <Bar!["0.2.0"]>::from_latest(Bar {
+        foo: Foo_v0_2_0 {
+            bar: 1,
+            baz: "baz".to_owned(),
+        },
+    });
+    assert_eq!(x.foo.bar, 1);
+}
+
+#[test]
+fn supersets_are_skipped() {
+    fn implements<T: FromLatest<Bar>>() {}
+
+    // `Bar!["0.1.0"]` doesn't implement `FromLatest<Bar>`
+    implements::<Bar>();
+}
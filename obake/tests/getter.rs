@@ -0,0 +1,55 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(getter)]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    #[obake(getter)]
+    count: u32,
+    #[obake(cfg("<0.3"))]
+    #[obake(getter)]
+    legacy: bool,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            count: 0,
+            legacy: from.legacy,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            name: from.name,
+            count: from.count,
+        }
+    }
+}
+
+#[test]
+fn fields_are_borrowed_from_versions_which_have_them() {
+    let old: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        name: "old".to_owned(),
+        legacy: true,
+    }
+    .into();
+    assert_eq!(old.name().map(String::as_str), Some("old"));
+    assert_eq!(old.count(), None);
+    assert_eq!(old.legacy(), Some(&true));
+
+    let latest: obake::AnyVersion<Foo> = Foo {
+        name: "latest".to_owned(),
+        count: 3,
+    }
+    .into();
+    assert_eq!(latest.name().map(String::as_str), Some("latest"));
+    assert_eq!(latest.count(), Some(&3));
+    assert_eq!(latest.legacy(), None);
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "async-graphql")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use async_graphql::{EmptySubscription, Object, Schema};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(async_graphql)]
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn latest(&self) -> Foo {
+        Foo {
+            field_0: 1,
+            field_1: "one".to_owned(),
+        }
+    }
+}
+
+struct Mutation;
+
+#[Object]
+impl Mutation {
+    async fn save(&self, value: AnyFoo) -> Foo {
+        value.into()
+    }
+
+    async fn save_old(&self, value: Foo_v0_1_0) -> u32 {
+        value.field_0
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn execute(query: &str) -> serde_json::Value {
+    let schema = Schema::new(Query, Mutation, EmptySubscription);
+    let response = block_on(schema.execute(query));
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+#[test]
+fn version_objects() {
+    let sdl = Schema::new(Query, Mutation, EmptySubscription).sdl();
+    assert!(sdl.contains("type FooV0_2_0 {"));
+    assert!(sdl.contains("input FooV0_1_0Input {"));
+    assert!(sdl.contains("scalar AnyFoo"));
+
+    assert_eq!(
+        execute("{ latest { field0 field1 } }"),
+        serde_json::json!({ "latest": { "field0": 1, "field1": "one" } }),
+    );
+    assert_eq!(
+        execute("mutation { saveOld(value: { field0: 3 }) }"),
+        serde_json::json!({ "saveOld": 3 }),
+    );
+}
+
+#[test]
+fn any_version_scalar() {
+    assert_eq!(
+        execute(
+            r#"mutation { save(value: { version: "0.1.0", data: { field_0: 7 } }) { field0 field1 } }"#
+        ),
+        serde_json::json!({ "save": { "field0": 7, "field1": "default" } }),
+    );
+    assert_eq!(
+        execute(r#"mutation { save(value: { field_0: 7, field_1: "x" }) { field1 } }"#),
+        serde_json::json!({ "save": { "field1": "x" } }),
+    );
+}
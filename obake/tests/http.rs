@@ -0,0 +1,74 @@
+#![cfg(feature = "json")]
+
+use obake::http::{negotiate, respond, UnsupportedVersion};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(downgrade)]
+#[derive(Clone, serde::Serialize)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { field_0: 0 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(_: Foo!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+#[test]
+fn negotiate_preferred_version() {
+    assert_eq!(negotiate::<Foo>(None), Ok("0.3.0"));
+    assert_eq!(negotiate::<Foo>(Some("0.2.0")), Ok("0.2.0"));
+    assert_eq!(negotiate::<Foo>(Some(" 1.0.0 ,0.1.0, 0.2.0")), Ok("0.1.0"));
+    assert_eq!(
+        negotiate::<Foo>(Some("1.0.0")),
+        Err(UnsupportedVersion {
+            requested: "1.0.0".to_owned(),
+            supported: &["0.1.0", "0.2.0", "0.3.0"],
+        }),
+    );
+}
+
+#[test]
+fn respond_at_negotiated_version() {
+    let value = Foo { field_0: 42 };
+    assert_eq!(
+        respond(value.clone(), None).unwrap().unwrap(),
+        br#"{"version":"0.3.0","data":{"field_0":42}}"#,
+    );
+    assert_eq!(
+        respond(value.clone(), Some("0.2.0")).unwrap().unwrap(),
+        br#"{"version":"0.2.0","data":{"field_0":42}}"#,
+    );
+    assert_eq!(
+        respond(value.clone(), Some("0.1.0")).unwrap().unwrap(),
+        br#"{"version":"0.1.0","data":{}}"#,
+    );
+    assert!(respond(value, Some("0.4.0")).is_err());
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "idl")]
+#![allow(dead_code)]
+
+use obake::idl::{render, write_schemas, Format};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(idl)]
+enum Bar {
+    X,
+    #[obake(cfg(">=0.2"))]
+    Y(u8, Vec<u8>),
+    Z {
+        field_0: i64,
+    },
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(idl)]
+struct Foo {
+    #[obake(inherit)]
+    field_0: Bar,
+    #[obake(cfg(">=0.2"))]
+    field_1: Option<Vec<String>>,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        match from {
+            Bar_v0_1_0::X => Self::X,
+            Bar_v0_1_0::Z { field_0 } => Self::Z { field_0 },
+        }
+    }
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0.into(),
+            field_1: None,
+        }
+    }
+}
+
+#[test]
+fn capnp() {
+    let schema = render::<Foo, Foo!["0.2.0"]>(Format::Capnp);
+    let body = schema.splitn(3, '\n').nth(2).unwrap();
+    assert!(schema.starts_with("# `Foo` version 0.2.0, generated by obake.\n@0x"));
+    assert_eq!(
+        body,
+        "
+struct Foo {
+  field0 @0 :Bar;
+  field1 @1 :List(Text);
+}
+
+struct Bar {
+  union {
+    x @0 :Void;
+    y :group {
+      field0 @1 :UInt8;
+      field1 @2 :Data;
+    }
+    z :group {
+      field0 @3 :Int64;
+    }
+  }
+}
+"
+    );
+
+    // IDs are stable, and distinct between versions
+    assert_eq!(schema, render::<Foo, Foo!["0.2.0"]>(Format::Capnp));
+    assert_ne!(
+        schema.lines().nth(1),
+        render::<Foo, Foo!["0.1.0"]>(Format::Capnp).lines().nth(1),
+    );
+}
+
+#[test]
+fn flatbuffers() {
+    assert_eq!(
+        render::<Bar, Bar!["0.1.0"]>(Format::Flatbuffers),
+        "// `Bar` version 0.1.0, generated by obake.
+
+table BarX {
+}
+
+table BarZ {
+  field_0:long;
+}
+
+union BarVariant { BarX, BarZ }
+
+table Bar {
+  value:BarVariant;
+}
+
+root_type Bar;
+"
+    );
+}
+
+#[test]
+fn write_all_schemas() {
+    let dir = std::env::temp_dir().join(format!("obake-idl-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    write_schemas::<Foo>(&dir).unwrap();
+
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            "foo_v0_1_0.capnp",
+            "foo_v0_1_0.fbs",
+            "foo_v0_2_0.capnp",
+            "foo_v0_2_0.fbs",
+        ],
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("foo_v0_1_0.fbs")).unwrap(),
+        render::<Foo, Foo!["0.1.0"]>(Format::Flatbuffers),
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "json")]
+
+use obake::json::{Error, ForwardCompatible, RawVersioned};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.to_string(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Message {
+    id: u32,
+    body: RawVersioned<Foo>,
+}
+
+#[test]
+fn raw_envelopes_are_forwarded_unchanged() {
+    let json = r#"{"id":1,"body":{"version":"0.1.0","data":{"field_0":7}}}"#;
+    let message: Message = serde_json::from_str(json).unwrap();
+    assert_eq!(message.body.version(), "0.1.0");
+    assert_eq!(serde_json::to_string(&message).unwrap(), json);
+
+    let foo: Foo = message.body.parse().unwrap().into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_1: "7".to_owned()
+        }
+    );
+}
+
+#[test]
+fn raw_envelopes_are_parsed_lazily() {
+    let raw =
+        RawVersioned::<Foo>::from_slice(br#"{"version":"0.2.0","data":{"field_0":7}}"#).unwrap();
+    assert!(matches!(raw.clone().parse(), Err(Error::Json(_))));
+
+    let raw = RawVersioned::<Foo>::from_slice(br#"{"version":"0.3.0","data":null}"#).unwrap();
+    assert!(matches!(raw.parse(), Err(Error::UnknownVersion(version)) if version == "0.3.0"));
+
+    assert!(RawVersioned::<Foo>::from_slice(br#"{"field_1":"x"}"#).is_err());
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Relayed {
+    id: u32,
+    body: ForwardCompatible<Foo>,
+}
+
+#[test]
+fn unknown_versions_are_passed_through() {
+    let json = r#"{"id":1,"body":{"version":"0.3.0","data":{"field_2":[1,2]}}}"#;
+    let relayed: Relayed = serde_json::from_str(json).unwrap();
+    assert_eq!(relayed.body.version(), "0.3.0");
+    assert!(matches!(relayed.body, ForwardCompatible::Unknown(_)));
+    assert_eq!(serde_json::to_string(&relayed).unwrap(), json);
+}
+
+#[test]
+fn known_versions_are_deserialized() {
+    let json = r#"{"version":"0.1.0","data":{"field_0":7}}"#;
+    let body = ForwardCompatible::<Foo>::from_slice(json.as_bytes()).unwrap();
+    assert_eq!(body.version(), "0.1.0");
+    assert_eq!(serde_json::to_string(&body).unwrap(), json);
+
+    let foo: Foo = body.known().unwrap().into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_1: "7".to_owned()
+        }
+    );
+
+    assert!(ForwardCompatible::<Foo>::from_slice(br#"{"version":"0.2.0","data":{}}"#).is_err());
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "json5")]
+
+use obake::json5::{from_str, to_string, Error};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.to_string(),
+        }
+    }
+}
+
+#[test]
+fn envelopes_round_trip() {
+    let tagged: obake::AnyVersion<Foo> = Foo {
+        field_1: "x".to_owned(),
+    }
+    .into();
+    let encoded = to_string::<Foo>(&tagged).unwrap();
+    assert_eq!(encoded, r#"{"version":"0.2.0","data":{"field_1":"x"}}"#);
+    assert_eq!(from_str::<Foo>(&encoded).unwrap(), tagged);
+}
+
+#[test]
+fn hand_edited_envelopes_are_decoded() {
+    let json5 = r#"
+        {
+            // Written before `field_1` was added
+            version: '0.1.0',
+            data: { field_0: 0x10, },
+        }
+    "#;
+    let foo: Foo = from_str::<Foo>(json5).unwrap().into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_1: "16".to_owned()
+        }
+    );
+}
+
+#[test]
+fn untagged_payloads_are_detected() {
+    let foo: Foo = from_str::<Foo>("{ field_0: 3 }").unwrap().into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_1: "3".to_owned()
+        }
+    );
+
+    let foo: Foo = from_str::<Foo>("{ field_1: 'y' }").unwrap().into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_1: "y".to_owned()
+        }
+    );
+
+    assert!(matches!(
+        from_str::<Foo>("{ field_2: [] }"),
+        Err(Error::Unrecognized)
+    ));
+}
+
+#[test]
+fn tagged_errors_are_reported() {
+    assert!(matches!(
+        from_str::<Foo>("{ version: '0.3.0', data: null }"),
+        Err(Error::UnknownVersion(version)) if version == "0.3.0"
+    ));
+    assert!(matches!(
+        from_str::<Foo>("{ version: '0.2.0', data: { field_0: 1 } }"),
+        Err(Error::Json5(_))
+    ));
+}
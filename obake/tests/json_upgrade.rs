@@ -0,0 +1,105 @@
+#![cfg(feature = "json")]
+#![allow(unused_macros)]
+
+use obake::json::{Error, FieldEdit, UpgradeValue};
+use serde_json::json;
+
+fn default_limit() -> u32 {
+    10
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(json_upgrade)]
+#[obake(auto_migrate)]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Foo {
+    #[obake(cfg_attr("<0.3", serde(rename = "user")))]
+    user_name: String,
+    #[obake(cfg("<0.2"))]
+    legacy: bool,
+    #[obake(cfg(">=0.2"))]
+    #[obake(migrate_default = default_limit())]
+    limit: u32,
+    #[obake(cfg("<0.3"))]
+    count: u32,
+    #[obake(cfg(">=0.3"))]
+    count: u64,
+}
+
+#[test]
+fn steps_list_renames_drops_and_additions() {
+    let steps = <Foo as UpgradeValue>::STEPS;
+    assert_eq!(steps.len(), 2);
+    assert_eq!((steps[0].from, steps[0].to), ("0.1.0", "0.2.0"));
+    assert!(matches!(
+        steps[0].edits,
+        [
+            FieldEdit::Drop("legacy"),
+            FieldEdit::Add { name: "limit", .. }
+        ]
+    ));
+    assert!(matches!(
+        steps[1].edits,
+        [FieldEdit::Rename {
+            from: "user",
+            to: "userName"
+        }]
+    ));
+}
+
+#[test]
+fn payloads_are_migrated_without_deserializing() {
+    let value = json!({
+        "version": "0.1.0",
+        "data": { "user": "obake", "legacy": true, "count": 1, "unmodelled": [1, 2] },
+    });
+    assert_eq!(
+        obake::json::upgrade_value::<Foo>(value).unwrap(),
+        json!({
+            "version": "0.3.0",
+            "data": { "userName": "obake", "limit": 10, "count": 1, "unmodelled": [1, 2] },
+        }),
+    );
+}
+
+#[test]
+fn upgraded_payloads_decode_as_the_latest_version() {
+    let value = json!({ "version": "0.2.0", "data": { "user": "obake", "limit": 3, "count": 2 } });
+    let upgraded = obake::json::upgrade_value::<Foo>(value).unwrap();
+    let decoded = obake::json::from_slice::<Foo>(upgraded.to_string().as_bytes()).unwrap();
+    assert_eq!(
+        Foo::from(decoded),
+        Foo {
+            user_name: "obake".to_owned(),
+            limit: 3,
+            count: 2,
+        },
+    );
+}
+
+#[test]
+fn fields_present_already_are_kept() {
+    let value = json!({ "version": "0.1.0", "data": { "user": "obake", "limit": 5 } });
+    let upgraded = obake::json::upgrade_value::<Foo>(value).unwrap();
+    assert_eq!(upgraded["data"]["limit"], 5);
+}
+
+#[test]
+fn malformed_payloads_are_rejected() {
+    assert!(matches!(
+        obake::json::upgrade_value::<Foo>(json!({ "version": "0.4.0", "data": {} })),
+        Err(Error::UnknownVersion(version)) if version == "0.4.0"
+    ));
+    assert!(matches!(
+        obake::json::upgrade_value::<Foo>(json!({ "version": "0.1.0", "data": [] })),
+        Err(Error::Json(_))
+    ));
+    assert!(matches!(
+        obake::json::upgrade_value::<Foo>(json!({ "user": "obake" })),
+        Err(Error::Json(_))
+    ));
+}
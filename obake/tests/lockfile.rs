@@ -0,0 +1,54 @@
+#![allow(dead_code, unused_macros)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(lockfile = "tests/lockfile.lock")]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    count: u32,
+    #[obake(cfg(">=0.2"))]
+    tags: Vec<Option<String>>,
+    #[obake(cfg(">=0.2"))]
+    digest: [u8; 4],
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self {
+            tags: Vec::new(),
+            digest: [0; 4],
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(lockfile = "tests/lockfile.lock")]
+enum Bar {
+    X,
+    Y(u32, String),
+    Z { w: bool },
+}
+
+#[test]
+fn schemas_are_recorded_in_the_lockfile() {
+    let locked = include_str!("lockfile.lock");
+
+    assert!(locked.starts_with("# Generated by obake"));
+    assert!(locked.contains("\n[Foo 0.1.0] b662504a2530e362\ncount: u32\n"));
+    assert!(locked.contains("\ntags: Vec<Option<String>>\ndigest: [u8; 4]\n"));
+    assert!(locked.contains("\n[Bar 0.1.0] "));
+    assert!(locked.contains("\nX\nY(u32, String)\nZ { w: bool }\n"));
+    assert!(locked.contains("\n[nested::Foo 0.1.0] "));
+    assert!(locked.contains("\nname: String\n"));
+}
+
+mod nested {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(lockfile("tests/lockfile.lock", key = "nested::Foo"))]
+    struct Foo {
+        name: String,
+    }
+}
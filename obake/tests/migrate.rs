@@ -0,0 +1,71 @@
+#![allow(unused_macros)]
+
+use obake::{AnyVersion, Migrate};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(PartialEq, Debug)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { field_0: 1 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(alias_identical)]
+#[derive(PartialEq, Debug)]
+enum Bar {
+    X,
+}
+
+/// A store holding the latest version of whatever it's given.
+struct Store<T> {
+    values: Vec<T>,
+}
+
+impl<T> Store<T> {
+    fn insert(&mut self, value: impl Migrate<Latest = T>) {
+        self.values.push(value.migrate());
+    }
+}
+
+#[test]
+fn migrate_versions() {
+    let mut store = Store { values: Vec::new() };
+    store.insert(Foo_v0_1_0 {});
+    store.insert(Foo_v0_2_0 { field_0: 3 });
+    store.insert(Foo { field_0: 7 });
+    assert_eq!(
+        store.values,
+        [Foo { field_0: 2 }, Foo { field_0: 4 }, Foo { field_0: 7 }],
+    );
+}
+
+#[test]
+fn migrate_any_version() {
+    let mut store = Store { values: Vec::new() };
+    store.insert(AnyVersion::<Foo>::from(Foo_v0_1_0 {}));
+    assert_eq!(store.values, [Foo { field_0: 2 }]);
+
+    let mut store = Store { values: Vec::new() };
+    store.insert(AnyVersion::<Bar>::from(Bar_v0_1_0::X));
+    store.insert(Bar::X);
+    assert_eq!(store.values, [Bar::X, Bar::X]);
+}
@@ -0,0 +1,142 @@
+use obake::graph::{Edge, EdgeKind};
+use obake::Migrations;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+struct Foo {}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(_: Foo!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(dense_migrations)]
+#[obake(downgrade)]
+#[derive(Clone)]
+struct Bar {}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.3.0"] {
+    fn from(_: Bar!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Bar!["0.3.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.3.0"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.1.0"] {
+    fn from(_: Bar!["0.2.0"]) -> Self {
+        Self {}
+    }
+}
+
+const fn edge(from: &'static str, to: &'static str, kind: EdgeKind) -> Edge {
+    Edge { from, to, kind }
+}
+
+#[test]
+fn adjacent_migrations() {
+    let graph = Foo::migration_graph();
+
+    assert_eq!(graph.name, "Foo");
+    assert_eq!(graph.versions, &["0.1.0", "0.2.0", "0.3.0"]);
+    assert_eq!(
+        graph.edges,
+        &[
+            edge("0.1.0", "0.2.0", EdgeKind::Upgrade),
+            edge("0.2.0", "0.3.0", EdgeKind::Upgrade),
+        ],
+    );
+}
+
+#[test]
+fn shortcuts_and_downgrades() {
+    let graph = Bar::migration_graph();
+
+    assert_eq!(graph.name, "Bar");
+    assert_eq!(
+        graph.edges,
+        &[
+            edge("0.1.0", "0.2.0", EdgeKind::Upgrade),
+            edge("0.2.0", "0.3.0", EdgeKind::Upgrade),
+            edge("0.1.0", "0.3.0", EdgeKind::Shortcut),
+            edge("0.3.0", "0.2.0", EdgeKind::Downgrade),
+            edge("0.2.0", "0.1.0", EdgeKind::Downgrade),
+        ],
+    );
+    assert_eq!(graph.edges_of_kind(EdgeKind::Shortcut).count(), 1);
+    assert_eq!(graph.edges_from("0.2.0").count(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn hops() {
+    let graph = Foo::migration_graph();
+
+    assert_eq!(graph.hops("0.1.0", "0.1.0"), Some(0));
+    assert_eq!(graph.hops("0.1.0", "0.3.0"), Some(2));
+    assert_eq!(graph.hops("0.3.0", "0.1.0"), None);
+    assert_eq!(graph.hops("0.1.0", "0.4.0"), None);
+
+    let graph = Bar::migration_graph();
+
+    assert_eq!(graph.hops("0.1.0", "0.3.0"), Some(1));
+    assert_eq!(graph.hops("0.3.0", "0.1.0"), Some(2));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn dot() {
+    assert_eq!(
+        Bar::migration_graph().to_dot(),
+        "digraph \"Bar\" {
+    \"0.1.0\";
+    \"0.2.0\";
+    \"0.3.0\";
+    \"0.1.0\" -> \"0.2.0\" [style=solid];
+    \"0.2.0\" -> \"0.3.0\" [style=solid];
+    \"0.1.0\" -> \"0.3.0\" [style=dashed];
+    \"0.3.0\" -> \"0.2.0\" [style=dotted];
+    \"0.2.0\" -> \"0.1.0\" [style=dotted];
+}
+",
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    assert_eq!(
+        serde_json::to_value(Foo::migration_graph()).unwrap(),
+        serde_json::json!({
+            "name": "Foo",
+            "versions": ["0.1.0", "0.2.0", "0.3.0"],
+            "edges": [
+                { "from": "0.1.0", "to": "0.2.0", "kind": "upgrade" },
+                { "from": "0.2.0", "to": "0.3.0", "kind": "upgrade" },
+            ],
+        }),
+    );
+}
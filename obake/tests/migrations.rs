@@ -1,4 +1,4 @@
-#![allow(unused)]
+#![allow(unused, clippy::disallowed_names)]
 
 #[obake::versioned]
 #[obake(version("0.1.0"))]
@@ -99,3 +99,52 @@ impl From<Baz!["0.2.0"]> for Baz!["0.3.0"] {
         }
     }
 }
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+enum Qux {
+    Settings(#[obake(inherit)] Foo, u32),
+    #[obake(cfg(">=0.2"))]
+    Nested(#[obake(inherit)] Baz),
+}
+
+impl From<Qux!["0.1.0"]> for Qux!["0.2.0"] {
+    fn from(from: Qux!["0.1.0"]) -> Self {
+        type Qux = Qux!["0.1.0"];
+        match from {
+            Qux::Settings(foo, x) => Self::Settings(foo.into(), x),
+        }
+    }
+}
+
+impl From<Qux!["0.2.0"]> for Qux!["0.3.0"] {
+    fn from(from: Qux!["0.2.0"]) -> Self {
+        type Qux = Qux!["0.2.0"];
+        match from {
+            Qux::Settings(foo, x) => Self::Settings(foo.into(), x),
+            Qux::Nested(baz) => Self::Nested(baz.into()),
+        }
+    }
+}
+
+#[test]
+fn inherited_unnamed_fields() {
+    let qux: obake::AnyVersion<Qux> = Qux_v0_1_0::Settings(
+        Foo_v0_1_0 {
+            field_0: 7,
+            field_2: 0,
+        },
+        1,
+    )
+    .into();
+    match Qux::from(qux) {
+        Qux::Settings(foo, x) => {
+            assert_eq!(foo.field_0, 7);
+            assert_eq!(foo.field_2, 42);
+            assert_eq!(x, 1);
+        }
+        Qux::Nested(_) => unreachable!(),
+    }
+}
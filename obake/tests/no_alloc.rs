@@ -0,0 +1,82 @@
+#![allow(unused_macros)]
+
+use core::mem::size_of;
+
+use obake::{NoAlloc, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(no_alloc)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Bar {
+    #[obake(cfg(">=0.2"))]
+    field_0: u8,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self { field_0: 0 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(no_alloc(max_size = 16))]
+#[derive(PartialEq, Debug)]
+struct Foo {
+    field_0: u32,
+    #[obake(inherit)]
+    #[obake(cfg(">=0.2"))]
+    field_1: Bar,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: Bar_v0_1_0 {}.into(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(no_alloc(max_size = 0x10))]
+enum Baz {
+    X(#[obake(inherit)] Bar),
+    Y {
+        #[obake(inherit)]
+        field_0: Bar,
+    },
+    Z,
+}
+
+const _: () = obake::assert_no_alloc::<Foo>();
+const _: () = obake::assert_no_alloc::<Baz>();
+
+fn assert_no_alloc<T: NoAlloc>() {}
+
+#[test]
+fn no_alloc_is_implemented() {
+    assert_no_alloc::<Bar>();
+    assert_no_alloc::<Foo>();
+    assert_no_alloc::<Baz>();
+}
+
+#[test]
+fn no_alloc_bounds_size() {
+    assert!(size_of::<obake::AnyVersion<Foo>>() <= 16);
+    assert!(size_of::<obake::AnyVersion<Baz>>() <= 16);
+}
+
+#[test]
+fn no_alloc_migrates() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 { field_0: 1 }.into();
+    assert_eq!(x.version_str(), "0.1.0");
+
+    let x: Foo = x.into();
+    assert_eq!(x.field_0, 1);
+    assert_eq!(x.field_1, Bar { field_0: 0 });
+}
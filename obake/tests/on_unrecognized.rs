@@ -0,0 +1,90 @@
+#![cfg(feature = "json")]
+
+use std::cell::RefCell;
+
+use obake::OnUnrecognized;
+
+thread_local! {
+    static RECORDED: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(on_unrecognized = recover)]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    bar: u32,
+    #[obake(cfg(">=0.2"))]
+    baz: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            baz: from.bar.to_string(),
+        }
+    }
+}
+
+// Recovers payloads where `bar` was written as a string, and records any others
+fn recover(payload: &[u8]) -> Option<obake::AnyVersion<Foo>> {
+    #[derive(serde::Deserialize)]
+    struct Legacy {
+        bar: String,
+    }
+
+    match serde_json::from_slice::<Legacy>(payload) {
+        Ok(legacy) => Some(
+            Foo_v0_1_0 {
+                bar: legacy.bar.parse().ok()?,
+            }
+            .into(),
+        ),
+        Err(_) => {
+            RECORDED.with(|recorded| recorded.borrow_mut().push(payload.to_vec()));
+            None
+        }
+    }
+}
+
+#[test]
+fn recognized_payloads_skip_hook() {
+    let x = obake::json::from_slice_or_recover::<Foo>(br#"{"bar":1}"#).unwrap();
+
+    assert_eq!(x, Foo_v0_1_0 { bar: 1 }.into());
+    RECORDED.with(|recorded| assert!(recorded.borrow().is_empty()));
+}
+
+#[test]
+fn unrecognized_payloads_are_recovered() {
+    let x = obake::json::from_slice_or_recover::<Foo>(br#"{"bar":"42"}"#).unwrap();
+
+    assert_eq!(
+        Foo::from(x),
+        Foo {
+            baz: "42".to_owned(),
+        },
+    );
+}
+
+#[test]
+fn unrecoverable_payloads_are_recorded() {
+    let err = obake::json::from_slice_or_recover::<Foo>(br#"{"qux":1}"#).unwrap_err();
+
+    assert!(matches!(err, obake::json::Error::Unrecognized));
+    RECORDED.with(|recorded| assert_eq!(*recorded.borrow(), vec![br#"{"qux":1}"#.to_vec()]));
+}
+
+#[test]
+fn malformed_tagged_payloads_reach_hook() {
+    let payload = br#"{"version":"0.1.0","data":{"bar":"7"}}"#;
+    let err = obake::json::from_slice_or_recover::<Foo>(payload).unwrap_err();
+
+    // The hook only recovers untagged payloads, so the original error is returned
+    assert!(matches!(err, obake::json::Error::Json(_)));
+    RECORDED.with(|recorded| assert_eq!(*recorded.borrow(), vec![payload.to_vec()]));
+    assert!(Foo::on_unrecognized(br#"{"bar":"7"}"#).is_some());
+}
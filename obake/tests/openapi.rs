@@ -0,0 +1,77 @@
+#![cfg(all(feature = "utoipa", feature = "json"))]
+#![allow(dead_code)]
+
+use obake::AnyVersion;
+use serde_json::json;
+use utoipa::{PartialSchema, ToSchema};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(utoipa)]
+struct Bar {
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(utoipa)]
+struct Foo {
+    #[obake(inherit)]
+    bar: Bar,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            bar: from.bar.into(),
+            field_1: String::new(),
+        }
+    }
+}
+
+#[test]
+fn version_names() {
+    assert_eq!(<Foo!["0.1.0"]>::name(), "FooV0_1_0");
+    assert_eq!(<Foo!["0.2.0"]>::name(), "FooV0_2_0");
+    assert_eq!(AnyVersion::<Foo>::name(), "VersionedFoo");
+}
+
+#[test]
+fn version_schemas() {
+    let mut schemas = Vec::new();
+    AnyVersion::<Foo>::schemas(&mut schemas);
+
+    let mut names: Vec<_> = schemas.iter().map(|(name, _)| name.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names, ["BarV0_1_0", "BarV0_2_0", "FooV0_1_0", "FooV0_2_0"]);
+}
+
+#[test]
+fn envelope_schema() {
+    let schema = serde_json::to_value(AnyVersion::<Foo>::schema()).unwrap();
+    assert_eq!(
+        schema["discriminator"],
+        json!({ "propertyName": "version" })
+    );
+    assert_eq!(
+        schema["oneOf"][0]["properties"]["version"]["enum"],
+        json!(["0.1.0"]),
+    );
+    assert_eq!(
+        schema["oneOf"][1]["properties"]["data"],
+        json!({ "$ref": "#/components/schemas/FooV0_2_0" }),
+    );
+}
@@ -0,0 +1,100 @@
+use obake::pin::{PinError, Pinned};
+use obake::{Upgrade, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    bar: u32,
+    #[obake(cfg(">=0.3"))]
+    baz: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { bar: 1 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            bar: from.bar + 1,
+            baz: 0,
+        }
+    }
+}
+
+#[test]
+fn partial_upgrade() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 {}.into();
+
+    assert_eq!(x.upgrade("0.2.0"), Some(Foo_v0_2_0 { bar: 1 }.into()),);
+}
+
+#[test]
+fn upgrade_to_own_version() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_2_0 { bar: 7 }.into();
+
+    assert_eq!(x.upgrade("0.2.0"), Some(Foo_v0_2_0 { bar: 7 }.into()),);
+}
+
+#[test]
+fn upgrade_to_older_or_undeclared_version() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_2_0 { bar: 7 }.into();
+    assert!(x.upgrade("0.1.0").is_none());
+
+    let x: obake::AnyVersion<Foo> = Foo_v0_2_0 { bar: 7 }.into();
+    assert!(x.upgrade("0.4.0").is_none());
+}
+
+#[test]
+fn pinned_accepts_older_versions() {
+    let pinned = Pinned::<Foo>::new("0.2.0").unwrap();
+
+    assert_eq!(pinned.version(), "0.2.0");
+    assert!(pinned.allows("0.1.0"));
+    assert!(!pinned.allows("0.3.0"));
+
+    let x = pinned.accept(Foo_v0_1_0 {}.into()).unwrap();
+    assert_eq!(x.version_str(), "0.2.0");
+    assert_eq!(x, Foo_v0_2_0 { bar: 1 }.into());
+}
+
+#[test]
+fn pinned_rejects_newer_versions() {
+    let pinned = Pinned::<Foo>::new("0.1.0").unwrap();
+    let err = pinned.accept(Foo_v0_2_0 { bar: 7 }.into()).unwrap_err();
+
+    assert_eq!(
+        err,
+        PinError {
+            version: "0.2.0",
+            pin: "0.1.0",
+        },
+    );
+    assert_eq!(
+        err.to_string(),
+        "version 0.2.0 is newer than the pinned version 0.1.0",
+    );
+}
+
+#[test]
+fn pinned_to_latest() {
+    let pinned = Pinned::<Foo>::latest();
+
+    assert_eq!(pinned, Pinned::new("0.3.0").unwrap());
+    assert_eq!(
+        pinned.accept(Foo_v0_1_0 {}.into()).unwrap(),
+        Foo { bar: 2, baz: 0 }.into(),
+    );
+}
+
+#[test]
+fn pinned_to_undeclared_version() {
+    assert!(Pinned::<Foo>::new("0.4.0").is_none());
+}
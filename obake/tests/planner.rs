@@ -0,0 +1,147 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::Cell;
+
+use obake::planner::Planner;
+use obake::Inherits;
+
+thread_local! {
+    static LEAF_MIGRATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+struct Leaf {
+    #[obake(cfg(">=0.2"))]
+    field_0: u32,
+}
+
+impl From<Leaf!["0.1.0"]> for Leaf!["0.2.0"] {
+    fn from(_: Leaf!["0.1.0"]) -> Self {
+        LEAF_MIGRATIONS.with(|count| count.set(count.get() + 1));
+        Self { field_0: 1 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+enum Branch {
+    A(#[obake(inherit)] Leaf),
+    B {
+        #[obake(inherit)]
+        leaf: Leaf,
+    },
+}
+
+impl From<Branch!["0.1.0"]> for Branch!["0.2.0"] {
+    fn from(from: Branch!["0.1.0"]) -> Self {
+        match from {
+            Branch_v0_1_0::A(leaf) => Self::A(leaf.into()),
+            Branch_v0_1_0::B { leaf } => Self::B { leaf: leaf.into() },
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = &'static str))]
+#[derive(Debug, PartialEq)]
+struct Tree {
+    #[obake(inherit)]
+    branch: Branch,
+    #[obake(cfg(">=0.2"))]
+    height: u8,
+}
+
+impl std::convert::TryFrom<Tree!["0.1.0"]> for Tree!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Tree!["0.1.0"]) -> Result<Self, Self::Error> {
+        match from.branch {
+            Branch_v0_1_0::A(_) => Ok(Self {
+                branch: from.branch.into(),
+                height: 1,
+            }),
+            Branch_v0_1_0::B { .. } => Err("unsupported branch"),
+        }
+    }
+}
+
+#[test]
+fn nesting_is_listed() {
+    assert_eq!(Leaf::INHERITED.len(), 0);
+    assert_eq!(Branch::INHERITED.len(), 1);
+    assert_eq!(Tree::INHERITED[0].name, "Branch");
+    assert_eq!((Leaf::depth(), Branch::depth(), Tree::depth()), (0, 1, 2));
+    assert!(Tree::inherits::<Leaf>());
+    assert!(!Leaf::inherits::<Tree>());
+}
+
+#[test]
+fn nested_values_migrate_first() {
+    let tree: obake::AnyVersion<Tree> = Tree_v0_1_0 {
+        branch: Branch_v0_1_0::A(Leaf_v0_1_0 {}),
+    }
+    .into();
+    let branch: obake::AnyVersion<Branch> = Branch_v0_1_0::B {
+        leaf: Leaf_v0_1_0 {},
+    }
+    .into();
+    let leaf: obake::AnyVersion<Leaf> = Leaf_v0_1_0 {}.into();
+
+    let mut planner = Planner::new();
+    planner
+        .add("tree", tree)
+        .add("branch", branch)
+        .add("leaf", leaf);
+    assert_eq!(
+        planner.order().collect::<Vec<_>>(),
+        [&"leaf", &"branch", &"tree"],
+    );
+
+    let mut migrated = planner.execute().unwrap();
+    assert_eq!(
+        migrated.keys().collect::<Vec<_>>(),
+        [&"leaf", &"branch", &"tree"],
+    );
+    assert_eq!(migrated.take::<Branch>(&"tree"), None);
+    assert_eq!(
+        migrated.take::<Tree>(&"tree"),
+        Some(Tree {
+            branch: Branch::A(Leaf { field_0: 1 }),
+            height: 1,
+        }),
+    );
+    assert_eq!(migrated.take::<Leaf>(&"leaf"), Some(Leaf { field_0: 1 }));
+    assert_eq!(migrated.take::<Leaf>(&"leaf"), None);
+}
+
+#[test]
+fn failures_are_all_or_nothing() {
+    let tree: obake::AnyVersion<Tree> = Tree_v0_1_0 {
+        branch: Branch_v0_1_0::B {
+            leaf: Leaf_v0_1_0 {},
+        },
+    }
+    .into();
+    let leaf: obake::AnyVersion<Leaf> = Leaf_v0_1_0 {}.into();
+
+    let next: obake::AnyVersion<Tree> = Tree_v0_1_0 {
+        branch: Branch_v0_1_0::A(Leaf_v0_1_0 {}),
+    }
+    .into();
+
+    let mut planner = Planner::new();
+    planner.add(1, tree).add(2, leaf).add(3, next);
+    let err = planner.execute().unwrap_err();
+    assert_eq!(err.key, 1);
+    assert_eq!(err.error.error, "unsupported branch");
+
+    // The leaf nested most deeply migrated first, but the tree following the failure didn't
+    assert_eq!(LEAF_MIGRATIONS.with(Cell::get), 1);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+
+use std::time::Duration;
+
+use obake::{Progress, Upgrade};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("<0.4"))]
+    field_0: u32,
+    #[obake(cfg(">=0.4"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        std::thread::sleep(Duration::from_millis(10));
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.4.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+fn upgrade(tagged: obake::AnyVersion<Foo>) -> (Foo, Vec<Progress>) {
+    let mut reported = Vec::new();
+    let latest = tagged.upgrade_with_progress(|progress| reported.push(progress));
+    (latest, reported)
+}
+
+#[test]
+fn every_migration_is_reported() {
+    let (latest, reported) = upgrade(Foo_v0_1_0 { field_0: 1 }.into());
+    assert_eq!(latest, Foo { field_1: 3 });
+
+    let hops: Vec<_> = reported
+        .iter()
+        .map(|progress| (progress.step, progress.steps, progress.from, progress.to))
+        .collect();
+    assert_eq!(
+        hops,
+        [
+            (0, 3, "0.1.0", "0.2.0"),
+            (1, 3, "0.2.0", "0.3.0"),
+            (2, 3, "0.3.0", "0.4.0"),
+        ],
+    );
+    assert!(reported[1].elapsed >= Duration::from_millis(10));
+}
+
+#[test]
+fn only_remaining_migrations_are_reported() {
+    let (latest, reported) = upgrade(Foo_v0_3_0 { field_0: 5 }.into());
+    assert_eq!(latest, Foo { field_1: 5 });
+    assert_eq!(reported.len(), 1);
+    assert_eq!((reported[0].step, reported[0].steps), (0, 1));
+
+    let (latest, reported) = upgrade(Foo { field_1: 6 }.into());
+    assert_eq!(latest, Foo { field_1: 6 });
+    assert!(reported.is_empty());
+}
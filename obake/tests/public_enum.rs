@@ -0,0 +1,78 @@
+#![allow(unused_macros)]
+
+use obake::{VersionOf, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(public_enum)]
+#[obake(derive(PartialEq, Debug))]
+#[derive(PartialEq, Debug)]
+pub struct Foo {
+    #[obake(cfg(">=0.2"))]
+    pub field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { field_0: 1 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0 + 1,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(version("1"))]
+#[obake(version("2"))]
+#[obake(alias_identical)]
+#[obake(public_enum(variants = "Version{version}"))]
+pub enum Bar {
+    X,
+}
+
+fn describe(x: &VersionedFoo) -> u32 {
+    match x {
+        VersionedFoo::V0_1_0(Foo_v0_1_0 {}) => 0,
+        VersionedFoo::V0_2_0(Foo_v0_2_0 { field_0 }) | VersionedFoo::V0_3_0(Foo { field_0 }) => {
+            *field_0
+        }
+    }
+}
+
+#[test]
+fn public_enum_variants_are_named_after_versions() {
+    let x: VersionedFoo = Foo_v0_1_0 {}.into();
+    assert_eq!(x, VersionedFoo::V0_1_0(Foo_v0_1_0 {}));
+    assert_eq!(describe(&x), 0);
+
+    let x = VersionedFoo::V0_2_0(Foo_v0_2_0 { field_0: 7 });
+    assert_eq!(x.version_str(), "0.2.0");
+    assert_eq!(describe(&x), 7);
+
+    let y = VersionedBar::Version2(Bar::X);
+    assert_eq!(y.version_str(), "2");
+    assert!(matches!(
+        VersionedBar::from(Bar_v1::X),
+        VersionedBar::Version1(Bar::X)
+    ));
+}
+
+#[test]
+fn public_enum_migrates() {
+    let x: Foo = VersionedFoo::V0_1_0(Foo_v0_1_0 {}).into();
+    assert_eq!(x, Foo { field_0: 2 });
+
+    let x = Foo_v0_2_0::try_from_versioned(VersionedFoo::V0_2_0(Foo_v0_2_0 { field_0: 3 }));
+    assert_eq!(x.unwrap(), Foo_v0_2_0 { field_0: 3 });
+
+    let y: Bar = VersionedBar::Version1(Bar::X).into();
+    assert!(matches!(y, Bar::X));
+}
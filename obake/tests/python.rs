@@ -0,0 +1,86 @@
+#![cfg(feature = "pyo3")]
+
+use obake::pyo3::prelude::*;
+use obake::pyo3::types::{PyDict, PyModule};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(pyo3)]
+#[derive(PartialEq, Debug, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+fn module(py: Python<'_>) -> Bound<'_, PyModule> {
+    let module = PyModule::new(py, "foo").unwrap();
+    register_foo(&module).unwrap();
+    module
+}
+
+#[test]
+fn registers_every_version() {
+    Python::initialize();
+    Python::attach(|py| {
+        let module = module(py);
+        assert!(module.hasattr("FooV0_1_0").unwrap());
+        assert!(module.hasattr("FooV0_2_0").unwrap());
+        assert!(module.hasattr("upgrade").unwrap());
+    });
+}
+
+#[test]
+fn upgrade() {
+    Python::initialize();
+    Python::attach(|py| {
+        let module = module(py);
+        let data = PyDict::new(py);
+        data.set_item("field_0", 7).unwrap();
+
+        let upgraded = module
+            .getattr("upgrade")
+            .unwrap()
+            .call1((data, "0.1.0"))
+            .unwrap();
+        assert_eq!(
+            *upgraded.extract::<PyRef<'_, Foo>>().unwrap(),
+            Foo {
+                field_0: 7,
+                field_1: "default".to_owned(),
+            },
+        );
+        assert_eq!(
+            upgraded
+                .getattr("field_1")
+                .unwrap()
+                .extract::<String>()
+                .unwrap(),
+            "default",
+        );
+    });
+}
+
+#[test]
+fn upgrade_unknown_version() {
+    Python::initialize();
+    Python::attach(|py| {
+        let module = module(py);
+        let err = module
+            .getattr("upgrade")
+            .unwrap()
+            .call1((PyDict::new(py), "0.3.0"))
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown version 0.3.0"));
+    });
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "registry")]
+#![allow(unused_macros)]
+
+use obake::registry::{self, Error};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(register)]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    bar: u32,
+    #[obake(cfg(">=0.2"))]
+    baz: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            baz: from.bar.to_string(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("1"))]
+#[obake(scheme = integer)]
+#[obake(register = "plugins.Greeting")]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+enum Greeting {
+    Hello,
+    Goodbye,
+}
+
+#[test]
+fn payloads_are_decoded_by_name_and_version() {
+    let decoded = registry::decode("Foo", "0.1.0", br#"{"bar":7}"#).unwrap();
+    assert_eq!(
+        decoded.downcast_ref::<Foo>(),
+        Some(&Foo {
+            baz: "7".to_owned()
+        })
+    );
+
+    let decoded = registry::decode("plugins.Greeting", "1", br#""Goodbye""#).unwrap();
+    assert_eq!(*decoded.downcast::<Greeting>().unwrap(), Greeting::Goodbye);
+}
+
+#[test]
+fn registrations_are_listed() {
+    let foo = registry::lookup("Foo").unwrap();
+    assert_eq!(foo.type_name(), "Foo");
+    assert_eq!(foo.versions(), ["0.1.0", "0.2.0"]);
+
+    let mut names: Vec<_> = registry::registrations()
+        .map(registry::Registration::type_name)
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, ["Foo", "plugins.Greeting"]);
+    assert!(registry::lookup("Greeting").is_none());
+}
+
+#[test]
+fn unknown_payloads_are_reported() {
+    let err = registry::decode("Bar", "0.1.0", b"{}").unwrap_err();
+    assert!(matches!(err, Error::UnknownType(ref name) if name == "Bar"));
+
+    let err = registry::decode("Foo", "0.3.0", b"{}").unwrap_err();
+    assert_eq!(err.to_string(), "`Foo` declares no version 0.3.0");
+
+    let err = registry::decode("Foo", "0.2.0", br#"{"bar":7}"#).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Decode {
+            type_name: "Foo",
+            ..
+        }
+    ));
+}
@@ -0,0 +1,50 @@
+#![allow(dead_code, unused_macros)]
+
+use obake::Releases;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(released("0.2.0", "2023-06-01"))]
+#[obake(released("0.1.0", "2023-01-10"))]
+struct Foo {
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {}
+
+#[test]
+fn releases_are_listed_in_version_order() {
+    assert_eq!(
+        Foo::RELEASES,
+        &[("0.1.0", "2023-01-10"), ("0.2.0", "2023-06-01")],
+    );
+    assert!(Bar::RELEASES.is_empty());
+}
+
+#[test]
+fn released_at_looks_up_dates() {
+    assert_eq!(Foo::released_at("0.1.0"), Some("2023-01-10"));
+    assert_eq!(Foo::released_at("0.3.0"), None);
+    assert_eq!(Foo::released_at("0.4.0"), None);
+    assert_eq!(Bar::released_at("0.1.0"), None);
+}
@@ -0,0 +1,93 @@
+use core::mem::{align_of, size_of};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("1.0.3"))]
+#[obake(repr_c)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Foo {
+    field_0: u8,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["1.0.3"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1 + 1,
+        }
+    }
+}
+
+#[test]
+fn stable_tags() {
+    assert_eq!(FooVersionTag::Foo_v0_1_0 as u32, 1 << 10);
+    assert_eq!(FooVersionTag::Foo_v0_2_0 as u32, 2 << 10);
+    assert_eq!(FooVersionTag::Foo_v1_0_3 as u32, 1 << 20 | 3);
+
+    let versioned: VersionedFoo = Foo {
+        field_0: 0,
+        field_1: 0,
+    }
+    .into();
+    assert_eq!(FooVersionTag::from(&versioned), FooVersionTag::Foo_v1_0_3);
+}
+
+#[test]
+fn c_layout() {
+    assert_eq!(size_of::<Foo!["0.1.0"]>(), 1);
+    assert_eq!(size_of::<Foo!["0.2.0"]>(), 16);
+    assert_eq!(align_of::<VersionedFoo>(), 8);
+    assert_eq!(size_of::<VersionedFoo>(), 24);
+
+    // The tag is laid out first, as in `struct { uint32_t tag; union { ... } data; }`
+    let versioned = VersionedFoo::from(Foo_v0_2_0 {
+        field_0: 0,
+        field_1: 0,
+    });
+    let tag = unsafe { *(&versioned as *const VersionedFoo).cast::<u32>() };
+    assert_eq!(tag, FooVersionTag::Foo_v0_2_0 as u32);
+}
+
+#[test]
+fn upgrade() {
+    let from = VersionedFoo::from(Foo_v0_1_0 { field_0: 3 });
+    assert_eq!(
+        upgrade_foo(from),
+        Foo {
+            field_0: 3,
+            field_1: 2,
+        },
+    );
+}
+
+extern "C" {
+    // Exported under the name of the crate declaring it
+    fn repr_c_upgrade_foo(from: VersionedFoo) -> Foo;
+}
+
+#[test]
+fn exported_upgrade() {
+    let from = VersionedFoo::from(Foo_v0_2_0 {
+        field_0: 3,
+        field_1: 4,
+    });
+    assert_eq!(
+        unsafe { repr_c_upgrade_foo(from) },
+        Foo {
+            field_0: 3,
+            field_1: 5,
+        },
+    );
+}
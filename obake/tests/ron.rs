@@ -0,0 +1,84 @@
+#![cfg(feature = "ron")]
+
+use obake::ron::{from_str, to_string, Error};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: Option<String>,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: Some("migrated".to_owned()),
+        }
+    }
+}
+
+#[test]
+fn envelopes_round_trip() {
+    let tagged: obake::AnyVersion<Foo> = Foo {
+        field_0: 1,
+        field_1: None,
+    }
+    .into();
+    let encoded = to_string::<Foo>(&tagged).unwrap();
+    assert_eq!(
+        encoded,
+        r#"(version:"0.2.0",data:(field_0:1,field_1:None))"#
+    );
+    assert_eq!(from_str::<Foo>(&encoded).unwrap(), tagged);
+}
+
+#[test]
+fn hand_edited_envelopes_are_decoded() {
+    let ron = r#"
+        // Written before `field_1` was added
+        (
+            version: "0.1.0",
+            data: (field_0: 7),
+        )
+    "#;
+    let foo: Foo = from_str::<Foo>(ron).unwrap().into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_0: 7,
+            field_1: Some("migrated".to_owned()),
+        }
+    );
+}
+
+#[test]
+fn named_payloads_select_their_version() {
+    // Would also deserialize as version 0.2.0 (with `field_1` defaulted to `None`) if unnamed
+    let named = from_str::<Foo>("Foo_v0_1_0(field_0: 7)").unwrap();
+    assert!(matches!(named, VersionedFoo::Foo_v0_1_0(_)));
+
+    let unnamed = from_str::<Foo>("(field_0: 7)").unwrap();
+    assert!(matches!(unnamed, VersionedFoo::Foo_v0_2_0(_)));
+
+    assert!(matches!(
+        from_str::<Foo>("Bar(field_0: 7)"),
+        Err(Error::Unrecognized)
+    ));
+}
+
+#[test]
+fn tagged_errors_are_reported() {
+    assert!(matches!(
+        from_str::<Foo>(r#"(version: "0.3.0", data: ())"#),
+        Err(Error::UnknownVersion(version)) if version == "0.3.0"
+    ));
+    assert!(matches!(
+        from_str::<Foo>(r#"(version: "0.2.0", data: (field_1: None))"#),
+        Err(Error::Ron(_))
+    ));
+}
@@ -0,0 +1,30 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("1.0.0"))]
+#[obake(version("2.0.0"))]
+#[obake(auto_migrate)]
+#[obake(rules(
+    deny(no_removal_without_major, new_fields_have_defaults),
+    warn(max_chain = 3)
+))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("<1.0"))]
+    legacy: u32,
+    #[obake(cfg(">=0.2"))]
+    #[obake(migrate_default = 7)]
+    bar: u32,
+    #[obake(cfg(">=1.0"))]
+    #[obake(migrate_default = true)]
+    baz: bool,
+    #[obake(cfg("<2.0"))]
+    qux: u32,
+}
+
+#[test]
+fn compliant_items_are_accepted() {
+    let old: Foo!["0.1.0"] = Foo_v0_1_0 { legacy: 1, qux: 2 };
+    let tagged: obake::AnyVersion<Foo> = old.into();
+    assert_eq!(Foo::from(tagged), Foo { bar: 7, baz: true });
+}
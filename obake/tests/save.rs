@@ -0,0 +1,172 @@
+#![cfg(feature = "json")]
+
+use obake::codec::{Codec, Json};
+use obake::save::{crc32, decode, encode, read_save, write_save, Error, MAGIC};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+fn legacy_save() -> Vec<u8> {
+    let payload = br#"{"field_0":3}"#;
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(4);
+    bytes.extend_from_slice(b"json");
+    bytes.push(5);
+    bytes.extend_from_slice(b"0.1.0");
+    bytes.extend_from_slice(&crc32(payload).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+#[test]
+fn round_trip() {
+    let foo = Foo {
+        field_0: 1,
+        field_1: "x".to_owned(),
+    };
+
+    let bytes = encode(&foo, &Json).unwrap();
+    assert!(bytes.starts_with(&MAGIC));
+    assert_eq!(decode::<Foo, _>(&bytes, &Json).unwrap(), foo);
+}
+
+#[test]
+fn migrates_on_load() {
+    assert_eq!(
+        decode::<Foo, _>(&legacy_save(), &Json).unwrap(),
+        Foo {
+            field_0: 3,
+            field_1: "migrated".to_owned(),
+        },
+    );
+}
+
+#[test]
+fn files() {
+    let path = std::env::temp_dir().join(format!("obake-save-{}.sav", std::process::id()));
+    let foo = Foo {
+        field_0: 2,
+        field_1: "y".to_owned(),
+    };
+
+    write_save(&path, &foo, &Json).unwrap();
+    assert_eq!(read_save::<Foo, _>(&path, &Json).unwrap(), foo);
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        read_save::<Foo, _>(&path, &Json),
+        Err(Error::Io(_))
+    ));
+}
+
+#[test]
+fn rejects_corrupt_saves() {
+    let mut bytes = legacy_save();
+    *bytes.last_mut().unwrap() = b']';
+    assert!(matches!(
+        decode::<Foo, _>(&bytes, &Json),
+        Err(Error::ChecksumMismatch { .. })
+    ));
+
+    assert!(matches!(
+        decode::<Foo, _>(b"not a save file", &Json),
+        Err(Error::BadMagic)
+    ));
+
+    assert!(matches!(
+        decode::<Foo, _>(&legacy_save()[..MAGIC.len() + 7], &Json),
+        Err(Error::Malformed)
+    ));
+
+    let mut bytes = legacy_save();
+    bytes[MAGIC.len() + 5 + 5] = b'9';
+    assert!(matches!(
+        decode::<Foo, _>(&bytes, &Json),
+        Err(Error::UnknownVersion(version)) if version == "0.1.9"
+    ));
+}
+
+#[test]
+fn rejects_other_codecs() {
+    struct Other;
+
+    impl Codec for Other {
+        type Error = serde_json::Error;
+
+        const NAME: &'static str = "other";
+
+        fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+        where
+            S: serde::Serialize + ?Sized,
+        {
+            Json.encode(value)
+        }
+
+        fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+        where
+            D: serde::Deserialize<'de>,
+        {
+            Json.decode(bytes)
+        }
+    }
+
+    assert!(matches!(
+        decode::<Foo, _>(&legacy_save(), &Other),
+        Err(Error::CodecMismatch(codec)) if codec == "json"
+    ));
+}
+
+#[test]
+fn rejects_long_codec_names() {
+    struct Long;
+
+    impl Codec for Long {
+        type Error = serde_json::Error;
+
+        const NAME: &'static str = concat!(
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        );
+
+        fn encode<S>(&self, value: &S) -> Result<Vec<u8>, Self::Error>
+        where
+            S: serde::Serialize + ?Sized,
+        {
+            Json.encode(value)
+        }
+
+        fn decode<'de, D>(&self, bytes: &'de [u8]) -> Result<D, Self::Error>
+        where
+            D: serde::Deserialize<'de>,
+        {
+            Json.decode(bytes)
+        }
+    }
+
+    let foo = Foo {
+        field_0: 1,
+        field_1: "one".to_owned(),
+    };
+    assert!(matches!(
+        encode(&foo, &Long),
+        Err(Error::TooLong(name)) if name == Long::NAME
+    ));
+}
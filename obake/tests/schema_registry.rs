@@ -0,0 +1,117 @@
+#![cfg(feature = "schema-registry")]
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use obake::schema_registry::{SchemaRegistry, Transport};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(schemars::JsonSchema)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    requests: RefCell<Vec<(String, serde_json::Value)>>,
+}
+
+impl Transport for Recorder {
+    type Error = std::convert::Infallible;
+
+    async fn post(&self, path: &str, body: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
+        let mut requests = self.requests.borrow_mut();
+        requests.push((path.to_owned(), serde_json::from_slice(&body).unwrap()));
+
+        Ok(if path.starts_with("/compatibility") {
+            br#"{"is_compatible":false}"#.to_vec()
+        } else {
+            format!(r#"{{"id":{}}}"#, requests.len()).into_bytes()
+        })
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("test transport never blocks"),
+    }
+}
+
+fn schema(request: &serde_json::Value) -> serde_json::Value {
+    assert_eq!(request["schemaType"], "JSON");
+    serde_json::from_str(request["schema"].as_str().unwrap()).unwrap()
+}
+
+#[test]
+fn register_every_version() {
+    let registry = SchemaRegistry::new(Recorder::default());
+    let ids = block_on(registry.register::<Foo>("foo-value")).unwrap();
+    assert_eq!(ids, vec![("0.1.0", 1), ("0.2.0", 2)]);
+
+    let requests = registry_requests(registry);
+    assert_eq!(requests[0].0, "/subjects/foo-value/versions");
+    assert!(schema(&requests[0].1)["properties"]
+        .get("field_1")
+        .is_none());
+    assert!(schema(&requests[1].1)["properties"]
+        .get("field_1")
+        .is_some());
+}
+
+#[test]
+fn check_latest_version() {
+    let registry = SchemaRegistry::new(Recorder::default());
+    assert!(!block_on(registry.is_compatible::<Foo>("foo-value")).unwrap());
+
+    let requests = registry_requests(registry);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].0,
+        "/compatibility/subjects/foo-value/versions/latest"
+    );
+    assert!(schema(&requests[0].1)["properties"]
+        .get("field_1")
+        .is_some());
+}
+
+#[test]
+fn subjects_are_percent_encoded() {
+    let registry = SchemaRegistry::new(Recorder::default());
+    block_on(registry.register::<Foo>("team/foo value?v=1#ü")).unwrap();
+    block_on(registry.is_compatible::<Foo>("team/foo")).unwrap();
+
+    let requests = registry_requests(registry);
+    assert_eq!(
+        requests[0].0,
+        "/subjects/team%2Ffoo%20value%3Fv%3D1%23%C3%BC/versions"
+    );
+    assert_eq!(
+        requests[2].0,
+        "/compatibility/subjects/team%2Ffoo/versions/latest"
+    );
+}
+
+fn registry_requests(registry: SchemaRegistry<Recorder>) -> Vec<(String, serde_json::Value)> {
+    registry.into_transport().requests.into_inner()
+}
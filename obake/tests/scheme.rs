@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+
+use obake::scheme::{Calver, Integer, VersionScheme};
+use obake::{AnyVersion, VersionTagged, Versioned};
+
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(version("1"))]
+#[obake(version("2"))]
+#[obake(version("10"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    bar: u32,
+    #[obake(cfg(">=2"))]
+    baz: u32,
+    #[obake(cfg("10"))]
+    qux: u32,
+}
+
+impl From<Foo!["1"]> for Foo!["2"] {
+    fn from(from: Foo!["1"]) -> Self {
+        Self {
+            bar: from.bar,
+            baz: 0,
+        }
+    }
+}
+
+impl From<Foo!["2"]> for Foo!["10"] {
+    fn from(from: Foo!["2"]) -> Self {
+        Self {
+            bar: from.bar,
+            baz: from.baz,
+            qux: 0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(scheme = calver)]
+#[obake(version("2023.12.01"))]
+#[obake(version("2024.01.15"))]
+#[obake(dto_modules)]
+#[derive(Debug, PartialEq)]
+struct Bar {
+    #[obake(cfg("2024.01.15"))]
+    baz: bool,
+}
+
+impl From<Bar!["2023.12.01"]> for Bar!["2024.01.15"] {
+    fn from(_: Bar!["2023.12.01"]) -> Self {
+        Self { baz: false }
+    }
+}
+
+/// Release channels, from least to most stable.
+struct Channel;
+
+impl VersionScheme for Channel {
+    fn compare(a: &str, b: &str) -> Option<Ordering> {
+        let rank = |x| {
+            ["alpha", "beta", "rc-1", "stable"]
+                .iter()
+                .position(|y| *y == x)
+        };
+        Some(rank(a)?.cmp(&rank(b)?))
+    }
+}
+
+#[obake::versioned]
+#[obake(scheme = Channel)]
+#[obake(version("alpha"))]
+#[obake(version("beta"))]
+#[obake(version("rc-1"))]
+#[derive(Debug, PartialEq)]
+struct Baz {
+    #[obake(cfg(">beta"))]
+    qux: u32,
+}
+
+impl From<Baz!["alpha"]> for Baz!["beta"] {
+    fn from(_: Baz!["alpha"]) -> Self {
+        Self {}
+    }
+}
+
+impl From<Baz!["beta"]> for Baz!["rc-1"] {
+    fn from(_: Baz!["beta"]) -> Self {
+        Self { qux: 1 }
+    }
+}
+
+#[test]
+fn integer_versions() {
+    assert_eq!(Foo::VERSIONS, &["1", "2", "10"]);
+    assert!(obake::scheme::is_ordered::<Foo>());
+
+    let foo: AnyVersion<Foo> = Foo_v1 { bar: 1 }.into();
+    assert_eq!(foo.version_str(), "1");
+    assert_eq!(
+        Foo::from(foo),
+        Foo {
+            bar: 1,
+            baz: 0,
+            qux: 0,
+        },
+    );
+
+    assert_eq!(
+        obake::scheme::compare::<Foo>("2", "10"),
+        Some(Ordering::Less)
+    );
+    assert_eq!(Integer::compare("02", "10"), None);
+}
+
+#[test]
+fn calendar_versions() {
+    assert_eq!(Bar::VERSIONS, &["2023.12.01", "2024.01.15"]);
+    assert!(obake::scheme::is_ordered::<Bar>());
+
+    let bar = v2023_12_01::Bar {};
+    assert_eq!(Bar::from(AnyVersion::<Bar>::from(bar)), Bar { baz: false });
+
+    assert_eq!(
+        Calver::compare("2024.01.15", "2023.12.31"),
+        Some(Ordering::Greater),
+    );
+    assert_eq!(Calver::compare("2024.1.15", "2023.12.31"), None);
+}
+
+#[test]
+fn custom_versions() {
+    assert_eq!(Baz::VERSIONS, &["alpha", "beta", "rc-1"]);
+    assert!(obake::scheme::is_ordered::<Baz>());
+
+    let baz: AnyVersion<Baz> = Baz_valpha {}.into();
+    assert_eq!(Baz::from(baz), Baz_vrc_1 { qux: 1 });
+    assert_eq!(
+        obake::scheme::compare::<Baz>("stable", "beta"),
+        Some(Ordering::Greater)
+    );
+}
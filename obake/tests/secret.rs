@@ -0,0 +1,104 @@
+#![cfg(all(feature = "zeroize", feature = "alloc"))]
+#![allow(unused_macros)]
+
+use std::cell::Cell;
+
+use obake::zeroize::Zeroize;
+use obake::AnyVersion;
+
+thread_local! {
+    static ZEROIZED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A key counting how many times it's been zeroized.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Key(Vec<u8>);
+
+impl Zeroize for Key {
+    fn zeroize(&mut self) {
+        ZEROIZED.with(|zeroized| zeroized.set(zeroized.get() + 1));
+        self.0.zeroize();
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[derive(Debug, PartialEq)]
+struct Credentials {
+    user: String,
+    #[obake(secret)]
+    key: Key,
+    #[obake(cfg(">=0.2"))]
+    #[obake(secret)]
+    #[obake(migrate_default = Key(vec![0xff]))]
+    token: Key,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+enum Auth {
+    Key {
+        #[obake(secret)]
+        key: Key,
+    },
+    Password(#[obake(secret)] String),
+}
+
+impl From<Auth!["0.1.0"]> for Auth!["0.2.0"] {
+    fn from(from: Auth!["0.1.0"]) -> Self {
+        match from {
+            // Cloning leaves a copy behind in the old version, which is zeroized when dropped
+            Auth_v0_1_0::Key { key } => Self::Key { key: key.clone() },
+            Auth_v0_1_0::Password(password) => Self::Password(password),
+        }
+    }
+}
+
+#[test]
+fn secrets_are_moved_by_migrations() {
+    ZEROIZED.with(|zeroized| zeroized.set(0));
+
+    let credentials: AnyVersion<Credentials> = Credentials_v0_1_0 {
+        user: "admin".to_owned(),
+        key: Key(vec![1, 2, 3]).into(),
+    }
+    .into();
+    let credentials = Credentials::from(credentials);
+
+    assert_eq!(*credentials.key, Key(vec![1, 2, 3]));
+    assert_eq!(*credentials.token, Key(vec![0xff]));
+    assert_eq!(ZEROIZED.with(Cell::get), 0);
+
+    drop(credentials);
+    assert_eq!(ZEROIZED.with(Cell::get), 2);
+}
+
+#[test]
+fn stale_copies_are_zeroized() {
+    ZEROIZED.with(|zeroized| zeroized.set(0));
+
+    let auth: AnyVersion<Auth> = Auth_v0_1_0::Key {
+        key: Key(vec![1, 2, 3]).into(),
+    }
+    .into();
+    let auth = Auth::from(auth);
+    assert_eq!(ZEROIZED.with(Cell::get), 1);
+
+    assert_eq!(
+        auth,
+        Auth::Key {
+            key: Key(vec![1, 2, 3]).into(),
+        },
+    );
+
+    let auth: AnyVersion<Auth> = Auth_v0_1_0::Password(String::from("hunter2").into()).into();
+    assert_eq!(
+        Auth::from(auth),
+        Auth::Password(String::from("hunter2").into())
+    );
+}
@@ -0,0 +1,97 @@
+#![allow(unused_macros)]
+
+use obake::FromLatest;
+
+#[obake::versioned]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[obake(auto_downgrade_subset)]
+#[derive(PartialEq, Debug, Default)]
+struct Proxy {
+    host: String,
+    #[obake(cfg(">=0.3"))]
+    port: u16,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[obake(auto_migrate)]
+#[derive(PartialEq, Debug)]
+struct Config {
+    name: String,
+    #[obake(inherit)]
+    #[obake(since("0.2.0"))]
+    #[obake(until("0.3.0"))]
+    proxy: Proxy,
+}
+
+#[test]
+fn fields_are_optional_outside_their_versions() {
+    let x: Config!["0.1.0"] = Config_v0_1_0 {
+        name: String::from("x"),
+        proxy: None,
+    };
+    let y: Config!["0.2.0"] = x.into();
+
+    assert_eq!(y.proxy, Proxy_v0_2_0::default());
+
+    let y = Config_v0_2_0 {
+        name: String::from("y"),
+        proxy: Proxy_v0_2_0 {
+            host: String::from("localhost"),
+        },
+    };
+    let z: Config = VersionedConfig::from(y).into();
+
+    assert_eq!(
+        z.proxy,
+        Some(Proxy {
+            host: String::from("localhost"),
+            port: 0,
+        }),
+    );
+}
+
+#[test]
+fn fields_present_before_their_versions_are_kept() {
+    let x = Config_v0_1_0 {
+        name: String::from("x"),
+        proxy: Some(Proxy_v0_2_0 {
+            host: String::from("localhost"),
+        }),
+    };
+    let y: Config!["0.2.0"] = x.into();
+
+    assert_eq!(y.proxy.host, "localhost");
+}
+
+#[obake::versioned]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(auto_migrate)]
+#[obake(auto_downgrade_subset)]
+#[derive(PartialEq, Debug)]
+struct Limits {
+    #[obake(inherit)]
+    #[obake(since("0.3.0"))]
+    proxy: Proxy,
+}
+
+#[test]
+fn fields_are_dropped_downgrading_before_their_versions() {
+    let latest = Limits {
+        proxy: Proxy {
+            host: String::from("localhost"),
+            port: 80,
+        },
+    };
+
+    assert_eq!(
+        Limits_v0_2_0::from_latest(latest),
+        Limits_v0_2_0 { proxy: None }
+    );
+}
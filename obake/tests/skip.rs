@@ -0,0 +1,117 @@
+#![allow(unused_macros)]
+
+use obake::{AnyVersion, Downgrade, Migrate, VersionTagged};
+
+#[derive(PartialEq, Debug)]
+struct Handle(u32);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(downgrade)]
+#[obake(derive(PartialEq, Debug))]
+#[derive(PartialEq, Debug)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+    #[obake(skip)]
+    cache: Vec<u32>,
+    #[obake(skip = Handle(7))]
+    handle: Handle,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+struct Bar {
+    #[obake(cfg(">=0.2"))]
+    field_0: u32,
+    #[obake(skip = vec![1, 2, 3])]
+    cache: Vec<u32>,
+}
+
+#[test]
+fn skip_populates_after_migration() {
+    let x: Foo = AnyVersion::<Foo>::from(Foo_v0_1_0 { field_0: 3 }).into();
+    assert_eq!(
+        x,
+        Foo {
+            field_0: 3,
+            field_1: 1,
+            cache: Vec::new(),
+            handle: Handle(7),
+        },
+    );
+
+    let y: Bar = AnyVersion::<Bar>::from(Bar_v0_1_0 {}).into();
+    assert_eq!(y.field_0, 0);
+    assert_eq!(y.cache, [1, 2, 3]);
+}
+
+#[test]
+fn skip_is_dropped_from_versions() {
+    let x = Foo {
+        field_0: 3,
+        field_1: 4,
+        cache: vec![5],
+        handle: Handle(6),
+    };
+    let latest: Foo_v0_2_0 = x.into();
+    assert_eq!(
+        latest,
+        Foo_v0_2_0 {
+            field_0: 3,
+            field_1: 4,
+        },
+    );
+
+    let x = Foo {
+        field_0: 3,
+        field_1: 4,
+        cache: vec![5],
+        handle: Handle(6),
+    };
+    let tagged = AnyVersion::<Foo>::from(x);
+    assert_eq!(tagged.version_str(), "0.2.0");
+
+    let x: Foo = tagged.into();
+    assert_eq!(x.cache, Vec::<u32>::new());
+    assert_eq!(x.handle, Handle(7));
+}
+
+#[test]
+fn skip_downgrades_and_migrates() {
+    let x = Foo {
+        field_0: 3,
+        field_1: 4,
+        cache: vec![5],
+        handle: Handle(6),
+    };
+    assert_eq!(
+        AnyVersion::<Foo>::downgrade(x, "0.1.0"),
+        Some(AnyVersion::<Foo>::from(Foo_v0_1_0 { field_0: 3 })),
+    );
+
+    let x: Foo = Foo_v0_1_0 { field_0: 3 }.migrate();
+    assert_eq!(x.field_1, 1);
+    assert_eq!(x.handle, Handle(7));
+}
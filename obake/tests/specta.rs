@@ -0,0 +1,67 @@
+#![cfg(feature = "specta")]
+#![allow(dead_code)]
+
+use obake::specta::datatype::{DataType, EnumRepr};
+use obake::specta::TypeCollection;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(specta)]
+#[obake(derive(serde::Deserialize))]
+#[obake(serde(tag = "version", content = "data"))]
+#[derive(serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: String::new(),
+        }
+    }
+}
+
+#[test]
+fn collects_every_version() {
+    let mut types = TypeCollection::default();
+    types.register::<VersionedFoo>();
+
+    let mut names: Vec<_> = types
+        .into_iter()
+        .map(|(_, ty)| ty.name().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["FooV0_1_0", "FooV0_2_0", "VersionedFoo"]);
+}
+
+#[test]
+fn follows_serde_representation() {
+    let mut types = TypeCollection::default();
+    types.register::<VersionedFoo>();
+
+    let (_, envelope) = types
+        .into_iter()
+        .find(|(_, ty)| ty.name() == "VersionedFoo")
+        .unwrap();
+    let DataType::Enum(envelope) = &envelope.inner else {
+        panic!("expected an enum");
+    };
+    assert_eq!(
+        envelope.repr(),
+        &EnumRepr::Adjacent {
+            tag: "version".into(),
+            content: "data".into(),
+        },
+    );
+    let variants: Vec<_> = envelope
+        .variants()
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    assert_eq!(variants, ["Foo_v0_1_0", "Foo_v0_2_0"]);
+}
@@ -0,0 +1,76 @@
+use obake::{Stability, StabilityMap};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("1.0.0"))]
+#[obake(deprecated("0.1.0"))]
+#[obake(stable("0.3.0"))]
+#[obake(stability)]
+#[obake(public_enum)]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    field_0: u32,
+}
+
+#[allow(deprecated)]
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["1.0.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("1"))]
+#[obake(version("2"))]
+#[obake(scheme = integer)]
+struct Bar {}
+
+impl From<Bar!["1"]> for Bar!["2"] {
+    fn from(_: Bar!["1"]) -> Self {
+        Self {}
+    }
+}
+
+#[test]
+fn every_version_is_mapped_to_its_stability() {
+    assert_eq!(
+        Foo::STABILITY,
+        &[
+            ("0.1.0", Stability::Deprecated),
+            ("0.2.0", Stability::Frozen),
+            ("0.3.0", Stability::Stable),
+            ("1.0.0", Stability::Stable),
+        ],
+    );
+    assert_eq!(Foo::stability("0.2.0"), Some(Stability::Frozen));
+    assert_eq!(Foo::stability("2.0.0"), None);
+}
+
+#[test]
+fn historical_versions_are_frozen_by_default() {
+    assert_eq!(
+        Bar::STABILITY,
+        &[("1", Stability::Frozen), ("2", Stability::Stable)],
+    );
+    assert_eq!(Stability::Frozen.to_string(), "frozen");
+}
@@ -0,0 +1,87 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::Hasher;
+
+use obake::{AnyVersion, StableHash};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(stable_hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1,
+        }
+    }
+}
+
+fn hash(value: &impl StableHash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.stable_hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn hashes_latest_form() {
+    let latest = Foo {
+        field_0: 1,
+        field_1: "migrated".to_owned(),
+    };
+    let v1: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 1 }.into();
+    let v2: AnyVersion<Foo> = Foo_v0_2_0 {
+        field_0: 1,
+        field_1: "migrated".to_owned(),
+    }
+    .into();
+    let v3: AnyVersion<Foo> = latest.clone().into();
+
+    assert_eq!(hash(&v1), hash(&latest));
+    assert_eq!(hash(&v2), hash(&latest));
+    assert_eq!(hash(&v3), hash(&latest));
+
+    let mut plain = DefaultHasher::new();
+    std::hash::Hash::hash(&latest, &mut plain);
+    assert_eq!(hash(&latest), plain.finish());
+}
+
+#[test]
+fn distinguishes_values() {
+    let v1: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 1 }.into();
+    let v2: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 2 }.into();
+    assert_ne!(hash(&v1), hash(&v2));
+}
+
+#[test]
+fn deduplicate() {
+    let values: Vec<AnyVersion<Foo>> = vec![
+        Foo_v0_1_0 { field_0: 1 }.into(),
+        Foo_v0_2_0 {
+            field_0: 1,
+            field_1: "migrated".to_owned(),
+        }
+        .into(),
+        Foo_v0_1_0 { field_0: 2 }.into(),
+    ];
+
+    let unique: HashSet<u64> = values.iter().map(hash).collect();
+    assert_eq!(unique.len(), 2);
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "json")]
+#![allow(unused_macros)]
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use obake::codec::{Envelope, Json};
+use obake::store::{Backend, Error, VersionedTree, WriteBack};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+type Tree = VersionedTree<Foo, RefCell<BTreeMap<Vec<u8>, Vec<u8>>>, Json>;
+
+fn record(version: &str, payload: &str) -> Vec<u8> {
+    let mut record = vec![version.len() as u8];
+    record.extend_from_slice(version.as_bytes());
+    record.extend_from_slice(payload.as_bytes());
+    record
+}
+
+fn tree() -> Tree {
+    let tree = Tree::new(RefCell::default(), Json);
+    tree.backend()
+        .insert(b"old", &record("0.1.0", r#"{"field_0":7}"#))
+        .unwrap();
+    tree
+}
+
+#[test]
+fn insert_then_get() {
+    let tree = tree();
+    let foo = Foo {
+        field_0: 1,
+        field_1: "one".to_owned(),
+    };
+    tree.insert(b"new", &foo).unwrap();
+    assert_eq!(tree.get(b"new").unwrap(), Some(foo));
+    assert_eq!(tree.get(b"missing").unwrap(), None);
+}
+
+#[test]
+fn upgrade_on_read() {
+    let tree = tree();
+    let expected = Foo {
+        field_0: 7,
+        field_1: "default".to_owned(),
+    };
+    assert_eq!(tree.get(b"old").unwrap(), Some(expected));
+    assert_eq!(
+        tree.backend().get(b"old").unwrap(),
+        Some(record("0.1.0", r#"{"field_0":7}"#)),
+    );
+}
+
+#[test]
+fn lazy_write_back() {
+    let tree = tree().with_write_back(WriteBack::Lazy);
+    tree.get(b"old").unwrap();
+    assert_eq!(
+        tree.backend().get(b"old").unwrap(),
+        Some(record("0.2.0", r#"{"field_0":7,"field_1":"default"}"#)),
+    );
+}
+
+#[test]
+fn compact() {
+    let tree = tree();
+    assert_eq!(tree.compact().unwrap(), 1);
+    assert_eq!(tree.compact().unwrap(), 0);
+    assert_eq!(
+        tree.backend().get(b"old").unwrap(),
+        Some(record("0.2.0", r#"{"field_0":7,"field_1":"default"}"#)),
+    );
+}
+
+#[test]
+fn unknown_version() {
+    let tree = tree();
+    tree.backend()
+        .insert(b"future", &record("0.3.0", "{}"))
+        .unwrap();
+    assert!(matches!(
+        tree.get(b"future"),
+        Err(Error::UnknownVersion(version)) if version == "0.3.0"
+    ));
+}
+
+struct Legacy;
+
+impl Envelope for Legacy {
+    const MAGIC: &'static [u8] = b"FOO";
+
+    fn write_version(&self, version: &str, bytes: &mut Vec<u8>) -> Option<()> {
+        bytes.push(match version {
+            "0.1.0" => 1,
+            "0.2.0" => 2,
+            _ => return None,
+        });
+        Some(())
+    }
+
+    fn read_version<'a>(&self, bytes: &'a [u8]) -> Option<(&'a str, &'a [u8])> {
+        match bytes.split_first()? {
+            (1, rest) => Some(("0.1.0", rest)),
+            (2, rest) => Some(("0.2.0", rest)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn custom_envelope() {
+    let tree = Tree::new(RefCell::default(), Json)
+        .with_envelope(Legacy)
+        .with_write_back(WriteBack::Lazy);
+    tree.backend()
+        .insert(b"old", b"FOO\x01{\"field_0\":7}")
+        .unwrap();
+    tree.backend().insert(b"bad", b"BAR\x01{}").unwrap();
+
+    assert_eq!(
+        tree.get(b"old").unwrap(),
+        Some(Foo {
+            field_0: 7,
+            field_1: "default".to_owned(),
+        }),
+    );
+    assert_eq!(
+        tree.backend().get(b"old").unwrap(),
+        Some(b"FOO\x02{\"field_0\":7,\"field_1\":\"default\"}".to_vec()),
+    );
+    assert!(matches!(tree.get(b"bad"), Err(Error::Corrupt)));
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"))]
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+struct Long {}
+
+#[test]
+fn unframeable_versions() {
+    let tree = VersionedTree::<Long, RefCell<BTreeMap<Vec<u8>, Vec<u8>>>, Json>::new(
+        RefCell::default(),
+        Json,
+    );
+    assert!(matches!(
+        tree.insert(b"long", &Long {}),
+        Err(Error::Unframeable(version)) if version.len() == 256
+    ));
+    assert!(tree.backend().borrow().is_empty());
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = &'static str))]
+#[derive(PartialEq, Eq, Debug, serde::Serialize, serde::Deserialize)]
+struct Bar {
+    #[obake(cfg("0.1.0"))]
+    field_0: i32,
+    #[obake(cfg("0.2.0"))]
+    field_0: u32,
+}
+
+impl std::convert::TryFrom<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Bar!["0.1.0"]) -> Result<Self, Self::Error> {
+        u32::try_from(from.field_0)
+            .map(|field_0| Self { field_0 })
+            .map_err(|_| "negative")
+    }
+}
+
+#[test]
+fn failing_migration() {
+    let tree = VersionedTree::<Bar, RefCell<BTreeMap<Vec<u8>, Vec<u8>>>, Json>::new(
+        RefCell::default(),
+        Json,
+    )
+    .with_write_back(WriteBack::Lazy);
+    tree.backend()
+        .insert(b"ok", &record("0.1.0", r#"{"field_0":1}"#))
+        .unwrap();
+    tree.backend()
+        .insert(b"bad", &record("0.1.0", r#"{"field_0":-1}"#))
+        .unwrap();
+
+    assert_eq!(tree.get(b"ok").unwrap(), Some(Bar { field_0: 1 }));
+    assert!(matches!(
+        tree.get(b"bad"),
+        Err(Error::Migrate(err)) if err.from == "0.1.0" && err.error == "negative"
+    ));
+    assert_eq!(
+        tree.backend().get(b"bad").unwrap(),
+        Some(record("0.1.0", r#"{"field_0":-1}"#)),
+    );
+}
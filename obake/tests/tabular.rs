@@ -0,0 +1,121 @@
+#![cfg(all(feature = "csv", feature = "arrow"))]
+
+use obake::tabular::{ArrowSchema, Columns, CsvReader};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(tabular)]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: Option<String>,
+    #[obake(cfg(">=0.3"))]
+    field_2: f64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: None,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1,
+            field_2: 0.0,
+        }
+    }
+}
+
+#[test]
+fn columns() {
+    assert_eq!(<Foo!["0.1.0"]>::COLUMNS, &["field_0"]);
+    assert_eq!(<Foo!["0.2.0"]>::COLUMNS, &["field_0", "field_1"]);
+    assert_eq!(<Foo!["0.3.0"]>::COLUMNS, &["field_0", "field_1", "field_2"]);
+
+    assert_eq!(
+        obake::tabular::detect_columns::<Foo>(&["field_0", "field_1"]),
+        Some("0.2.0"),
+    );
+    assert_eq!(
+        obake::tabular::detect_columns::<Foo>(&["field_1", "field_0"]),
+        None,
+    );
+}
+
+#[test]
+fn arrow_schemas() {
+    use arrow_schema::{DataType, Field, Schema};
+
+    let schema = <Foo!["0.2.0"]>::arrow_schema();
+    assert_eq!(
+        schema,
+        Schema::new(vec![
+            Field::new("field_0", DataType::UInt32, false),
+            Field::new("field_1", DataType::Utf8, true),
+        ]),
+    );
+
+    assert_eq!(obake::tabular::detect_schema::<Foo>(&schema), Some("0.2.0"));
+    assert_eq!(
+        obake::tabular::detect_schema::<Foo>(&Schema::new(vec![Field::new(
+            "field_0",
+            DataType::Int64,
+            false,
+        )])),
+        None,
+    );
+}
+
+#[test]
+fn read_csv() {
+    let data = "field_0,field_1\n1,a\n2,\n";
+    let reader = CsvReader::<Foo, _>::from_reader(data.as_bytes()).unwrap();
+    assert_eq!(reader.version(), "0.2.0");
+
+    let rows: Vec<Foo> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(
+        rows,
+        [
+            Foo {
+                field_0: 1,
+                field_1: Some("a".to_owned()),
+                field_2: 0.0,
+            },
+            Foo {
+                field_0: 2,
+                field_1: None,
+                field_2: 0.0,
+            },
+        ],
+    );
+}
+
+#[test]
+fn read_unrecognized_csv() {
+    let data = "field_0,field_3\n1,a\n";
+    assert!(matches!(
+        CsvReader::<Foo, _>::from_reader(data.as_bytes()),
+        Err(obake::tabular::Error::Unrecognized),
+    ));
+}
+
+#[test]
+fn read_malformed_csv() {
+    let data = "field_0\nx\n";
+    let mut reader = CsvReader::<Foo, _>::from_reader(data.as_bytes()).unwrap();
+    assert!(matches!(
+        reader.next(),
+        Some(Err(obake::tabular::Error::Csv(_)))
+    ));
+    assert!(reader.next().is_none());
+}
@@ -0,0 +1,72 @@
+#![allow(unused_macros)]
+
+use core::mem::size_of;
+
+use obake::{Declared, TagIndex, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("1.0.0"))]
+#[obake(tag = u8)]
+#[derive(PartialEq, Debug)]
+struct Foo {
+    field_0: u8,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["1.0.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(tag = u16)]
+struct Bar {}
+
+#[test]
+fn tags_follow_version_order() {
+    let versioned = VersionedFoo::from(Foo_v0_1_0 { field_0: 0 });
+    assert_eq!(versioned.tag_index(), 0);
+    let versioned = VersionedFoo::from(Foo_v0_2_0 { field_0: 0 });
+    assert_eq!(versioned.tag_index(), 1);
+    let versioned = VersionedFoo::from(Foo { field_0: 0 });
+    assert_eq!(versioned.tag_index(), 2);
+
+    let versioned = VersionedBar::from(Bar {});
+    assert_eq!(versioned.tag_index(), 0_u16);
+}
+
+#[test]
+fn tags_are_discriminants() {
+    assert_eq!(size_of::<VersionedFoo>(), 2);
+
+    let versioned = VersionedFoo::from(Foo_v0_2_0 { field_0: 0 });
+    let tag = unsafe { *(&versioned as *const VersionedFoo).cast::<u8>() };
+    assert_eq!(tag, 1);
+}
+
+#[test]
+fn versions_of_tags() {
+    assert_eq!(VersionedFoo::version_of_tag_index(1), Some("0.2.0"));
+    assert_eq!(VersionedFoo::version_of_tag_index(3), None);
+    assert!(VersionedFoo::from_tag_index(2, Declared).is_some());
+    assert!(VersionedFoo::from_tag_index(3, Declared).is_none());
+
+    let versioned = VersionedFoo::from(Foo { field_0: 0 });
+    assert_eq!(
+        VersionedFoo::version_of_tag_index(versioned.tag_index()),
+        Some(versioned.version_str())
+    );
+}
@@ -0,0 +1,57 @@
+use std::convert::TryFrom;
+
+use obake::{TagMismatch, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.1.1"))]
+#[obake(version("0.2.0"))]
+#[obake(alias_identical)]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg(">=0.2"))]
+    bar: u32,
+}
+
+impl From<Foo!["0.1.1"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.1"]) -> Self {
+        Self { bar: 0 }
+    }
+}
+
+#[test]
+fn tag_with_runtime_version() {
+    // e.g., read from a database column alongside the value
+    let versions = ["0.1.0", "0.1.1"];
+
+    for version in versions {
+        let x = obake::tag_as::<Foo, _>(version, Foo_v0_1_0 {}).unwrap();
+        assert_eq!(x.version_str(), version);
+    }
+}
+
+#[test]
+fn try_from_pair() {
+    let x = VersionedFoo::try_from(("0.2.0", Foo { bar: 1 })).unwrap();
+
+    assert_eq!(x, Foo { bar: 1 }.into());
+}
+
+#[test]
+fn mismatched_version() {
+    assert_eq!(
+        VersionedFoo::try_from(("0.1.0", Foo { bar: 1 })).err(),
+        Some(TagMismatch {
+            version: "0.1.0",
+            expected: &["0.2.0"],
+        }),
+    );
+    assert_eq!(
+        obake::tag_as::<Foo, _>("0.3.0", Foo_v0_1_0 {}).err(),
+        Some(TagMismatch {
+            version: "0.3.0",
+            expected: &["0.1.0", "0.1.1"],
+        }),
+    );
+}
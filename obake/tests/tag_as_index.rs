@@ -0,0 +1,76 @@
+#![cfg(feature = "json")]
+
+use obake::VersionTagged;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(serde(tag_as_index))]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    bar: u32,
+    #[obake(cfg(">=0.2"))]
+    baz: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            bar: from.bar,
+            baz: String::new(),
+        }
+    }
+}
+
+#[test]
+fn serialize() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 { bar: 42 }.into();
+    assert_eq!(serde_json::to_string(&x).unwrap(), r#"[0,{"bar":42}]"#);
+
+    let x: obake::AnyVersion<Foo> = Foo {
+        bar: 42,
+        baz: "baz".to_owned(),
+    }
+    .into();
+    assert_eq!(
+        serde_json::to_string(&x).unwrap(),
+        r#"[1,{"bar":42,"baz":"baz"}]"#,
+    );
+}
+
+#[test]
+fn deserialize() {
+    let x: obake::AnyVersion<Foo> = serde_json::from_str(r#"[0,{"bar":42}]"#).unwrap();
+    assert_eq!(x.version_str(), "0.1.0");
+    assert_eq!(x, Foo_v0_1_0 { bar: 42 }.into());
+
+    let x: obake::AnyVersion<Foo> = serde_json::from_str(r#"[1,{"bar":42,"baz":"baz"}]"#).unwrap();
+    assert_eq!(x.version_str(), "0.2.0");
+}
+
+#[test]
+fn round_trip() {
+    let x: obake::AnyVersion<Foo> = Foo_v0_1_0 { bar: 42 }.into();
+    let y = serde_json::from_str(&serde_json::to_string(&x).unwrap()).unwrap();
+    assert_eq!(x, y);
+}
+
+#[test]
+fn out_of_bounds_index() {
+    let err = serde_json::from_str::<obake::AnyVersion<Foo>>(r#"[2,{"bar":42}]"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("invalid value: integer `2`, expected a version index less than 2"));
+}
+
+#[test]
+fn missing_value() {
+    assert!(serde_json::from_str::<obake::AnyVersion<Foo>>("[0]").is_err());
+    assert!(serde_json::from_str::<obake::AnyVersion<Foo>>("[]").is_err());
+}
+
+#[test]
+fn mismatched_value() {
+    assert!(serde_json::from_str::<obake::AnyVersion<Foo>>(r#"[1,{"bar":42}]"#).is_err());
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "json")]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, PartialEq, serde::Serialize, serde::Deserialize))]
+#[obake(serde(tag_case = "kebab"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct HttpConfig {
+    port: u16,
+}
+
+impl From<HttpConfig!["0.1.0"]> for HttpConfig!["0.2.0"] {
+    fn from(from: HttpConfig!["0.1.0"]) -> Self {
+        Self { port: from.port }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("1.0.0"))]
+#[obake(derive(Debug, PartialEq, serde::Serialize, serde::Deserialize))]
+#[obake(serde(tag = "version", content = "data"))]
+#[obake(serde(tag_case = "SCREAMING_SNAKE_CASE"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["1.0.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[test]
+fn versions_are_tagged_in_kebab_case() {
+    let x: obake::AnyVersion<HttpConfig> = HttpConfig_v0_1_0 { port: 80 }.into();
+    let json = serde_json::to_string(&x).unwrap();
+    assert_eq!(json, r#"{"http-config-v0-1-0":{"port":80}}"#);
+    assert_eq!(
+        serde_json::from_str::<obake::AnyVersion<HttpConfig>>(&json).unwrap(),
+        x
+    );
+
+    let x: obake::AnyVersion<HttpConfig> = HttpConfig { port: 443 }.into();
+    assert_eq!(
+        serde_json::to_string(&x).unwrap(),
+        r#"{"http-config-v0-2-0":{"port":443}}"#,
+    );
+}
+
+#[test]
+fn versions_are_tagged_in_screaming_snake_case() {
+    let x: obake::AnyVersion<Foo> =
+        serde_json::from_str(r#"{"version":"FOO_V0_1_0","data":{"field_0":1}}"#).unwrap();
+    assert_eq!(x, Foo_v0_1_0 { field_0: 1 }.into());
+
+    let x: obake::AnyVersion<Foo> = Foo { field_0: 2 }.into();
+    assert_eq!(
+        serde_json::to_string(&x).unwrap(),
+        r#"{"version":"FOO_V1_0_0","data":{"field_0":2}}"#,
+    );
+}
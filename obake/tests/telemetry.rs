@@ -0,0 +1,118 @@
+#![cfg(all(feature = "metrics", feature = "json"))]
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use obake::telemetry::{DECODED, TYPE, UPGRADED, VERSION};
+use obake::AnyVersion;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+/// Counts keyed by name, type and version.
+type Counts = BTreeMap<(String, String, String), Arc<AtomicU64>>;
+
+#[derive(Default)]
+struct TestRecorder(Mutex<Counts>);
+
+impl TestRecorder {
+    fn count(&self, name: &str, version: &str) -> u64 {
+        let key = (
+            name.to_owned(),
+            std::any::type_name::<Foo>().to_owned(),
+            version.to_owned(),
+        );
+        self.0
+            .lock()
+            .unwrap()
+            .get(&key)
+            .map_or(0, |count| count.load(Ordering::SeqCst))
+    }
+}
+
+impl Recorder for TestRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let label = |name: &str| {
+            key.labels()
+                .find(|label| label.key() == name)
+                .unwrap()
+                .value()
+                .to_owned()
+        };
+        let count = self
+            .0
+            .lock()
+            .unwrap()
+            .entry((key.name().to_owned(), label(TYPE), label(VERSION)))
+            .or_default()
+            .clone();
+        Counter::from_arc(count)
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[test]
+fn counts_decoded_versions() {
+    let recorder = TestRecorder::default();
+    metrics::with_local_recorder(&recorder, || {
+        for payload in [
+            r#"{"version":"0.1.0","data":{"field_0":1}}"#,
+            r#"{"version":"0.1.0","data":{"field_0":2}}"#,
+            r#"{"version":"0.2.0","data":{"field_0":3,"field_1":"x"}}"#,
+        ] {
+            obake::json::from_slice::<Foo>(payload.as_bytes()).unwrap();
+        }
+
+        assert!(obake::json::from_slice::<Foo>(br#"{"version":"0.2.0","data":{}}"#).is_err());
+    });
+
+    assert_eq!(recorder.count(DECODED, "0.1.0"), 2);
+    assert_eq!(recorder.count(DECODED, "0.2.0"), 1);
+}
+
+#[test]
+fn counts_upgraded_versions() {
+    let recorder = TestRecorder::default();
+    let values: Vec<AnyVersion<Foo>> = vec![
+        Foo_v0_1_0 { field_0: 1 }.into(),
+        Foo_v0_1_0 { field_0: 2 }.into(),
+        Foo_v0_1_0 { field_0: 3 }.into(),
+    ];
+    let upgraded: Vec<Foo> =
+        metrics::with_local_recorder(&recorder, || obake::collections::upgrade_vec(values));
+
+    assert_eq!(upgraded.len(), 3);
+    assert_eq!(recorder.count(UPGRADED, "0.1.0"), 3);
+    assert_eq!(recorder.count(UPGRADED, "0.2.0"), 0);
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "alloc")]
+#![allow(unused_macros)]
+
+use std::convert::TryFrom;
+
+use obake::testing::{self, Named};
+use obake::{TestValues, VersionTagged};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(test_values("0.1.0", examples::v0_1_0))]
+#[obake(test_values("0.3.0", examples::v0_3_0))]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: String::new(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1,
+        }
+    }
+}
+
+mod examples {
+    use super::*;
+
+    pub fn v0_1_0() -> Vec<Foo!["0.1.0"]> {
+        vec![Foo_v0_1_0 { field_0: 0 }, Foo_v0_1_0 { field_0: 1 }]
+    }
+
+    pub fn v0_3_0() -> [Foo!["0.3.0"]; 1] {
+        [Foo {
+            field_0: 2,
+            field_1: "latest".to_owned(),
+        }]
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible("0.2.0", error = &'static str))]
+#[obake(test_values("0.1.0", bar_examples))]
+#[derive(Debug, PartialEq)]
+struct Bar {
+    field: u8,
+}
+
+impl TryFrom<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Bar!["0.1.0"]) -> Result<Self, Self::Error> {
+        if from.field == 0 {
+            return Err("zero");
+        }
+        Ok(Self { field: from.field })
+    }
+}
+
+fn bar_examples() -> Vec<Bar!["0.1.0"]> {
+    vec![Bar_v0_1_0 { field: 1 }]
+}
+
+// Its generated test is `http_config_test_values::migrate`, not `h_t_t_p_config_...`
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(test_values("0.1.0", http_configs))]
+#[derive(Debug, PartialEq)]
+struct HTTPConfig {
+    port: u16,
+}
+
+fn http_configs() -> Vec<HTTPConfig!["0.1.0"]> {
+    vec![HTTPConfig { port: 80 }]
+}
+
+#[test]
+fn values_are_gathered_from_oldest_to_newest() {
+    let versions: Vec<_> = Foo::test_values()
+        .iter()
+        .map(VersionTagged::version_str)
+        .collect();
+    assert_eq!(versions, ["0.1.0", "0.1.0", "0.3.0"]);
+}
+
+#[test]
+fn values_are_named_by_version_and_position() {
+    let names: Vec<_> = testing::named::<Foo>()
+        .into_iter()
+        .map(|Named { name, .. }| name)
+        .collect();
+    assert_eq!(names, ["0.1.0-0", "0.1.0-1", "0.3.0-0"]);
+}
+
+#[test]
+fn fallible_migrations_are_checked() {
+    testing::assert_migrate::<Bar>();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn snapshots_encode_values_at_their_version() {
+    use obake::codec::Json;
+
+    let snapshots = testing::snapshots::<Foo, _>(&Json).unwrap();
+    assert_eq!(snapshots[1].name, "0.1.0-1");
+    assert_eq!(snapshots[1].version, "0.1.0");
+    assert_eq!(snapshots[1].payload, br#"{"field_0":1}"#);
+    assert_eq!(snapshots[2].payload, br#"{"field_0":2,"field_1":"latest"}"#);
+
+    let cases = obake::bench::test_value_cases::<Foo, _>(&Json).unwrap();
+    assert_eq!(cases[0].name(), "0.1.0 -> 0.3.0");
+    assert_eq!(
+        cases[2].run().unwrap(),
+        Foo {
+            field_0: 2,
+            field_1: "latest".to_owned(),
+        }
+    );
+}
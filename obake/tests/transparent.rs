@@ -0,0 +1,57 @@
+use core::mem::size_of;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(transparent)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq)]
+struct Port {
+    number: u16,
+    #[obake(cfg(">=0.3"))]
+    protocol: String,
+}
+
+impl From<Port!["0.1.0"]> for Port!["0.2.0"] {
+    fn from(from: Port!["0.1.0"]) -> Self {
+        Self {
+            number: from.number,
+        }
+    }
+}
+
+impl From<Port!["0.2.0"]> for Port!["0.3.0"] {
+    fn from(from: Port!["0.2.0"]) -> Self {
+        Self {
+            number: from.number,
+            protocol: "tcp".to_owned(),
+        }
+    }
+}
+
+#[test]
+fn single_field_versions_are_laid_out_as_their_field() {
+    assert_eq!(size_of::<Port!["0.1.0"]>(), size_of::<u16>());
+    assert_eq!(size_of::<Port!["0.2.0"]>(), size_of::<u16>());
+    assert!(size_of::<Port>() > size_of::<String>());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn single_field_versions_are_encoded_as_their_field() {
+    let old: Port!["0.1.0"] = serde_json::from_str("8080").unwrap();
+    assert_eq!(old, Port_v0_1_0 { number: 8080 });
+    assert_eq!(
+        serde_json::to_string(&Port_v0_2_0 { number: 80 }).unwrap(),
+        "80"
+    );
+
+    let latest = Port {
+        number: 443,
+        protocol: "tcp".to_owned(),
+    };
+    let json = serde_json::to_string(&latest).unwrap();
+    assert_eq!(json, r#"{"number":443,"protocol":"tcp"}"#);
+    assert_eq!(serde_json::from_str::<Port>(&json).unwrap(), latest);
+}
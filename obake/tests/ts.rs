@@ -0,0 +1,51 @@
+#![cfg(feature = "ts-rs")]
+#![allow(dead_code)]
+
+use obake::ts_rs::{Config, TS};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(ts)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: String::new(),
+        }
+    }
+}
+
+#[test]
+fn version_names() {
+    let cfg = Config::default();
+    assert_eq!(<Foo!["0.1.0"]>::name(&cfg), "FooV0_1_0");
+    assert_eq!(<Foo!["0.2.0"]>::name(&cfg), "FooV0_2_0");
+    assert_eq!(VersionedFoo::name(&cfg), "VersionedFoo");
+}
+
+#[test]
+fn version_decls() {
+    let cfg = Config::default();
+    assert_eq!(
+        <Foo!["0.1.0"]>::decl(&cfg),
+        "type FooV0_1_0 = { field_0: number, };",
+    );
+    assert_eq!(
+        <Foo!["0.2.0"]>::decl(&cfg),
+        "type FooV0_2_0 = { field_0: number, field_1: string, };",
+    );
+}
+
+#[test]
+fn envelope_decl() {
+    let decl = VersionedFoo::decl(&Config::default());
+    assert!(decl.contains(r#"{ "version": "0.1.0", "data": FooV0_1_0 }"#));
+    assert!(decl.contains(r#"{ "version": "0.2.0", "data": FooV0_2_0 }"#));
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+
+use obake::collections::KeyedError;
+use obake::txn::migrate_all;
+use obake::{AnyVersion, StepError};
+
+thread_local! {
+    static FOO_MIGRATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        FOO_MIGRATIONS.with(|count| count.set(count.get() + 1));
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = &'static str))]
+#[derive(Debug, PartialEq)]
+struct Bar {
+    #[obake(cfg("0.1.0"))]
+    field_0: i32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+}
+
+impl TryFrom<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    type Error = &'static str;
+
+    fn try_from(from: Bar!["0.1.0"]) -> Result<Self, Self::Error> {
+        let field_1 = u32::try_from(from.field_0).map_err(|_| "negative")?;
+        Ok(Self { field_1 })
+    }
+}
+
+#[test]
+fn related_values_are_migrated_together() {
+    let a: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 1 }.into();
+    let b: AnyVersion<Bar> = Bar_v0_1_0 { field_0: 2 }.into();
+    let c: AnyVersion<Bar> = Bar { field_1: 3 }.into();
+    let migrated = migrate_all!((a: AnyVersion<Foo>), (b: AnyVersion<Bar>), (c: AnyVersion<Bar>));
+    assert_eq!(
+        migrated,
+        Ok((Foo { field_1: 1 }, Bar { field_1: 2 }, Bar { field_1: 3 })),
+    );
+
+    let a: AnyVersion<Foo> = Foo { field_1: 4 }.into();
+    assert_eq!(
+        migrate_all!((a: AnyVersion<Foo>),),
+        Ok((Foo { field_1: 4 },))
+    );
+}
+
+#[test]
+fn every_failure_is_reported() {
+    let a: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 1 }.into();
+    let b: AnyVersion<Bar> = Bar_v0_1_0 { field_0: -1 }.into();
+    let c: AnyVersion<Foo> = Foo_v0_1_0 { field_0: 2 }.into();
+    let d: AnyVersion<Bar> = Bar_v0_1_0 { field_0: -2 }.into();
+    let errors = migrate_all!(
+        (a: AnyVersion<Foo>),
+        (b: AnyVersion<Bar>),
+        (c: AnyVersion<Foo>),
+        (d: AnyVersion<Bar>),
+    )
+    .unwrap_err();
+
+    // Every value was migrated, though none of them is returned
+    assert_eq!(FOO_MIGRATIONS.with(Cell::get), 2);
+
+    let error = |key| KeyedError {
+        key,
+        error: StepError {
+            from: "0.1.0",
+            to: "0.2.0",
+            error: "negative".to_owned(),
+        },
+    };
+    assert_eq!(errors, [error("b"), error("d")]);
+    assert_eq!(
+        errors[1].to_string(),
+        "\"d\": migration from version 0.1.0 to version 0.2.0 failed: negative",
+    );
+}
@@ -0,0 +1,31 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(assert_fields("0.1.0", [field_0, field_1]))]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(assert_fields("0.1.0", [field_0]))]
+struct Bar {
+    field_0: u32,
+    field_1: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(assert_fields("0.2.0", []))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(assert_fields("0.1.0", [A, A]))]
+enum Qux {
+    A,
+}
+
+fn main() {}
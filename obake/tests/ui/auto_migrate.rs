@@ -0,0 +1,44 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+enum Foo {
+    #[obake(cfg("<0.2"))]
+    X(u32),
+    Y,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+enum Bar {
+    #[obake(cfg("<0.2"))]
+    X(u32),
+    #[obake(cfg(">=0.2"))]
+    X(u32, u32),
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+enum Baz {
+    #[obake(cfg("<0.2"))]
+    X,
+    #[obake(cfg(">=0.2"))]
+    X { field_0: u32 },
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+enum Qux {
+    #[obake(cfg("<0.2"))]
+    X(String),
+    #[obake(cfg(">=0.2"))]
+    X(u32),
+}
+
+fn main() {}
@@ -56,6 +56,161 @@ mod derives {
     }
 }
 
+mod downgrades {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(downgrade)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Bar {
+        #[obake(downgrade)]
+        X,
+    }
+}
+
+mod repr_cs {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(repr_c)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Bar {
+        #[obake(repr_c)]
+        X,
+    }
+}
+
+mod dense_migrations {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(dense_migrations)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Bar {
+        #[obake(dense_migrations)]
+        X,
+    }
+}
+
+mod alias_identicals {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(alias_identical)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Bar {
+        #[obake(alias_identical)]
+        X,
+    }
+}
+
+mod auto_migrate {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(auto_migrate)]
+        field_0: u32,
+    }
+}
+
+mod auto_downgrade_subset {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(auto_downgrade_subset)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(auto_downgrade_subset)]
+    enum Bar {
+        X,
+    }
+}
+
+mod dto_modules {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(dto_modules)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(dto_modules)]
+    pub(super) struct Bar {}
+}
+
+mod migrate_default {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(migrate_default = 0)]
+    struct Foo {}
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Bar {
+        #[obake(migrate_default = 0)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Baz {
+        #[obake(migrate_default = 0)]
+        X,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Qux {
+        X(#[obake(migrate_default = 0)] u32),
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(auto_migrate)]
+    struct Quux {
+        #[obake(migrate_default("0.1", 0))]
+        #[obake(migrate_default(0))]
+        field_0: u32,
+    }
+}
+
+mod notes {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(note("0.1.0", "..."))]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(note("0.2.0", "..."))]
+    enum Bar {
+        X,
+    }
+}
+
 mod serdes {
     #[obake::versioned]
     #[obake(version("0.1.0"))]
@@ -81,4 +236,58 @@ mod serdes {
     }
 }
 
+mod derive_bounds {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(derive_bounds(u32: Clone))]
+        field_0: u32,
+    }
+}
+
+mod on_unrecognized {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(on_unrecognized = recover)]
+        field_0: u32,
+    }
+}
+
+mod scheme {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(scheme = integer)]
+        field_0: u32,
+    }
+}
+
+mod no_alloc {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(no_alloc)]
+        field_0: u32,
+    }
+}
+
+mod no_macro {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(no_macro)]
+        field_0: u32,
+    }
+}
+
+mod public_enum {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    enum Foo {
+        #[obake(public_enum)]
+        X,
+    }
+}
+
 fn main() {}
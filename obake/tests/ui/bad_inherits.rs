@@ -20,4 +20,51 @@ enum Baz {
     }
 }
 
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Qux {
+    #[obake(inherit)]
+    field_0: &'static Foo,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Quux {
+    #[obake(inherit)]
+    field_0: dyn std::any::Any,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Corge {
+    #[obake(inherit)]
+    field_0: Option<Foo>,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Grault {
+    #[obake(inherit)]
+    field_0: <Foo as Iterator>::Item,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Garply {
+    #[obake(inherit)]
+    field_0: (Foo, Foo),
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Waldo {
+    Variant(#[obake(inherit)] [Foo; 3]),
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Fred {
+    Variant(#[obake(cfg("0.1.0"))] u32),
+}
+
 fn main() {}
@@ -0,0 +1,33 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(cfg_when(feature = "legacy", ">=0.2"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(cfg_when(feature = "legacy", "0.2.0"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(cfg_when(feature = "legacy", "<0.2"))]
+#[obake(cfg_when(unix, "<0.2"))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(cfg_when(feature = "legacy", "<0.2"))]
+#[obake(tag = u8)]
+struct Qux {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(cfg_when(feature = "legacy"))]
+struct Quux {}
+
+fn main() {}
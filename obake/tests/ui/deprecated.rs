@@ -0,0 +1,33 @@
+#![deny(deprecated)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(deprecated("0.1.0", "migrate to 0.2.0"))]
+struct Foo {}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self {}
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(deprecated("0.2.0"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(deprecated("0.2.0"))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(deprecated("0.1.0"))]
+#[obake(deprecated("0.1.0", "again"))]
+struct Qux {}
+
+fn main() {}
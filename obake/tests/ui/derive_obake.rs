@@ -0,0 +1,15 @@
+#[derive(obake::Obake)]
+#[obake(version("0.1.0"))]
+struct Foo {}
+
+#[derive(obake::Obake)]
+#[obake(name = Bar)]
+#[obake(version("0.1.0"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(name = Qux)]
+#[obake(version("0.1.0"))]
+struct Baz {}
+
+fn main() {}
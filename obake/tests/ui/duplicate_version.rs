@@ -8,4 +8,14 @@ struct Foo {}
 #[obake(version("0.1.0"))]
 enum Bar {}
 
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.1.0+build5"))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0+b"))]
+#[obake(version("0.1.0+a"))]
+enum Qux {}
+
 fn main() {}
@@ -0,0 +1,17 @@
+#![allow(unused_macros)]
+
+mod upstream {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    pub struct Config {}
+}
+
+#[obake::extend_versions(basis = upstream::Config)]
+#[obake(version("0.2.0"))]
+struct Foo {}
+
+#[obake::extend_versions(base = upstream::Config)]
+#[obake(version("0.2.0"))]
+struct Bar {}
+
+fn main() {}
@@ -0,0 +1,37 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(external(serde_shim = shim))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {
+    #[obake(external(with = shim))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Baz {
+    #[obake(external("<0.2", serde_shim = shim))]
+    #[obake(external(">=0.1", serde_shim = shim))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Qux {
+    #[obake(external(serde_shim = shim))]
+    X(u32),
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flim {
+    #[obake(skip)]
+    #[obake(external(serde_shim = shim))]
+    field_0: u32,
+}
+
+fn main() {}
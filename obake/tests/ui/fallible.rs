@@ -0,0 +1,41 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(error = ()))]
+#[obake(fallible("0.2.0", error = ()))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible("0.1.0", error = ()))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(auto_migrate)]
+#[obake(fallible(error = ()))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(dense_migrations)]
+#[obake(fallible(error = ()))]
+struct Qux {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(fallible(err = ()))]
+struct Flim {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flam {
+    #[obake(fallible(error = ()))]
+    field_0: u32,
+}
+
+fn main() {}
@@ -10,4 +10,11 @@ enum Baz<T> {}
 #[obake::versioned]
 enum Flim<'a> {}
 
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flam<'a> {
+    #[serde(borrow)]
+    field_0: &'a str,
+}
+
 fn main() {}
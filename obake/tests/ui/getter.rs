@@ -0,0 +1,37 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(getter)]
+struct Foo {
+    bar: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Baz {
+    Qux {
+        #[obake(getter)]
+        quux: u32,
+    },
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Corge {
+    #[obake(cfg("0.1.0"))]
+    #[obake(getter)]
+    grault: u32,
+    #[obake(cfg("0.2.0"))]
+    #[obake(getter)]
+    grault: u64,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Garply {
+    #[obake(skip)]
+    #[obake(getter)]
+    waldo: u32,
+}
+
+fn main() {}
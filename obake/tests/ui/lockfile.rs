@@ -0,0 +1,35 @@
+mod before {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(lockfile = "ui-lockfile.lock")]
+    struct Foo {
+        bar: u32,
+    }
+
+    impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+        fn from(from: Foo!["0.1.0"]) -> Self {
+            Self { bar: from.bar }
+        }
+    }
+}
+
+// Locked by the same name, as if `before::Foo` were edited
+mod after {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(lockfile = "ui-lockfile.lock")]
+    struct Foo {
+        bar: u64,
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Baz {
+    #[obake(lockfile = "ui-lockfile.lock")]
+    qux: u32,
+}
+
+fn main() {}
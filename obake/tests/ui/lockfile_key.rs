@@ -0,0 +1,6 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(lockfile("obake.lock", key = "config Settings"))]
+struct Foo {}
+
+fn main() {}
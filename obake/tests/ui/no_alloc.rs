@@ -0,0 +1,13 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(no_alloc(max_size = 4))]
+struct Foo {
+    field_0: [u64; 4],
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(no_alloc(max = 4))]
+struct Bar {}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#[obake::versioned]
+#[obake(version("0.2.0"))]
+#[obake(version("0.1.0"))]
+#[obake(public_enum)]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(public_enum(variants = "Version"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(public_enum(variants = "{version}"))]
+struct Baz {}
+
+fn main() {}
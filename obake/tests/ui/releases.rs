@@ -0,0 +1,31 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(released("0.1.0", "2023-6-1"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(released("0.2.0", "2023-06-01"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(released("0.1.0", "2023-06-01"))]
+#[obake(released("0.1.0", "2023-07-01"))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(released("0.1.0", "2023-06-01"))]
+#[obake(released("0.2.0", "2023-05-01"))]
+struct Qux {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flim {
+    #[obake(released("0.1.0", "2023-06-01"))]
+    field_0: u32,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#[obake::versioned]
+#[obake(version("0.1.0-alpha"))]
+#[obake(repr_c)]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1024.0"))]
+#[obake(repr_c)]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0+a"))]
+#[obake(version("0.1.0+b"))]
+#[obake(repr_c)]
+struct Baz {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(repr_c)]
+#[obake(fallible(error = ()))]
+struct Foo {}
+
+fn main() {}
@@ -0,0 +1,31 @@
+#[obake::versioned]
+#[obake(version("1.0.0"))]
+#[obake(version("1.1.0"))]
+#[obake(rules(deny(no_removal_without_major)))]
+struct Foo {
+    #[obake(cfg("<1.1"))]
+    bar: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(rules(deny(new_fields_have_defaults)))]
+struct Bar {
+    #[obake(cfg(">=0.2"))]
+    baz: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(rules(deny(max_chain = 1)))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(rules(deny(unstable_fields)))]
+struct Corge {}
+
+fn main() {}
@@ -0,0 +1,16 @@
+// Warnings are raised as deprecation warnings, which can be denied
+#![deny(deprecated)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.1.1"))]
+#[obake(rules(warn(no_removal_without_major)))]
+#[obake(auto_migrate)]
+struct Foo {
+    #[obake(cfg("=0.1.0"))]
+    bar: u32,
+}
+
+fn main() {
+    let _: Foo!["0.1.0"] = Foo_v0_1_0 { bar: 0 };
+}
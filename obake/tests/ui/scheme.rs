@@ -0,0 +1,35 @@
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(version("01"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(scheme = calver)]
+#[obake(version("2024.13.01"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(version("1"))]
+struct Baz {
+    #[obake(cfg("^1"))]
+    field_0: u32,
+}
+
+struct Channel;
+
+#[obake::versioned]
+#[obake(scheme = Channel)]
+#[obake(version("alpha"))]
+struct Flim {
+    #[obake(cfg(">=beta"))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(scheme = calver)]
+#[obake(version("1"))]
+struct Flam {}
+
+fn main() {}
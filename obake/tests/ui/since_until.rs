@@ -0,0 +1,46 @@
+#![allow(unused_macros)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(since("0.1.0"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Baz {
+    #[obake(since("0.1.0"))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Qux {
+    #[obake(inherit)]
+    #[obake(until("0.2.0"))]
+    field_0: Foo,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Flim {
+    #[obake(inherit)]
+    #[obake(since("0.2.0"))]
+    #[obake(until("0.1.0"))]
+    field_0: Foo,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flam {
+    #[obake(inherit)]
+    #[obake(since("0.1.0"))]
+    #[obake(since("0.1.0"))]
+    field_0: Foo,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(skip)]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {
+    #[obake(skip)]
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Baz {
+    X {
+        #[obake(skip)]
+        field_0: u32,
+    },
+    Y(#[obake(skip)] u32),
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(repr_c)]
+struct Flim {
+    #[obake(skip)]
+    field_0: u32,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(stable("0.2.0"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(stable("0.2.0"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(deprecated("0.1.0"))]
+#[obake(stable("0.1.0"))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(stable("0.1.0"))]
+#[obake(stable("0.1.0"))]
+struct Qux {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Quux {
+    #[obake(stability)]
+    field_0: u32,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(stable_hash)]
+#[derive(Clone, Hash)]
+struct Foo {
+    field_0: u32,
+    #[obake(skip)]
+    cache: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {
+    #[obake(stable_hash)]
+    field_0: u32,
+}
+
+fn main() {}
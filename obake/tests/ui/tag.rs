@@ -0,0 +1,12 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(tag = i8)]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(tag = u8)]
+#[obake(repr_c)]
+struct Bar {}
+
+fn main() {}
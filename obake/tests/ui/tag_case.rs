@@ -0,0 +1,18 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(serde(tag_case = "camel"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(serde(tag_case = "kebab"))]
+#[obake(serde(tag_case = "snake"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(serde(tag_as_index))]
+#[obake(serde(tag_case = "kebab"))]
+struct Baz {}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(transparent)]
+enum Foo {
+    Bar,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(transparent)]
+#[obake(repr_c)]
+struct Baz {
+    qux: u32,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version_default("0.2.0", foo_default))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version_default("0.1.0", bar_default))]
+#[obake(version_default("0.1.0", bar_default))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(alias_identical)]
+#[obake(version_default("0.2.0", baz_default))]
+struct Baz {}
+
+fn main() {}
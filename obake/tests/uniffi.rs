@@ -0,0 +1,45 @@
+#![cfg(feature = "uniffi")]
+#![allow(unused_macros)]
+
+uniffi::setup_scaffolding!();
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(uniffi)]
+#[derive(PartialEq, Debug)]
+pub struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "default".to_owned(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(uniffi)]
+#[derive(PartialEq, Debug)]
+pub enum Bar {
+    X,
+    Y { field_0: u32 },
+}
+
+#[test]
+fn upgrade() {
+    assert_eq!(
+        upgrade_foo(VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field_0: 7 })),
+        Foo {
+            field_0: 7,
+            field_1: "default".to_owned(),
+        },
+    );
+    assert_eq!(upgrade_bar(VersionedBar::Bar_v0_1_0(Bar::X)), Bar::X);
+}
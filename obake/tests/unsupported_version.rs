@@ -0,0 +1,99 @@
+#![cfg(feature = "json")]
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use obake::codec::{decode_version_with, Json, UnsupportedVersion};
+use obake::store::{Backend, Error, VersionedTree};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    #[serde(default)]
+    field_1: String,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: "migrated".to_owned(),
+        }
+    }
+}
+
+const NEWER: &[u8] = br#"{"field_0":1,"field_1":"x","field_2":true}"#;
+
+#[test]
+fn reject() {
+    assert!(
+        decode_version_with::<Foo, _>(&Json, "0.3.0", NEWER, UnsupportedVersion::Reject).is_none()
+    );
+    assert!(obake::json::from_slice_with::<Foo>(
+        br#"{"version":"0.3.0","data":{"field_0":1}}"#,
+        UnsupportedVersion::default(),
+    )
+    .is_err());
+}
+
+#[test]
+fn best_effort_latest() {
+    let foo =
+        decode_version_with::<Foo, _>(&Json, "0.3.0", NEWER, UnsupportedVersion::BestEffortLatest)
+            .unwrap()
+            .unwrap();
+    assert_eq!(
+        Foo::from(foo),
+        Foo {
+            field_0: 1,
+            field_1: "x".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn callback() {
+    fn legacy(version: &str) -> Option<&'static str> {
+        version.starts_with("0.0.").then_some("0.1.0")
+    }
+
+    let policy = UnsupportedVersion::Callback(legacy);
+    let foo: Foo = decode_version_with::<Foo, _>(&Json, "0.0.9", br#"{"field_0":2}"#, policy)
+        .unwrap()
+        .unwrap()
+        .into();
+    assert_eq!(foo.field_1, "migrated");
+    assert!(decode_version_with::<Foo, _>(&Json, "0.3.0", NEWER, policy).is_none());
+}
+
+#[test]
+fn declared_versions_are_unaffected() {
+    let policy = UnsupportedVersion::Callback(|_| None);
+    let foo: Foo = decode_version_with::<Foo, _>(&Json, "0.1.0", br#"{"field_0":2}"#, policy)
+        .unwrap()
+        .unwrap()
+        .into();
+    assert_eq!(foo.field_0, 2);
+}
+
+#[test]
+fn store() {
+    type Tree = VersionedTree<Foo, RefCell<BTreeMap<Vec<u8>, Vec<u8>>>, Json>;
+
+    let mut record = vec![5];
+    record.extend_from_slice(b"0.3.0");
+    record.extend_from_slice(NEWER);
+
+    let tree = Tree::new(RefCell::default(), Json);
+    tree.backend().insert(b"newer", &record).unwrap();
+    assert!(
+        matches!(tree.get(b"newer"), Err(Error::UnknownVersion(version)) if version == "0.3.0")
+    );
+
+    let tree = tree.with_unsupported_version(UnsupportedVersion::BestEffortLatest);
+    assert_eq!(tree.get(b"newer").unwrap().unwrap().field_1, "x");
+}
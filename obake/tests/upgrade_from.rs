@@ -0,0 +1,81 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("<0.3"))]
+    bar: u32,
+    #[obake(cfg(">=0.3"))]
+    baz: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self { bar: from.bar + 1 }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            baz: u64::from(from.bar) * 10,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.4.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self { baz: from.baz + 1 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Bar {
+    #[obake(cfg(">=0.2"))]
+    count: u32,
+    #[obake(skip)]
+    cache: Option<u32>,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self { count: 1 }
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.3.0"] {
+    fn from(from: Bar!["0.2.0"]) -> Self {
+        Self {
+            count: from.count + 1,
+        }
+    }
+}
+
+#[test]
+fn every_version_converts_into_the_latest() {
+    let old: Foo!["0.1.0"] = Foo_v0_1_0 { bar: 1 };
+    assert_eq!(Foo::from(old), Foo { baz: 21 });
+
+    let old: Foo!["0.2.0"] = Foo_v0_2_0 { bar: 1 };
+    let latest: Foo = old.into();
+    assert_eq!(latest, Foo { baz: 11 });
+
+    let old: Foo!["0.3.0"] = Foo_v0_3_0 { baz: 1 };
+    assert_eq!(Foo::from(old), Foo { baz: 2 });
+}
+
+#[test]
+fn versions_convert_into_types_holding_skipped_fields() {
+    let old: Bar!["0.1.0"] = Bar_v0_1_0 {};
+    let latest: Bar = old.into();
+    assert_eq!(latest.count, 2);
+
+    let old: Bar!["0.2.0"] = Bar_v0_2_0 { count: 5 };
+    assert_eq!(Bar::from(old).count, 6);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "validator")]
+
+use obake::validate::ValidateVersion;
+use validator::Validate;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Validate)]
+struct Foo {
+    #[obake(cfg_attr("0.1.0", validate(length(max = 4))))]
+    #[obake(cfg_attr(">=0.2", validate(length(max = 8))))]
+    field_0: String,
+    #[obake(cfg(">=0.2"))]
+    #[validate(range(min = 1))]
+    field_1: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 1,
+        }
+    }
+}
+
+#[test]
+fn legacy_rules_apply_to_legacy_versions() {
+    let legacy: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        field_0: "abcdef".to_owned(),
+    }
+    .into();
+    assert!(legacy.validate().is_err());
+
+    let legacy: obake::AnyVersion<Foo> = Foo_v0_1_0 {
+        field_0: "abcd".to_owned(),
+    }
+    .into();
+    assert!(legacy.validate().is_ok());
+}
+
+#[test]
+fn latest_rules_apply_to_latest_version() {
+    let latest: obake::AnyVersion<Foo> = Foo {
+        field_0: "abcdef".to_owned(),
+        field_1: 1,
+    }
+    .into();
+    assert!(latest.validate().is_ok());
+
+    let latest: obake::AnyVersion<Foo> = Foo {
+        field_0: "abcdef".to_owned(),
+        field_1: 0,
+    }
+    .into();
+    assert!(latest.validate().is_err());
+}
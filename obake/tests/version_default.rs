@@ -0,0 +1,73 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version_default("0.1.0", legacy_default))]
+#[obake(version_default("0.2.0", timeout_default))]
+#[obake(auto_migrate("0.2"))]
+#[derive(Default, PartialEq, Eq, Debug)]
+struct Foo {
+    retries: u32,
+    #[obake(cfg(">=0.2"))]
+    timeout: u32,
+    #[obake(cfg(">=0.3"))]
+    verbose: bool,
+}
+
+fn legacy_default() -> Foo!["0.1.0"] {
+    Foo_v0_1_0 { retries: 5 }
+}
+
+fn timeout_default() -> Foo!["0.2.0"] {
+    Foo_v0_2_0 {
+        retries: 3,
+        timeout: 60,
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            retries: from.retries,
+            timeout: from.timeout,
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn versions_take_their_own_default() {
+    assert_eq!(<Foo!["0.1.0"]>::default(), Foo_v0_1_0 { retries: 5 });
+    assert_eq!(
+        <Foo!["0.2.0"]>::default(),
+        Foo_v0_2_0 {
+            retries: 3,
+            timeout: 60,
+        },
+    );
+}
+
+#[test]
+fn other_versions_keep_derived_default() {
+    assert_eq!(
+        Foo::default(),
+        Foo {
+            retries: 0,
+            timeout: 0,
+            verbose: false,
+        },
+    );
+}
+
+#[test]
+fn migrations_fill_in_version_defaults() {
+    let foo: Foo = obake::AnyVersion::<Foo>::from(Foo_v0_1_0 { retries: 1 }).into();
+    assert_eq!(
+        foo,
+        Foo {
+            retries: 1,
+            timeout: 60,
+            verbose: false,
+        },
+    );
+}
@@ -0,0 +1,92 @@
+use obake::VersionOf;
+
+mod a {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(no_macro)]
+    #[derive(PartialEq, Debug)]
+    pub struct Foo {
+        #[obake(cfg(">=0.2"))]
+        pub field_0: u32,
+    }
+
+    impl From<obake::version_of!(Foo, "0.1.0")> for obake::version_of!(Foo, "0.2.0") {
+        fn from(_: obake::version_of!(Foo, "0.1.0")) -> Self {
+            Self { field_0: 1 }
+        }
+    }
+}
+
+mod b {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(no_macro)]
+    #[derive(PartialEq, Debug)]
+    pub struct Foo {
+        pub field_0: super::a::Foo,
+    }
+}
+
+// A macro sharing the name of a versioned data-structure
+macro_rules! Bar {
+    () => {
+        42
+    };
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0-rc.1"))]
+#[obake(no_macro)]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(scheme = integer)]
+#[obake(version("1"))]
+#[obake(version("2"))]
+#[obake(no_macro)]
+struct Baz {}
+
+impl From<obake::version_of!(Baz, "1")> for obake::version_of!(Baz, "2") {
+    fn from(_: obake::version_of!(Baz, "1")) -> Self {
+        Self {}
+    }
+}
+
+#[obake::versioned]
+#[obake(scheme = calver)]
+#[obake(version("2024.01.15"))]
+#[obake(no_macro)]
+struct Qux {}
+
+#[test]
+fn version_of_names_versions_across_modules() {
+    let x: obake::version_of!(a::Foo, "0.1.0") = a::Foo_v0_1_0 {};
+    let x: a::Foo = obake::AnyVersion::<a::Foo>::from(x).into();
+    assert_eq!(x, a::Foo { field_0: 1 });
+
+    let y: obake::version_of!(b::Foo, "0.1.0") = b::Foo { field_0: x };
+    assert_eq!(y.field_0.field_0, 1);
+
+    assert_eq!(
+        <obake::version_of!(a::Foo, "0.2.0") as VersionOf<a::Foo>>::VERSION,
+        "0.2.0",
+    );
+}
+
+#[test]
+fn version_of_follows_scheme() {
+    assert_eq!(Bar!(), 42);
+    assert_eq!(
+        <obake::version_of!(Bar, "0.1.0-rc.1") as VersionOf<Bar>>::VERSION,
+        "0.1.0-rc.1",
+    );
+    assert_eq!(
+        <obake::version_of!(Baz, "1") as VersionOf<Baz>>::VERSION,
+        "1"
+    );
+    assert_eq!(
+        <obake::version_of!(Qux, "2024.01.15") as VersionOf<Qux>>::VERSION,
+        "2024.01.15",
+    );
+}
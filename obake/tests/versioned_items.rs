@@ -0,0 +1,71 @@
+use obake::Versioned;
+
+obake::versioned_items! {
+    #![obake(version("0.1.0"))]
+    #![obake(version("0.2.0"))]
+    #![derive(Clone, Debug, PartialEq)]
+
+    struct Foo {
+        field_0: u32,
+        #[obake(cfg(">=0.2"))]
+        field_1: Bar,
+    }
+
+    #[obake(version("0.3.0"))]
+    #[obake(derive(Debug, PartialEq))]
+    pub enum Bar {
+        X,
+        #[obake(cfg(">=0.2"))]
+        Y(u32),
+    }
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: Bar::X,
+        }
+    }
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        match from {
+            Bar_v0_1_0::X => Self::X,
+        }
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.3.0"] {
+    fn from(from: Bar!["0.2.0"]) -> Self {
+        match from {
+            Bar_v0_2_0::X => Self::X,
+            Bar_v0_2_0::Y(y) => Self::Y(y + 1),
+        }
+    }
+}
+
+#[test]
+fn shared_attributes_apply_to_every_item() {
+    assert_eq!(Foo::VERSIONS, &["0.1.0", "0.2.0"]);
+    assert_eq!(Bar::VERSIONS, &["0.1.0", "0.2.0", "0.3.0"]);
+
+    let bar = Bar_v0_2_0::Y(1);
+    assert_eq!(bar.clone(), bar);
+}
+
+#[test]
+fn items_migrate() {
+    let foo: Foo = obake::AnyVersion::<Foo>::from(Foo_v0_1_0 { field_0: 1 }).into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_0: 1,
+            field_1: Bar::X,
+        }
+    );
+
+    let bar: Bar = obake::AnyVersion::<Bar>::from(Bar_v0_2_0::Y(1)).into();
+    assert_eq!(bar, Bar::Y(2));
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "wasm")]
+
+use wasm_bindgen::JsValue;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(wasm)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct HTTPClientConfig {
+    #[obake(cfg(">=0.2"))]
+    pub theme: String,
+}
+
+impl From<HTTPClientConfig!["0.1.0"]> for HTTPClientConfig!["0.2.0"] {
+    fn from(_: HTTPClientConfig!["0.1.0"]) -> Self {
+        Self {
+            theme: "light".to_owned(),
+        }
+    }
+}
+
+#[test]
+fn bindings_generated() {
+    // `JsValue`s can only be constructed on wasm targets, so only check the signatures here. The
+    // bindings are run below with `cargo test --target wasm32-unknown-unknown --features wasm`,
+    // using `wasm-bindgen-test-runner` as the runner
+    let _: fn(&str) -> Result<JsValue, JsValue> = upgrade_http_client_config;
+    let _: fn() -> js_sys::Array = http_client_config_versions;
+}
+
+#[cfg(target_arch = "wasm32")]
+mod wasm32 {
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::{http_client_config_versions, upgrade_http_client_config};
+
+    #[wasm_bindgen_test]
+    fn old_versions_are_upgraded() {
+        let latest = upgrade_http_client_config(r#"{"version":"0.1.0","data":{}}"#).unwrap();
+        let theme = js_sys::Reflect::get(&latest, &JsValue::from_str("theme")).unwrap();
+        assert_eq!(theme.as_string().as_deref(), Some("light"));
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_payloads_throw() {
+        let err = upgrade_http_client_config("not json").unwrap_err();
+        assert!(err.is_instance_of::<js_sys::Error>());
+    }
+
+    #[wasm_bindgen_test]
+    fn versions_are_listed() {
+        let versions: Vec<_> = http_client_config_versions()
+            .iter()
+            .map(|version| version.as_string().unwrap())
+            .collect();
+        assert_eq!(versions, ["0.1.0", "0.2.0"]);
+    }
+}
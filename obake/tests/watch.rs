@@ -0,0 +1,192 @@
+#![cfg(all(feature = "notify", feature = "json"))]
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use obake::codec::UnsupportedVersion;
+use obake::watch::{ConfigWatcher, Error};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Settings {
+    #[obake(cfg("0.1.0"))]
+    port: u16,
+    #[obake(cfg(">=0.2"))]
+    address: String,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            address: format!("localhost:{}", from.port),
+        }
+    }
+}
+
+fn write(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("obake-watch-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn not_empty(settings: &Settings) -> Result<(), &'static str> {
+    if settings.address.is_empty() {
+        return Err("no address given");
+    }
+    Ok(())
+}
+
+#[test]
+fn files_are_loaded_at_the_latest_version() {
+    let path = write("load.json", r#"{ "port": 80 }"#);
+    let watcher = ConfigWatcher::<Settings>::new(&path).unwrap();
+    assert_eq!(watcher.version(), "0.1.0");
+    assert_eq!(watcher.current().address, "localhost:80");
+}
+
+#[test]
+fn undeclared_versions_are_handled_by_policy() {
+    let path = write(
+        "newer.json",
+        r#"{ "version": "0.3.0", "data": { "address": "example.com" } }"#,
+    );
+    assert!(matches!(
+        ConfigWatcher::<Settings>::new(&path),
+        Err(Error::Load(_))
+    ));
+
+    let watcher = ConfigWatcher::<Settings>::validated_with(
+        &path,
+        not_empty,
+        UnsupportedVersion::BestEffortLatest,
+    )
+    .unwrap();
+    assert_eq!(watcher.version(), "0.2.0");
+    assert_eq!(watcher.current().address, "example.com");
+
+    std::fs::write(
+        &path,
+        r#"{ "version": "0.4.0", "data": { "address": "example.org" } }"#,
+    )
+    .unwrap();
+    watcher.reload().unwrap();
+    assert_eq!(watcher.current().address, "example.org");
+}
+
+#[test]
+fn changes_are_swapped_in_and_reported() {
+    let path = write("reload.json", r#"{ "port": 80 }"#);
+    let watcher = ConfigWatcher::<Settings>::new(&path).unwrap();
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    watcher.on_change({
+        let changes = Arc::clone(&changes);
+        move |change| {
+            changes.lock().unwrap().push((
+                change.previous_version,
+                change.version,
+                change.previous.address.clone(),
+                change.current.address.clone(),
+            ));
+        }
+    });
+
+    std::fs::write(&path, r#"{ "address": "example.com:443" }"#).unwrap();
+    watcher.reload().unwrap();
+    assert_eq!(watcher.version(), "0.2.0");
+    assert_eq!(watcher.current().address, "example.com:443");
+    assert!(changes.lock().unwrap().contains(&(
+        "0.1.0",
+        "0.2.0",
+        "localhost:80".to_owned(),
+        "example.com:443".to_owned()
+    )));
+}
+
+#[test]
+fn subscribers_may_reload_and_subscribe() {
+    let path = write("reentrant.json", r#"{ "address": "example.com:443" }"#);
+    let watcher = Arc::new(ConfigWatcher::<Settings>::new(&path).unwrap());
+    let versions = Arc::new(Mutex::new(Vec::new()));
+    watcher.on_change({
+        let watcher = Arc::downgrade(&watcher);
+        let path = path.clone();
+        let versions = Arc::clone(&versions);
+        move |change| {
+            versions.lock().unwrap().push(change.version);
+
+            // Files written at an older version are rewritten at the latest one and reloaded,
+            // replacing the file so that neither thread reloading it reads it half-written
+            if change.version == "0.1.0" {
+                if let Some(watcher) = watcher.upgrade() {
+                    let temp = path.with_extension(format!("{:?}", std::thread::current().id()));
+                    std::fs::write(&temp, r#"{ "address": "localhost:8080" }"#).unwrap();
+                    std::fs::rename(&temp, &path).unwrap();
+                    watcher.reload().unwrap();
+                    watcher.on_change(|_| {});
+                }
+            }
+        }
+    });
+
+    std::fs::write(&path, r#"{ "port": 8080 }"#).unwrap();
+    watcher.reload().unwrap();
+
+    // The file may also be reloaded by the thread watching it, which notifies the changes it
+    // swaps in itself
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let rewritten = {
+            let versions = versions.lock().unwrap();
+            versions
+                .iter()
+                .position(|version| *version == "0.1.0")
+                .is_some_and(|old| versions[old + 1..].contains(&"0.2.0"))
+        };
+        if rewritten && watcher.version() == "0.2.0" {
+            break;
+        }
+        assert!(Instant::now() < deadline, "rewrite wasn't reloaded");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(watcher.current().address, "localhost:8080");
+}
+
+#[test]
+fn invalid_changes_keep_the_previous_configuration() {
+    let path = write("invalid.json", r#"{ "address": "example.com:443" }"#);
+    let watcher = ConfigWatcher::<Settings>::validated(&path, not_empty).unwrap();
+
+    std::fs::write(&path, r#"{ "address": "" }"#).unwrap();
+    assert!(matches!(watcher.reload(), Err(Error::Invalid(_))));
+    std::fs::write(&path, "{").unwrap();
+    assert!(matches!(watcher.reload(), Err(Error::Load(_))));
+    assert_eq!(watcher.version(), "0.2.0");
+    assert_eq!(watcher.current().address, "example.com:443");
+}
+
+#[test]
+fn invalid_files_are_rejected_on_load() {
+    let path = write("rejected.json", r#"{ "address": "" }"#);
+    assert!(matches!(
+        ConfigWatcher::<Settings>::validated(&path, not_empty),
+        Err(Error::Invalid(_))
+    ));
+}
+
+#[test]
+fn changes_to_the_file_are_reloaded() {
+    let path = write("watched.json", r#"{ "port": 80 }"#);
+    let watcher = ConfigWatcher::<Settings>::new(&path).unwrap();
+
+    std::fs::write(&path, r#"{ "port": 8080 }"#).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while watcher.current().address != "localhost:8080" {
+        assert!(Instant::now() < deadline, "change wasn't reloaded");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
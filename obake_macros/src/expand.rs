@@ -1,8 +1,12 @@
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::Result;
 
-use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use quote::{format_ident, quote, quote_spanned, ToTokens, TokenStreamExt};
 
 use crate::internal::*;
+use crate::lockfile;
+use crate::rename;
 
 macro_rules! try_expand {
     ($x:expr) => {
@@ -14,87 +18,401 @@ macro_rules! try_expand {
 }
 
 trait VersionExt {
-    fn version(&self, version: &Version) -> Self;
+    fn version(&self, version: &Tag) -> Self;
 }
 
 impl VersionExt for syn::Ident {
-    fn version(&self, version: &Version) -> Self {
-        format_ident!(
-            "{}_v{}_{}_{}",
-            self,
-            version.major,
-            version.minor,
-            version.patch
-        )
+    fn version(&self, version: &Tag) -> Self {
+        format_ident!("{}_v{}", self, version.suffix)
     }
 }
 
-impl VersionedField {
-    fn expand_ty_versioned(&self, version: &Version) -> Result<TokenStream2> {
-        if self.attrs.inherits().next().is_none() {
-            let ty = &self.ty;
-            return Ok(quote!(#ty));
-        }
+/// An identifier in `snake_case`, split into words as by [`split_words`] (e.g., `http_config` for
+/// `HTTPConfig`).
+fn snake_case(ident: &str) -> String {
+    split_words(ident).join("_").to_lowercase()
+}
 
-        if let syn::Type::Path(ty_path) = &self.ty {
-            let mut ty_path = ty_path.clone();
+/// The stable `#[obake(repr_c)]` tag of `version`, packing the major, minor and patch
+/// components into 12, 10 and 10 bits respectively.
+fn repr_c_tag(version: &Tag) -> Option<u32> {
+    if !version.pre.is_empty() || version.major >= 1 << 12 {
+        return None;
+    }
 
-            if let Some(mut terminator) = ty_path.path.segments.last_mut() {
-                terminator.ident = terminator.ident.version(version);
-                return Ok(quote!(#ty_path));
-            }
+    if version.minor >= 1 << 10 || version.patch >= 1 << 10 {
+        return None;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    Some((version.major << 20 | version.minor << 10 | version.patch) as u32)
+}
+
+/// The words of an identifier, split at underscores and changes of case (e.g., `HttpConfig` and
+/// `HTTPConfig` are both `Http`, `Config`).
+fn split_words(ident: &str) -> Vec<String> {
+    let chars: Vec<char> = ident.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            continue;
         }
 
-        Err(syn::Error::new(
-            self.attrs.inherits().next().unwrap().span,
-            "`#[obake(inherit)]` can only be applied to fields with `#[obake::versioned]` types",
-        ))
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && (prev.is_some_and(|prev| prev.is_lowercase() || prev.is_ascii_digit())
+                || prev.is_some_and(char::is_uppercase)
+                    && next.is_some_and(|next| next.is_lowercase()));
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    words.extend((!word.is_empty()).then_some(word));
+
+    words
+}
+
+/// Whether `next` is a breaking bump from `prev` under semantic versioning (i.e., the leftmost
+/// non-zero component changed, as from `1.2.0` to `2.0.0` or from `0.1.3` to `0.2.0`).
+fn is_breaking_bump(prev: &Version, next: &Version) -> bool {
+    match (prev.major, prev.minor) {
+        (0, 0) => next.major != 0 || next.minor != 0 || next.patch != prev.patch,
+        (0, _) => next.major != 0 || next.minor != prev.minor,
+        _ => next.major != prev.major,
     }
+}
+
+impl VersionedAttributes {
+    /// Reject attributes which are only valid on the versioned item itself.
+    fn check_item_only(&self) -> Result<()> {
+        for attr in self.obake() {
+            let (span, name) = match attr {
+                ObakeAttribute::Derive(attr) => (attr.span, "derive(...)"),
+                ObakeAttribute::DeriveBounds(attr) => (attr.span, "derive_bounds(...)"),
+                ObakeAttribute::Downgrade(attr) => (attr.span, "downgrade"),
+                ObakeAttribute::ReprC(attr) => (attr.span, "repr_c"),
+                ObakeAttribute::DenseMigrations(attr) => (attr.span, "dense_migrations"),
+                ObakeAttribute::AliasIdentical(attr) => (attr.span, "alias_identical"),
+                ObakeAttribute::Tag(attr) => (attr.span, "tag = ..."),
+                ObakeAttribute::AutoMigrate(attr) => (attr.span, "auto_migrate"),
+                ObakeAttribute::AutoDowngradeSubset(attr) => (attr.span, "auto_downgrade_subset"),
+                ObakeAttribute::DtoModules(attr) => (attr.span, "dto_modules"),
+                ObakeAttribute::Note(attr) => (attr.span, "note(...)"),
+                ObakeAttribute::Released(attr) => (attr.span, "released(...)"),
+                ObakeAttribute::Deprecated(attr) => (attr.span, "deprecated(...)"),
+                ObakeAttribute::Stable(attr) => (attr.span, "stable(...)"),
+                ObakeAttribute::Stability(attr) => (attr.span, "stability"),
+                ObakeAttribute::VersionDefault(attr) => (attr.span, "version_default(...)"),
+                ObakeAttribute::AssertFields(attr) => (attr.span, "assert_fields(...)"),
+                ObakeAttribute::Fallible(attr) => (attr.span, "fallible(...)"),
+                ObakeAttribute::Name(attr) => (attr.span, "name = ..."),
+                ObakeAttribute::CfgWhen(attr) => (attr.span, "cfg_when(...)"),
+                ObakeAttribute::Scheme(attr) => (attr.span, "scheme = ..."),
+                ObakeAttribute::NoAlloc(attr) => (attr.span, "no_alloc"),
+                ObakeAttribute::NoMacro(attr) => (attr.span, "no_macro"),
+                ObakeAttribute::StableHash(attr) => (attr.span, "stable_hash"),
+                ObakeAttribute::Transparent(attr) => (attr.span, "transparent"),
+                ObakeAttribute::Rules(attr) => (attr.span, "rules(...)"),
+                ObakeAttribute::Lockfile(attr) => (attr.span, "lockfile = ..."),
+                ObakeAttribute::PublicEnum(attr) => (attr.span, "public_enum"),
+                #[cfg(feature = "alloc")]
+                ObakeAttribute::Boxed(attr) => (attr.span, "boxed"),
+                #[cfg(feature = "alloc")]
+                ObakeAttribute::TestValues(attr) => (attr.span, "test_values(...)"),
+                #[cfg(feature = "serde")]
+                ObakeAttribute::Serde(attr) => (attr.span, "serde(...)"),
+                #[cfg(feature = "serde")]
+                ObakeAttribute::Strictness(attr) => (attr.span, "serde(...)"),
+                #[cfg(feature = "serde")]
+                ObakeAttribute::TagAsIndex(attr) => (attr.span, "serde(tag_as_index)"),
+                #[cfg(feature = "serde")]
+                ObakeAttribute::TagCase(attr) => (attr.span, "serde(tag_case = ...)"),
+                #[cfg(feature = "serde")]
+                ObakeAttribute::OnUnrecognized(attr) => (attr.span, "on_unrecognized = ..."),
+                #[cfg(feature = "json")]
+                ObakeAttribute::Display(attr) => (attr.span, "display"),
+                #[cfg(feature = "json")]
+                ObakeAttribute::JsonUpgrade(attr) => (attr.span, "json_upgrade"),
+                #[cfg(feature = "wasm")]
+                ObakeAttribute::Wasm(attr) => (attr.span, "wasm"),
+                #[cfg(feature = "ts-rs")]
+                ObakeAttribute::Ts(attr) => (attr.span, "ts"),
+                #[cfg(feature = "specta")]
+                ObakeAttribute::Specta(attr) => (attr.span, "specta"),
+                #[cfg(feature = "pyo3")]
+                ObakeAttribute::Pyo3(attr) => (attr.span, "pyo3"),
+                #[cfg(feature = "abi_stable")]
+                ObakeAttribute::AbiStable(attr) => (attr.span, "abi_stable"),
+                #[cfg(feature = "uniffi")]
+                ObakeAttribute::Uniffi(attr) => (attr.span, "uniffi"),
+                #[cfg(feature = "utoipa")]
+                ObakeAttribute::Utoipa(attr) => (attr.span, "utoipa"),
+                #[cfg(feature = "async-graphql")]
+                ObakeAttribute::AsyncGraphql(attr) => (attr.span, "async_graphql"),
+                #[cfg(feature = "tabular")]
+                ObakeAttribute::Tabular(attr) => (attr.span, "tabular"),
+                #[cfg(feature = "idl")]
+                ObakeAttribute::Idl(attr) => (attr.span, "idl"),
+                #[cfg(feature = "debug-expansion")]
+                ObakeAttribute::DebugExpansion(attr) => (attr.span, "debug_expansion = ..."),
+                #[cfg(feature = "defmt")]
+                ObakeAttribute::Defmt(attr) => (attr.span, "defmt"),
+                #[cfg(feature = "fake")]
+                ObakeAttribute::Fake(attr) => (attr.span, "fake"),
+                #[cfg(feature = "registry")]
+                ObakeAttribute::Register(attr) => (attr.span, "register"),
+                _ => continue,
+            };
 
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        if let Some(derive) = self.attrs.derives().next() {
             return Err(syn::Error::new(
-                derive.span,
-                "`#[obake(derive(...))]` not valid in this context",
+                span,
+                format!("`#[obake({name})]` not valid in this context"),
             ));
         }
 
-        #[cfg(feature = "serde")]
-        if let Some(serde) = self.attrs.serdes().next() {
+        Ok(())
+    }
+
+    /// Wrap the type of a field marked `#[obake(secret)]` in `Zeroizing`, so that every copy of
+    /// it left behind by a migration is zeroized when dropped.
+    #[cfg(feature = "zeroize")]
+    fn expand_secret(&self, ty: TokenStream2) -> Result<TokenStream2> {
+        let Some(secret) = self.secrets().next() else {
+            return Ok(ty);
+        };
+
+        // Inherited fields are converted between versions, which `Zeroizing` doesn't forward
+        if self.inherits().next().is_some() {
             return Err(syn::Error::new(
-                serde.span,
-                "`#[obake(serde(...))]` not valid in this context",
+                secret.span,
+                "`#[obake(secret)]` cannot be combined with `#[obake(inherit)]`, mark the secret \
+                 fields of the inherited data-structure instead",
             ));
         }
 
-        let mut reqs: Vec<_> = self.attrs.cfgs().map(|attr| attr.req.clone()).collect();
+        Ok(quote!(::obake::zeroize::Zeroizing<#ty>))
+    }
+
+    /// Wrap an expression setting a field marked `#[obake(secret)]` in `Zeroizing`.
+    #[cfg_attr(not(feature = "zeroize"), allow(clippy::unused_self))]
+    fn expand_secret_expr(&self, expr: &syn::Expr) -> TokenStream2 {
+        #[cfg(feature = "zeroize")]
+        if self.secrets().next().is_some() {
+            return quote!(::obake::zeroize::Zeroizing::new(#expr));
+        }
+
+        expr.to_token_stream()
+    }
+
+    fn is_enabled(&self, version: &Tag) -> bool {
+        // Skipped fields only appear in the latest version's runtime type, never in a version
+        if self.skips().next().is_some() {
+            return false;
+        }
+
+        let mut reqs: Vec<_> = self.cfgs().map(|attr| attr.req.clone()).collect();
 
         // If we have no `#[obake(cfg(...))]` attributes, default to `#[obake(cfg("*"))]`
         if reqs.is_empty() {
             reqs.push(VersionReq::STAR);
         }
 
+        reqs.iter().any(|req| req.matches(version))
+    }
+
+    fn expand_conditional(&self, version: &Tag) -> TokenStream2 {
+        let attrs = self
+            .conditionals()
+            .filter(|attr| attr.req.matches(version))
+            .map(|attr| &attr.tokens);
+
+        quote!(#(#[#attrs])*)
+    }
+
+    /// Where `version` falls relative to the versions an inherited field marked
+    /// `#[obake(since(...))]` or `#[obake(until(...))]` exists in.
+    fn lifespan(&self, version: &Tag) -> Lifespan<'_> {
+        if let Some(since) = self.sinces().find(|attr| *version < attr.version.version) {
+            return Lifespan::Before(&since.version.version);
+        }
+
+        if let Some(until) = self.untils().find(|attr| *version > attr.version.version) {
+            return Lifespan::After(&until.version.version);
+        }
+
+        Lifespan::Within
+    }
+
+    /// Delegate the (de)serialization of a field marked `#[obake(external(...))]` to the shim
+    /// module matching `version`, if any.
+    #[cfg(feature = "serde")]
+    fn expand_external(&self, version: &Tag) -> Result<TokenStream2> {
+        let mut shims = self.externals().filter(|attr| attr.req.matches(version));
+        let Some(external) = shims.next() else {
+            return Ok(quote!());
+        };
+
+        if let Some(other) = shims.next() {
+            return Err(syn::Error::new(
+                other.span,
+                format!("more than one `#[obake(external(...))]` matches version {version}"),
+            ));
+        }
+
+        let shim = external
+            .serde_shim
+            .to_token_stream()
+            .to_string()
+            .replace(' ', "");
+
+        Ok(quote!(#[serde(with = #shim)]))
+    }
+
+    /// The attributes applied in a version, including those applied conditionally.
+    fn metas(&self, version: &Tag) -> Vec<syn::Meta> {
+        let conditional = self
+            .conditionals()
+            .filter(|attr| attr.req.matches(version))
+            .filter_map(|attr| syn::parse2(attr.tokens.clone()).ok());
+
+        self.attrs()
+            .filter_map(|attr| attr.parse_meta().ok())
+            .chain(conditional)
+            .collect()
+    }
+}
+
+/// Where a version of a data-structure falls relative to the versions an inherited field exists
+/// in, which outside of them holds the version it was introduced in (or removed after).
+enum Lifespan<'a> {
+    Before(&'a Tag),
+    Within,
+    After(&'a Tag),
+}
+
+/// The type of a field in a particular version, which for fields marked `#[obake(inherit)]` is
+/// the matching version of the field's versioned type (wrapped in `Option` in the versions it
+/// doesn't exist in).
+fn expand_ty_versioned(
+    attrs: &VersionedAttributes,
+    ty: &syn::Type,
+    version: &Tag,
+) -> Result<TokenStream2> {
+    if attrs.inherits().next().is_none() {
+        let bound = (attrs.sinces().next().map(|attr| (attr.span, "since(...)")))
+            .or_else(|| attrs.untils().next().map(|attr| (attr.span, "until(...)")));
+        if let Some((span, name)) = bound {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "`#[obake({name})]` can only be applied to fields marked `#[obake(inherit)]`"
+                ),
+            ));
+        }
+
+        return Ok(quote!(#ty));
+    }
+
+    let (version, optional) = match attrs.lifespan(version) {
+        Lifespan::Before(version) | Lifespan::After(version) => (version, true),
+        Lifespan::Within => (version, false),
+    };
+
+    let mut ty = ty;
+    while let syn::Type::Paren(syn::TypeParen { elem, .. })
+    | syn::Type::Group(syn::TypeGroup { elem, .. }) = ty
+    {
+        ty = elem;
+    }
+
+    let message = match ty {
+        syn::Type::Path(ty_path) if ty_path.qself.is_some() => {
+            "`#[obake(inherit)]` cannot be applied to fields with qualified path types, name \
+             the `#[obake::versioned]` type directly (e.g., `field: Foo`)"
+        }
+        syn::Type::Path(ty_path) => match ty_path.path.segments.last() {
+            Some(terminator) if terminator.arguments.is_empty() => {
+                let mut ty_path = ty_path.clone();
+                let terminator = ty_path.path.segments.last_mut().unwrap();
+                terminator.ident = terminator.ident.version(version);
+                return Ok(if optional {
+                    quote!(::core::option::Option<#ty_path>)
+                } else {
+                    quote!(#ty_path)
+                });
+            }
+            Some(_) => {
+                "`#[obake(inherit)]` cannot be applied to fields with generic types, as \
+                 `#[obake::versioned]` types can't be generic (e.g., use `field: Foo` rather \
+                 than `field: Option<Foo>`)"
+            }
+            None => unreachable!("paths have at least one segment"),
+        },
+        syn::Type::Array(_) | syn::Type::Slice(_) => {
+            "`#[obake(inherit)]` cannot be applied to fields with array or slice types, \
+             declare a `#[obake::versioned]` type wrapping the elements and inherit that \
+             instead (e.g., `field: Foos`)"
+        }
+        syn::Type::Reference(_) | syn::Type::Ptr(_) => {
+            "`#[obake(inherit)]` cannot be applied to fields with reference or pointer types, \
+             as versioned fields must be owned (e.g., use `field: Foo` rather than \
+             `field: &Foo`)"
+        }
+        syn::Type::TraitObject(_) | syn::Type::ImplTrait(_) => {
+            "`#[obake(inherit)]` cannot be applied to fields with trait types, only to fields \
+             with `#[obake::versioned]` types (e.g., `field: Foo`)"
+        }
+        _ => {
+            "`#[obake(inherit)]` can only be applied to fields with `#[obake::versioned]` \
+             types (e.g., `field: Foo`)"
+        }
+    };
+
+    Err(syn::Error::new_spanned(ty, message))
+}
+
+impl VersionedField {
+    fn expand_ty_versioned(&self, version: &Tag) -> Result<TokenStream2> {
+        expand_ty_versioned(&self.attrs, &self.ty, version)
+    }
+
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
+        self.attrs.check_item_only()?;
+
         // If we can't find a matching `#[obake(cfg(...))]` attribute, this field is disabled
         // in this version, so return nothing
-        if !reqs.iter().any(|req| req.matches(version)) {
+        if !self.attrs.is_enabled(version) {
             return Ok(quote!());
         }
 
         let attrs = self.attrs.attrs();
+        let conditional_attrs = self.attrs.expand_conditional(version);
+        #[cfg(feature = "serde")]
+        let conditional_attrs = {
+            let external = self.attrs.expand_external(version)?;
+            quote!(#conditional_attrs #external)
+        };
         let vis = &self.vis;
         let ident = &self.ident;
         let colon_token = &self.colon_token;
         let ty = self.expand_ty_versioned(version)?;
+        #[cfg(feature = "zeroize")]
+        let ty = self.attrs.expand_secret(ty)?;
 
         Ok(quote! {
             #(#attrs)*
+            #conditional_attrs
             #vis #ident #colon_token #ty,
         })
     }
 }
 
 impl VersionedFields {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
         let fields = self
             .fields
             .iter()
@@ -108,10 +426,76 @@ impl VersionedFields {
     }
 }
 
+impl VersionedUnnamedField {
+    fn expand_ty_versioned(&self, version: &Tag) -> Result<TokenStream2> {
+        expand_ty_versioned(&self.attrs, &self.ty, version)
+    }
+
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
+        self.attrs.check_item_only()?;
+
+        // Removing an unnamed field would shift the positions of those following it
+        if let Some(cfg) = self.attrs.cfgs().next() {
+            return Err(syn::Error::new(
+                cfg.span,
+                "`#[obake(cfg(...))]` cannot be applied to unnamed fields, version the variant \
+                 instead",
+            ));
+        }
+
+        if let Some(skip) = self.attrs.skips().next() {
+            return Err(syn::Error::new(
+                skip.span,
+                "`#[obake(skip)]` not valid in this context",
+            ));
+        }
+
+        if let Some(getter) = self.attrs.getters().next() {
+            return Err(syn::Error::new(
+                getter.span,
+                "`#[obake(getter)]` not valid in this context",
+            ));
+        }
+
+        let attrs = self.attrs.attrs();
+        let conditional_attrs = self.attrs.expand_conditional(version);
+        #[cfg(feature = "serde")]
+        let conditional_attrs = {
+            let external = self.attrs.expand_external(version)?;
+            quote!(#conditional_attrs #external)
+        };
+        let vis = &self.vis;
+        let ty = self.expand_ty_versioned(version)?;
+        #[cfg(feature = "zeroize")]
+        let ty = self.attrs.expand_secret(ty)?;
+
+        Ok(quote! {
+            #(#attrs)*
+            #conditional_attrs
+            #vis #ty,
+        })
+    }
+}
+
+impl VersionedUnnamedFields {
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| field.expand_version(version))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+
+        Ok(quote!((
+            #(#fields)*
+        )))
+    }
+}
+
 impl VersionedVariantFields {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
         Ok(match &self {
-            Self::Unnamed(unnamed) => quote!(#unnamed),
+            Self::Unnamed(unnamed) => unnamed.expand_version(version)?,
             Self::Named(named) => {
                 let fields = named.expand_version(version)?;
                 quote!(#fields)
@@ -122,7 +506,7 @@ impl VersionedVariantFields {
 }
 
 impl VersionedVariant {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
         if let Some(derive) = self.attrs.inherits().next() {
             return Err(syn::Error::new(
                 derive.span,
@@ -130,47 +514,80 @@ impl VersionedVariant {
             ));
         }
 
-        if let Some(derive) = self.attrs.derives().next() {
+        if let Some(migrate_default) = self.attrs.migrate_defaults().next() {
             return Err(syn::Error::new(
-                derive.span,
-                "`#[obake(derive(...))]` not valid in this context",
+                migrate_default.span,
+                "`#[obake(migrate_default = ...)]` not valid in this context",
             ));
         }
 
-        #[cfg(feature = "serde")]
-        if let Some(serde) = self.attrs.serdes().next() {
+        if let Some(skip) = self.attrs.skips().next() {
             return Err(syn::Error::new(
-                serde.span,
-                "`#[obake(serde(...))]` not valid in this context",
+                skip.span,
+                "`#[obake(skip)]` not valid in this context",
             ));
         }
 
-        let mut reqs: Vec<_> = self.attrs.cfgs().map(|attr| attr.req.clone()).collect();
+        if let Some(since) = self.attrs.sinces().next() {
+            return Err(syn::Error::new(
+                since.span,
+                "`#[obake(since(...))]` not valid in this context",
+            ));
+        }
 
-        // If we have no `#[obake(cfg(...))]` attributes, default to `#[obake(cfg("*"))]`
-        if reqs.is_empty() {
-            reqs.push(VersionReq::STAR);
+        if let Some(until) = self.attrs.untils().next() {
+            return Err(syn::Error::new(
+                until.span,
+                "`#[obake(until(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(getter) = self.attrs.getters().next() {
+            return Err(syn::Error::new(
+                getter.span,
+                "`#[obake(getter)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "zeroize")]
+        if let Some(secret) = self.attrs.secrets().next() {
+            return Err(syn::Error::new(
+                secret.span,
+                "`#[obake(secret)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "serde")]
+        if let Some(external) = self.attrs.externals().next() {
+            return Err(syn::Error::new(
+                external.span,
+                "`#[obake(external(...))]` not valid in this context",
+            ));
         }
 
+        self.attrs.check_item_only()?;
+
         // If we can't find a matching `#[obake(cfg(...))]` variant, this field is disabled
         // in this version, so return nothing
-        if !reqs.iter().any(|req| req.matches(version)) {
+        if !self.attrs.is_enabled(version) {
             return Ok(quote!());
         }
 
         let attrs = self.attrs.attrs();
+        let conditional_attrs = self.attrs.expand_conditional(version);
         let ident = &self.ident;
         let fields = self.fields.expand_version(version)?;
 
         Ok(quote! {
             #(#attrs)*
+            #conditional_attrs
             #ident #fields,
         })
     }
 }
 
 impl VersionedVariants {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
         let variants = self
             .variants
             .iter()
@@ -204,10 +621,69 @@ impl VersionedItem {
             }
         }
 
+        // Versions differing only in build metadata would be given the same name, and build
+        // metadata shouldn't distinguish versions anyway, so these are duplicates too. They
+        // needn't be adjacent once sorted (e.g., `0.1.0+a`, `0.1.0+a.b` and `0.1.0+b`), but do
+        // share a precedence.
+        for (i, head) in versions.iter().enumerate() {
+            let duplicate = versions[..i]
+                .iter()
+                .find(|prev| prev.version.cmp_precedence(&head.version).is_eq());
+
+            if let Some(prev) = duplicate {
+                return Err(syn::Error::new(
+                    head.span,
+                    format!(
+                        "duplicate definition of version {}, as it differs from version {} \
+                         only in build metadata, which is ignored when ordering versions",
+                        head.version, prev.version,
+                    ),
+                ));
+            }
+        }
+
         Ok(versions)
     }
 
+    /// Rename the item deriving `obake::Obake` after its `#[obake(name = ...)]` attribute, so that
+    /// it can be expanded in the same way as an item marked with `#[obake::versioned]`.
+    ///
+    /// The derive can't replace the item it's applied to, so the generated data-structure must
+    /// be given a different name.
+    pub fn into_derived(mut self) -> Result<Self> {
+        let name = match self.attrs.names().next() {
+            Some(name) if name.ident == *self.ident() => {
+                return Err(syn::Error::new(
+                    name.ident.span(),
+                    "`#[obake(name = ...)]` must differ from the name of the definition",
+                ));
+            }
+            Some(name) => name.ident.clone(),
+            None => {
+                return Err(syn::Error::new(
+                    self.ident().span(),
+                    "`#[derive(obake::Obake)]` requires an `#[obake(name = ...)]` attribute naming \
+                     the generated data-structure",
+                ));
+            }
+        };
+
+        *self.ident_mut() = name;
+        self.attrs
+            .attrs
+            .retain(|attr| !matches!(attr, VersionedAttribute::Obake(ObakeAttribute::Name(_))));
+
+        Ok(self)
+    }
+
     fn check_preconditions(&self) -> Result<()> {
+        if let Some(name) = self.attrs.names().next() {
+            return Err(syn::Error::new(
+                name.span,
+                "`#[obake(name = ...)]` is only valid with `#[derive(obake::Obake)]`",
+            ));
+        }
+
         if let Some(inherit) = self.attrs.inherits().next() {
             return Err(syn::Error::new(
                 inherit.span,
@@ -222,6 +698,57 @@ impl VersionedItem {
             ));
         }
 
+        if let Some(migrate_default) = self.attrs.migrate_defaults().next() {
+            return Err(syn::Error::new(
+                migrate_default.span,
+                "`#[obake(migrate_default = ...)]` not valid in this context",
+            ));
+        }
+
+        if let Some(skip) = self.attrs.skips().next() {
+            return Err(syn::Error::new(
+                skip.span,
+                "`#[obake(skip)]` not valid in this context",
+            ));
+        }
+
+        if let Some(since) = self.attrs.sinces().next() {
+            return Err(syn::Error::new(
+                since.span,
+                "`#[obake(since(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(until) = self.attrs.untils().next() {
+            return Err(syn::Error::new(
+                until.span,
+                "`#[obake(until(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(getter) = self.attrs.getters().next() {
+            return Err(syn::Error::new(
+                getter.span,
+                "`#[obake(getter)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "zeroize")]
+        if let Some(secret) = self.attrs.secrets().next() {
+            return Err(syn::Error::new(
+                secret.span,
+                "`#[obake(secret)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "serde")]
+        if let Some(external) = self.attrs.externals().next() {
+            return Err(syn::Error::new(
+                external.span,
+                "`#[obake(external(...))]` not valid in this context",
+            ));
+        }
+
         if self.attrs.versions().next().is_none() {
             return Err(syn::Error::new(
                 self.keyword_span(),
@@ -232,222 +759,5276 @@ impl VersionedItem {
         Ok(())
     }
 
-    fn alias(&self) -> Option<syn::Ident> {
-        self.attrs
-            .versions()
-            .last()
-            .map(|attr| self.ident().version(&attr.version))
-    }
+    fn check_repr_c(&self, versions: &[VersionAttr]) -> Result<()> {
+        if !self.is_repr_c() {
+            return Ok(());
+        }
 
-    fn versioned_ident(&self) -> syn::Ident {
-        format_ident!("Versioned{}", self.ident())
-    }
+        for i in 0..versions.len() {
+            let attr = &versions[i];
+            let tag = repr_c_tag(&attr.version).ok_or_else(|| {
+                syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {} has no stable tag under `#[obake(repr_c)]` (pre-release \
+                         versions and components exceeding {}.{}.{} are not supported)",
+                        attr.version,
+                        (1 << 12) - 1,
+                        (1 << 10) - 1,
+                        (1 << 10) - 1,
+                    ),
+                )
+            })?;
 
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        let current = self.ident();
-        let version_str = &version.to_string();
-        let attrs = self.attrs.attrs();
-        let vis = &self.vis;
-        let ident = self.ident().version(version);
-        let body = match &self.kind {
-            VersionedItemKind::Struct(inner) => {
-                let struct_token = &inner.struct_token;
-                let fields = inner.fields.expand_version(version)?;
-                quote!(#struct_token #ident #fields)
-            }
-            VersionedItemKind::Enum(inner) => {
-                let enum_token = &inner.enum_token;
-                let variants = inner.variants.expand_version(version)?;
-                quote!(#enum_token #ident #variants)
+            // Versions differing only in build metadata share a tag
+            if versions[..i]
+                .iter()
+                .any(|prev| repr_c_tag(&prev.version) == Some(tag))
+            {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {} has the same `#[obake(repr_c)]` tag as an earlier version",
+                        attr.version
+                    ),
+                ));
             }
-        };
-        let versioned_ident = self.versioned_ident();
+        }
 
-        Ok(quote! {
-            #[doc(hidden)]
-            #[allow(non_camel_case_types)]
-            #(#attrs)*
-            #vis #body
+        Ok(())
+    }
 
-            #[automatically_derived]
-            impl ::obake::VersionOf<#current> for #ident {
-                const VERSION: &'static str = #version_str;
+    fn check_tag(&self, versions: &[VersionAttr]) -> Result<()> {
+        let Some(tag) = self.attrs.tags().next() else {
+            return Ok(());
+        };
 
-                #[inline]
-                fn try_from_versioned(
-                    from: ::obake::AnyVersion<#current>,
-                ) -> ::core::result::Result<Self, ::obake::VersionMismatch> {
-                    use ::obake::VersionTagged;
-                    match from {
-                        ::obake::AnyVersion::<#current>::#ident(x) => ::core::result::Result::Ok(x),
-                        other => ::core::result::Result::Err(::obake::VersionMismatch {
-                            expected: Self::VERSION,
-                            found: other.version_str(),
-                        }),
-                    }
-                }
-            }
+        if self.is_repr_c() {
+            return Err(syn::Error::new(
+                tag.span,
+                "`#[obake(tag = ...)]` cannot be combined with `#[obake(repr_c)]`",
+            ));
+        }
 
-            #[automatically_derived]
-            impl ::core::convert::From<#ident> for #versioned_ident {
-                #[inline]
-                fn from(from: #ident) -> #versioned_ident {
-                    #versioned_ident::#ident(from)
+        let bits = match tag.ty.to_string().as_str() {
+            "u8" => 8,
+            "u16" => 16,
+            _ => 32,
+        };
+        if let Some(attr) = versions.get(1 << bits) {
+            return Err(syn::Error::new(
+                attr.span,
+                format!(
+                    "version {} has no tag under `#[obake(tag = {})]` (at most {} versions are \
+                     supported)",
+                    attr.version,
+                    tag.ty,
+                    1_u64 << bits,
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that each `#[obake(released(...))]` names a declared version (at most once), and
+    /// that newer versions weren't released before older ones.
+    fn check_fallible(&self, versions: &[VersionAttr]) -> Result<()> {
+        let mut fallibles = self.attrs.fallibles();
+        let Some(fallible) = fallibles.next() else {
+            return Ok(());
+        };
+
+        if let Some(duplicate) = fallibles.next() {
+            return Err(syn::Error::new(
+                duplicate.span,
+                "duplicate `#[obake(fallible(...))]`",
+            ));
+        }
+
+        // Dense migrations are composed of the `From` implementations between versions
+        if let Some(dense_migrations) = self.attrs.dense_migrations().next() {
+            return Err(syn::Error::new(
+                dense_migrations.span,
+                "`#[obake(dense_migrations)]` cannot be combined with `#[obake(fallible(...))]`",
+            ));
+        }
+
+        // The `extern "C"` upgrade entry point has no way to report a failing migration
+        if self.is_repr_c() {
+            return Err(syn::Error::new(
+                fallible.span,
+                "`#[obake(fallible(...))]` cannot be combined with `#[obake(repr_c)]`",
+            ));
+        }
+
+        let mut steps = versions[1..]
+            .iter()
+            .filter(|attr| fallible.req.matches(&attr.version));
+        if steps.clone().next().is_none() {
+            return Err(syn::Error::new(
+                fallible.span,
+                "`#[obake(fallible(...))]` matches no migration between declared versions",
+            ));
+        }
+
+        let auto_migrated = steps.find(|attr| {
+            self.attrs
+                .auto_migrates()
+                .any(|auto_migrate| auto_migrate.req.matches(&attr.version))
+        });
+        if let Some(attr) = auto_migrated {
+            return Err(syn::Error::new(
+                fallible.span,
+                format!(
+                    "the migration to version {} cannot be both fallible and generated by \
+                     `#[obake(auto_migrate)]`",
+                    attr.version
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_releases(&self, versions: &[VersionAttr]) -> Result<()> {
+        let mut releases: Vec<_> = self.attrs.releaseds().collect();
+        for (i, released) in releases.iter().enumerate() {
+            if !versions.contains(&released.version) {
+                return Err(syn::Error::new(
+                    released.version.span,
+                    format!(
+                        "release date for undeclared version {}",
+                        released.version.version
+                    ),
+                ));
+            }
+
+            if releases[..i]
+                .iter()
+                .any(|prev| prev.version == released.version)
+            {
+                return Err(syn::Error::new(
+                    released.span,
+                    format!(
+                        "duplicate release date for version {}",
+                        released.version.version
+                    ),
+                ));
+            }
+        }
+
+        releases.sort_by(|a, b| a.version.version.cmp(&b.version.version));
+        for pair in releases.windows(2) {
+            if pair[1].date.value() < pair[0].date.value() {
+                return Err(syn::Error::new(
+                    pair[1].date.span(),
+                    format!(
+                        "version {} is released before the older version {} (on {})",
+                        pair[1].version.version,
+                        pair[0].version.version,
+                        pair[0].date.value(),
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the versions named by `#[obake(since(...))]` and `#[obake(until(...))]` have
+    /// been declared, and that they leave each field a version to exist in.
+    fn check_lifespans(&self, versions: &[VersionAttr]) -> Result<()> {
+        let fields: Vec<&VersionedAttributes> = match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .map(|field| &field.attrs)
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .flat_map(|variant| match &variant.fields {
+                    VersionedVariantFields::Named(named) => {
+                        named.fields.iter().map(|field| &field.attrs).collect()
+                    }
+                    VersionedVariantFields::Unnamed(unnamed) => {
+                        unnamed.fields.iter().map(|field| &field.attrs).collect()
+                    }
+                    VersionedVariantFields::Unit => Vec::new(),
+                })
+                .collect(),
+        };
+
+        for attrs in fields {
+            let duplicate = (attrs.sinces().nth(1).map(|attr| (attr.span, "since(...)")))
+                .or_else(|| attrs.untils().nth(1).map(|attr| (attr.span, "until(...)")));
+            if let Some((span, name)) = duplicate {
+                return Err(syn::Error::new(
+                    span,
+                    format!("duplicate `#[obake({name})]` attribute"),
+                ));
+            }
+
+            let bounds = (attrs.sinces().map(|attr| (&attr.version, "since(...)")))
+                .chain(attrs.untils().map(|attr| (&attr.version, "until(...)")));
+            for (version, name) in bounds {
+                if !versions.contains(version) {
+                    return Err(syn::Error::new(
+                        version.span,
+                        format!(
+                            "`#[obake({name})]` names undeclared version {}",
+                            version.version
+                        ),
+                    ));
+                }
+            }
+
+            if let (Some(since), Some(until)) = (attrs.sinces().next(), attrs.untils().next()) {
+                if since.version.version > until.version.version {
+                    return Err(syn::Error::new(
+                        until.span,
+                        format!(
+                            "`#[obake(until(...))]` names version {}, which precedes version {} \
+                             named by `#[obake(since(...))]`",
+                            until.version.version, since.version.version,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_notes(&self, versions: &[VersionAttr]) -> Result<()> {
+        for note in self.attrs.notes() {
+            if !versions.contains(&note.version) {
+                return Err(syn::Error::new(
+                    note.version.span,
+                    format!("note for undeclared version {}", note.version.version),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that each `#[obake(deprecated(...))]` names a declared version other than the latest
+    /// (at most once).
+    fn check_deprecated(&self, versions: &[VersionAttr]) -> Result<()> {
+        let deprecated: Vec<_> = self.attrs.deprecateds().collect();
+        for (i, attr) in deprecated.iter().enumerate() {
+            if !versions.contains(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!("deprecation of undeclared version {}", attr.version.version),
+                ));
+            }
+
+            if versions.last() == Some(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!(
+                        "the latest version {} cannot be deprecated",
+                        attr.version.version
+                    ),
+                ));
+            }
+
+            if deprecated[..i]
+                .iter()
+                .any(|prev| prev.version == attr.version)
+            {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {} is deprecated more than once",
+                        attr.version.version
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that each `#[obake(stable(...))]` names a declared version other than the latest
+    /// (which is always stable), which isn't also deprecated (at most once).
+    fn check_stables(&self, versions: &[VersionAttr]) -> Result<()> {
+        let stables: Vec<_> = self.attrs.stables().collect();
+        for (i, attr) in stables.iter().enumerate() {
+            if !versions.contains(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!(
+                        "undeclared version {} cannot be marked stable",
+                        attr.version.version
+                    ),
+                ));
+            }
+
+            if versions.last() == Some(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!(
+                        "the latest version {} is always stable",
+                        attr.version.version
+                    ),
+                ));
+            }
+
+            if self
+                .attrs
+                .deprecateds()
+                .any(|deprecated| deprecated.version == attr.version)
+            {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!(
+                        "version {} cannot be both stable and deprecated",
+                        attr.version.version
+                    ),
+                ));
+            }
+
+            if stables[..i].iter().any(|prev| prev.version == attr.version) {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {} is marked stable more than once",
+                        attr.version.version
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that each `#[obake(version_default(...))]` names a declared version (at most once).
+    fn check_version_defaults(&self, versions: &[VersionAttr]) -> Result<()> {
+        let defaults: Vec<_> = self.attrs.version_defaults().collect();
+        for (i, attr) in defaults.iter().enumerate() {
+            if !versions.contains(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!("default for undeclared version {}", attr.version.version),
+                ));
+            }
+
+            if defaults[..i]
+                .iter()
+                .any(|prev| prev.version == attr.version)
+            {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {} is given a default more than once",
+                        attr.version.version
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn check_test_values(&self, versions: &[VersionAttr]) -> Result<()> {
+        let test_values: Vec<_> = self.attrs.test_valueses().collect();
+        for (i, attr) in test_values.iter().enumerate() {
+            if !versions.contains(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!(
+                        "test values for undeclared version {}",
+                        attr.version.version
+                    ),
+                ));
+            }
+
+            if test_values[..i]
+                .iter()
+                .any(|prev| prev.version == attr.version)
+            {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {} is given test values more than once",
+                        attr.version.version
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// With `#[obake(test_values(...))]`, implement `TestValues`, gathering the values of each
+    /// version given them, and generate a test migrating each of them to the latest version.
+    #[cfg(feature = "alloc")]
+    fn expand_test_values_impl(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        if self.attrs.test_valueses().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let extends = versions.iter().zip(definitions).filter_map(|(version, definition)| {
+            let attr = self
+                .attrs
+                .test_valueses()
+                .find(|attr| attr.version == *version)?;
+            let gate = self.expand_cfg_when(&version.version);
+            let path = &attr.path;
+
+            // Values are annotated with their version, so that values of the wrong version are
+            // reported on the function returning them
+            Some(quote_spanned! {path.span()=>
+                #gate
+                values.extend(
+                    ::core::iter::IntoIterator::into_iter(#path())
+                        .map(|value: #definition| -> ::obake::AnyVersion<#ident> { value.into() }),
+                );
+            })
+        });
+        // The test is nested in a module named after the data-structure, so that tests of several
+        // data-structures in one module don't collide and none is compiled outside of tests
+        let module = format_ident!("{}_test_values", snake_case(&ident.unraw().to_string()));
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::TestValues for #ident {
+                fn test_values() -> ::obake::alloc::vec::Vec<::obake::AnyVersion<Self>> {
+                    let mut values = ::obake::alloc::vec::Vec::new();
+                    #(#extends)*
+                    values
+                }
+            }
+
+            #[cfg(test)]
+            mod #module {
+                #[test]
+                fn migrate() {
+                    ::obake::testing::assert_migrate::<super::#ident>();
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(test_values(...))]`, a section of the documentation of a version naming the
+    /// function giving its example values.
+    #[cfg(feature = "alloc")]
+    fn expand_test_values_doc(&self, version: &Tag) -> TokenStream2 {
+        let Some(attr) = self
+            .attrs
+            .test_valueses()
+            .find(|attr| attr.version.version == *version)
+        else {
+            return quote!();
+        };
+
+        let path = lockfile::tidy(&attr.path.to_token_stream().to_string());
+        let lines = [
+            String::new(),
+            "## Examples".to_owned(),
+            String::new(),
+            format!("Example values of this version are given by [`{path}`]."),
+        ];
+
+        quote!(#(#[doc = #lines])*)
+    }
+
+    /// Check that each version named by an `#[obake(assert_fields(...))]` has exactly the fields
+    /// (or variants) listed, guarding historical versions against accidental changes.
+    fn check_assert_fields(&self, versions: &[VersionAttr]) -> Result<()> {
+        let kind = match self.kind {
+            VersionedItemKind::Struct(_) => "field",
+            VersionedItemKind::Enum(_) => "variant",
+        };
+
+        for attr in self.attrs.assert_fieldses() {
+            if !versions.contains(&attr.version) {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!(
+                        "assertion of the {}s of undeclared version {}",
+                        kind, attr.version.version
+                    ),
+                ));
+            }
+
+            let version = &attr.version.version;
+            let present: Vec<_> = self
+                .members()
+                .into_iter()
+                .filter(|(_, attrs)| attrs.is_enabled(version))
+                .map(|(ident, _)| ident.unraw())
+                .collect();
+            let listed: Vec<_> = attr.fields.iter().map(syn::Ident::unraw).collect();
+
+            let mut errors = Vec::new();
+            for (i, ident) in listed.iter().enumerate() {
+                if listed[..i].contains(ident) {
+                    errors.push(syn::Error::new(
+                        ident.span(),
+                        format!("{kind} `{ident}` is listed more than once"),
+                    ));
+                } else if !present.contains(ident) {
+                    errors.push(syn::Error::new(
+                        ident.span(),
+                        format!("version {version} has no {kind} `{ident}`"),
+                    ));
+                }
+            }
+            for ident in present.iter().filter(|ident| !listed.contains(ident)) {
+                errors.push(syn::Error::new(
+                    attr.version.span,
+                    format!("version {version} has the unlisted {kind} `{ident}`"),
+                ));
+            }
+
+            let mut errors = errors.into_iter();
+            if let Some(mut error) = errors.next() {
+                for other in errors {
+                    error.combine(other);
+                }
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that nothing generated for an item marked `#[obake(no_alloc)]` requires `alloc`.
+    fn check_no_alloc(&self) -> Result<()> {
+        let Some(no_alloc) = self.attrs.no_allocs().next() else {
+            return Ok(());
+        };
+
+        let unsupported: Option<&str> = self.attrs.obake().find_map(|attr| match attr {
+            #[cfg(feature = "alloc")]
+            ObakeAttribute::Boxed(_) => Some("boxed"),
+            #[cfg(feature = "alloc")]
+            ObakeAttribute::TestValues(_) => Some("test_values(...)"),
+            #[cfg(feature = "json")]
+            ObakeAttribute::Display(_) => Some("display"),
+            #[cfg(feature = "json")]
+            ObakeAttribute::JsonUpgrade(_) => Some("json_upgrade"),
+            #[cfg(feature = "wasm")]
+            ObakeAttribute::Wasm(_) => Some("wasm"),
+            #[cfg(feature = "ts-rs")]
+            ObakeAttribute::Ts(_) => Some("ts"),
+            #[cfg(feature = "specta")]
+            ObakeAttribute::Specta(_) => Some("specta"),
+            #[cfg(feature = "pyo3")]
+            ObakeAttribute::Pyo3(_) => Some("pyo3"),
+            #[cfg(feature = "abi_stable")]
+            ObakeAttribute::AbiStable(_) => Some("abi_stable"),
+            #[cfg(feature = "uniffi")]
+            ObakeAttribute::Uniffi(_) => Some("uniffi"),
+            #[cfg(feature = "utoipa")]
+            ObakeAttribute::Utoipa(_) => Some("utoipa"),
+            #[cfg(feature = "async-graphql")]
+            ObakeAttribute::AsyncGraphql(_) => Some("async_graphql"),
+            #[cfg(feature = "tabular")]
+            ObakeAttribute::Tabular(_) => Some("tabular"),
+            #[cfg(feature = "idl")]
+            ObakeAttribute::Idl(_) => Some("idl"),
+            #[cfg(feature = "fake")]
+            ObakeAttribute::Fake(_) => Some("fake"),
+            #[cfg(feature = "registry")]
+            ObakeAttribute::Register(_) => Some("register"),
+            _ => None,
+        });
+        if let Some(name) = unsupported {
+            return Err(syn::Error::new(
+                no_alloc.span,
+                format!(
+                    "`#[obake(no_alloc)]` cannot be combined with `#[obake({name})]`, which \
+                     requires `alloc`"
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that `#[obake(cfg_when(...))]` only excludes the oldest versions, so that every
+    /// version compiled can still be migrated to the latest.
+    fn check_cfg_when(&self, versions: &[VersionAttr]) -> Result<()> {
+        let mut cfg_whens = self.attrs.cfg_whens();
+        let Some(cfg_when) = cfg_whens.next() else {
+            return Ok(());
+        };
+
+        if let Some(duplicate) = cfg_whens.next() {
+            return Err(syn::Error::new(
+                duplicate.span,
+                "duplicate `#[obake(cfg_when(...))]` attribute, combine the conditions into one \
+                 predicate (e.g., with `all(...)`)",
+            ));
+        }
+
+        let unsupported: Option<&str> = self.attrs.obake().find_map(|attr| match attr {
+            ObakeAttribute::ReprC(_) => Some("repr_c"),
+            ObakeAttribute::AliasIdentical(_) => Some("alias_identical"),
+            ObakeAttribute::Tag(_) => Some("tag = ..."),
+            #[cfg(feature = "wasm")]
+            ObakeAttribute::Wasm(_) => Some("wasm"),
+            #[cfg(feature = "ts-rs")]
+            ObakeAttribute::Ts(_) => Some("ts"),
+            #[cfg(feature = "specta")]
+            ObakeAttribute::Specta(_) => Some("specta"),
+            #[cfg(feature = "pyo3")]
+            ObakeAttribute::Pyo3(_) => Some("pyo3"),
+            #[cfg(feature = "abi_stable")]
+            ObakeAttribute::AbiStable(_) => Some("abi_stable"),
+            #[cfg(feature = "uniffi")]
+            ObakeAttribute::Uniffi(_) => Some("uniffi"),
+            #[cfg(feature = "utoipa")]
+            ObakeAttribute::Utoipa(_) => Some("utoipa"),
+            #[cfg(feature = "async-graphql")]
+            ObakeAttribute::AsyncGraphql(_) => Some("async_graphql"),
+            #[cfg(feature = "tabular")]
+            ObakeAttribute::Tabular(_) => Some("tabular"),
+            #[cfg(feature = "idl")]
+            ObakeAttribute::Idl(_) => Some("idl"),
+            _ => None,
+        });
+        if let Some(name) = unsupported {
+            return Err(syn::Error::new(
+                cfg_when.span,
+                format!("`#[obake(cfg_when(...))]` cannot be combined with `#[obake({name})]`"),
+            ));
+        }
+
+        if let Some(latest) = versions
+            .last()
+            .filter(|attr| cfg_when.req.matches(&attr.version))
+        {
+            return Err(syn::Error::new(
+                cfg_when.span,
+                format!(
+                    "`#[obake(cfg_when(...))]` cannot exclude the latest version ({})",
+                    latest.version,
+                ),
+            ));
+        }
+
+        if let Some(pair) = versions.windows(2).find(|pair| {
+            !cfg_when.req.matches(&pair[0].version) && cfg_when.req.matches(&pair[1].version)
+        }) {
+            return Err(syn::Error::new(
+                cfg_when.span,
+                format!(
+                    "`#[obake(cfg_when(...))]` can only exclude the oldest versions, but excludes \
+                     version {} and not the older version {}",
+                    pair[1].version, pair[0].version,
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that `#[obake(migrate_default = ...)]` is only applied alongside
+    /// `#[obake(auto_migrate)]`, and that every variant of an `enum` can be migrated by it.
+    fn check_auto_migrate(&self, versions: &[VersionAttr]) -> Result<()> {
+        let auto_migrate = self.attrs.auto_migrates().next();
+
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => {
+                let migrate_default = inner
+                    .fields
+                    .fields
+                    .iter()
+                    .find_map(|field| field.attrs.migrate_defaults().next());
+
+                if let (None, Some(migrate_default)) = (auto_migrate, migrate_default) {
+                    return Err(syn::Error::new(
+                        migrate_default.span,
+                        "`#[obake(migrate_default = ...)]` requires `#[obake(auto_migrate)]`",
+                    ));
+                }
+            }
+            VersionedItemKind::Enum(inner) => {
+                if let Some(auto_migrate) = auto_migrate {
+                    check_enum_auto_migrate(&inner.variants, auto_migrate, versions)?;
+                }
+
+                let migrate_default = inner
+                    .variants
+                    .variants
+                    .iter()
+                    .flat_map(|variant| match &variant.fields {
+                        VersionedVariantFields::Named(named) => named
+                            .fields
+                            .iter()
+                            .flat_map(|field| field.attrs.migrate_defaults())
+                            .collect(),
+                        VersionedVariantFields::Unnamed(unnamed) => unnamed
+                            .fields
+                            .iter()
+                            .flat_map(|field| field.attrs.migrate_defaults())
+                            .collect(),
+                        VersionedVariantFields::Unit => Vec::new(),
+                    })
+                    .next();
+
+                if let Some(migrate_default) = migrate_default {
+                    return Err(syn::Error::new(
+                        migrate_default.span,
+                        "`#[obake(migrate_default = ...)]` can only be applied to fields of \
+                         `struct`s",
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The fields of a `struct` marked `#[obake(skip)]`.
+    fn skipped_fields(&self) -> Vec<&VersionedField> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.skips().next().is_some())
+                .collect(),
+            VersionedItemKind::Enum(_) => Vec::new(),
+        }
+    }
+
+    /// Check that `#[obake(skip)]` is only applied to otherwise unversioned fields of `struct`s
+    /// whose latest version needn't be the type named after them.
+    fn check_skip(&self) -> Result<()> {
+        if let VersionedItemKind::Enum(inner) = &self.kind {
+            let skip = inner
+                .variants
+                .variants
+                .iter()
+                .filter_map(|variant| match &variant.fields {
+                    VersionedVariantFields::Named(named) => Some(named),
+                    _ => None,
+                })
+                .flat_map(|named| &named.fields)
+                .find_map(|field| field.attrs.skips().next());
+
+            if let Some(skip) = skip {
+                return Err(syn::Error::new(
+                    skip.span,
+                    "`#[obake(skip)]` can only be applied to fields of `struct`s",
+                ));
+            }
+        }
+
+        let Some(skip) = self.skipped_fields().first().map(|field| &field.attrs) else {
+            return Ok(());
+        };
+
+        for field in self.skipped_fields() {
+            let versioned = field.attrs.obake().find_map(|attr| match attr {
+                ObakeAttribute::Cfg(_) => Some("cfg(...)"),
+                ObakeAttribute::Conditional(_) => Some("cfg_attr(...)"),
+                ObakeAttribute::Inherit(_) => Some("inherit"),
+                ObakeAttribute::MigrateDefault(_) => Some("migrate_default = ..."),
+                ObakeAttribute::Since(_) => Some("since(...)"),
+                ObakeAttribute::Until(_) => Some("until(...)"),
+                ObakeAttribute::Getter(_) => Some("getter"),
+                #[cfg(feature = "zeroize")]
+                ObakeAttribute::Secret(_) => Some("secret"),
+                #[cfg(feature = "serde")]
+                ObakeAttribute::External(_) => Some("external(...)"),
+                _ => None,
+            });
+            if let Some(name) = versioned {
+                return Err(syn::Error::new(
+                    field.attrs.skips().next().unwrap().span,
+                    format!("`#[obake(skip)]` cannot be combined with `#[obake({name})]`"),
+                ));
+            }
+        }
+
+        // These treat the type named after the data-structure as its latest version
+        let unsupported: Option<&str> = if self.is_repr_c() {
+            Some("repr_c")
+        } else {
+            self.attrs.obake().find_map(|attr| match attr {
+                ObakeAttribute::StableHash(_) => Some("stable_hash"),
+                #[cfg(feature = "wasm")]
+                ObakeAttribute::Wasm(_) => Some("wasm"),
+                #[cfg(feature = "ts-rs")]
+                ObakeAttribute::Ts(_) => Some("ts"),
+                #[cfg(feature = "specta")]
+                ObakeAttribute::Specta(_) => Some("specta"),
+                #[cfg(feature = "pyo3")]
+                ObakeAttribute::Pyo3(_) => Some("pyo3"),
+                #[cfg(feature = "uniffi")]
+                ObakeAttribute::Uniffi(_) => Some("uniffi"),
+                #[cfg(feature = "utoipa")]
+                ObakeAttribute::Utoipa(_) => Some("utoipa"),
+                #[cfg(feature = "async-graphql")]
+                ObakeAttribute::AsyncGraphql(_) => Some("async_graphql"),
+                #[cfg(feature = "tabular")]
+                ObakeAttribute::Tabular(_) => Some("tabular"),
+                #[cfg(feature = "idl")]
+                ObakeAttribute::Idl(_) => Some("idl"),
+                _ => None,
+            })
+        };
+        if let Some(name) = unsupported {
+            return Err(syn::Error::new(
+                skip.skips().next().unwrap().span,
+                format!("`#[obake(skip)]` cannot be combined with `#[obake({name})]`"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The fields marked `#[obake(getter)]`.
+    fn getter_fields(&self) -> Vec<&VersionedField> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.getters().next().is_some())
+                .collect(),
+            VersionedItemKind::Enum(_) => Vec::new(),
+        }
+    }
+
+    /// Check that `#[obake(getter)]` is only applied to fields of `struct`s whose type is the
+    /// same in every version, and to at most one declaration of each field.
+    fn check_getters(&self) -> Result<()> {
+        if let VersionedItemKind::Enum(inner) = &self.kind {
+            let getter = inner
+                .variants
+                .variants
+                .iter()
+                .filter_map(|variant| match &variant.fields {
+                    VersionedVariantFields::Named(named) => Some(named),
+                    _ => None,
+                })
+                .flat_map(|named| &named.fields)
+                .find_map(|field| field.attrs.getters().next());
+
+            if let Some(getter) = getter {
+                return Err(syn::Error::new(
+                    getter.span,
+                    "`#[obake(getter)]` can only be applied to fields of `struct`s",
+                ));
+            }
+        }
+
+        let fields = self.getter_fields();
+        for (i, field) in fields.iter().enumerate() {
+            let getter = field.attrs.getters().next().unwrap();
+            if field.attrs.inherits().next().is_some() {
+                return Err(syn::Error::new(
+                    getter.span,
+                    "`#[obake(getter)]` cannot be combined with `#[obake(inherit)]`, as the type \
+                     of an inherited field differs between versions",
+                ));
+            }
+
+            if fields[..i].iter().any(|other| other.ident == field.ident) {
+                return Err(syn::Error::new(
+                    getter.span,
+                    format!(
+                        "`#[obake(getter)]` applied to more than one declaration of `{}`",
+                        field.ident
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_auto_downgrade_subset(&self) -> Result<()> {
+        let Some(auto_downgrade_subset) = self.attrs.auto_downgrade_subsets().next() else {
+            return Ok(());
+        };
+
+        if let VersionedItemKind::Enum(_) = &self.kind {
+            return Err(syn::Error::new(
+                auto_downgrade_subset.span,
+                "`#[obake(auto_downgrade_subset)]` can only be applied to `struct`s",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that the pattern of `#[obake(public_enum(variants = "..."))]` names each variant
+    /// uniquely, and that versions are declared in the order they're held by the enum.
+    fn check_public_enum(&self, versions: &[VersionAttr]) -> Result<()> {
+        let Some(public_enum) = self.attrs.public_enums().next() else {
+            return Ok(());
+        };
+
+        if let Some(variants) = &public_enum.variants {
+            let pattern = variants.value();
+            if !pattern.contains("{version}") {
+                return Err(syn::Error::new(
+                    variants.span(),
+                    "the pattern of `#[obake(public_enum(variants = \"...\"))]` must contain \
+                     `{version}`",
+                ));
+            }
+
+            for attr in versions {
+                let name = pattern.replace("{version}", &attr.version.suffix);
+                if syn::parse_str::<syn::Ident>(&name).is_err() {
+                    return Err(syn::Error::new(
+                        variants.span(),
+                        format!(
+                            "`{name}` is not a valid variant name for version {}",
+                            attr.version
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // The variants are held in the order versions are declared
+        if let Some((attr, _)) = self.attrs.versions().zip(versions).find(|(a, b)| a != b) {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(public_enum)]` requires versions to be declared from oldest to newest",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_scheme(&self) -> Result<()> {
+        if let Some(duplicate) = self.attrs.schemes().nth(1) {
+            return Err(syn::Error::new(
+                duplicate.span,
+                "duplicate `#[obake(scheme = ...)]` attribute",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check the item against the policies declared with `#[obake(rules(...))]`, failing on the
+    /// first violation of a denied rule and warning of each violation of the others.
+    fn expand_rules(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let mut warnings = Vec::new();
+        for rule in self.attrs.ruleses().flat_map(|attr| &attr.rules) {
+            for (span, message) in self.rule_violations(rule, versions) {
+                if rule.level == RuleLevel::Deny {
+                    return Err(syn::Error::new(span, message));
+                }
+
+                // There's no stable way to raise a warning from a procedural macro, so use
+                // a deprecated item instead
+                warnings.push(quote_spanned! {span=>
+                    const _: () = {
+                        #[deprecated(note = #message)]
+                        #[allow(non_upper_case_globals)]
+                        const obake_rule_violated: () = ();
+                        obake_rule_violated
+                    };
+                });
+            }
+        }
+
+        Ok(quote!(#(#warnings)*))
+    }
+
+    /// The spans and descriptions of each violation of `rule` between consecutive `versions`.
+    fn rule_violations(&self, rule: &Rule, versions: &[VersionAttr]) -> Vec<(Span, String)> {
+        let member = match self.kind {
+            VersionedItemKind::Struct(_) => "field",
+            VersionedItemKind::Enum(_) => "variant",
+        };
+        let members = self.members();
+        let mut violations = Vec::new();
+
+        if let RuleKind::MaxChain(max) = rule.kind {
+            let chain = versions.len().saturating_sub(1);
+            if chain > max {
+                violations.push((
+                    rule.span,
+                    format!(
+                        "{chain} migrations separate the oldest and latest versions, exceeding \
+                         the maximum of {max} (rule `max_chain`)"
+                    ),
+                ));
+            }
+            return violations;
+        }
+
+        for pair in versions.windows(2) {
+            let (prev, next) = (&pair[0].version, &pair[1].version);
+            let is_enabled = |ident: &syn::Ident, version: &Tag| {
+                members
+                    .iter()
+                    .any(|(other, attrs)| *other == ident && attrs.is_enabled(version))
+            };
+
+            for (i, (ident, _)) in members.iter().enumerate() {
+                // Fields redeclared with a new type are only reported once
+                if members[..i].iter().any(|(other, _)| other == ident) {
+                    continue;
+                }
+
+                match rule.kind {
+                    RuleKind::NoRemovalWithoutMajor => {
+                        if is_enabled(ident, prev)
+                            && !is_enabled(ident, next)
+                            && !is_breaking_bump(prev, next)
+                        {
+                            violations.push((
+                                ident.span(),
+                                format!(
+                                    "{member} `{ident}` is removed in version {next} without a \
+                                     breaking version bump from {prev} (rule \
+                                     `no_removal_without_major`)"
+                                ),
+                            ));
+                        }
+                    }
+                    RuleKind::NewFieldsHaveDefaults => {
+                        if member != "field" || is_enabled(ident, prev) || !is_enabled(ident, next)
+                        {
+                            continue;
+                        }
+
+                        let added: Vec<_> = members
+                            .iter()
+                            .filter(|(other, attrs)| other == ident && attrs.is_enabled(next))
+                            .map(|(_, attrs)| attrs)
+                            .collect();
+                        let has_default = added.iter().any(|attrs| {
+                            attrs.migrate_defaults().any(|attr| attr.req.matches(next))
+                                || rename::serde_items(attrs.metas(next))
+                                    .iter()
+                                    .any(|meta| meta.path().is_ident("default"))
+                        });
+                        if !has_default {
+                            let span = added[0].cfgs().next().map_or(ident.span(), |cfg| cfg.span);
+                            violations.push((
+                                span,
+                                format!(
+                                    "field `{ident}` is added in version {next} without \
+                                     declaring a default, with `#[obake(migrate_default = ...)]` \
+                                     or `#[serde(default)]` (rule `new_fields_have_defaults`)"
+                                ),
+                            ));
+                        }
+                    }
+                    RuleKind::MaxChain(_) => unreachable!(),
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Check that `#[obake(transparent)]` is only applied to `struct`s without a C layout.
+    fn check_transparent(&self) -> Result<()> {
+        let Some(transparent) = self.attrs.transparents().next() else {
+            return Ok(());
+        };
+
+        if matches!(self.kind, VersionedItemKind::Enum(_)) {
+            return Err(syn::Error::new(
+                transparent.span,
+                "`#[obake(transparent)]` can only be applied to `struct`s",
+            ));
+        }
+
+        if self.is_repr_c() {
+            return Err(syn::Error::new(
+                transparent.span,
+                "`#[obake(transparent)]` cannot be combined with `#[obake(repr_c)]`",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn is_repr_c(&self) -> bool {
+        // `StableAbi` requires a stable layout, so `#[obake(abi_stable)]` implies
+        // `#[obake(repr_c)]`
+        #[cfg(feature = "abi_stable")]
+        if self.attrs.abi_stables().next().is_some() {
+            return true;
+        }
+
+        self.attrs.repr_cs().next().is_some()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn check_boxed(&self) -> Result<()> {
+        let Some(boxed) = self.attrs.boxeds().next() else {
+            return Ok(());
+        };
+
+        // Boxed variants have no C layout
+        if self.is_repr_c() {
+            return Err(syn::Error::new(
+                boxed.span,
+                "`#[obake(boxed)]` cannot be combined with `#[obake(repr_c)]`",
+            ));
+        }
+
+        #[cfg(feature = "uniffi")]
+        if self.attrs.uniffis().next().is_some() {
+            return Err(syn::Error::new(
+                boxed.span,
+                "`#[obake(boxed)]` cannot be combined with `#[obake(uniffi)]`",
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    fn check_tag_case(&self) -> Result<()> {
+        let Some(tag_case) = self.attrs.tag_cases().next() else {
+            return Ok(());
+        };
+
+        if let Some(duplicate) = self.attrs.tag_cases().nth(1) {
+            return Err(syn::Error::new(
+                duplicate.span,
+                "duplicate `#[obake(serde(tag_case = ...))]` attribute",
+            ));
+        }
+
+        // Versions tagged by index have no names to change the case of
+        if self.attrs.tag_as_indexes().next().is_some() {
+            return Err(syn::Error::new(
+                tag_case.span,
+                "`#[obake(serde(tag_case = ...))]` cannot be combined with \
+                 `#[obake(serde(tag_as_index))]`",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// With `#[obake(serde(tag_case = "..."))]`, renames each variant of the version-tagged enum
+    /// after its data-structure and version in that case (e.g., `foo-v0-1-0` for version 0.1.0
+    /// of `Foo` in `kebab` case), in the order versions were declared.
+    #[cfg(feature = "serde")]
+    fn expand_tag_renames(&self) -> Vec<TokenStream2> {
+        let Some(tag_case) = self.attrs.tag_cases().next() else {
+            return self.expand_variants().map(|_| quote!()).collect();
+        };
+
+        let mut words = split_words(&self.ident().unraw().to_string());
+        let base = words.len();
+        self.attrs
+            .versions()
+            .map(|attr| {
+                words.truncate(base);
+                let mut parts = attr
+                    .version
+                    .suffix
+                    .split('_')
+                    .filter(|part| !part.is_empty());
+                words.extend(parts.next().map(|part| format!("v{part}")));
+                words.extend(parts.map(str::to_owned));
+
+                let (separator, upper) = match tag_case.case {
+                    TagCase::Snake => ("_", false),
+                    TagCase::ScreamingSnake => ("_", true),
+                    TagCase::Kebab => ("-", false),
+                    TagCase::ScreamingKebab => ("-", true),
+                };
+                let tag = words.join(separator);
+                let tag = if upper {
+                    tag.to_uppercase()
+                } else {
+                    tag.to_lowercase()
+                };
+
+                quote!(#[serde(rename = #tag)])
+            })
+            .collect()
+    }
+
+    #[cfg_attr(not(feature = "alloc"), allow(clippy::unused_self))]
+    fn is_boxed(&self) -> bool {
+        #[cfg(feature = "alloc")]
+        if self.attrs.boxeds().next().is_some() {
+            return true;
+        }
+
+        false
+    }
+
+    /// The type of the payload of the variant of the version-tagged enum holding `variant`.
+    fn expand_variant_ty(&self, variant: &syn::Ident) -> TokenStream2 {
+        if self.is_boxed() {
+            quote!(::obake::alloc::boxed::Box<#variant>)
+        } else {
+            quote!(#variant)
+        }
+    }
+
+    /// Wrap a value as the payload of a variant of the version-tagged enum.
+    fn expand_box(&self, value: &TokenStream2) -> TokenStream2 {
+        if self.is_boxed() {
+            quote!(::obake::alloc::boxed::Box::new(#value))
+        } else {
+            quote!(#value)
+        }
+    }
+
+    /// Unwrap the payload of a variant of the version-tagged enum.
+    fn expand_unbox(&self, payload: &TokenStream2) -> TokenStream2 {
+        if self.is_boxed() {
+            quote!(*#payload)
+        } else {
+            quote!(#payload)
+        }
+    }
+
+    fn alias(&self) -> Option<syn::Ident> {
+        self.attrs
+            .versions()
+            .last()
+            .map(|attr| self.ident().version(&attr.version))
+    }
+
+    /// The variant of the version-tagged enum holding `version`, named by the pattern of any
+    /// `#[obake(public_enum(variants = "..."))]` attribute (by default, `V{version}`).
+    fn variant(&self, version: &Tag) -> syn::Ident {
+        match self.attrs.public_enums().next() {
+            Some(public_enum) => {
+                let pattern = public_enum
+                    .variants
+                    .as_ref()
+                    .map_or_else(|| "V{version}".to_owned(), syn::LitStr::value);
+                format_ident!("{}", pattern.replace("{version}", &version.suffix))
+            }
+            None => self.ident().version(version),
+        }
+    }
+
+    /// The variant of the version-tagged enum holding the latest version.
+    fn latest_variant(&self) -> syn::Ident {
+        self.variant(&self.attrs.versions().last().unwrap().version)
+    }
+
+    fn versioned_ident(&self) -> syn::Ident {
+        format_ident!("Versioned{}", self.ident())
+    }
+
+    /// The fields (or variants) of a particular version.
+    fn expand_body(&self, version: &Tag) -> Result<TokenStream2> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => inner.fields.expand_version(version),
+            VersionedItemKind::Enum(inner) => inner.variants.expand_version(version),
+        }
+    }
+
+    /// The type defining each version, which under `#[obake(alias_identical)]` is that of the
+    /// preceding version when both have identical bodies.
+    fn expand_definitions(&self, versions: &[VersionAttr]) -> Result<Vec<syn::Ident>> {
+        let alias_identical = self.attrs.alias_identicals().next().is_some();
+        let mut definitions: Vec<syn::Ident> = Vec::with_capacity(versions.len());
+        let mut prev_body = None;
+
+        for attr in versions {
+            // Versions differing only in their conditional attributes (e.g., in how `serde`
+            // renames their fields) aren't identical either
+            let conditional_attrs = self.attrs.expand_conditional(&attr.version);
+            #[cfg(feature = "serde")]
+            let strictness = self.expand_strictness(&attr.version);
+            #[cfg(not(feature = "serde"))]
+            let strictness = quote!();
+            let body = self.expand_body(&attr.version)?;
+            let body = quote!(#conditional_attrs #strictness #body).to_string();
+            let definition = match definitions.last() {
+                Some(prev) if alias_identical && prev_body.as_ref() == Some(&body) => prev.clone(),
+                _ => self.ident().version(&attr.version),
+            };
+
+            definitions.push(definition);
+            prev_body = Some(body);
+        }
+
+        Ok(definitions)
+    }
+
+    /// The definition (or alias) of each version, gated by any `#[obake(cfg_when(...))]`.
+    fn expand_versions(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> Result<Vec<TokenStream2>> {
+        versions
+            .iter()
+            .zip(definitions)
+            .map(|(attr, definition)| {
+                let gate = self.expand_cfg_when(&attr.version);
+                let def = if *definition == self.ident().version(&attr.version) {
+                    self.expand_version(&attr.version)?
+                } else {
+                    self.expand_version_alias(&attr.version, definition)
+                };
+
+                Ok(quote!(#gate #def))
+            })
+            .collect()
+    }
+
+    fn expand_version_alias(&self, version: &Tag, definition: &syn::Ident) -> TokenStream2 {
+        let vis = &self.vis;
+        let ident = self.ident().version(version);
+        let doc_hidden = self.expand_doc_hidden();
+
+        quote! {
+            #doc_hidden
+            #[allow(non_camel_case_types)]
+            #vis type #ident = #definition;
+        }
+    }
+
+    fn expand_version(&self, version: &Tag) -> Result<TokenStream2> {
+        let attrs = self.expand_version_attrs(version)?;
+        let conditional_attrs = self.attrs.expand_conditional(version);
+        #[cfg(feature = "serde")]
+        let strictness = self.expand_strictness(version);
+        #[cfg(not(feature = "serde"))]
+        let strictness = quote!();
+        let vis = &self.vis;
+        let ident = self.ident().version(version);
+        let fields = self.expand_body(version)?;
+        let body = match &self.kind {
+            VersionedItemKind::Struct(inner) => {
+                let struct_token = &inner.struct_token;
+                quote!(#struct_token #ident #fields)
+            }
+            VersionedItemKind::Enum(inner) => {
+                let enum_token = &inner.enum_token;
+                quote!(#enum_token #ident #fields)
+            }
+        };
+        let doc_hidden = self.expand_doc_hidden();
+        let stability_doc = self.expand_stability_doc(version);
+        #[cfg(feature = "alloc")]
+        let test_values_doc = self.expand_test_values_doc(version);
+        #[cfg(not(feature = "alloc"))]
+        let test_values_doc = quote!();
+        let repr_c = self.is_repr_c().then(|| quote!(#[repr(C)]));
+        let transparent = self.expand_transparent(version);
+        let (leading_attrs, trailing_attrs) = self.expand_binding_attrs(version);
+        #[cfg(feature = "tabular")]
+        let tabular_impl = self.expand_tabular_impl(version)?;
+        #[cfg(not(feature = "tabular"))]
+        let tabular_impl = quote!();
+        #[cfg(feature = "idl")]
+        let idl_impl = self.expand_idl_impl(version)?;
+        #[cfg(not(feature = "idl"))]
+        let idl_impl = quote!();
+
+        Ok(quote! {
+            #doc_hidden
+            #[allow(non_camel_case_types)]
+            #repr_c
+            #leading_attrs
+            #stability_doc
+            #(#attrs)*
+            #test_values_doc
+            #conditional_attrs
+            #strictness
+            #transparent
+            #trailing_attrs
+            #vis #body
+
+            #tabular_impl
+            #idl_impl
+        })
+    }
+
+    /// With `#[obake(transparent)]`, lay out versions with exactly one field as that field, and
+    /// (if they derive `serde`'s traits) encode them as it too, as they were before growing
+    /// structure.
+    fn expand_transparent(&self, version: &Tag) -> TokenStream2 {
+        if self.attrs.transparents().next().is_none() {
+            return quote!();
+        }
+
+        let fields = self
+            .members()
+            .into_iter()
+            .filter(|(_, attrs)| attrs.is_enabled(version))
+            .count();
+        if fields != 1 {
+            return quote!();
+        }
+
+        let derives_serde = self.attrs.metas(version).iter().any(|meta| match meta {
+            syn::Meta::List(list) if list.path.is_ident("derive") => {
+                list.nested.iter().any(|nested| match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                        path.segments.last().is_some_and(|segment| {
+                            segment.ident == "Serialize" || segment.ident == "Deserialize"
+                        })
+                    }
+                    _ => false,
+                })
+            }
+            _ => false,
+        });
+        let serde = derives_serde.then(|| quote!(#[serde(transparent)]));
+
+        quote! {
+            #[repr(transparent)]
+            #serde
+        }
+    }
+
+    /// `#[serde(deny_unknown_fields)]` for a version held to it by
+    /// `#[obake(serde(deny_unknown_on_latest))]` or `#[obake(serde(deny_unknown_fields(...)))]`,
+    /// unless it's exempted by `#[obake(serde(allow_unknown_fields(...)))]`.
+    #[cfg(feature = "serde")]
+    fn expand_strictness(&self, version: &Tag) -> TokenStream2 {
+        let latest = &self.attrs.versions().last().unwrap().version;
+        let (denied, allowed): (Vec<_>, Vec<_>) = self
+            .attrs
+            .strictnesses()
+            .filter(|attr| {
+                attr.req
+                    .as_ref()
+                    .map_or(version == latest, |req| req.matches(version))
+            })
+            .partition(|attr| attr.deny);
+
+        if denied.is_empty() || !allowed.is_empty() {
+            return quote!();
+        }
+
+        quote!(#[serde(deny_unknown_fields)])
+    }
+
+    /// The attributes applied to a particular version, which drop any derived `Default` from
+    /// versions given their own with `#[obake(version_default(...))]`.
+    fn expand_version_attrs(&self, version: &Tag) -> Result<Vec<TokenStream2>> {
+        let has_default = self
+            .attrs
+            .version_defaults()
+            .any(|attr| attr.version.version == *version);
+
+        self.attrs
+            .attrs()
+            .map(|attr| {
+                if !has_default || !attr.path.is_ident("derive") {
+                    return Ok(quote!(#attr));
+                }
+
+                let paths = attr.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )?;
+                let paths: Vec<_> = paths
+                    .into_iter()
+                    .filter(|path| {
+                        path.segments
+                            .last()
+                            .is_none_or(|segment| segment.ident != "Default")
+                    })
+                    .collect();
+
+                Ok(if paths.is_empty() {
+                    quote!()
+                } else {
+                    quote!(#[derive(#(#paths),*)])
+                })
+            })
+            .collect()
+    }
+
+    /// Implement `Default` for each version given its own with `#[obake(version_default(...))]`.
+    fn expand_version_defaults(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> Result<TokenStream2> {
+        let impls = self
+            .attrs
+            .version_defaults()
+            .map(|attr| {
+                let definition = versions
+                    .iter()
+                    .zip(definitions)
+                    .find(|(version, _)| **version == attr.version)
+                    .map(|(_, definition)| definition);
+                let ident = self.ident().version(&attr.version.version);
+                if definition != Some(&ident) {
+                    return Err(syn::Error::new(
+                        attr.version.span,
+                        format!(
+                            "version {} is an alias of an identical version, which must be given \
+                             the default instead",
+                            attr.version.version
+                        ),
+                    ));
+                }
+
+                let ctor = &attr.ctor;
+                let gate = self.expand_cfg_when(&attr.version.version);
+
+                Ok(quote! {
+                    #gate
+                    #[automatically_derived]
+                    impl ::core::default::Default for #ident {
+                        #[inline]
+                        fn default() -> Self {
+                            #ctor()
+                        }
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote!(#(#impls)*))
+    }
+
+    /// The variants (and versions) of the versions aliasing `definition` under
+    /// `#[obake(alias_identical)]`, other than `attr` itself.
+    fn expand_version_aliases(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+        attr: &VersionAttr,
+        definition: &syn::Ident,
+    ) -> (Vec<syn::Ident>, Vec<String>) {
+        versions
+            .iter()
+            .zip(definitions)
+            .filter(|(other, other_definition)| {
+                other.version != attr.version && *other_definition == definition
+            })
+            .map(|(other, _)| (self.variant(&other.version), other.version.to_string()))
+            .unzip()
+    }
+
+    /// Implement `Migrate` and `TryMigrate` for the version `ty`.
+    fn expand_version_migrate_impls(&self, ty: &TokenStream2) -> TokenStream2 {
+        let current = self.ident();
+        let versioned_ident = self.versioned_ident();
+        let migrate_impl = quote! {
+            #[automatically_derived]
+            impl ::obake::Migrate for #ty {
+                type Latest = #current;
+
+                #[inline]
+                fn migrate(self) -> #current {
+                    ::core::convert::Into::into(#versioned_ident::from(self))
+                }
+            }
+        };
+        let try_migrate_impl = self.expand_try_migrate_impl(
+            ty,
+            &quote!(::obake::TryUpgrade::try_upgrade(#versioned_ident::from(self))),
+        );
+
+        quote! {
+            #migrate_impl
+
+            #try_migrate_impl
+        }
+    }
+
+    /// Implement `VersionOf`, `From`, `Migrate`, `TryMigrate` and `TryFrom` (from a version
+    /// string and the version) for every defined version.
+    fn expand_version_of_impls(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        let current = self.ident();
+        let impls = versions
+            .iter()
+            .zip(definitions)
+            .enumerate()
+            .filter(|(_, (attr, definition))| **definition == current.version(&attr.version))
+            .map(|(position, (attr, definition))| {
+                let (aliases, alias_strs) =
+                    self.expand_version_aliases(versions, definitions, attr, definition);
+                let gate = self.expand_cfg_when(&attr.version);
+                let impls =
+                    self.expand_version_of_impl(position, attr, definition, &aliases, &alias_strs);
+
+                quote! {
+                    #gate
+                    const _: () = {
+                        #impls
+                    };
+                }
+            });
+
+        quote!(#(#impls)*)
+    }
+
+    /// The implementations of `expand_version_of_impls` for the version `attr`, declared
+    /// as `definition` at `position` among every declared version.
+    fn expand_version_of_impl(
+        &self,
+        position: usize,
+        attr: &VersionAttr,
+        definition: &syn::Ident,
+        aliases: &[syn::Ident],
+        alias_strs: &[String],
+    ) -> TokenStream2 {
+        let current = self.ident();
+        let versioned_ident = self.versioned_ident();
+        let unboxed = self.expand_unbox(&quote!(x));
+        let boxed = self.expand_box(&quote!(from));
+        let version_str = attr.version.to_string();
+        // Counted among every declared version, whether or not it's excluded by
+        // `#[obake(cfg_when(...))]`, so that the index is the same whatever is compiled
+        let index = proc_macro2::Literal::usize_unsuffixed(position);
+        let variant = self.variant(&attr.version);
+        let migrate_impls = self.expand_version_migrate_impls(&quote!(#definition));
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::VersionOf<#current> for #definition {
+                const VERSION: &'static str = #version_str;
+
+                const INDEX: usize = #index;
+
+                #[inline]
+                fn try_from_versioned(
+                    from: ::obake::AnyVersion<#current>,
+                ) -> ::core::result::Result<Self, ::obake::VersionMismatch> {
+                    #![allow(unreachable_patterns)]
+                    use ::obake::VersionTagged;
+                    match from {
+                        #versioned_ident::#variant(x)
+                        #(| #versioned_ident::#aliases(x))* => {
+                            ::core::result::Result::Ok(#unboxed)
+                        }
+                        other => ::core::result::Result::Err(::obake::VersionMismatch {
+                            expected: #version_str,
+                            found: other.version_str(),
+                        }),
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<#definition> for #versioned_ident {
+                #[inline]
+                fn from(from: #definition) -> Self {
+                    #versioned_ident::#variant(#boxed)
+                }
+            }
+
+            #migrate_impls
+
+            #[automatically_derived]
+            impl<'a> ::core::convert::TryFrom<(&'a str, #definition)> for #versioned_ident {
+                type Error = ::obake::TagMismatch<'a>;
+
+                #[inline]
+                fn try_from(
+                    (version, from): (&'a str, #definition),
+                ) -> ::core::result::Result<Self, Self::Error> {
+                    match version {
+                        #version_str => ::core::result::Result::Ok(
+                            #versioned_ident::#variant(#boxed),
+                        ),
+                        #(#alias_strs => ::core::result::Result::Ok(
+                            #versioned_ident::#aliases(#boxed),
+                        ),)*
+                        _ => ::core::result::Result::Err(::obake::TagMismatch {
+                            version,
+                            expected: &[#version_str #(, #alias_strs)*],
+                        }),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Attributes generated for a particular version by the binding features, split into those
+    /// which must precede the user's attributes and those which must follow them (i.e., helper
+    /// attributes of derives).
+    #[allow(unused_variables, clippy::unused_self)]
+    fn expand_binding_attrs(&self, version: &Tag) -> (TokenStream2, TokenStream2) {
+        #[cfg(feature = "ts-rs")]
+        let ts = self.expand_ts_version_attrs(version);
+        #[cfg(not(feature = "ts-rs"))]
+        let ts = quote!();
+        #[cfg(feature = "specta")]
+        let specta = self.expand_specta_attrs(&format!("{}V{}", self.ident(), version.suffix));
+        #[cfg(not(feature = "specta"))]
+        let specta = quote!();
+        #[cfg(feature = "pyo3")]
+        let pyclass = self.expand_pyclass_attr(version);
+        #[cfg(not(feature = "pyo3"))]
+        let pyclass = quote!();
+        #[cfg(feature = "abi_stable")]
+        let stable_abi = self.expand_stable_abi_derive();
+        #[cfg(not(feature = "abi_stable"))]
+        let stable_abi = quote!();
+        #[cfg(feature = "uniffi")]
+        let uniffi = self.expand_uniffi_version_derive();
+        #[cfg(not(feature = "uniffi"))]
+        let uniffi = quote!();
+        #[cfg(feature = "utoipa")]
+        let utoipa = self.expand_utoipa_version_attrs(version);
+        #[cfg(not(feature = "utoipa"))]
+        let utoipa = quote!();
+        #[cfg(feature = "async-graphql")]
+        let graphql = self.expand_graphql_version_attrs(version);
+        #[cfg(not(feature = "async-graphql"))]
+        let graphql = quote!();
+        #[cfg(feature = "fake")]
+        let fake = self.expand_fake_attrs();
+        #[cfg(not(feature = "fake"))]
+        let fake = quote!();
+
+        (
+            quote!(#stable_abi #uniffi #pyclass),
+            quote!(#ts #specta #utoipa #graphql #fake),
+        )
+    }
+
+    fn expand_alias(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let vis = &self.vis;
+        let ident = self.ident();
+        let alias = self.alias().unwrap();
+        let coverage_doc = self.expand_coverage_doc(versions);
+        let history_doc = self.expand_history_doc(versions);
+
+        if self.skipped_fields().is_empty() {
+            return Ok(quote! {
+                #coverage_doc
+                #history_doc
+                #vis type #ident = #alias;
+            });
+        }
+
+        let runtime = self.expand_runtime(&versions.last().unwrap().version)?;
+
+        Ok(quote! {
+            #coverage_doc
+            #history_doc
+            #runtime
+        })
+    }
+
+    /// The type named after a `struct` with `#[obake(skip)]` fields, holding the fields of its
+    /// latest version along with the skipped fields, which are populated after migrating to the
+    /// latest version and dropped before converting back to it.
+    fn expand_runtime(&self, latest: &Tag) -> Result<TokenStream2> {
+        let VersionedItemKind::Struct(inner) = &self.kind else {
+            unreachable!("only the fields of `struct`s can be skipped");
+        };
+
+        let attrs = self.attrs.attrs();
+        let conditional_attrs = self.attrs.expand_conditional(latest);
+        let vis = &self.vis;
+        let ident = self.ident();
+        let alias = self.alias().unwrap();
+        let enum_ident = self.versioned_ident();
+        let struct_token = &inner.struct_token;
+        let fields = inner
+            .fields
+            .fields
+            .iter()
+            .map(|field| match field.attrs.skips().next() {
+                Some(_) => {
+                    let attrs = field.attrs.attrs();
+                    let vis = &field.vis;
+                    let ident = &field.ident;
+                    let colon_token = &field.colon_token;
+                    let ty = &field.ty;
+                    Ok(quote!(#(#attrs)* #vis #ident #colon_token #ty,))
+                }
+                None => field.expand_version(latest),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let persistent: Vec<_> = inner
+            .fields
+            .fields
+            .iter()
+            .filter(|field| field.attrs.is_enabled(latest))
+            .map(|field| &field.ident)
+            .collect();
+        let skipped = self.skipped_fields().into_iter().map(|field| &field.ident);
+        let exprs = self.skipped_fields().into_iter().map(|field| {
+            let expr = &field.attrs.skips().next().unwrap().expr;
+            expr.as_ref().map_or_else(
+                || quote!(::core::default::Default::default()),
+                |expr| quote!(#expr),
+            )
+        });
+
+        let try_migrate_impl = self
+            .expand_try_migrate_impl(&quote!(#ident), &quote!(::core::result::Result::Ok(self)));
+
+        Ok(quote! {
+            #(#attrs)*
+            #conditional_attrs
+            #vis #struct_token #ident {
+                #(#fields)*
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<#alias> for #ident {
+                #[inline]
+                fn from(from: #alias) -> Self {
+                    let #alias { #(#persistent),* } = from;
+                    Self {
+                        #(#persistent,)*
+                        #(#skipped: #exprs,)*
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<#ident> for #alias {
+                #[inline]
+                fn from(from: #ident) -> Self {
+                    let #ident { #(#persistent,)* .. } = from;
+                    Self { #(#persistent),* }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<#ident> for #enum_ident {
+                #[inline]
+                fn from(from: #ident) -> Self {
+                    <#alias>::from(from).into()
+                }
+            }
+
+            #[automatically_derived]
+            impl ::obake::Migrate for #ident {
+                type Latest = Self;
+
+                #[inline]
+                fn migrate(self) -> Self {
+                    self
+                }
+            }
+
+            #try_migrate_impl
+        })
+    }
+
+    /// The fields (or variants) of this item, with their attributes.
+    fn members(&self) -> Vec<(&syn::Ident, &VersionedAttributes)> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .map(|field| (&field.ident, &field.attrs))
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .map(|variant| (&variant.ident, &variant.attrs))
+                .collect(),
+        }
+    }
+
+    /// The names of the fields (or variants) of a version, as deserialized by `serde`, along with
+    /// their positions among the item's members.
+    fn wire_names(&self, version: &Tag) -> Vec<(usize, String)> {
+        let is_enum = matches!(self.kind, VersionedItemKind::Enum(_));
+        let container = rename::serde_items(self.attrs.metas(version));
+        let rule = rename::deserialize_value(&container, "rename_all");
+
+        self.members()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, attrs))| attrs.is_enabled(version))
+            .filter_map(|(i, (ident, attrs))| {
+                let items = rename::serde_items(attrs.metas(version));
+                if rename::is_excluded(&items) {
+                    return None;
+                }
+
+                let name = ident.unraw().to_string();
+                let renamed = rename::deserialize_value(&items, "rename").or_else(|| {
+                    rule.as_ref()
+                        .and_then(|rule| rename::apply_rule(rule, &name, is_enum))
+                });
+                Some((i, renamed.unwrap_or(name)))
+            })
+            .collect()
+    }
+
+    /// The names of the fields (or variants) of a version, as deserialized by `serde`.
+    fn field_names(&self, version: &Tag) -> Vec<String> {
+        self.wire_names(version)
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// The schema of a version as recorded in a lockfile: a line per field giving its name (as
+    /// deserialized by `serde`) and type, or per variant giving its name and payload.
+    fn schema(&self, version: &Tag) -> Result<Vec<String>> {
+        let ty = |ty: TokenStream2| lockfile::tidy(&ty.to_string());
+
+        self.wire_names(version)
+            .into_iter()
+            .map(|(i, name)| match &self.kind {
+                VersionedItemKind::Struct(inner) => {
+                    let field = &inner.fields.fields[i];
+                    Ok(format!(
+                        "{}: {}",
+                        name,
+                        ty(field.expand_ty_versioned(version)?)
+                    ))
+                }
+                VersionedItemKind::Enum(inner) => match &inner.variants.variants[i].fields {
+                    VersionedVariantFields::Unnamed(unnamed) => {
+                        let tys = unnamed
+                            .fields
+                            .iter()
+                            .map(|field| Ok(ty(field.expand_ty_versioned(version)?)))
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok(format!("{}({})", name, tys.join(", ")))
+                    }
+                    VersionedVariantFields::Named(named) => {
+                        let fields = named
+                            .fields
+                            .iter()
+                            .filter(|field| field.attrs.is_enabled(version))
+                            .map(|field| {
+                                let field_ty = ty(field.expand_ty_versioned(version)?);
+                                Ok(format!("{}: {}", field.ident.unraw(), field_ty))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok(format!("{} {{ {} }}", name, fields.join(", ")))
+                    }
+                    VersionedVariantFields::Unit => Ok(name),
+                },
+            })
+            .collect()
+    }
+
+    /// Check the schema of each version but the latest against the lockfile named by any
+    /// `#[obake(lockfile = "...")]` attribute (relative to the manifest directory of the crate
+    /// being compiled), then record the schema of every version in it.
+    ///
+    /// With `OBAKE_UPDATE_LOCKFILE` set, every version is recorded without being checked. The
+    /// lockfile is included in the expansion, so that editing it recompiles the crate.
+    fn expand_lockfile(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.lockfiles().next() else {
+            return Ok(quote!());
+        };
+
+        let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let path = std::path::Path::new(&manifest_dir).join(attr.path.value());
+        let error = |message| syn::Error::new(attr.path.span(), message);
+        let locked = lockfile::read(&path).map_err(error)?;
+
+        let item = attr
+            .key
+            .as_ref()
+            .map_or_else(|| self.ident().to_string(), syn::LitStr::value);
+        let entries = versions
+            .iter()
+            .map(|version| {
+                let schema = self.schema(&version.version)?;
+                Ok(lockfile::Entry::new(
+                    item.clone(),
+                    version.version.to_string(),
+                    schema,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The latest version may still change, but those before it are frozen
+        if std::env::var_os(lockfile::UPDATE_VAR).is_none() {
+            for (version, entry) in versions.iter().zip(&entries).take(versions.len() - 1) {
+                let Some(locked) = locked
+                    .iter()
+                    .find(|locked| locked.item == item && locked.version == entry.version)
+                else {
+                    continue;
+                };
+
+                if locked.fingerprint != entry.fingerprint {
+                    return Err(syn::Error::new(
+                        version.span,
+                        format!(
+                            "version {} of `{}` no longer matches its schema in `{}` (expected \
+                             `{}`, found `{}`), if this change is intentional, rebuild with \
+                             `{}=1` set to regenerate it",
+                            entry.version,
+                            item,
+                            attr.path.value(),
+                            locked.fields.join(", "),
+                            entry.fields.join(", "),
+                            lockfile::UPDATE_VAR,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        lockfile::update(&path, locked, &item, &entries).map_err(error)?;
+
+        let path = path.display().to_string();
+        Ok(quote!(
+            const _: &[u8] = ::core::include_bytes!(#path);
+        ))
+    }
+
+    /// Implement `FieldNames` for every defined version.
+    fn expand_field_names_impls(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        let impls = versions
+            .iter()
+            .zip(definitions)
+            .filter(|(attr, definition)| **definition == self.ident().version(&attr.version))
+            .map(|(attr, definition)| {
+                let gate = self.expand_cfg_when(&attr.version);
+                let names = self.field_names(&attr.version);
+
+                quote! {
+                    #gate
+                    #[automatically_derived]
+                    impl ::obake::FieldNames for #definition {
+                        const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+                    }
+                }
+            });
+
+        quote!(#(#impls)*)
+    }
+
+    /// The names of the fields (or variants) of this item, each with whether it is present in
+    /// each version.
+    fn coverage(&self, versions: &[VersionAttr]) -> Vec<(String, Vec<bool>)> {
+        self.members()
+            .into_iter()
+            .map(|(ident, attrs)| {
+                let present = versions
+                    .iter()
+                    .map(|attr| attrs.is_enabled(&attr.version))
+                    .collect();
+                (ident.unraw().to_string(), present)
+            })
+            .collect()
+    }
+
+    /// A table of the fields (or variants) present in each version, documenting the alias for
+    /// the latest version.
+    fn expand_coverage_doc(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let header = versions
+            .iter()
+            .map(|attr| format!(" `{}` |", attr.version))
+            .collect::<Vec<_>>()
+            .concat();
+        let align = " :-: |".repeat(versions.len());
+        let rows = self.coverage(versions).into_iter().map(|(name, present)| {
+            let cells = present
+                .into_iter()
+                .map(|present| if present { " ✓ |" } else { " |" })
+                .collect::<String>();
+            format!("| `{name}` |{cells}")
+        });
+        let lines = vec![
+            "## Version Coverage".to_owned(),
+            String::new(),
+            format!("| |{header}"),
+            format!("|---|{align}"),
+        ]
+        .into_iter()
+        .chain(rows);
+
+        quote!(#(#[doc = #lines])*)
+    }
+
+    /// A changelog of the fields (or variants) added and removed by each version, along with
+    /// any `#[obake(note(...))]`s, documenting the alias for the latest version.
+    fn expand_history_doc(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let kind = match &self.kind {
+            VersionedItemKind::Struct(_) => "field",
+            VersionedItemKind::Enum(_) => "variant",
+        };
+        let coverage = self.coverage(versions);
+
+        let mut lines = vec![String::new(), "## History".to_owned()];
+        for (i, attr) in versions.iter().enumerate().rev() {
+            lines.push(String::new());
+            match self
+                .attrs
+                .releaseds()
+                .find(|released| released.version == *attr)
+            {
+                Some(released) => lines.push(format!(
+                    "### `{}` (released {})",
+                    attr.version,
+                    released.date.value(),
+                )),
+                None => lines.push(format!("### `{}`", attr.version)),
+            }
+            lines.push(String::new());
+
+            if i == 0 {
+                lines.push("- Initial version.".to_owned());
+            } else {
+                for (name, present) in &coverage {
+                    match (present[i - 1], present[i]) {
+                        (false, true) => lines.push(format!("- Added {kind} `{name}`.")),
+                        (true, false) => lines.push(format!("- Removed {kind} `{name}`.")),
+                        _ => {}
+                    }
+                }
+            }
+
+            lines.extend(
+                self.attrs
+                    .notes()
+                    .filter(|note| note.version == *attr)
+                    .map(|note| format!("- {}", note.note.value())),
+            );
+
+            if lines.last().is_some_and(String::is_empty) {
+                lines.push(format!("- No {kind}s added or removed."));
+            }
+        }
+
+        quote!(#(#[doc = #lines])*)
+    }
+
+    fn expand_coverage_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let gates: Vec<_> = versions
+            .iter()
+            .map(|attr| self.expand_cfg_when(&attr.version))
+            .collect();
+        let rows = self
+            .coverage(versions)
+            .into_iter()
+            .map(|(name, present)| quote!((#name, &[#(#gates #present),*])));
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Coverage for #ident {
+                const COVERAGE: &'static [(&'static str, &'static [bool])] = &[#(#rows),*];
+            }
+        }
+    }
+
+    fn expand_releases_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let rows = versions.iter().filter_map(|attr| {
+            let released = self
+                .attrs
+                .releaseds()
+                .find(|released| released.version == *attr)?;
+            let gate = self.expand_cfg_when(&attr.version);
+            let version = attr.version.to_string();
+            let date = &released.date;
+
+            Some(quote!(#gate (#version, #date)))
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Releases for #ident {
+                const RELEASES: &'static [(&'static str, &'static str)] = &[#(#rows),*];
+            }
+        }
+    }
+
+    /// The stability of `version`: the latest version (and those marked `#[obake(stable(...))]`)
+    /// may still evolve, deprecated versions are awaiting removal, and the rest are frozen.
+    fn stability(&self, version: &Tag, latest: &Tag) -> &'static str {
+        if version == latest
+            || self
+                .attrs
+                .stables()
+                .any(|attr| attr.version.version == *version)
+        {
+            "Stable"
+        } else if self
+            .attrs
+            .deprecateds()
+            .any(|attr| attr.version.version == *version)
+        {
+            "Deprecated"
+        } else {
+            "Frozen"
+        }
+    }
+
+    fn expand_stability_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let latest = &versions.last().unwrap().version;
+        let rows = versions.iter().map(|attr| {
+            let gate = self.expand_cfg_when(&attr.version);
+            let version = attr.version.to_string();
+            let stability = format_ident!("{}", self.stability(&attr.version, latest));
+
+            quote!(#gate (#version, ::obake::Stability::#stability))
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::StabilityMap for #ident {
+                const STABILITY: &'static [(&'static str, ::obake::Stability)] = &[#(#rows),*];
+            }
+        }
+    }
+
+    /// With `#[obake(stability)]`, a badge heading the documentation of a version with its
+    /// stability, as `#[doc(cfg(...))]` heads items with the platforms they're available on.
+    fn expand_stability_doc(&self, version: &Tag) -> TokenStream2 {
+        if self.attrs.stabilities().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident().unraw();
+        let latest = self
+            .attrs
+            .versions()
+            .map(|attr| &attr.version)
+            .max()
+            .unwrap();
+        let stability = self.stability(version, latest);
+        let (class, summary) = match stability {
+            "Stable" => ("portability", format!("version {version} of {ident} may still evolve")),
+            "Deprecated" => (
+                "deprecated",
+                format!("version {version} of {ident} is kept only to migrate data written at it"),
+            ),
+            _ => (
+                "portability",
+                format!("version {version} of {ident} must not change, as data written at it relies on its schema"),
+            ),
+        };
+        let badge = format!(
+            "<div class=\"stab {class}\" data-obake-stability=\"{}\">{stability}: {summary}.</div>",
+            stability.to_lowercase(),
+        );
+
+        quote! {
+            #[doc = #badge]
+            #[doc = ""]
+        }
+    }
+
+    fn expand_inherits_impl(&self) -> TokenStream2 {
+        let ident = self.ident();
+        let (mut names, mut inherited) = (Vec::new(), Vec::new());
+        for ty in self.inherited_tys() {
+            let name = lockfile::tidy(&quote!(#ty).to_string());
+            if !names.contains(&name) {
+                names.push(name);
+                inherited.push(ty);
+            }
+        }
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Inherits for #ident {
+                const INHERITED: &'static [::obake::Inherited] = &[#(::obake::Inherited {
+                    name: #names,
+                    type_id: ::core::any::TypeId::of::<#inherited>,
+                    inherited: <#inherited as ::obake::Inherits>::INHERITED,
+                }),*];
+            }
+        }
+    }
+
+    fn expand_migrations_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let name = ident.unraw().to_string();
+        // Edges are gated along with the older of the two versions they connect
+        let edge = |from: &VersionAttr, to: &VersionAttr, kind: TokenStream2| {
+            let gate = self.expand_cfg_when(std::cmp::min(&from.version, &to.version));
+            let (from, to) = (from.version.to_string(), to.version.to_string());
+            quote!(#gate ::obake::graph::Edge {
+                from: #from,
+                to: #to,
+                kind: ::obake::graph::EdgeKind::#kind,
+            })
+        };
+
+        let mut edges: Vec<_> = versions
+            .windows(2)
+            .map(|pair| edge(&pair[0], &pair[1], quote!(Upgrade)))
+            .collect();
+
+        if self.attrs.dense_migrations().next().is_some() {
+            edges.extend((0..versions.len()).flat_map(|to| {
+                (0..to.saturating_sub(1))
+                    .map(move |from| edge(&versions[from], &versions[to], quote!(Shortcut)))
+            }));
+        }
+
+        if self.attrs.downgrades().next().is_some() {
+            edges.extend(
+                versions
+                    .windows(2)
+                    .rev()
+                    .map(|pair| edge(&pair[1], &pair[0], quote!(Downgrade))),
+            );
+        }
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Migrations for #ident {
+                fn migration_graph() -> ::obake::graph::MigrationGraph {
+                    ::obake::graph::MigrationGraph {
+                        name: #name,
+                        versions: <Self as ::obake::Versioned>::VERSIONS,
+                        edges: &[#(#edges),*],
+                    }
+                }
+            }
+        }
+    }
+
+    /// The `#[cfg(...)]` attribute gating a version behind the predicate of a matching
+    /// `#[obake(cfg_when(...))]`, if any.
+    fn expand_cfg_when(&self, version: &Tag) -> TokenStream2 {
+        let predicates = self
+            .attrs
+            .cfg_whens()
+            .filter(|attr| attr.req.matches(version))
+            .map(|attr| &attr.predicate);
+
+        quote!(#(#[cfg(#predicates)])*)
+    }
+
+    /// Expand an item enumerating the declared versions in its bounds once for the versions
+    /// compiled when the predicate of an `#[obake(cfg_when(...))]` holds, and once for those
+    /// compiled when it doesn't, as the bounds of an item can't be gated individually.
+    fn expand_per_cfg(&self, expand: impl Fn(&[&VersionAttr]) -> TokenStream2) -> TokenStream2 {
+        let versions: Vec<_> = self.attrs.versions().collect();
+        let Some(cfg_when) = self.attrs.cfg_whens().next() else {
+            return expand(&versions);
+        };
+
+        let predicate = &cfg_when.predicate;
+        let enabled = expand(&versions);
+        let disabled = expand(
+            &versions
+                .into_iter()
+                .filter(|attr| !cfg_when.req.matches(&attr.version))
+                .collect::<Vec<_>>(),
+        );
+
+        quote! {
+            #[cfg(#predicate)]
+            #enabled
+
+            #[cfg(not(#predicate))]
+            #disabled
+        }
+    }
+
+    /// The variants of the version-tagged enum, in the order versions were declared.
+    fn expand_variants(&self) -> impl Iterator<Item = syn::Ident> + '_ {
+        self.attrs
+            .versions()
+            .map(move |attr| self.variant(&attr.version))
+    }
+
+    /// The versions held by each variant of the version-tagged enum.
+    fn expand_version_idents(&self) -> impl Iterator<Item = syn::Ident> + '_ {
+        self.attrs
+            .versions()
+            .map(move |attr| self.ident().version(&attr.version))
+    }
+
+    fn expand_versioned_enum(&self) -> TokenStream2 {
+        let enum_ident = self.versioned_ident();
+        let vis = &self.vis;
+        let variants = self.expand_variants();
+        let variant_tys = self
+            .expand_version_idents()
+            .map(|version| self.expand_variant_ty(&version));
+        let gates = self
+            .attrs
+            .versions()
+            .map(|attr| self.expand_cfg_when(&attr.version));
+        #[cfg(feature = "ts-rs")]
+        let (ts, variant_attrs) = self.expand_ts_enum_attrs();
+        #[cfg(not(feature = "ts-rs"))]
+        let (ts, variant_attrs) = (quote!(), self.expand_variants().map(|_| quote!()));
+        #[cfg(feature = "specta")]
+        let specta = self.expand_specta_attrs(&enum_ident.to_string());
+        #[cfg(not(feature = "specta"))]
+        let specta = quote!();
+        #[cfg(feature = "fake")]
+        let fake = self.expand_fake_attrs();
+        #[cfg(not(feature = "fake"))]
+        let fake = quote!();
+        #[cfg(feature = "serde")]
+        let tag_renames = self.expand_tag_renames();
+        #[cfg(not(feature = "serde"))]
+        let tag_renames = self.expand_variants().map(|_| quote!());
+        let (docs, variant_docs) = self.expand_versioned_enum_docs();
+        let (repr_c, discriminants) = self.expand_discriminants();
+        #[cfg(feature = "abi_stable")]
+        let stable_abi = self.expand_stable_abi_derive();
+        #[cfg(not(feature = "abi_stable"))]
+        let stable_abi = quote!();
+        #[cfg(feature = "uniffi")]
+        let uniffi = self.expand_uniffi_enum_derive();
+        #[cfg(not(feature = "uniffi"))]
+        let uniffi = quote!();
+        let derives = self.attrs.derives().map(|attr| {
+            let tokens = &attr.tokens;
+            quote!(#[derive(#tokens)])
+        });
+        #[cfg(feature = "serde")]
+        let derives = derives.chain(self.attrs.serdes().map(|attr| {
+            let tokens = &attr.tokens;
+            quote!(#[serde(#tokens)])
+        }));
+        let bounds: Vec<_> = self
+            .attrs
+            .derive_boundses()
+            .flat_map(|attr| &attr.predicates)
+            .collect();
+        let where_clause = if bounds.is_empty() {
+            quote!()
+        } else {
+            quote!(where #(#bounds),*)
+        };
+
+        quote! {
+            #docs
+            #(#derives)*
+            #ts
+            #specta
+            #fake
+            #repr_c
+            #stable_abi
+            #uniffi
+            #[allow(clippy::enum_variant_names)]
+            #vis enum #enum_ident #where_clause {
+                #(
+                    #gates
+                    #variant_docs
+                    #[allow(non_camel_case_types)]
+                    #variant_attrs
+                    #tag_renames
+                    #variants(#variant_tys) #discriminants,
+                )*
+            }
+        }
+    }
+
+    fn expand_discriminants(&self) -> (TokenStream2, Vec<TokenStream2>) {
+        if let Some(tag) = self.attrs.tags().next() {
+            let ty = &tag.ty;
+            let discriminants = self
+                .expand_tag_indices(ty)
+                .map(|index| quote!(= #index))
+                .collect();
+
+            return (quote!(#[repr(#ty)]), discriminants);
+        }
+
+        if !self.is_repr_c() {
+            return (quote!(), self.expand_variants().map(|_| quote!()).collect());
+        }
+
+        let discriminants = self
+            .attrs
+            .versions()
+            .map(|attr| {
+                let tag = repr_c_tag(&attr.version).unwrap();
+                quote!(= #tag)
+            })
+            .collect();
+
+        (quote!(#[repr(C, u32)]), discriminants)
+    }
+
+    /// Match arms migrating each version of the version-tagged enum but the latest to the next.
+    fn expand_migration_arms<'a>(
+        &'a self,
+        versions: &'a [VersionAttr],
+    ) -> impl Iterator<Item = TokenStream2> + 'a {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let unboxed = self.expand_unbox(&quote!(x));
+
+        versions.windows(2).map(move |pair| {
+            let gate = self.expand_cfg_when(&pair[0].version);
+            let prev = ident.version(&pair[0].version);
+            let next = ident.version(&pair[1].version);
+            let prev_variant = self.variant(&pair[0].version);
+            let next_variant = self.variant(&pair[1].version);
+            let migrated = self.expand_box(
+                &quote!(::obake::__private::Migration::<#prev, #next>::migrate(#unboxed)),
+            );
+            quote!(#gate #enum_ident::#prev_variant(x) => #enum_ident::#next_variant(#migrated),)
+        })
+    }
+
+    /// Whether the migration to version `next`, with the type `definition`, is a `TryFrom`
+    /// implementation under `#[obake(fallible(...))]` (which never applies between aliased
+    /// versions, as they're the same type).
+    fn is_fallible_step(&self, next: &Tag, definition: &syn::Ident) -> bool {
+        *definition == self.ident().version(next)
+            && self.attrs.fallibles().any(|attr| attr.req.matches(next))
+    }
+
+    fn expand_from_impl(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        let ident = self.ident();
+        let latest = self.latest_variant();
+        let enum_ident = self.versioned_ident();
+        let unboxed = self.expand_unbox(&quote!(x));
+        let migrations = self.expand_migration_arms(versions);
+        let steps = versions.windows(2).zip(&definitions[1..]);
+        let assertions = steps
+            .filter(|(pair, definition)| !self.is_fallible_step(&pair[1].version, definition))
+            .map(|(pair, _)| {
+                let gate = self.expand_cfg_when(&pair[0].version);
+                let prev = ident.version(&pair[0].version);
+                let next = ident.version(&pair[1].version);
+                let message = format!(
+                    "missing migration from version {} to version {} of `{}`, implement \
+                 `From<{}![\"{}\"]> for {}![\"{}\"]`",
+                    pair[0].version,
+                    pair[1].version,
+                    ident,
+                    ident,
+                    pair[0].version,
+                    ident,
+                    pair[1].version,
+                );
+
+                quote_spanned! {pair[1].span=>
+                    #gate
+                    const _: () = {
+                        #[allow(unused_imports)]
+                        use ::obake::__private::MissingMigration as _;
+                        ::core::assert!(
+                            ::obake::__private::Migration::<#prev, #next>::EXISTS,
+                            #message,
+                        );
+                    };
+                }
+            });
+
+        let try_migrate_impl = self.expand_try_migrate_impl(
+            &quote!(#enum_ident),
+            &quote!(::obake::TryUpgrade::try_upgrade(self)),
+        );
+
+        if self.attrs.fallibles().next().is_some() {
+            let try_upgrade_impl = self.expand_try_upgrade_impl(versions, definitions);
+
+            // `VersionTagged` requires `Into<#ident>`, which can only panic on a failed step, so
+            // failing migrations are reported through `TryUpgrade` (and `TryMigrate`)
+            return quote! {
+                #(#assertions)*
+                #try_upgrade_impl
+
+                #[automatically_derived]
+                impl ::core::convert::From<#enum_ident> for #ident {
+                    #[inline]
+                    fn from(from: #enum_ident) -> Self {
+                        match ::obake::TryUpgrade::try_upgrade(from) {
+                            ::core::result::Result::Ok(x) => x,
+                            ::core::result::Result::Err(error) => ::core::panic!("{}", error),
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::obake::Migrate for #enum_ident {
+                    type Latest = #ident;
+
+                    #[inline]
+                    fn migrate(self) -> #ident {
+                        ::core::convert::Into::into(self)
+                    }
+                }
+
+                #try_migrate_impl
+            };
+        }
+
+        let infallible_try_upgrade_impl = self.expand_infallible_try_upgrade_impl();
+        quote! {
+            #(#assertions)*
+
+            #[automatically_derived]
+            impl ::core::convert::From<#enum_ident> for #ident {
+                #[inline]
+                fn from(mut from: #enum_ident) -> Self {
+                    #![allow(unreachable_code)]
+                    #[allow(unused_imports)]
+                    use ::obake::__private::MissingMigration as _;
+
+                    loop {
+                        from = match from {
+                            #(#migrations)*
+                            #enum_ident::#latest(x) => {
+                                return ::core::convert::Into::into(#unboxed)
+                            }
+                        };
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::obake::Migrate for #enum_ident {
+                type Latest = #ident;
+
+                #[inline]
+                fn migrate(self) -> #ident {
+                    ::core::convert::Into::into(self)
+                }
+            }
+
+            #infallible_try_upgrade_impl
+            #try_migrate_impl
+        }
+    }
+
+    /// `TryMigrate` for `ty`, the version-tagged enum or a version of this item, migrating it to
+    /// the latest version with `body`.
+    fn expand_try_migrate_impl(&self, ty: &TokenStream2, body: &TokenStream2) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::TryMigrate for #ty {
+                type Latest = #ident;
+                type Error = <#enum_ident as ::obake::TryUpgrade<#ident>>::Error;
+
+                #[inline]
+                fn try_migrate(
+                    self,
+                ) -> ::core::result::Result<#ident, ::obake::StepError<Self::Error>> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// `TryUpgrade` for items without fallible migrations, which never fails, so that they can be
+    /// migrated alongside fallible ones (e.g., by `obake::txn::migrate_all!`).
+    fn expand_infallible_try_upgrade_impl(&self) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::TryUpgrade<#ident> for #enum_ident {
+                type Error = ::core::convert::Infallible;
+
+                #[inline]
+                fn try_upgrade_with<S, F>(
+                    self,
+                    _: S,
+                    _: F,
+                ) -> ::core::result::Result<#ident, ::obake::StepError<Self::Error>>
+                where
+                    S: ::core::ops::FnMut(&Self) -> ::core::option::Option<Self>,
+                    F: ::core::ops::FnOnce(&::obake::StepError<Self::Error>, &Self),
+                {
+                    ::core::result::Result::Ok(::core::convert::Into::into(self))
+                }
+            }
+        }
+    }
+
+    /// `TryUpgrade` for items marked `#[obake(fallible(...))]`, which migrates through
+    /// `TryFrom` to the versions matching its requirement and `From` to the others.
+    fn expand_try_upgrade_impl(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        let ident = self.ident();
+        let latest = self.latest_variant();
+        let enum_ident = self.versioned_ident();
+        let error = &self.attrs.fallibles().next().unwrap().error;
+        let unboxed = self.expand_unbox(&quote!(x));
+        let boxed = self.expand_box(&quote!(x));
+        let mut arms = self.expand_migration_arms(versions);
+        let (snapshots, migrations): (Vec<_>, Vec<_>) = versions
+            .windows(2)
+            .zip(&definitions[1..])
+            .map(|(pair, definition)| {
+                let arm = arms.next().unwrap();
+                if !self.is_fallible_step(&pair[1].version, definition) {
+                    return (quote!(), arm);
+                }
+
+                let gate = self.expand_cfg_when(&pair[0].version);
+                let prev = ident.version(&pair[0].version);
+                let next = ident.version(&pair[1].version);
+                let prev_variant = self.variant(&pair[0].version);
+                let next_variant = self.variant(&pair[1].version);
+                let (from, to) = (pair[0].version.to_string(), pair[1].version.to_string());
+                let snapshot = quote!(#gate #enum_ident::#prev_variant(_) => snapshot(&self),);
+                let migration = quote! {
+                    #gate
+                    #enum_ident::#prev_variant(x) => {
+                        match <#next as ::core::convert::TryFrom<#prev>>::try_from(#unboxed) {
+                            ::core::result::Result::Ok(x) => #enum_ident::#next_variant(#boxed),
+                            ::core::result::Result::Err(error) => {
+                                let error = ::obake::StepError {
+                                    from: #from,
+                                    to: #to,
+                                    error: ::core::convert::Into::into(error),
+                                };
+                                if let ::core::option::Option::Some(before) = &before {
+                                    inspect(&error, before);
+                                }
+                                return ::core::result::Result::Err(error);
+                            }
+                        }
+                    }
+                };
+
+                (snapshot, migration)
+            })
+            .unzip();
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::TryUpgrade<#ident> for #enum_ident {
+                type Error = #error;
+
+                fn try_upgrade_with<S, F>(
+                    mut self,
+                    mut snapshot: S,
+                    inspect: F,
+                ) -> ::core::result::Result<#ident, ::obake::StepError<#error>>
+                where
+                    S: ::core::ops::FnMut(&Self) -> ::core::option::Option<Self>,
+                    F: ::core::ops::FnOnce(&::obake::StepError<#error>, &Self),
+                {
+                    #![allow(unreachable_code, unused_mut, unused_variables)]
+                    #[allow(unused_imports)]
+                    use ::obake::__private::MissingMigration as _;
+
+                    loop {
+                        // Fallible migrations consume the value they fail on, so a snapshot is
+                        // taken beforehand for `inspect`
+                        let before = match &self {
+                            #(#snapshots)*
+                            _ => ::core::option::Option::None,
+                        };
+
+                        self = match self {
+                            #(#migrations)*
+                            #enum_ident::#latest(x) => {
+                                return ::core::result::Result::Ok(::core::convert::Into::into(
+                                    #unboxed,
+                                ))
+                            }
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    fn expand_upgrade_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        // Partial upgrades have no way to report a failed migration
+        if self.attrs.fallibles().next().is_some() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let latest = self.latest_variant();
+        let enum_ident = self.versioned_ident();
+        let migrations = self.expand_migration_arms(versions);
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Upgrade<#ident> for #enum_ident {
+                #[inline]
+                fn upgrade(mut self, version: &str) -> ::core::option::Option<Self> {
+                    #![allow(unreachable_code)]
+                    #[allow(unused_imports)]
+                    use ::obake::__private::MissingMigration as _;
+
+                    let versions = <#ident as ::obake::Versioned>::VERSIONS;
+                    let position = |version: &str| versions.iter().position(|x| *x == version);
+                    if position(version)? < position(::obake::VersionTagged::version_str(&self))? {
+                        return ::core::option::Option::None;
+                    }
+
+                    loop {
+                        if ::obake::VersionTagged::version_str(&self) == version {
+                            return ::core::option::Option::Some(self);
+                        }
+
+                        self = match self {
+                            #(#migrations)*
+                            #enum_ident::#latest(_) => return ::core::option::Option::None,
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    fn expand_dense_migrations(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        if self.attrs.dense_migrations().next().is_none() {
+            return quote!();
+        }
+
+        // Aliased versions are the same type as the version they alias, so are skipped
+        let mut variants: Vec<_> = versions.iter().zip(definitions).collect();
+        variants.dedup_by(|(_, a), (_, b)| a == b);
+
+        // Each conversion skipping `n` versions is composed of the conversion skipping `n - 1`
+        // versions and the migration from the version preceding the target
+        let impls = (0..variants.len()).flat_map(|to| {
+            let variants = &variants;
+            (0..to.saturating_sub(1)).map(move |from| {
+                let gate = self.expand_cfg_when(&variants[from].0.version);
+                let from_ty = variants[from].1;
+                let to_ty = variants[to].1;
+                let prev_ty = variants[to - 1].1;
+
+                quote! {
+                    #gate
+                    #[automatically_derived]
+                    impl ::core::convert::From<#from_ty> for #to_ty {
+                        #[inline]
+                        fn from(from: #from_ty) -> Self {
+                            <#to_ty>::from(<#prev_ty>::from(from))
+                        }
+                    }
+                }
+            })
+        });
+
+        quote!(#(#impls)*)
+    }
+
+    /// Convert each older version directly into the type named after the data-structure,
+    /// upgrading it through the version-tagged enum.
+    ///
+    /// The latest version is that type, and the version preceding it already converts into it by
+    /// its own migration, as does every version under `#[obake(dense_migrations)]`.
+    /// The versions of fallible data-structures are left to be upgraded with `TryUpgrade`.
+    fn expand_upgrade_from_impls(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        if self.attrs.dense_migrations().next().is_some() || self.attrs.fallibles().next().is_some()
+        {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+
+        // Aliased versions are the same type as the version they alias, so are skipped
+        let mut variants: Vec<_> = versions.iter().zip(definitions).collect();
+        variants.dedup_by(|(_, a), (_, b)| a == b);
+        // With `#[obake(skip)]` fields, that type is distinct from the latest version, but
+        // already converts from it
+        let converted = if self.skipped_fields().is_empty() {
+            2
+        } else {
+            1
+        };
+        variants.truncate(variants.len().saturating_sub(converted));
+
+        let impls = variants.into_iter().map(|(attr, definition)| {
+            let gate = self.expand_cfg_when(&attr.version);
+
+            quote! {
+                #gate
+                #[automatically_derived]
+                impl ::core::convert::From<#definition> for #ident {
+                    #[inline]
+                    fn from(from: #definition) -> Self {
+                        <#ident>::from(<#enum_ident>::from(from))
+                    }
+                }
+            }
+        });
+
+        quote!(#(#impls)*)
+    }
+
+    /// Accessors on the version-tagged enum for each field marked `#[obake(getter)]`, borrowing
+    /// the field from the version held if that version has it.
+    fn expand_getters(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let fields = self.getter_fields();
+        if fields.is_empty() {
+            return quote!();
+        }
+
+        let enum_ident = self.versioned_ident();
+        let getters = fields.into_iter().map(|field| {
+            let vis = &field.vis;
+            let field_ident = &field.ident;
+            let ty = &field.ty;
+            let doc = format!(
+                "The `{}` field of the version held, if that version has it.",
+                field_ident.unraw()
+            );
+            #[cfg(feature = "zeroize")]
+            let value = if field.attrs.secrets().next().is_some() {
+                quote!(&*value.#field_ident)
+            } else {
+                quote!(&value.#field_ident)
+            };
+            #[cfg(not(feature = "zeroize"))]
+            let value = quote!(&value.#field_ident);
+            let arms = versions.iter().map(|attr| {
+                let gate = self.expand_cfg_when(&attr.version);
+                let variant = self.variant(&attr.version);
+                if field.attrs.is_enabled(&attr.version) {
+                    quote!(#gate Self::#variant(value) => ::core::option::Option::Some(#value),)
+                } else {
+                    quote!(#gate Self::#variant(_) => ::core::option::Option::None,)
+                }
+            });
+
+            quote! {
+                #[doc = #doc]
+                #[inline]
+                #[must_use]
+                #vis fn #field_ident(&self) -> ::core::option::Option<&#ty> {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        });
+
+        quote! {
+            impl #enum_ident {
+                #(#getters)*
+            }
+        }
+    }
+
+    fn expand_versioned_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let version_strs = versions.iter().map(|attr| attr.version.to_string());
+        let gates = versions
+            .iter()
+            .map(|attr| self.expand_cfg_when(&attr.version));
+        let scheme = match self.attrs.schemes().next().map(|attr| &attr.scheme) {
+            None | Some(Scheme::Semver) => quote!(::obake::scheme::Semver),
+            Some(Scheme::Integer) => quote!(::obake::scheme::Integer),
+            Some(Scheme::Calver) => quote!(::obake::scheme::Calver),
+            Some(Scheme::Custom(path)) => quote!(#path),
+        };
+
+        quote! {
+            impl ::obake::Versioned for #ident {
+                type Versioned = #enum_ident;
+
+                type Scheme = #scheme;
+
+                const VERSIONS: &'static [&'static str] = &[#(#gates #version_strs),*];
+            }
+        }
+    }
+
+    /// The types of the fields (of any version) marked `#[obake(inherit)]`.
+    fn inherited_tys(&self) -> Vec<&syn::Type> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.inherits().next().is_some())
+                .map(|field| &field.ty)
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .flat_map(|variant| match &variant.fields {
+                    VersionedVariantFields::Named(named) => named
+                        .fields
+                        .iter()
+                        .filter(|field| field.attrs.inherits().next().is_some())
+                        .map(|field| &field.ty)
+                        .collect(),
+                    VersionedVariantFields::Unnamed(unnamed) => unnamed
+                        .fields
+                        .iter()
+                        .filter(|field| field.attrs.inherits().next().is_some())
+                        .map(|field| &field.ty)
+                        .collect(),
+                    VersionedVariantFields::Unit => Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// `NoAlloc` for items marked `#[obake(no_alloc)]`, along with compile-time assertions that
+    /// the version-tagged enum fits within any `max_size`, and that the data-structures of
+    /// inherited fields are marked `#[obake(no_alloc)]` too.
+    fn expand_no_alloc_impl(&self) -> TokenStream2 {
+        let Some(no_alloc) = self.attrs.no_allocs().next() else {
+            return quote!();
+        };
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let max_size = no_alloc.max_size.iter().map(|max_size| {
+            let message = format!(
+                "`{enum_ident}` is larger than the `max_size` of {} bytes set by \
+                 `#[obake(no_alloc(...))]`",
+                max_size.base10_digits(),
+            );
+            quote!(::core::assert!(::core::mem::size_of::<#enum_ident>() <= #max_size, #message);)
+        });
+
+        let inherited = self.inherited_tys();
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::NoAlloc for #ident {}
+
+            const _: () = {
+                #(#max_size)*
+                #(::obake::assert_no_alloc::<#inherited>();)*
+            };
+        }
+    }
+
+    fn expand_version_tagged_impl(&self) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let variants = self.expand_variants();
+        let version_strs = self.attrs.versions().map(|attr| attr.version.to_string());
+        let gates = self
+            .attrs
+            .versions()
+            .map(|attr| self.expand_cfg_when(&attr.version));
+
+        #[cfg(feature = "defmt")]
+        let format_version_impl = self.expand_format_version_impl();
+        #[cfg(not(feature = "defmt"))]
+        let format_version_impl = quote!();
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::VersionTagged<#ident> for #enum_ident {
+                #[inline]
+                fn version_str(&self) -> &'static str {
+                    match self {
+                        #(#gates #enum_ident::#variants(_) => #version_strs,)*
+                    }
+                }
+            }
+
+            #format_version_impl
+        }
+    }
+
+    /// With `#[obake(defmt)]`, format the version of the version-tagged enum as a `defmt` format
+    /// string, which is interned, so costs only an index on the wire.
+    #[cfg(feature = "defmt")]
+    fn expand_format_version_impl(&self) -> TokenStream2 {
+        if self.attrs.defmts().next().is_none() {
+            return quote!();
+        }
+
+        let enum_ident = self.versioned_ident();
+        let variants = self.expand_variants();
+        let formats = self
+            .attrs
+            .versions()
+            .map(|attr| attr.version.label.replace('{', "{{").replace('}', "}}"));
+        let gates = self
+            .attrs
+            .versions()
+            .map(|attr| self.expand_cfg_when(&attr.version));
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::FormatVersion for #enum_ident {
+                fn format_version(&self, fmt: ::defmt::Formatter<'_>) {
+                    match self {
+                        #(#gates #enum_ident::#variants(_) => ::defmt::write!(fmt, #formats),)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(stable_hash)]`, hash every version as the latest version it migrates to.
+    fn expand_stable_hash_impl(&self) -> TokenStream2 {
+        if self.attrs.stable_hashes().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let mut variants: Vec<_> = self.expand_variants().collect();
+        let latest = variants.pop().unwrap();
+        // The latest version is never excluded by `#[obake(cfg_when(...))]`
+        let gates = self
+            .attrs
+            .versions()
+            .take(variants.len())
+            .map(|attr| self.expand_cfg_when(&attr.version));
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::StableHash for #enum_ident {
+                fn stable_hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                    match self {
+                        #(
+                            #gates
+                            #enum_ident::#variants(version) => {
+                                let version = ::core::clone::Clone::clone(version);
+                                let latest: #ident = #enum_ident::#variants(version).into();
+                                ::core::hash::Hash::hash(&latest, state);
+                            }
+                        )*
+                        #enum_ident::#latest(latest) => ::core::hash::Hash::hash(latest, state),
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::obake::StableHash for #ident {
+                #[inline]
+                fn stable_hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                    ::core::hash::Hash::hash(self, state);
+                }
+            }
+        }
+    }
+
+    fn expand_dispatch_impl(&self) -> TokenStream2 {
+        self.expand_per_cfg(|versions| self.expand_dispatch_impl_for(versions))
+    }
+
+    fn expand_dispatch_impl_for(&self, versions: &[&VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let variants: Vec<_> = versions
+            .iter()
+            .map(|attr| ident.version(&attr.version))
+            .collect();
+        let version_strs = versions.iter().map(|attr| attr.version.to_string());
+        let count = versions.len();
+        // Indices count every declared version, of which only the oldest can be excluded by
+        // `#[obake(cfg_when(...))]`, so the versions compiled are those from an offset on
+        let excluded = self.attrs.versions().count() - count;
+        let index = if excluded == 0 {
+            quote!(index)
+        } else {
+            quote!(index.checked_sub(#excluded)?)
+        };
+
+        quote! {
+            #[automatically_derived]
+            impl<Vis> ::obake::Dispatch<#ident, Vis> for #enum_ident
+            where
+                Vis: ::obake::VersionVisitor<#ident>
+                    #(+ ::obake::VisitVersion<#ident, #variants>)*,
+            {
+                #[inline]
+                fn dispatch(
+                    version: &str,
+                    visitor: Vis,
+                ) -> ::core::option::Option<<Vis as ::obake::VersionVisitor<#ident>>::Output> {
+                    match version {
+                        #(#version_strs => ::core::option::Option::Some(
+                            <Vis as ::obake::VisitVersion<#ident, #variants>>::visit(visitor),
+                        ),)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+
+                #[inline]
+                fn dispatch_index(
+                    index: usize,
+                    visitor: Vis,
+                ) -> ::core::option::Option<<Vis as ::obake::VersionVisitor<#ident>>::Output> {
+                    let table: [
+                        fn(Vis) -> <Vis as ::obake::VersionVisitor<#ident>>::Output;
+                        #count
+                    ] = [#(<Vis as ::obake::VisitVersion<#ident, #variants>>::visit),*];
+                    table.get(#index).map(|visit| visit(visitor))
+                }
+            }
+        }
+    }
+
+    fn expand_accept_impl(&self) -> TokenStream2 {
+        self.expand_per_cfg(|versions| self.expand_accept_impl_for(versions))
+    }
+
+    fn expand_accept_impl_for(&self, versions: &[&VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let tys: Vec<_> = versions
+            .iter()
+            .map(|attr| ident.version(&attr.version))
+            .collect();
+        let variants = versions.iter().map(|attr| self.variant(&attr.version));
+
+        quote! {
+            #[automatically_derived]
+            impl<Vis> ::obake::Accept<#ident, Vis> for #enum_ident
+            where
+                Vis: ::obake::VersionVisitor<#ident>
+                    #(+ ::obake::VisitValue<#ident, #tys>)*,
+            {
+                #[inline]
+                fn accept(&self, visitor: Vis) -> <Vis as ::obake::VersionVisitor<#ident>>::Output {
+                    match self {
+                        #(#enum_ident::#variants(x) =>
+                            <Vis as ::obake::VisitValue<#ident, #tys>>::visit_value(visitor, x),)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Migrations from each version to the next matching an `#[obake(auto_migrate)]`
+    /// constraint, moving (never cloning) the fields the two versions share and defaulting
+    /// those which are new.
+    fn expand_auto_migrations(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        let reqs: Vec<_> = self.attrs.auto_migrates().map(|attr| &attr.req).collect();
+        let migrations = (1..versions.len())
+            .filter(|&i| definitions[i] != definitions[i - 1])
+            .filter(|&i| reqs.iter().any(|req| req.matches(&versions[i].version)))
+            .map(|i| {
+                let prev = &versions[i - 1].version;
+                let next = &versions[i].version;
+                let prev_ty = &definitions[i - 1];
+                let next_ty = &definitions[i];
+                let body = match &self.kind {
+                    VersionedItemKind::Struct(inner) => {
+                        self.expand_struct_auto_migration(&inner.fields, prev, next, next_ty)
+                    }
+                    VersionedItemKind::Enum(inner) => {
+                        expand_enum_auto_migration(&inner.variants, prev, next, prev_ty)
+                    }
+                };
+                let gate = self.expand_cfg_when(prev);
+
+                quote! {
+                    #gate
+                    #[automatically_derived]
+                    impl ::core::convert::From<#prev_ty> for #next_ty {
+                        #[inline]
+                        #[allow(unused_variables)]
+                        fn from(from: #prev_ty) -> Self {
+                            #body
+                        }
+                    }
+                }
+            });
+
+        quote!(#(#migrations)*)
+    }
+
+    /// The body of a generated migration between consecutive versions of a `struct`.
+    fn expand_struct_auto_migration(
+        &self,
+        fields: &VersionedFields,
+        prev: &Tag,
+        next: &Tag,
+        next_ty: &syn::Ident,
+    ) -> TokenStream2 {
+        let has_version_default = self
+            .attrs
+            .version_defaults()
+            .any(|attr| attr.version.version == *next);
+        let fields = fields
+            .fields
+            .iter()
+            .filter(|field| field.attrs.is_enabled(next))
+            .map(|field| {
+                let ident = &field.ident;
+                let migrate_default = field
+                    .attrs
+                    .migrate_defaults()
+                    .find(|attr| attr.req.matches(next));
+                // Fields without a `Default` (e.g., `Box<dyn Trait>`) can declare their own
+                // default for the versions they're added in, and otherwise take the default of
+                // the version they're added in if it has its own
+                let default = || {
+                    migrate_default.map_or_else(
+                        || {
+                            if has_version_default {
+                                quote!(<#next_ty as ::core::default::Default>::default().#ident)
+                            } else {
+                                quote!(::core::default::Default::default())
+                            }
+                        },
+                        |attr| field.attrs.expand_secret_expr(&attr.expr),
+                    )
+                };
+
+                if !field.attrs.is_enabled(prev) {
+                    // A field redeclared with a new type converts from the field it replaces
+                    let retyped = migrate_default.is_none()
+                        && fields
+                            .fields
+                            .iter()
+                            .any(|other| other.ident == *ident && other.attrs.is_enabled(prev));
+                    if retyped {
+                        let ty = &field.ty;
+                        quote_spanned!(ty.span()=> #ident: ::core::convert::Into::into(from.#ident))
+                    } else {
+                        let default = default();
+                        quote!(#ident: #default)
+                    }
+                } else if field.attrs.inherits().next().is_some() {
+                    let from = quote!(from.#ident);
+                    let value = match (field.attrs.lifespan(prev), field.attrs.lifespan(next)) {
+                        (Lifespan::Within, Lifespan::Within) => {
+                            quote!(::core::convert::Into::into(#from))
+                        }
+                        (Lifespan::Within, _) => quote! {
+                            ::core::option::Option::Some(::core::convert::Into::into(#from))
+                        },
+                        (_, Lifespan::Within) => {
+                            let default = default();
+                            quote! {
+                                match #from {
+                                    ::core::option::Option::Some(from) => {
+                                        ::core::convert::Into::into(from)
+                                    }
+                                    ::core::option::Option::None => #default,
+                                }
+                            }
+                        }
+                        _ => quote! {
+                            ::core::option::Option::map(#from, ::core::convert::Into::into)
+                        },
+                    };
+                    quote!(#ident: #value)
+                } else {
+                    quote!(#ident: from.#ident)
+                }
+            });
+
+        quote! {
+            Self {
+                #(#fields,)*
+            }
+        }
+    }
+
+    /// `FromLatest` for each version whose fields are all present in the latest version.
+    fn expand_from_latest_impls(
+        &self,
+        versions: &[VersionAttr],
+        definitions: &[syn::Ident],
+    ) -> TokenStream2 {
+        let VersionedItemKind::Struct(inner) = &self.kind else {
+            return quote!();
+        };
+        if self.attrs.auto_downgrade_subsets().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let latest = &versions[versions.len() - 1].version;
+
+        // Aliased versions are the same type as the version they alias, so are skipped
+        let mut variants: Vec<_> = versions.iter().zip(definitions).collect();
+        variants.dedup_by(|(_, a), (_, b)| a == b);
+
+        let impls = variants.into_iter().filter_map(|(attr, definition)| {
+            let version = &attr.version;
+            let fields: Vec<_> = inner
+                .fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.is_enabled(version))
+                .collect();
+            // Inherited fields removed before the latest version can't be rebuilt either
+            let missing = |field: &&VersionedField| {
+                !field.attrs.is_enabled(latest)
+                    || matches!(field.attrs.lifespan(version), Lifespan::Within)
+                        && !matches!(field.attrs.lifespan(latest), Lifespan::Within)
+            };
+            if fields.iter().any(missing) {
+                return None;
+            }
+
+            let fields = fields.into_iter().map(|field| {
+                let ident = &field.ident;
+                if field.attrs.inherits().next().is_none() {
+                    return quote!(#ident: latest.#ident);
+                }
+
+                match field.attrs.lifespan(version) {
+                    Lifespan::Within => {
+                        quote!(#ident: ::obake::__private::from_latest_inherited(latest.#ident))
+                    }
+                    Lifespan::Before(_) => quote!(#ident: ::core::option::Option::None),
+                    Lifespan::After(_) => quote! {
+                        #ident: ::core::option::Option::map(
+                            latest.#ident,
+                            ::obake::__private::from_latest_inherited,
+                        )
+                    },
+                }
+            });
+            let gate = self.expand_cfg_when(version);
+
+            Some(quote! {
+                #gate
+                #[automatically_derived]
+                impl ::obake::FromLatest<#ident> for #definition {
+                    #[inline]
+                    #[allow(unused_variables)]
+                    fn from_latest(latest: #ident) -> Self {
+                        Self {
+                            #(#fields,)*
+                        }
+                    }
+                }
+            })
+        });
+
+        quote!(#(#impls)*)
+    }
+
+    fn expand_tag_indices<'a>(
+        &'a self,
+        ty: &'a syn::Ident,
+    ) -> impl Iterator<Item = syn::LitInt> + 'a {
+        self.attrs
+            .versions()
+            .enumerate()
+            .map(move |(i, attr)| syn::LitInt::new(&format!("{i}{ty}"), attr.span))
+    }
+
+    fn expand_tag_index_impl(&self) -> TokenStream2 {
+        let Some(tag) = self.attrs.tags().next() else {
+            return quote!();
+        };
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let ty = &tag.ty;
+        let variants = self.expand_variants();
+        let indices = self.expand_tag_indices(ty);
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::TagIndex<#ident> for #enum_ident {
+                type Index = #ty;
+
+                #[inline]
+                fn tag_index(&self) -> #ty {
+                    match self {
+                        #(#enum_ident::#variants(_) => #indices,)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// `Serialize` and `Deserialize` for the version-tagged enum, encoding it as a tuple of the
+    /// index of its version in `VERSIONS` and the value of that version.
+    #[cfg(feature = "serde")]
+    fn expand_tag_as_index_impls(&self) -> TokenStream2 {
+        if self.attrs.tag_as_indexes().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let variants: Vec<_> = self.expand_variants().collect();
+        let tys: Vec<_> = self.expand_version_idents().collect();
+        let indices: Vec<_> = self.expand_tag_indices(&format_ident!("u32")).collect();
+        let gates: Vec<_> = self
+            .attrs
+            .versions()
+            .map(|attr| self.expand_cfg_when(&attr.version))
+            .collect();
+        let boxed = self.expand_box(&quote!(x));
+        let expecting = format!("a version index and a value of that version of `{ident}`");
+        let out_of_bounds = format!("a version index less than {}", variants.len());
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::__private::serde::Serialize for #enum_ident {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: ::obake::__private::serde::Serializer,
+                {
+                    use ::obake::__private::serde::ser::SerializeTuple;
+
+                    let mut tuple = serializer.serialize_tuple(2)?;
+                    match self {
+                        #(#gates #enum_ident::#variants(x) => {
+                            let x: &#tys = x;
+                            tuple.serialize_element(&#indices)?;
+                            tuple.serialize_element(x)?;
+                        })*
+                    }
+                    tuple.end()
+                }
+            }
+
+            #[automatically_derived]
+            impl<'de> ::obake::__private::serde::Deserialize<'de> for #enum_ident {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: ::obake::__private::serde::Deserializer<'de>,
+                {
+                    use ::obake::__private::serde::de::{Error, SeqAccess, Unexpected, Visitor};
+
+                    struct TagAsIndex;
+
+                    impl<'de> Visitor<'de> for TagAsIndex {
+                        type Value = #enum_ident;
+
+                        fn expecting(
+                            &self,
+                            f: &mut ::core::fmt::Formatter<'_>,
+                        ) -> ::core::fmt::Result {
+                            f.write_str(#expecting)
+                        }
+
+                        fn visit_seq<A>(
+                            self,
+                            mut seq: A,
+                        ) -> ::core::result::Result<Self::Value, A::Error>
+                        where
+                            A: SeqAccess<'de>,
+                        {
+                            let index: u32 = seq
+                                .next_element()?
+                                .ok_or_else(|| Error::invalid_length(0, &self))?;
+                            let value = match index {
+                                #(#gates #indices => seq
+                                    .next_element::<#tys>()?
+                                    .map(|x| #enum_ident::#variants(#boxed)),)*
+                                _ => {
+                                    return Err(Error::invalid_value(
+                                        Unexpected::Unsigned(index.into()),
+                                        &#out_of_bounds,
+                                    ))
+                                }
+                            };
+                            value.ok_or_else(|| Error::invalid_length(1, &self))
+                        }
+                    }
+
+                    deserializer.deserialize_tuple(2, TagAsIndex)
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn expand_on_unrecognized_impl(&self) -> TokenStream2 {
+        let Some(on_unrecognized) = self.attrs.on_unrecognizeds().next() else {
+            return quote!();
+        };
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let path = &on_unrecognized.path;
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::OnUnrecognized for #ident {
+                #[inline]
+                fn on_unrecognized(payload: &[u8]) -> ::core::option::Option<#enum_ident> {
+                    #path(payload)
+                }
+            }
+        }
+    }
+
+    fn expand_downgrade_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.downgrades().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let boxed = self.expand_box(&quote!(from));
+        let cfg_when = self.attrs.cfg_whens().next();
+        let step = |i: usize, attr: &VersionAttr| {
+            let ty = ident.version(&attr.version);
+            let variant = self.variant(&attr.version);
+            let version_str = attr.version.to_string();
+            let convert = quote!(let from: #ty = ::core::convert::Into::into(from););
+
+            if i + 1 == versions.len() && cfg_when.is_none() {
+                quote! {
+                    #convert
+                    ::core::option::Option::Some(#enum_ident::#variant(#boxed))
+                }
+            } else {
+                quote! {
+                    #convert
+                    if version == #version_str {
+                        return ::core::option::Option::Some(#enum_ident::#variant(#boxed));
+                    }
+                }
+            }
+        };
+        let steps = versions.iter().rev().enumerate();
+        let steps = if let Some(cfg_when) = cfg_when {
+            // Gated versions are always the oldest, so are downgraded to last
+            let (gated, ungated): (Vec<_>, Vec<_>) =
+                steps.partition(|(_, attr)| cfg_when.req.matches(&attr.version));
+            let gated = gated.into_iter().map(|(i, attr)| step(i, attr));
+            let ungated = ungated.into_iter().map(|(i, attr)| step(i, attr));
+            let predicate = &cfg_when.predicate;
+
+            quote! {
+                #(#ungated)*
+                #[cfg(#predicate)]
+                {
+                    #(#gated)*
+                }
+                ::core::option::Option::None
+            }
+        } else {
+            let steps = steps.map(|(i, attr)| step(i, attr));
+            quote!(#(#steps)*)
+        };
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Downgrade<#ident> for #enum_ident {
+                #[inline]
+                fn downgrade(from: #ident, version: &str) -> ::core::option::Option<Self> {
+                    if !<#ident as ::obake::Versioned>::VERSIONS.contains(&version) {
+                        return ::core::option::Option::None;
+                    }
+
+                    #steps
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(display)]`, implement `Display` and `FromStr` for the version-tagged enum
+    /// using the canonical JSON envelope.
+    #[cfg(feature = "json")]
+    fn expand_display_impls(&self) -> TokenStream2 {
+        if self.attrs.displays().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+
+        quote! {
+            #[automatically_derived]
+            impl ::core::fmt::Display for #enum_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    let envelope = ::obake::json::to_string::<#ident>(self)
+                        .map_err(|_| ::core::fmt::Error)?;
+                    f.write_str(&envelope)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::str::FromStr for #enum_ident {
+                type Err = ::obake::json::Error;
+
+                #[inline]
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    ::obake::json::from_slice::<#ident>(s.as_bytes())
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn check_json_upgrade(&self) -> Result<()> {
+        let Some(json_upgrade) = self.attrs.json_upgrades().next() else {
+            return Ok(());
+        };
+
+        if let VersionedItemKind::Enum(_) = &self.kind {
+            return Err(syn::Error::new(
+                json_upgrade.span,
+                "`#[obake(json_upgrade)]` can only be applied to `struct`s",
+            ));
+        }
+
+        // Transparent versions aren't encoded as objects, so have no fields to edit
+        if self.attrs.transparents().next().is_some() {
+            return Err(syn::Error::new(
+                json_upgrade.span,
+                "`#[obake(json_upgrade)]` cannot be combined with `#[obake(transparent)]`",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// With `#[obake(json_upgrade)]`, implement `obake::json::UpgradeValue`, listing the edits
+    /// migrating the fields of a JSON payload between each pair of consecutive versions: fields
+    /// renamed (as deserialized by `serde`), dropped, or added with their defaults.
+    #[cfg(feature = "json")]
+    fn expand_json_upgrade_impl(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let VersionedItemKind::Struct(inner) = &self.kind else {
+            return Ok(quote!());
+        };
+
+        if self.attrs.json_upgrades().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let fields = &inner.fields.fields;
+        // Fields are matched across versions by identifier, so that a field redeclared with a new
+        // type keeps its value
+        let name_of = |names: &[(usize, String)], ident: &syn::Ident| {
+            names
+                .iter()
+                .find(|(i, _)| fields[*i].ident == *ident)
+                .map(|(_, name)| name.clone())
+        };
+        let steps = versions
+            .windows(2)
+            .map(|pair| {
+                let (prev, next) = (&pair[0].version, &pair[1].version);
+                let prev_names = self.wire_names(prev);
+                let next_names = self.wire_names(next);
+
+                let mut edits = Vec::new();
+                for (i, from) in &prev_names {
+                    match name_of(&next_names, &fields[*i].ident) {
+                        Some(to) if to != *from => edits.push(
+                            quote!(::obake::json::FieldEdit::Rename { from: #from, to: #to }),
+                        ),
+                        Some(_) => {}
+                        None => edits.push(quote!(::obake::json::FieldEdit::Drop(#from))),
+                    }
+                }
+                for (i, name) in &next_names {
+                    let field = &fields[*i];
+                    if name_of(&prev_names, &field.ident).is_some() {
+                        continue;
+                    }
+
+                    let ty = field.expand_ty_versioned(next)?;
+                    let default = field
+                        .attrs
+                        .migrate_defaults()
+                        .find(|attr| attr.req.matches(next))
+                        .map_or_else(
+                            || quote!(::core::default::Default::default()),
+                            |attr| attr.expr.to_token_stream(),
+                        );
+                    edits.push(quote! {
+                        ::obake::json::FieldEdit::Add {
+                            name: #name,
+                            default: || ::obake::__private::serde_json::to_value::<#ty>(#default),
+                        }
+                    });
+                }
+
+                let gate = self.expand_cfg_when(prev);
+                let (from, to) = (prev.to_string(), next.to_string());
+                Ok(quote! {
+                    #gate
+                    ::obake::json::ValueStep { from: #from, to: #to, edits: &[#(#edits),*] }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let ident = self.ident();
+        Ok(quote! {
+            #[automatically_derived]
+            impl ::obake::json::UpgradeValue for #ident {
+                const STEPS: &'static [::obake::json::ValueStep] = &[#(#steps),*];
+            }
+        })
+    }
+
+    #[cfg(feature = "wasm")]
+    fn expand_wasm_bindings(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.wasms().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let vis = &self.vis;
+        let snake = snake_case(&ident.to_string());
+        let upgrade_fn = format_ident!("upgrade_{}", snake);
+        let versions_fn = format_ident!("{}_versions", snake);
+        let typescript = format!(
+            "export type {}Version = {};",
+            ident,
+            versions
+                .iter()
+                .map(|attr| format!("\"{}\"", attr.version))
+                .collect::<Vec<_>>()
+                .join(" | "),
+        );
+
+        quote! {
+            const _: () = {
+                #[::wasm_bindgen::prelude::wasm_bindgen(typescript_custom_section)]
+                const TS_VERSIONS: &'static str = #typescript;
+            };
+
+            /// Upgrade a JSON payload of any declared version to the latest version.
+            #[::wasm_bindgen::prelude::wasm_bindgen]
+            #vis fn #upgrade_fn(
+                json: &str,
+            ) -> ::core::result::Result<
+                ::wasm_bindgen::JsValue,
+                ::wasm_bindgen::JsValue,
+            > {
+                ::obake::wasm::upgrade::<#ident>(json)
+            }
+
+            /// The declared versions, ordered from oldest to newest.
+            #[::wasm_bindgen::prelude::wasm_bindgen]
+            #vis fn #versions_fn() -> ::obake::wasm::Array {
+                ::obake::wasm::versions::<#ident>()
+            }
+        }
+    }
+
+    #[cfg(feature = "ts-rs")]
+    fn expand_ts_version_attrs(&self, version: &Tag) -> TokenStream2 {
+        if self.attrs.tss().next().is_none() {
+            return quote!();
+        }
+
+        let name = format!("{}V{}", self.ident(), version.suffix);
+
+        quote! {
+            #[derive(::obake::ts_rs::TS)]
+            #[ts(crate = "::obake::ts_rs", export, rename = #name)]
+        }
+    }
+
+    #[cfg(feature = "ts-rs")]
+    fn expand_ts_enum_attrs(&self) -> (TokenStream2, Vec<TokenStream2>) {
+        if self.attrs.tss().next().is_none() {
+            return (quote!(), self.expand_variants().map(|_| quote!()).collect());
+        }
+
+        // The envelope is described in its canonical JSON form, i.e.
+        // `{ "version": "x.y.z", "data": ... }`, rather than by variant name
+        let name = self.versioned_ident().to_string();
+        let variant_attrs = self
+            .attrs
+            .versions()
+            .map(|attr| {
+                let version = attr.version.to_string();
+                quote!(#[ts(rename = #version)])
+            })
+            .collect();
+
+        (
+            quote! {
+                #[derive(::obake::ts_rs::TS)]
+                #[ts(
+                    crate = "::obake::ts_rs",
+                    export,
+                    rename = #name,
+                    tag = "version",
+                    content = "data"
+                )]
+            },
+            variant_attrs,
+        )
+    }
+
+    #[cfg_attr(not(feature = "specta"), allow(clippy::unused_self))]
+    /// Documentation for the version-tagged enum and each of its variants, which are only
+    /// documented under `#[obake(public_enum)]`.
+    fn expand_versioned_enum_docs(&self) -> (TokenStream2, Vec<TokenStream2>) {
+        if self.attrs.public_enums().next().is_none() {
+            let variant_docs = self.expand_variants().map(|_| quote!()).collect();
+            return (self.expand_doc_hidden(), variant_docs);
+        }
+
+        let ident = self.ident();
+        let doc = format!(
+            "Every declared version of [`{ident}`], from oldest to newest.\n\n\
+             Each variant is named after the version it holds, so the variants can be matched \
+             on directly.",
+        );
+        let variant_docs = self
+            .attrs
+            .versions()
+            .map(|attr| {
+                let doc = format!(
+                    "Version `{}` of `{ident}` ([`{}`]).",
+                    attr.version,
+                    ident.version(&attr.version),
+                );
+                quote!(#[doc = #doc])
+            })
+            .collect();
+
+        (quote!(#[doc = #doc]), variant_docs)
+    }
+
+    fn expand_doc_hidden(&self) -> TokenStream2 {
+        if self.attrs.public_enums().next().is_some() {
+            return quote!();
+        }
+
+        // `specta` rejects `#[doc(...)]` attributes without a string literal, so generated
+        // types deriving `specta::Type` can't be hidden from documentation
+        #[cfg(feature = "specta")]
+        if self.attrs.spectas().next().is_some() {
+            return quote!();
+        }
+
+        quote!(#[doc(hidden)])
+    }
+
+    #[cfg(feature = "specta")]
+    fn expand_specta_attrs(&self, name: &str) -> TokenStream2 {
+        if self.attrs.spectas().next().is_none() {
+            return quote!();
+        }
+
+        // Unlike `ts-rs`, `specta` picks up `#[serde(...)]` attributes itself, so the
+        // generated bindings follow the serde representation of each type
+        quote! {
+            #[derive(::obake::specta::Type)]
+            #[specta(crate = obake::specta, rename = #name)]
+        }
+    }
+
+    /// With `#[obake(fake)]`, derive `fake::Dummy` for every version and the version-tagged
+    /// enum, which picks a version at random.
+    #[cfg(feature = "fake")]
+    fn expand_fake_attrs(&self) -> TokenStream2 {
+        if self.attrs.fakes().next().is_none() {
+            return quote!();
+        }
+
+        quote! {
+            #[derive(::obake::fake::Dummy)]
+            #[dummy(crate_name = "::obake::fake")]
+        }
+    }
+
+    #[cfg(feature = "pyo3")]
+    fn expand_pyclass_attr(&self, version: &Tag) -> TokenStream2 {
+        if self.attrs.pyo3s().next().is_none() {
+            return quote!();
+        }
+
+        let name = format!("{}V{}", self.ident(), version.suffix);
+        let accessors = match &self.kind {
+            VersionedItemKind::Struct(_) => quote!(get_all, set_all,),
+            VersionedItemKind::Enum(_) => quote!(),
+        };
+
+        quote! {
+            #[::obake::pyo3::pyclass(
+                crate = "::obake::pyo3",
+                name = #name,
+                #accessors
+                skip_from_py_object
+            )]
+        }
+    }
+
+    #[cfg(feature = "pyo3")]
+    fn expand_pyo3_bindings(&self) -> TokenStream2 {
+        if self.attrs.pyo3s().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let vis = &self.vis;
+        let register_fn = format_ident!("register_{}", snake_case(&ident.to_string()));
+        let versions = self.expand_version_idents();
+
+        quote! {
+            /// Add a class for every declared version and an `upgrade(data, version)` function,
+            /// migrating a `dict` of any declared version to the latest version, to `module`.
+            #vis fn #register_fn(
+                module: &::obake::pyo3::Bound<'_, ::obake::pyo3::types::PyModule>,
+            ) -> ::obake::pyo3::PyResult<()> {
+                use ::obake::pyo3::types::PyModuleMethods;
+
+                #[::obake::pyo3::pyfunction]
+                #[pyo3(crate = "::obake::pyo3", name = "upgrade")]
+                fn __obake_upgrade(
+                    data: &::obake::pyo3::Bound<'_, ::obake::pyo3::PyAny>,
+                    version: &str,
+                ) -> ::obake::pyo3::PyResult<#ident> {
+                    ::obake::python::upgrade::<#ident>(data, version)
+                }
+
+                #(module.add_class::<#versions>()?;)*
+                module.add_function(::obake::pyo3::wrap_pyfunction!(__obake_upgrade, module)?)
+            }
+        }
+    }
+
+    fn expand_repr_c_impl(&self) -> TokenStream2 {
+        if !self.is_repr_c() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let vis = &self.vis;
+        let enum_ident = self.versioned_ident();
+        let tag_ident = format_ident!("{}VersionTag", ident);
+        let upgrade_fn = format_ident!("upgrade_{}", snake_case(&ident.to_string()));
+        // Prefixed by the name of the crate, so that crates declaring data-structures of the
+        // same name can be linked together
+        let export_name = match std::env::var("CARGO_CRATE_NAME") {
+            Ok(krate) => format!("{krate}_{upgrade_fn}"),
+            Err(_) => upgrade_fn.to_string(),
+        };
+        let variants: Vec<_> = self.expand_variants().collect();
+        let tags = self
+            .attrs
+            .versions()
+            .map(|attr| repr_c_tag(&attr.version).unwrap());
+
+        quote! {
+            /// The stable version tag of the version-tagged `#[repr(C, u32)]` enum.
+            #[repr(u32)]
+            #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+            #vis enum #tag_ident {
+                #(
+                    #[allow(non_camel_case_types)]
+                    #variants = #tags,
+                )*
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<&#enum_ident> for #tag_ident {
+                #[inline]
+                fn from(from: &#enum_ident) -> Self {
+                    match from {
+                        #(#enum_ident::#variants(_) => #tag_ident::#variants,)*
+                    }
+                }
+            }
+
+            /// Migrate a value of any declared version to the latest version.
+            #[unsafe(export_name = #export_name)]
+            #vis extern "C" fn #upgrade_fn(from: #enum_ident) -> #ident {
+                from.into()
+            }
+        }
+    }
+
+    #[cfg(feature = "abi_stable")]
+    fn expand_stable_abi_derive(&self) -> TokenStream2 {
+        if self.attrs.abi_stables().next().is_none() {
+            return quote!();
+        }
+
+        quote!(#[derive(::abi_stable::StableAbi)])
+    }
+
+    #[cfg(feature = "abi_stable")]
+    fn expand_upgrade_table(&self) -> TokenStream2 {
+        if self.attrs.abi_stables().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let vis = &self.vis;
+        let enum_ident = self.versioned_ident();
+        let table_ident = format_ident!("{}UpgradeTable", ident);
+        let upgrade_fn = format_ident!("upgrade_{}", snake_case(&ident.to_string()));
+        let versions: Vec<_> = self.attrs.versions().collect();
+        let tys: Vec<_> = self.expand_version_idents().collect();
+        let fields: Vec<_> = versions
+            .iter()
+            .map(|attr| format_ident!("v{}", attr.version.suffix))
+            .collect();
+        let docs = versions
+            .iter()
+            .map(|attr| format!("Migrate version {} to the latest version.", attr.version));
+        let version_strs = versions.iter().map(|attr| attr.version.to_string());
+
+        quote! {
+            /// An FFI-safe table of functions migrating every declared version to the latest
+            /// version.
+            #[repr(C)]
+            #[derive(Copy, Clone, ::abi_stable::StableAbi)]
+            #vis struct #table_ident {
+                /// The declared versions.
+                pub versions: ::abi_stable::std_types::RSlice<
+                    'static,
+                    ::abi_stable::std_types::RStr<'static>,
+                >,
+                /// Migrate a value of any declared version to the latest version.
+                pub upgrade: extern "C" fn(#enum_ident) -> #ident,
+                #(
+                    #[doc = #docs]
+                    pub #fields: extern "C" fn(#tys) -> #ident,
+                )*
+            }
+
+            impl #table_ident {
+                /// The table of functions defined by this version of the data-structure.
+                pub const TABLE: Self = Self {
+                    versions: ::abi_stable::std_types::RSlice::from_slice(&[
+                        #(::abi_stable::std_types::RStr::from_str(#version_strs),)*
+                    ]),
+                    upgrade: #upgrade_fn,
+                    #(
+                        #fields: {
+                            extern "C" fn upgrade(from: #tys) -> #ident {
+                                #enum_ident::from(from).into()
+                            }
+                            upgrade
+                        },
+                    )*
+                };
+            }
+        }
+    }
+
+    #[cfg(feature = "uniffi")]
+    fn check_uniffi(&self) -> Result<()> {
+        let Some(uniffi) = self.attrs.uniffis().next() else {
+            return Ok(());
+        };
+
+        // Both generate an upgrade function with the same name
+        if self.is_repr_c() {
+            return Err(syn::Error::new(
+                uniffi.span,
+                "`#[obake(uniffi)]` cannot be combined with `#[obake(repr_c)]`",
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "uniffi")]
+    fn expand_uniffi_version_derive(&self) -> TokenStream2 {
+        if self.attrs.uniffis().next().is_none() {
+            return quote!();
+        }
+
+        match &self.kind {
+            VersionedItemKind::Struct(_) => quote!(#[derive(::uniffi::Record)]),
+            VersionedItemKind::Enum(_) => quote!(#[derive(::uniffi::Enum)]),
+        }
+    }
+
+    #[cfg(feature = "uniffi")]
+    fn expand_uniffi_enum_derive(&self) -> TokenStream2 {
+        if self.attrs.uniffis().next().is_none() {
+            return quote!();
+        }
+
+        quote!(#[derive(::uniffi::Enum)])
+    }
+
+    #[cfg(feature = "uniffi")]
+    fn expand_uniffi_bindings(&self) -> TokenStream2 {
+        if self.attrs.uniffis().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let vis = &self.vis;
+        let enum_ident = self.versioned_ident();
+        let upgrade_fn = format_ident!("upgrade_{}", snake_case(&ident.to_string()));
+
+        quote! {
+            /// Migrate a value of any declared version to the latest version.
+            #[::uniffi::export]
+            #vis fn #upgrade_fn(from: #enum_ident) -> #ident {
+                from.into()
+            }
+        }
+    }
+
+    #[cfg(feature = "utoipa")]
+    fn expand_utoipa_version_attrs(&self, version: &Tag) -> TokenStream2 {
+        if self.attrs.utoipas().next().is_none() {
+            return quote!();
+        }
+
+        let name = format_ident!("{}V{}", self.ident(), version.suffix);
+
+        quote! {
+            #[derive(::utoipa::ToSchema)]
+            #[schema(as = #name)]
+        }
+    }
+
+    #[cfg(feature = "utoipa")]
+    fn expand_utoipa_impl(&self) -> TokenStream2 {
+        if self.attrs.utoipas().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.versioned_ident();
+        let name = enum_ident.to_string();
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::utoipa::PartialSchema for #enum_ident {
+                fn schema() -> ::obake::utoipa::openapi::RefOr<
+                    ::obake::utoipa::openapi::schema::Schema,
+                > {
+                    ::obake::openapi::envelope_schema::<#ident>()
+                }
+            }
+
+            #[automatically_derived]
+            impl ::obake::utoipa::ToSchema for #enum_ident {
+                fn name() -> ::std::borrow::Cow<'static, str> {
+                    ::std::borrow::Cow::Borrowed(#name)
+                }
+
+                fn schemas(
+                    schemas: &mut ::std::vec::Vec<(
+                        ::std::string::String,
+                        ::obake::utoipa::openapi::RefOr<
+                            ::obake::utoipa::openapi::schema::Schema,
+                        >,
+                    )>,
+                ) {
+                    ::obake::openapi::version_schemas::<#ident>(schemas);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "tabular")]
+    fn check_tabular(&self) -> Result<()> {
+        let Some(tabular) = self.attrs.tabulars().next() else {
+            return Ok(());
+        };
+
+        if let VersionedItemKind::Enum(_) = &self.kind {
+            return Err(syn::Error::new(
+                tabular.span,
+                "`#[obake(tabular)]` can only be applied to `struct`s",
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tabular")]
+    #[cfg_attr(not(feature = "arrow"), allow(clippy::unnecessary_wraps))]
+    fn expand_tabular_impl(&self, version: &Tag) -> Result<TokenStream2> {
+        let VersionedItemKind::Struct(inner) = &self.kind else {
+            return Ok(quote!());
+        };
+
+        if self.attrs.tabulars().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let ident = self.ident().version(version);
+        let fields: Vec<_> = inner
+            .fields
+            .fields
+            .iter()
+            .filter(|field| field.attrs.is_enabled(version))
+            .collect();
+        let columns = fields.iter().map(|field| field.ident.to_string());
+
+        #[cfg(feature = "arrow")]
+        let arrow_impl = {
+            let arrow_fields = fields
+                .iter()
+                .map(|field| {
+                    let column = field.ident.to_string();
+                    let ty = field.expand_ty_versioned(version)?;
+                    Ok(quote!(::obake::tabular::arrow_field::<#ty>(#column)))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            quote! {
+                #[automatically_derived]
+                impl ::obake::tabular::ArrowSchema for #ident {
+                    fn arrow_fields() -> ::obake::arrow_schema::Fields {
+                        ::core::iter::Iterator::collect(::core::iter::IntoIterator::into_iter([
+                            #(#arrow_fields),*
+                        ]))
+                    }
+                }
+            }
+        };
+        #[cfg(not(feature = "arrow"))]
+        let arrow_impl = quote!();
+
+        Ok(quote! {
+            #[automatically_derived]
+            impl ::obake::tabular::Columns for #ident {
+                const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+            }
+
+            #arrow_impl
+        })
+    }
+
+    #[cfg(feature = "idl")]
+    fn expand_idl_impl(&self, version: &Tag) -> Result<TokenStream2> {
+        if self.attrs.idls().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let ident = self.ident().version(version);
+        let name = self.ident().to_string();
+        let named_fields = |fields: &VersionedFields| {
+            fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.is_enabled(version))
+                .map(|field| Ok((field.ident.to_string(), field.expand_ty_versioned(version)?)))
+                .collect::<Result<Vec<_>>>()
+        };
+        let expand_fields = |fields: &[(String, TokenStream2)]| {
+            let fields = fields.iter().map(
+                |(field_name, ty)| quote!(::obake::idl::Field::of::<#ty>(#field_name, format)),
+            );
+            quote!([#(#fields),*])
+        };
+
+        let mut tys = Vec::new();
+        let item = match &self.kind {
+            VersionedItemKind::Struct(inner) => {
+                let fields = named_fields(&inner.fields)?;
+                let expanded = expand_fields(&fields);
+                tys.extend(fields.into_iter().map(|(_, ty)| ty));
+                quote!(::obake::idl::Item::structure(#name, #expanded))
+            }
+            VersionedItemKind::Enum(inner) => {
+                let mut variants = Vec::new();
+                for variant in &inner.variants.variants {
+                    if !variant.attrs.is_enabled(version) {
+                        continue;
+                    }
+
+                    let variant_name = variant.ident.to_string();
+                    let fields = match &variant.fields {
+                        VersionedVariantFields::Named(named) => named_fields(named)?,
+                        VersionedVariantFields::Unnamed(unnamed) => unnamed
+                            .fields
+                            .iter()
+                            .enumerate()
+                            .map(|(i, field)| {
+                                Ok((format!("field_{i}"), field.expand_ty_versioned(version)?))
+                            })
+                            .collect::<Result<_>>()?,
+                        VersionedVariantFields::Unit => Vec::new(),
+                    };
+                    let expanded = expand_fields(&fields);
+                    tys.extend(fields.into_iter().map(|(_, ty)| ty));
+                    variants.push(quote!(::obake::idl::Variant::new(#variant_name, #expanded)));
+                }
+                quote!(::obake::idl::Item::enumeration(#name, [#(#variants),*]))
+            }
+        };
+
+        Ok(quote! {
+            #[automatically_derived]
+            impl ::obake::idl::IdlType for #ident {
+                fn idl_type(_: ::obake::idl::Format) -> ::obake::idl::TypeName {
+                    ::core::convert::From::from(#name)
+                }
+
+                fn idl_items(format: ::obake::idl::Format, schema: &mut ::obake::idl::Schema) {
+                    if schema.contains(#name) {
+                        return;
+                    }
+
+                    schema.push(#item);
+                    #(<#tys as ::obake::idl::IdlType>::idl_items(format, schema);)*
                 }
             }
         })
     }
 
-    fn expand_alias(&self) -> TokenStream2 {
-        let vis = &self.vis;
-        let ident = self.ident();
-        let alias = self.alias().unwrap();
+    #[cfg(feature = "async-graphql")]
+    fn check_async_graphql(&self) -> Result<()> {
+        let Some(async_graphql) = self.attrs.async_graphqls().next() else {
+            return Ok(());
+        };
 
-        quote!(#vis type #ident = #alias;)
-    }
+        if let VersionedItemKind::Enum(_) = &self.kind {
+            return Err(syn::Error::new(
+                async_graphql.span,
+                "`#[obake(async_graphql)]` can only be applied to `struct`s",
+            ));
+        }
 
-    fn expand_variants(&self) -> impl Iterator<Item = syn::Ident> + '_ {
-        self.attrs
-            .versions()
-            .map(move |attr| self.ident().version(&attr.version))
+        Ok(())
     }
 
-    fn expand_versioned_enum(&self) -> TokenStream2 {
-        let enum_ident = self.versioned_ident();
-        let vis = &self.vis;
-        let variants = self.expand_variants();
-        let derives = self.attrs.derives().map(|attr| {
-            let tokens = &attr.tokens;
-            quote!(#[derive(#tokens)])
-        });
-        #[cfg(feature = "serde")]
-        let derives = derives.chain(self.attrs.serdes().map(|attr| {
-            let tokens = &attr.tokens;
-            quote!(#[serde(#tokens)])
-        }));
+    #[cfg(feature = "async-graphql")]
+    fn expand_graphql_version_attrs(&self, version: &Tag) -> TokenStream2 {
+        if self.attrs.async_graphqls().next().is_none() {
+            return quote!();
+        }
+
+        let name = format!("{}V{}", self.ident(), version.suffix);
+        let input_name = format!("{name}Input");
 
         quote! {
-            #[doc(hidden)]
-            #(#derives)*
-            #[allow(clippy::enum_variant_names)]
-            #vis enum #enum_ident {
-                #(
-                    #[allow(non_camel_case_types)]
-                    #variants(#variants),
-                )*
-            }
+            #[derive(::async_graphql::SimpleObject, ::async_graphql::InputObject)]
+            #[graphql(name = #name, input_name = #input_name)]
         }
     }
 
-    fn expand_from_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+    #[cfg(feature = "async-graphql")]
+    fn expand_graphql_scalar(&self) -> TokenStream2 {
+        if self.attrs.async_graphqls().next().is_none() {
+            return quote!();
+        }
+
         let ident = self.ident();
-        let alias = self.alias().unwrap();
-        let enum_ident = self.versioned_ident();
-        let migrations = versions
-            .iter()
-            .skip(1)
-            .zip(self.expand_variants())
-            .map(|(attr, prev)| {
-                let next = ident.version(&attr.version);
-                quote!(#enum_ident::#prev(x) => #enum_ident::#next(x.into()),)
-            });
+        let vis = &self.vis;
+        let scalar_ident = format_ident!("Any{}", ident);
+        let name = scalar_ident.to_string();
+        let doc = format!(
+            "A GraphQL scalar accepting any declared version of `{ident}`, resolved to the \
+             latest version."
+        );
 
         quote! {
+            #[doc = #doc]
+            #vis struct #scalar_ident(pub #ident);
+
+            #[::async_graphql::Scalar(name = #name)]
+            impl ::async_graphql::ScalarType for #scalar_ident {
+                fn parse(
+                    value: ::async_graphql::Value,
+                ) -> ::async_graphql::InputValueResult<Self> {
+                    ::obake::graphql::parse::<#ident>(value)
+                        .map(#scalar_ident)
+                        .map_err(::async_graphql::InputValueError::custom)
+                }
+
+                fn to_value(&self) -> ::async_graphql::Value {
+                    ::obake::graphql::to_value(&self.0)
+                }
+            }
+
             #[automatically_derived]
-            impl ::core::convert::From<#enum_ident> for #ident {
+            impl ::core::convert::From<#scalar_ident> for #ident {
                 #[inline]
-                fn from(mut from: #enum_ident) -> Self {
-                    #![allow(unreachable_code)]
-                    loop {
-                        from = match from {
-                            #(#migrations)*
-                            #enum_ident::#alias(x) => return x,
-                        };
-                    }
+                fn from(from: #scalar_ident) -> Self {
+                    from.0
                 }
             }
         }
     }
 
-    fn expand_versioned_impl(&self) -> TokenStream2 {
+    /// Declare the `Foo!` macro naming each version by its version number.
+    ///
+    /// The arms of versions marked `#[obake(deprecated(...))]` name a deprecated alias of the
+    /// version instead, so that uses of the macro for those versions raise a warning.
+    fn expand_macro_rules(&self) -> Result<TokenStream2> {
+        if self.attrs.no_macros().next().is_some() {
+            return Ok(quote!());
+        }
+
         let ident = self.ident();
-        let enum_ident = self.versioned_ident();
+        let vis = &self.vis;
+        let mut aliases = Vec::new();
+        let rules = self
+            .attrs
+            .versions()
+            .zip(self.expand_version_idents())
+            .map(|(attr, variant)| {
+                let version = attr.version.to_string();
+                let deprecated = self
+                    .attrs
+                    .deprecateds()
+                    .find(|deprecated| deprecated.version == *attr);
+                let Some(deprecated) = deprecated else {
+                    return quote!([#version] => { #variant };);
+                };
 
-        quote! {
-            impl ::obake::Versioned for #ident {
-                type Versioned = #enum_ident;
+                let alias = format_ident!("__{}_deprecated", variant);
+                let note = deprecated.note.as_ref().map_or_else(
+                    || format!("version {version} of `{ident}` is deprecated"),
+                    syn::LitStr::value,
+                );
+                let gate = self.expand_cfg_when(&attr.version);
+                aliases.push(quote! {
+                    #gate
+                    #[doc(hidden)]
+                    #[deprecated(note = #note)]
+                    #[allow(non_camel_case_types)]
+                    #vis type #alias = #variant;
+                });
+
+                quote!([#version] => { #alias };)
+            })
+            .collect::<Vec<_>>();
+        let any_of_rules = self.expand_any_of_rules()?;
+
+        Ok(quote! {
+            #(#aliases)*
+
+            macro_rules! #ident {
+                #(#rules)*
+                #any_of_rules
             }
-        }
+        })
     }
 
-    fn expand_version_tagged_impl(&self) -> TokenStream2 {
+    /// The arms of the `Foo!` macro behind `obake::any_of!`, which collect the variant of the
+    /// version-tagged enum and the fields of each of the versions matched, from which
+    /// `obake::__any_of_pattern!` builds a pattern binding the fields common to them all.
+    fn expand_any_of_rules(&self) -> Result<TokenStream2> {
         let ident = self.ident();
         let enum_ident = self.versioned_ident();
-        let variants = self.expand_variants();
+        let rules = self
+            .attrs
+            .versions()
+            .map(|attr| {
+                let version = attr.version.to_string();
+                let variant = self.variant(&attr.version);
+                let definition = self.ident().version(&attr.version);
+
+                // Boxed versions can't be destructured, and `enum`s have no fields in common
+                let fields = match &self.kind {
+                    VersionedItemKind::Struct(inner) if !self.is_boxed() => {
+                        let fields = inner
+                            .fields
+                            .fields
+                            .iter()
+                            .filter(|field| field.attrs.is_enabled(&attr.version))
+                            .map(|field| {
+                                let ident = &field.ident;
+                                let ty = field.expand_ty_versioned(&attr.version)?;
+                                #[cfg(feature = "zeroize")]
+                                let ty = field.attrs.expand_secret(ty)?;
+                                Ok(quote!(#ident: #ty))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        quote!(, #definition { #(#fields),* })
+                    }
+                    _ => quote!(),
+                };
+
+                Ok(quote! {
+                    (@any_of $binding:ident [$($acc:tt)*] #version $(| $rest:tt)*) => {
+                        #ident!(@any_of $binding [$($acc)* (#enum_ident::#variant #fields)] $($rest)|*)
+                    };
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            #(#rules)*
+            (@any_of $binding:ident [$($acc:tt)*]) => {
+                ::obake::__any_of_pattern!($binding $($acc)*)
+            };
+        })
+    }
+
+    /// With `#[obake::extend_versions(base = ...)]`, the version-tagged enum holding any version of
+    /// either `base` or this item, upgrading the latest version of `base` through the oldest
+    /// version of this item.
+    fn expand_extension(&self, base: &syn::Path) -> TokenStream2 {
+        // Errors in the versions are reported by the item's own expansion
+        let Some(oldest) = self
+            .extract_versions()
+            .ok()
+            .and_then(|versions| versions.into_iter().next())
+        else {
+            return quote!();
+        };
+
+        let ident = self.ident();
+        let oldest = ident.version(&oldest.version);
+        let extended_ident = format_ident!("Extended{}", ident);
+        let vis = &self.vis;
+        let derives = self.attrs.derives().map(|attr| {
+            let tokens = &attr.tokens;
+            quote!(#[derive(#tokens)])
+        });
+        let base_name = base
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+        let doc = format!(
+            "Any version of `{ident}`, including the versions of `{base_name}` that it extends."
+        );
 
         quote! {
+            #[doc = #doc]
+            #(#derives)*
+            #vis enum #extended_ident {
+                /// A version of the extended data-structure.
+                Base(::obake::AnyVersion<#base>),
+                /// A version declared by this data-structure.
+                Extension(::obake::AnyVersion<#ident>),
+            }
+
             #[automatically_derived]
-            impl ::obake::VersionTagged<#ident> for #enum_ident {
+            impl ::obake::Extends for #ident {
+                type Base = #base;
+                type Extended = #extended_ident;
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<#ident> for #extended_ident {
+                #[inline]
+                fn from(from: #ident) -> Self {
+                    Self::Extension(::core::convert::From::from(from))
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<::obake::AnyVersion<#base>> for #extended_ident {
+                #[inline]
+                fn from(from: ::obake::AnyVersion<#base>) -> Self {
+                    Self::Base(from)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<::obake::AnyVersion<#ident>> for #extended_ident {
+                #[inline]
+                fn from(from: ::obake::AnyVersion<#ident>) -> Self {
+                    Self::Extension(from)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<#extended_ident> for #ident {
+                fn from(from: #extended_ident) -> Self {
+                    match from {
+                        #extended_ident::Base(base) => {
+                            let latest: #base = ::core::convert::Into::into(base);
+                            let oldest = <#oldest as ::core::convert::From<#base>>::from(latest);
+                            ::obake::AnyVersion::<#ident>::from(oldest).into()
+                        }
+                        #extended_ident::Extension(x) => x.into(),
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::obake::VersionTagged<#ident> for #extended_ident {
                 #[inline]
                 fn version_str(&self) -> &'static str {
-                    use ::obake::VersionOf;
                     match self {
-                        #(#enum_ident::#variants(_) => #variants::VERSION,)*
+                        Self::Base(x) => ::obake::VersionTagged::<#base>::version_str(x),
+                        Self::Extension(x) => ::obake::VersionTagged::<#ident>::version_str(x),
                     }
                 }
             }
         }
     }
 
-    fn expand_macro_rules(&self) -> TokenStream2 {
+    /// Write the formatted expansion of this item to the directories given by any
+    /// `#[obake(debug_expansion = "...")]` attributes, relative to the manifest directory of the
+    /// crate being compiled.
+    #[cfg(feature = "debug-expansion")]
+    fn write_debug_expansion(&self, expanded: &TokenStream2) -> Result<()> {
+        for attr in self.attrs.debug_expansions() {
+            let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+            let dir = std::path::Path::new(&manifest_dir).join(attr.path.value());
+            let file = syn::parse2::<syn::File>(expanded.clone())?;
+
+            std::fs::create_dir_all(&dir)
+                .and_then(|()| {
+                    std::fs::write(
+                        dir.join(format!("{}.rs", self.ident())),
+                        prettyplease::unparse(&file),
+                    )
+                })
+                .map_err(|err| {
+                    syn::Error::new(
+                        attr.path.span(),
+                        format!("failed to write expansion to `{}`: {}", dir.display(), err),
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Check the item's attributes are consistent with each other and its declared versions.
+    fn check_attrs(&self, versions: &[VersionAttr]) -> Result<()> {
+        self.check_repr_c(versions)?;
+        self.check_tag(versions)?;
+        self.check_cfg_when(versions)?;
+        self.check_auto_migrate(versions)?;
+        self.check_auto_downgrade_subset()?;
+        self.check_scheme()?;
+        self.check_public_enum(versions)?;
+        self.check_transparent()?;
+        self.check_skip()?;
+        self.check_getters()?;
+        self.check_lifespans(versions)?;
+        self.check_no_alloc()?;
+        self.check_notes(versions)?;
+        self.check_releases(versions)?;
+        self.check_deprecated(versions)?;
+        self.check_stables(versions)?;
+        self.check_version_defaults(versions)?;
+        self.check_assert_fields(versions)?;
+        self.check_fallible(versions)?;
+        #[cfg(feature = "alloc")]
+        self.check_boxed()?;
+        #[cfg(feature = "alloc")]
+        self.check_test_values(versions)?;
+        #[cfg(feature = "serde")]
+        self.check_tag_case()?;
+        #[cfg(feature = "json")]
+        self.check_json_upgrade()?;
+        #[cfg(feature = "uniffi")]
+        self.check_uniffi()?;
+        #[cfg(feature = "async-graphql")]
+        self.check_async_graphql()?;
+        #[cfg(feature = "tabular")]
+        self.check_tabular()?;
+
+        Ok(())
+    }
+
+    /// With `#[obake(register)]`, submit the data-structure to `obake::registry` under its name
+    /// (or the name given by `#[obake(register = "...")]`).
+    #[cfg(feature = "registry")]
+    fn expand_registration(&self) -> TokenStream2 {
+        let Some(register) = self.attrs.registers().next() else {
+            return quote!();
+        };
+
         let ident = self.ident();
-        let rules = self
-            .attrs
-            .versions()
-            .zip(self.expand_variants())
-            .map(|(attr, variant)| {
-                let version = attr.version.to_string();
-                quote!([#version] => { #variant };)
-            });
+        let name = register
+            .name
+            .as_ref()
+            .map_or_else(|| ident.unraw().to_string(), syn::LitStr::value);
 
         quote! {
-            macro_rules! #ident {
-                #(#rules)*
+            ::obake::inventory::submit! {
+                ::obake::registry::Registration::new(
+                    #name,
+                    <#ident as ::obake::Versioned>::VERSIONS,
+                    ::obake::registry::decode_latest::<#ident>,
+                )
             }
         }
     }
 
+    /// The items generated for an item by the binding features (e.g., `wasm` or `pyo3`).
+    #[allow(unused_variables, clippy::unused_self)]
+    fn expand_bindings(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        #[cfg(feature = "abi_stable")]
+        let upgrade_table = self.expand_upgrade_table();
+        #[cfg(not(feature = "abi_stable"))]
+        let upgrade_table = quote!();
+        #[cfg(feature = "uniffi")]
+        let uniffi_bindings = self.expand_uniffi_bindings();
+        #[cfg(not(feature = "uniffi"))]
+        let uniffi_bindings = quote!();
+        #[cfg(feature = "utoipa")]
+        let utoipa_impl = self.expand_utoipa_impl();
+        #[cfg(not(feature = "utoipa"))]
+        let utoipa_impl = quote!();
+        #[cfg(feature = "async-graphql")]
+        let graphql_scalar = self.expand_graphql_scalar();
+        #[cfg(not(feature = "async-graphql"))]
+        let graphql_scalar = quote!();
+        #[cfg(feature = "json")]
+        let display_impls = self.expand_display_impls();
+        #[cfg(not(feature = "json"))]
+        let display_impls = quote!();
+        #[cfg(feature = "wasm")]
+        let wasm_bindings = self.expand_wasm_bindings(versions);
+        #[cfg(not(feature = "wasm"))]
+        let wasm_bindings = quote!();
+        #[cfg(feature = "pyo3")]
+        let pyo3_bindings = self.expand_pyo3_bindings();
+        #[cfg(not(feature = "pyo3"))]
+        let pyo3_bindings = quote!();
+        #[cfg(feature = "registry")]
+        let registration = self.expand_registration();
+        #[cfg(not(feature = "registry"))]
+        let registration = quote!();
+
+        quote! {
+            #upgrade_table
+            #uniffi_bindings
+            #utoipa_impl
+            #graphql_scalar
+            #display_impls
+            #wasm_bindings
+            #pyo3_bindings
+            #registration
+        }
+    }
+
     fn expand(&self) -> TokenStream2 {
         try_expand!(self.check_preconditions());
 
         let versions = try_expand!(self.extract_versions());
-        let defs = try_expand!(versions
-            .iter()
-            .map(|attr| self.expand_version(&attr.version))
-            .collect::<Result<Vec<_>>>())
-        .into_iter();
+        try_expand!(self.check_attrs(&versions));
+        let rule_warnings = try_expand!(self.expand_rules(&versions));
+        let lockfile = try_expand!(self.expand_lockfile(&versions));
+        let definitions = try_expand!(self.expand_definitions(&versions));
+        let defs = try_expand!(self.expand_versions(&versions, &definitions));
 
-        let alias_decl = self.expand_alias();
+        let version_of_impls = self.expand_version_of_impls(&versions, &definitions);
+        let alias_decl = try_expand!(self.expand_alias(&versions));
         let enum_decl = self.expand_versioned_enum();
-        let from_impl = self.expand_from_impl(&versions);
-        let versioned_impl = self.expand_versioned_impl();
+        let from_impl = self.expand_from_impl(&versions, &definitions);
+        let upgrade_impl = self.expand_upgrade_impl(&versions);
+        let versioned_impl = self.expand_versioned_impl(&versions);
+        let coverage_impl = self.expand_coverage_impl(&versions);
+        let releases_impl = self.expand_releases_impl(&versions);
+        let stability_impl = self.expand_stability_impl(&versions);
+        let inherits_impl = self.expand_inherits_impl();
+        let field_names_impls = self.expand_field_names_impls(&versions, &definitions);
+        let migrations_impl = self.expand_migrations_impl(&versions);
         let version_tagged_impl = self.expand_version_tagged_impl();
-        let macro_rules = self.expand_macro_rules();
+        let dispatch_impl = self.expand_dispatch_impl();
+        let accept_impl = self.expand_accept_impl();
+        let downgrade_impl = self.expand_downgrade_impl(&versions);
+        let auto_migrations = self.expand_auto_migrations(&versions, &definitions);
+        let from_latest_impls = self.expand_from_latest_impls(&versions, &definitions);
+        let dense_migrations = self.expand_dense_migrations(&versions, &definitions);
+        let upgrade_from_impls = self.expand_upgrade_from_impls(&versions, &definitions);
+        let getters = self.expand_getters(&versions);
+        let version_defaults = try_expand!(self.expand_version_defaults(&versions, &definitions));
+        let tag_index_impl = self.expand_tag_index_impl();
+        #[cfg(feature = "serde")]
+        let tag_as_index_impls = self.expand_tag_as_index_impls();
+        #[cfg(not(feature = "serde"))]
+        let tag_as_index_impls = quote!();
+        #[cfg(feature = "serde")]
+        let on_unrecognized_impl = self.expand_on_unrecognized_impl();
+        #[cfg(not(feature = "serde"))]
+        let on_unrecognized_impl = quote!();
+        #[cfg(feature = "json")]
+        let json_upgrade_impl = try_expand!(self.expand_json_upgrade_impl(&versions));
+        #[cfg(not(feature = "json"))]
+        let json_upgrade_impl = quote!();
+        #[cfg(feature = "alloc")]
+        let test_values_impl = self.expand_test_values_impl(&versions, &definitions);
+        #[cfg(not(feature = "alloc"))]
+        let test_values_impl = quote!();
+        let repr_c_impl = self.expand_repr_c_impl();
+        let bindings = self.expand_bindings(&versions);
+        let no_alloc_impl = self.expand_no_alloc_impl();
+        let stable_hash_impl = self.expand_stable_hash_impl();
+        let macro_rules = try_expand!(self.expand_macro_rules());
 
-        quote! {
+        let expanded = quote! {
             #(#defs)*
+            #version_of_impls
             #alias_decl
             #enum_decl
             #from_impl
+            #upgrade_impl
             #versioned_impl
+            #coverage_impl
+            #releases_impl
+            #stability_impl
+            #inherits_impl
+            #field_names_impls
+            #migrations_impl
             #version_tagged_impl
+            #stable_hash_impl
+            #dispatch_impl
+            #accept_impl
+            #downgrade_impl
+            #auto_migrations
+            #from_latest_impls
+            #dense_migrations
+            #upgrade_from_impls
+            #getters
+            #version_defaults
+            #tag_index_impl
+            #tag_as_index_impls
+            #on_unrecognized_impl
+            #json_upgrade_impl
+            #test_values_impl
+            #repr_c_impl
+            #bindings
+            #no_alloc_impl
             #macro_rules
+            #rule_warnings
+            #lockfile
+        };
+
+        #[cfg(feature = "debug-expansion")]
+        try_expand!(self.write_debug_expansion(&expanded));
+
+        expanded
+    }
+}
+
+/// The variant of `variants` present in `version` named `ident`, if any.
+fn variant_in<'a>(
+    variants: &'a VersionedVariants,
+    ident: &syn::Ident,
+    version: &Tag,
+) -> Option<&'a VersionedVariant> {
+    variants
+        .variants
+        .iter()
+        .find(|variant| variant.ident == *ident && variant.attrs.is_enabled(version))
+}
+
+/// The body of a generated migration between consecutive versions of an `enum`, mapping each
+/// variant to the variant of the same name in the next version.
+///
+/// The payload of a variant redeclared with different fields (e.g., `X(u32)` until some version
+/// and `X(u64)` from it) is converted with `Into`, as are inherited fields; otherwise fields are
+/// moved, and named fields new to the next version are set with `Default::default()`.
+fn expand_enum_auto_migration(
+    variants: &VersionedVariants,
+    prev: &Tag,
+    next: &Tag,
+    prev_ty: &syn::Ident,
+) -> TokenStream2 {
+    // Conversions are spanned to the type converted to, where a missing `From` is reported
+    let convert = |value: TokenStream2, ty: &syn::Type, convert: bool| {
+        if convert {
+            quote_spanned!(ty.span()=> ::core::convert::Into::into(#value))
+        } else {
+            value
+        }
+    };
+    let arms = variants
+        .variants
+        .iter()
+        .filter(|variant| variant.attrs.is_enabled(prev))
+        .filter_map(|variant| {
+            let ident = &variant.ident;
+            // Checked by `check_auto_migrate`
+            let target = variant_in(variants, ident, next)?;
+            let retyped = !core::ptr::eq(variant, target);
+
+            Some(match (&variant.fields, &target.fields) {
+                (VersionedVariantFields::Unnamed(from), VersionedVariantFields::Unnamed(to)) => {
+                    let bindings: Vec<_> = (0..from.fields.len())
+                        .map(|i| format_ident!("field_{}", i))
+                        .collect();
+                    let values = bindings.iter().zip(&to.fields).map(|(binding, field)| {
+                        let inherited = field.attrs.inherits().next().is_some();
+                        convert(quote!(#binding), &field.ty, retyped || inherited)
+                    });
+                    quote!(#prev_ty::#ident(#(#bindings),*) => Self::#ident(#(#values),*))
+                }
+                (VersionedVariantFields::Named(from), VersionedVariantFields::Named(to)) => {
+                    let mut bindings = Vec::new();
+                    let values: Vec<_> = to
+                        .fields
+                        .iter()
+                        .filter(|field| field.attrs.is_enabled(next))
+                        .map(|field| {
+                            let field_ident = &field.ident;
+                            let source = from.fields.iter().find(|other| {
+                                other.ident == *field_ident && other.attrs.is_enabled(prev)
+                            });
+                            let value = if let Some(source) = source {
+                                bindings.push(field_ident);
+                                let inherited = field.attrs.inherits().next().is_some();
+                                convert(
+                                    quote!(#field_ident),
+                                    &field.ty,
+                                    retyped || !core::ptr::eq(source, field) || inherited,
+                                )
+                            } else {
+                                quote!(::core::default::Default::default())
+                            };
+                            quote!(#field_ident: #value)
+                        })
+                        .collect();
+                    quote! {
+                        #prev_ty::#ident { #(#bindings,)* .. } => Self::#ident { #(#values,)* }
+                    }
+                }
+                _ => quote!(#prev_ty::#ident => Self::#ident),
+            })
+        });
+
+    quote! {
+        match from {
+            #(#arms,)*
+        }
+    }
+}
+
+/// Check that every variant of an `enum` present in a version migrated to by
+/// `#[obake(auto_migrate)]` has a counterpart of the same name and shape in that version.
+fn check_enum_auto_migrate(
+    variants: &VersionedVariants,
+    auto_migrate: &AutoMigrateAttr,
+    versions: &[VersionAttr],
+) -> Result<()> {
+    for pair in versions.windows(2) {
+        let (prev, next) = (&pair[0].version, &pair[1].version);
+        if !auto_migrate.req.matches(next) {
+            continue;
+        }
+
+        for variant in &variants.variants {
+            if !variant.attrs.is_enabled(prev) {
+                continue;
+            }
+
+            let ident = &variant.ident;
+            let Some(target) = variant_in(variants, ident, next) else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "`#[obake(auto_migrate)]` cannot migrate variant `{ident}` of version \
+                         {prev}, which is removed in version {next}; write the migration by hand"
+                    ),
+                ));
+            };
+
+            let compatible = match (&variant.fields, &target.fields) {
+                (VersionedVariantFields::Unnamed(from), VersionedVariantFields::Unnamed(to)) => {
+                    from.fields.len() == to.fields.len()
+                }
+                (VersionedVariantFields::Named(_), VersionedVariantFields::Named(_))
+                | (VersionedVariantFields::Unit, VersionedVariantFields::Unit) => true,
+                _ => false,
+            };
+            if !compatible {
+                return Err(syn::Error::new(
+                    target.ident.span(),
+                    format!(
+                        "`#[obake(auto_migrate)]` cannot migrate variant `{ident}` of version \
+                         {prev} to version {next}, whose fields differ in kind (or number); \
+                         write the migration by hand"
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The visibility of a re-export from a module nested in the item's own module, ranked by how
+/// widely it's visible.
+fn dto_vis(vis: &syn::Visibility) -> Result<(u8, TokenStream2)> {
+    match vis {
+        syn::Visibility::Inherited => Ok((0, quote!(pub(super)))),
+        syn::Visibility::Crate(_) => Ok((1, quote!(pub(crate)))),
+        syn::Visibility::Restricted(restricted)
+            if restricted.in_token.is_none() && restricted.path.is_ident("crate") =>
+        {
+            Ok((1, quote!(pub(crate))))
+        }
+        syn::Visibility::Public(_) => Ok((2, quote!(pub))),
+        syn::Visibility::Restricted(_) => Err(syn::Error::new_spanned(
+            vis,
+            "`#[obake(dto_modules)]` only supports private, `pub(crate)` and `pub` items",
+        )),
+    }
+}
+
+/// A module for each version declared by any of `items` marked with `#[obake(dto_modules)]`,
+/// re-exporting that version of each of them under its unversioned name.
+fn expand_dto_modules(items: &[VersionedItem]) -> TokenStream2 {
+    let module_ident = |version: &Tag| format_ident!("v{}", version.suffix);
+    let mut modules: Vec<(Tag, u8, Vec<TokenStream2>)> = Vec::new();
+
+    for item in items {
+        if item.attrs.dto_moduleses().next().is_none() {
+            continue;
+        }
+
+        // Errors in the versions are reported by the item's own expansion
+        let Ok(versions) = item.extract_versions() else {
+            continue;
+        };
+        let (rank, vis) = match dto_vis(&item.vis) {
+            Ok(vis) => vis,
+            Err(err) => return err.into_compile_error(),
+        };
+        let ident = item.ident();
+
+        for attr in versions {
+            let gate = item.expand_cfg_when(&attr.version);
+            let definition = ident.version(&attr.version);
+            let reexport = quote!(#gate #vis use super::#definition as #ident;);
+
+            let module = module_ident(&attr.version);
+            match modules
+                .iter_mut()
+                .find(|(version, ..)| module_ident(version) == module)
+            {
+                Some((_, max_rank, reexports)) => {
+                    *max_rank = (*max_rank).max(rank);
+                    reexports.push(reexport);
+                }
+                None => modules.push((attr.version, rank, vec![reexport])),
+            }
         }
     }
+
+    modules.sort_by(|(a, ..), (b, ..)| a.cmp_precedence(b));
+    let modules = modules.into_iter().map(|(version, rank, reexports)| {
+        let module = module_ident(&version);
+        let vis = match rank {
+            0 => quote!(),
+            1 => quote!(pub(crate)),
+            _ => quote!(pub),
+        };
+        let doc = format!("Version {version} of each data-structure.");
+
+        quote! {
+            #[doc = #doc]
+            #vis mod #module {
+                #(#reexports)*
+            }
+        }
+    });
+
+    quote!(#(#modules)*)
 }
 
 impl ToTokens for VersionedItem {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         tokens.append_all(self.expand());
+        tokens.append_all(expand_dto_modules(std::slice::from_ref(self)));
+    }
+}
+
+impl ToTokens for ExtendedItem {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        self.item.to_tokens(tokens);
+        tokens.append_all(self.item.expand_extension(&self.base));
+    }
+}
+
+impl ToTokens for VersionPath {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let label = self.label.value();
+
+        // The scheme of the data-structure isn't known here, but each names its versions after
+        // the version as written, except that semantic versioning drops any pre-release or build
+        // metadata
+        let suffix = match Version::parse(&label) {
+            Ok(version) => format!("{}_{}_{}", version.major, version.minor, version.patch),
+            Err(_) => label
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect(),
+        };
+
+        let mut path = self.path.clone();
+        if let Some(last) = path.segments.last_mut() {
+            last.ident = format_ident!("{}_v{}", last.ident, suffix);
+        }
+
+        path.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for AnyOfPattern {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let binding = &self.binding;
+        let same_ty = |a: &syn::Type, b: &syn::Type| {
+            a.to_token_stream().to_string() == b.to_token_stream().to_string()
+        };
+
+        // The fields present in every matched version, with the same type in each
+        let common: Vec<&syn::Ident> = match self.versions.first() {
+            Some(AnyOfVersion {
+                definition: Some((_, first)),
+                ..
+            }) => first
+                .named
+                .iter()
+                .filter(|field| {
+                    self.versions.iter().all(|version| {
+                        version.definition.as_ref().is_some_and(|(_, fields)| {
+                            fields.named.iter().any(|other| {
+                                other.ident == field.ident && same_ty(&other.ty, &field.ty)
+                            })
+                        })
+                    })
+                })
+                .filter_map(|field| field.ident.as_ref())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        // Bound at the call site of `obake::any_of!`, rather than hygienically within `Foo!`
+        let bindings = common.iter().map(|field| {
+            let mut bound = (*field).clone();
+            bound.set_span(binding.span());
+            quote!(#field: ref #bound)
+        });
+        let bindings = quote!(#(#bindings,)*);
+        let alternatives = self.versions.iter().map(|version| {
+            let variant = &version.variant;
+            version.definition.as_ref().map_or_else(
+                || quote!(#variant(_)),
+                |(definition, _)| quote!(#variant(#definition { #bindings .. })),
+            )
+        });
+
+        tokens.extend(quote!(ref #binding @ (#(#alternatives)|*)));
+    }
+}
+
+impl ToTokens for VersionedItems {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        tokens.append_all(self.items.iter().map(VersionedItem::expand));
+        tokens.append_all(expand_dto_modules(&self.items));
     }
 }
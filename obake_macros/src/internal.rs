@@ -1,13 +1,71 @@
 use syn::Token;
 
-pub use proc_macro::TokenStream;
 pub use proc_macro2::{Span, TokenStream as TokenStream2};
 
 pub use semver::{Version, VersionReq};
 
+/// A declared version, written as `label` under the item's version scheme and ordered among
+/// the item's other versions by `version`.
 #[derive(Clone)]
-pub struct VersionAttr {
+pub struct Tag {
     pub version: Version,
+    pub label: String,
+    /// Appended to the item's name to name this version of it (e.g., `0_1_0` in `Foo_v0_1_0`).
+    pub suffix: String,
+}
+
+impl std::ops::Deref for Tag {
+    type Target = Version;
+
+    fn deref(&self) -> &Version {
+        &self.version
+    }
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version
+    }
+}
+
+impl Eq for Tag {}
+
+impl PartialOrd for Tag {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tag {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.version.cmp(&other.version)
+    }
+}
+
+#[derive(Clone)]
+pub enum Scheme {
+    Semver,
+    Integer,
+    Calver,
+    /// A user-defined scheme, whose versions are ordered as they're declared.
+    Custom(syn::Path),
+}
+
+#[derive(Clone)]
+pub struct SchemeAttr {
+    pub span: Span,
+    pub scheme: Scheme,
+}
+
+#[derive(Clone)]
+pub struct VersionAttr {
+    pub version: Tag,
     pub span: Span,
 }
 
@@ -21,108 +79,1045 @@ impl Eq for VersionAttr {}
 
 impl PartialOrd for VersionAttr {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.version.partial_cmp(&other.version)
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VersionAttr {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.version.cmp(&other.version)
+    }
+}
+
+#[derive(Clone)]
+pub struct CfgAttr {
+    pub req: VersionReq,
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct ConditionalAttr {
+    pub req: VersionReq,
+    pub tokens: TokenStream2,
+}
+
+#[derive(Clone)]
+pub struct InheritAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DeriveAttr {
+    pub span: Span,
+    pub tokens: TokenStream2,
+}
+
+#[derive(Clone)]
+pub struct DeriveBoundsAttr {
+    pub span: Span,
+    pub predicates: syn::punctuated::Punctuated<syn::WherePredicate, Token![,]>,
+}
+
+#[derive(Clone)]
+pub struct DowngradeAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct ReprCAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DenseMigrationsAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct AliasIdenticalAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct TagAttr {
+    pub span: Span,
+    pub ty: syn::Ident,
+}
+
+#[derive(Clone)]
+pub struct NameAttr {
+    pub span: Span,
+    pub ident: syn::Ident,
+}
+
+#[derive(Clone)]
+pub struct DtoModulesAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct AutoDowngradeSubsetAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct NoAllocAttr {
+    pub span: Span,
+    pub max_size: Option<syn::LitInt>,
+}
+
+#[derive(Clone)]
+pub struct NoMacroAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct StableHashAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct TransparentAttr {
+    pub span: Span,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum RuleLevel {
+    Deny,
+    Warn,
+}
+
+#[derive(Clone)]
+pub enum RuleKind {
+    NoRemovalWithoutMajor,
+    NewFieldsHaveDefaults,
+    MaxChain(usize),
+}
+
+#[derive(Clone)]
+pub struct Rule {
+    pub span: Span,
+    pub level: RuleLevel,
+    pub kind: RuleKind,
+}
+
+#[derive(Clone)]
+pub struct LockfileAttr {
+    pub span: Span,
+    pub path: syn::LitStr,
+    pub key: Option<syn::LitStr>,
+}
+
+#[derive(Clone)]
+pub struct RulesAttr {
+    pub span: Span,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Clone)]
+pub struct PublicEnumAttr {
+    pub span: Span,
+    pub variants: Option<syn::LitStr>,
+}
+
+#[derive(Clone)]
+pub struct SkipAttr {
+    pub span: Span,
+    pub expr: Option<syn::Expr>,
+}
+
+#[derive(Clone)]
+pub struct MigrateDefaultAttr {
+    pub span: Span,
+    pub req: VersionReq,
+    pub expr: syn::Expr,
+}
+
+#[derive(Clone)]
+pub struct NoteAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub note: syn::LitStr,
+}
+
+#[derive(Clone)]
+pub struct SinceAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+}
+
+#[derive(Clone)]
+pub struct UntilAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+}
+
+#[derive(Clone)]
+pub struct GetterAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DeprecatedAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub note: Option<syn::LitStr>,
+}
+
+#[derive(Clone)]
+pub struct StableAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+}
+
+#[derive(Clone)]
+pub struct StabilityAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct VersionDefaultAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub ctor: syn::Path,
+}
+
+#[derive(Clone)]
+pub struct AssertFieldsAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub fields: syn::punctuated::Punctuated<syn::Ident, Token![,]>,
+}
+
+#[derive(Clone)]
+pub struct ReleasedAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub date: syn::LitStr,
+}
+
+#[derive(Clone)]
+pub struct FallibleAttr {
+    pub span: Span,
+    pub req: VersionReq,
+    pub error: syn::Type,
+}
+
+#[derive(Clone)]
+pub struct CfgWhenAttr {
+    pub span: Span,
+    pub predicate: syn::Meta,
+    pub req: VersionReq,
+}
+
+#[derive(Clone)]
+pub struct AutoMigrateAttr {
+    pub span: Span,
+    pub req: VersionReq,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct BoxedAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct TestValuesAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub path: syn::Path,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct SerdeAttr {
+    pub span: Span,
+    pub tokens: TokenStream2,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct StrictnessAttr {
+    pub span: Span,
+    pub deny: bool,
+    pub req: Option<VersionReq>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct TagAsIndexAttr {
+    pub span: Span,
+}
+
+/// The case of the tags given to versions by `#[obake(serde(tag_case = "..."))]`.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TagCase {
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct TagCaseAttr {
+    pub span: Span,
+    pub case: TagCase,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct OnUnrecognizedAttr {
+    pub span: Span,
+    pub path: syn::Path,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct ExternalAttr {
+    pub span: Span,
+    pub req: VersionReq,
+    pub serde_shim: syn::Path,
+}
+
+#[cfg(feature = "json")]
+#[derive(Clone)]
+pub struct DisplayAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "json")]
+#[derive(Clone)]
+pub struct JsonUpgradeAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "wasm")]
+#[derive(Clone)]
+pub struct WasmAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "ts-rs")]
+#[derive(Clone)]
+pub struct TsAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "specta")]
+#[derive(Clone)]
+pub struct SpectaAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "pyo3")]
+#[derive(Clone)]
+pub struct Pyo3Attr {
+    pub span: Span,
+}
+
+#[cfg(feature = "abi_stable")]
+#[derive(Clone)]
+pub struct AbiStableAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "uniffi")]
+#[derive(Clone)]
+pub struct UniffiAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "utoipa")]
+#[derive(Clone)]
+pub struct UtoipaAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "async-graphql")]
+#[derive(Clone)]
+pub struct AsyncGraphqlAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "tabular")]
+#[derive(Clone)]
+pub struct TabularAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "debug-expansion")]
+#[derive(Clone)]
+pub struct DebugExpansionAttr {
+    pub span: Span,
+    pub path: syn::LitStr,
+}
+
+#[cfg(feature = "idl")]
+#[derive(Clone)]
+pub struct IdlAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "zeroize")]
+#[derive(Clone)]
+pub struct SecretAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "defmt")]
+#[derive(Clone)]
+pub struct DefmtAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "fake")]
+#[derive(Clone)]
+pub struct FakeAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "registry")]
+#[derive(Clone)]
+pub struct RegisterAttr {
+    pub span: Span,
+    pub name: Option<syn::LitStr>,
+}
+
+#[derive(Clone)]
+pub enum ObakeAttribute {
+    Version(VersionAttr),
+    Cfg(CfgAttr),
+    Conditional(ConditionalAttr),
+    Inherit(InheritAttr),
+    Derive(DeriveAttr),
+    DeriveBounds(DeriveBoundsAttr),
+    Downgrade(DowngradeAttr),
+    ReprC(ReprCAttr),
+    DenseMigrations(DenseMigrationsAttr),
+    AliasIdentical(AliasIdenticalAttr),
+    Tag(TagAttr),
+    AutoMigrate(AutoMigrateAttr),
+    AutoDowngradeSubset(AutoDowngradeSubsetAttr),
+    DtoModules(DtoModulesAttr),
+    MigrateDefault(MigrateDefaultAttr),
+    Note(NoteAttr),
+    Released(ReleasedAttr),
+    Deprecated(DeprecatedAttr),
+    Stable(StableAttr),
+    Stability(StabilityAttr),
+    VersionDefault(VersionDefaultAttr),
+    AssertFields(AssertFieldsAttr),
+    Fallible(FallibleAttr),
+    Name(NameAttr),
+    CfgWhen(CfgWhenAttr),
+    Scheme(SchemeAttr),
+    NoAlloc(NoAllocAttr),
+    NoMacro(NoMacroAttr),
+    StableHash(StableHashAttr),
+    Transparent(TransparentAttr),
+    Rules(RulesAttr),
+    Lockfile(LockfileAttr),
+    PublicEnum(PublicEnumAttr),
+    Skip(SkipAttr),
+    Since(SinceAttr),
+    Until(UntilAttr),
+    Getter(GetterAttr),
+    #[cfg(feature = "alloc")]
+    Boxed(BoxedAttr),
+    #[cfg(feature = "alloc")]
+    TestValues(TestValuesAttr),
+    #[cfg(feature = "serde")]
+    Serde(SerdeAttr),
+    #[cfg(feature = "serde")]
+    Strictness(StrictnessAttr),
+    #[cfg(feature = "serde")]
+    TagAsIndex(TagAsIndexAttr),
+    #[cfg(feature = "serde")]
+    TagCase(TagCaseAttr),
+    #[cfg(feature = "serde")]
+    OnUnrecognized(OnUnrecognizedAttr),
+    #[cfg(feature = "serde")]
+    External(ExternalAttr),
+    #[cfg(feature = "json")]
+    Display(DisplayAttr),
+    #[cfg(feature = "json")]
+    JsonUpgrade(JsonUpgradeAttr),
+    #[cfg(feature = "wasm")]
+    Wasm(WasmAttr),
+    #[cfg(feature = "ts-rs")]
+    Ts(TsAttr),
+    #[cfg(feature = "specta")]
+    Specta(SpectaAttr),
+    #[cfg(feature = "pyo3")]
+    Pyo3(Pyo3Attr),
+    #[cfg(feature = "abi_stable")]
+    AbiStable(AbiStableAttr),
+    #[cfg(feature = "uniffi")]
+    Uniffi(UniffiAttr),
+    #[cfg(feature = "utoipa")]
+    Utoipa(UtoipaAttr),
+    #[cfg(feature = "async-graphql")]
+    AsyncGraphql(AsyncGraphqlAttr),
+    #[cfg(feature = "tabular")]
+    Tabular(TabularAttr),
+    #[cfg(feature = "idl")]
+    Idl(IdlAttr),
+    #[cfg(feature = "debug-expansion")]
+    DebugExpansion(DebugExpansionAttr),
+    #[cfg(feature = "zeroize")]
+    Secret(SecretAttr),
+    #[cfg(feature = "defmt")]
+    Defmt(DefmtAttr),
+    #[cfg(feature = "fake")]
+    Fake(FakeAttr),
+    #[cfg(feature = "registry")]
+    Register(RegisterAttr),
+}
+
+#[derive(Clone)]
+pub struct VersionedField {
+    pub attrs: VersionedAttributes,
+    pub vis: syn::Visibility,
+    pub ident: syn::Ident,
+    pub colon_token: Token![:],
+    pub ty: syn::Type,
+}
+
+#[derive(Clone)]
+pub enum VersionedAttribute {
+    Obake(ObakeAttribute),
+    Attribute(syn::Attribute),
+}
+
+#[derive(Clone)]
+pub struct VersionedAttributes {
+    pub attrs: Vec<VersionedAttribute>,
+}
+
+impl ObakeAttribute {
+    pub fn version(&self) -> Option<&VersionAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Version(version) => Some(version),
+            _ => None,
+        }
+    }
+
+    pub fn cfg(&self) -> Option<&CfgAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Cfg(cfg) => Some(cfg),
+            _ => None,
+        }
+    }
+
+    pub fn conditional(&self) -> Option<&ConditionalAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Conditional(conditional) => Some(conditional),
+            _ => None,
+        }
+    }
+
+    pub fn inherit(&self) -> Option<&InheritAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Inherit(inherit) => Some(inherit),
+            _ => None,
+        }
+    }
+
+    pub fn derive(&self) -> Option<&DeriveAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Derive(derive) => Some(derive),
+            _ => None,
+        }
+    }
+
+    pub fn derive_bounds(&self) -> Option<&DeriveBoundsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::DeriveBounds(derive_bounds) => Some(derive_bounds),
+            _ => None,
+        }
+    }
+
+    pub fn downgrade(&self) -> Option<&DowngradeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Downgrade(downgrade) => Some(downgrade),
+            _ => None,
+        }
+    }
+
+    pub fn repr_c(&self) -> Option<&ReprCAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::ReprC(repr_c) => Some(repr_c),
+            _ => None,
+        }
+    }
+
+    pub fn dense_migrations(&self) -> Option<&DenseMigrationsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::DenseMigrations(dense_migrations) => Some(dense_migrations),
+            _ => None,
+        }
+    }
+
+    pub fn alias_identical(&self) -> Option<&AliasIdenticalAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AliasIdentical(alias_identical) => Some(alias_identical),
+            _ => None,
+        }
+    }
+
+    pub fn tag(&self) -> Option<&TagAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Tag(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    pub fn auto_migrate(&self) -> Option<&AutoMigrateAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AutoMigrate(auto_migrate) => Some(auto_migrate),
+            _ => None,
+        }
+    }
+
+    pub fn auto_downgrade_subset(&self) -> Option<&AutoDowngradeSubsetAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AutoDowngradeSubset(auto_downgrade_subset) => {
+                Some(auto_downgrade_subset)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn dto_modules(&self) -> Option<&DtoModulesAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::DtoModules(dto_modules) => Some(dto_modules),
+            _ => None,
+        }
+    }
+
+    pub fn migrate_default(&self) -> Option<&MigrateDefaultAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::MigrateDefault(migrate_default) => Some(migrate_default),
+            _ => None,
+        }
+    }
+
+    pub fn note(&self) -> Option<&NoteAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Note(note) => Some(note),
+            _ => None,
+        }
+    }
+
+    pub fn released(&self) -> Option<&ReleasedAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Released(released) => Some(released),
+            _ => None,
+        }
+    }
+
+    pub fn deprecated(&self) -> Option<&DeprecatedAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Deprecated(deprecated) => Some(deprecated),
+            _ => None,
+        }
+    }
+
+    pub fn stable(&self) -> Option<&StableAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Stable(stable) => Some(stable),
+            _ => None,
+        }
+    }
+
+    pub fn stability(&self) -> Option<&StabilityAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Stability(stability) => Some(stability),
+            _ => None,
+        }
+    }
+
+    pub fn version_default(&self) -> Option<&VersionDefaultAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::VersionDefault(version_default) => Some(version_default),
+            _ => None,
+        }
+    }
+
+    pub fn assert_fields(&self) -> Option<&AssertFieldsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AssertFields(assert_fields) => Some(assert_fields),
+            _ => None,
+        }
+    }
+
+    pub fn fallible(&self) -> Option<&FallibleAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Fallible(fallible) => Some(fallible),
+            _ => None,
+        }
+    }
+
+    pub fn cfg_when(&self) -> Option<&CfgWhenAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::CfgWhen(cfg_when) => Some(cfg_when),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> Option<&NameAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn scheme(&self) -> Option<&SchemeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Scheme(scheme) => Some(scheme),
+            _ => None,
+        }
+    }
+
+    pub fn no_alloc(&self) -> Option<&NoAllocAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::NoAlloc(no_alloc) => Some(no_alloc),
+            _ => None,
+        }
+    }
+
+    pub fn no_macro(&self) -> Option<&NoMacroAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::NoMacro(no_macro) => Some(no_macro),
+            _ => None,
+        }
+    }
+
+    pub fn stable_hash(&self) -> Option<&StableHashAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::StableHash(stable_hash) => Some(stable_hash),
+            _ => None,
+        }
+    }
+
+    pub fn transparent(&self) -> Option<&TransparentAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Transparent(transparent) => Some(transparent),
+            _ => None,
+        }
+    }
+
+    pub fn rules(&self) -> Option<&RulesAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Rules(rules) => Some(rules),
+            _ => None,
+        }
+    }
+
+    pub fn lockfile(&self) -> Option<&LockfileAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Lockfile(lockfile) => Some(lockfile),
+            _ => None,
+        }
+    }
+
+    pub fn public_enum(&self) -> Option<&PublicEnumAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::PublicEnum(public_enum) => Some(public_enum),
+            _ => None,
+        }
+    }
+
+    pub fn skip(&self) -> Option<&SkipAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Skip(skip) => Some(skip),
+            _ => None,
+        }
+    }
+
+    pub fn since(&self) -> Option<&SinceAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Since(since) => Some(since),
+            _ => None,
+        }
+    }
+
+    pub fn until(&self) -> Option<&UntilAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Until(until) => Some(until),
+            _ => None,
+        }
+    }
+
+    pub fn getter(&self) -> Option<&GetterAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Getter(getter) => Some(getter),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn boxed(&self) -> Option<&BoxedAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Boxed(boxed) => Some(boxed),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn test_values(&self) -> Option<&TestValuesAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::TestValues(test_values) => Some(test_values),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serde(&self) -> Option<&SerdeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Serde(serde) => Some(serde),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn strictness(&self) -> Option<&StrictnessAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Strictness(strictness) => Some(strictness),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn tag_as_index(&self) -> Option<&TagAsIndexAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::TagAsIndex(tag_as_index) => Some(tag_as_index),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn tag_case(&self) -> Option<&TagCaseAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::TagCase(tag_case) => Some(tag_case),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn on_unrecognized(&self) -> Option<&OnUnrecognizedAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::OnUnrecognized(on_unrecognized) => Some(on_unrecognized),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn external(&self) -> Option<&ExternalAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::External(external) => Some(external),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn display(&self) -> Option<&DisplayAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Display(display) => Some(display),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn json_upgrade(&self) -> Option<&JsonUpgradeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::JsonUpgrade(json_upgrade) => Some(json_upgrade),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn wasm(&self) -> Option<&WasmAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Wasm(wasm) => Some(wasm),
+            _ => None,
+        }
     }
-}
 
-impl Ord for VersionAttr {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.version.cmp(&other.version)
+    #[cfg(feature = "ts-rs")]
+    pub fn ts(&self) -> Option<&TsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Ts(ts) => Some(ts),
+            _ => None,
+        }
     }
-}
 
-#[derive(Clone)]
-pub struct CfgAttr {
-    pub req: VersionReq,
-    pub span: Span,
-}
+    #[cfg(feature = "specta")]
+    pub fn specta(&self) -> Option<&SpectaAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Specta(specta) => Some(specta),
+            _ => None,
+        }
+    }
 
-#[derive(Clone)]
-pub struct InheritAttr {
-    pub span: Span,
-}
+    #[cfg(feature = "pyo3")]
+    pub fn pyo3(&self) -> Option<&Pyo3Attr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Pyo3(pyo3) => Some(pyo3),
+            _ => None,
+        }
+    }
 
-#[derive(Clone)]
-pub struct DeriveAttr {
-    pub span: Span,
-    pub tokens: TokenStream2,
-}
+    #[cfg(feature = "abi_stable")]
+    pub fn abi_stable(&self) -> Option<&AbiStableAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AbiStable(abi_stable) => Some(abi_stable),
+            _ => None,
+        }
+    }
 
-#[cfg(feature = "serde")]
-#[derive(Clone)]
-pub struct SerdeAttr {
-    pub span: Span,
-    pub tokens: TokenStream2,
-}
+    #[cfg(feature = "uniffi")]
+    pub fn uniffi(&self) -> Option<&UniffiAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Uniffi(uniffi) => Some(uniffi),
+            _ => None,
+        }
+    }
 
-#[derive(Clone)]
-pub enum ObakeAttribute {
-    Version(VersionAttr),
-    Cfg(CfgAttr),
-    Inherit(InheritAttr),
-    Derive(DeriveAttr),
-    #[cfg(feature = "serde")]
-    Serde(SerdeAttr),
-}
+    #[cfg(feature = "utoipa")]
+    pub fn utoipa(&self) -> Option<&UtoipaAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Utoipa(utoipa) => Some(utoipa),
+            _ => None,
+        }
+    }
 
-#[derive(Clone)]
-pub struct VersionedField {
-    pub attrs: VersionedAttributes,
-    pub vis: syn::Visibility,
-    pub ident: syn::Ident,
-    pub colon_token: Token![:],
-    pub ty: syn::Type,
-}
+    #[cfg(feature = "async-graphql")]
+    pub fn async_graphql(&self) -> Option<&AsyncGraphqlAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AsyncGraphql(async_graphql) => Some(async_graphql),
+            _ => None,
+        }
+    }
 
-#[derive(Clone)]
-pub enum VersionedAttribute {
-    Obake(ObakeAttribute),
-    Attribute(syn::Attribute),
-}
+    #[cfg(feature = "tabular")]
+    pub fn tabular(&self) -> Option<&TabularAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Tabular(tabular) => Some(tabular),
+            _ => None,
+        }
+    }
 
-#[derive(Clone)]
-pub struct VersionedAttributes {
-    pub attrs: Vec<VersionedAttribute>,
-}
+    #[cfg(feature = "idl")]
+    pub fn idl(&self) -> Option<&IdlAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Idl(idl) => Some(idl),
+            _ => None,
+        }
+    }
 
-impl ObakeAttribute {
-    pub fn version(&self) -> Option<&VersionAttr> {
+    #[cfg(feature = "debug-expansion")]
+    pub fn debug_expansion(&self) -> Option<&DebugExpansionAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
         match &self {
-            ObakeAttribute::Version(version) => Some(version),
+            ObakeAttribute::DebugExpansion(debug_expansion) => Some(debug_expansion),
             _ => None,
         }
     }
 
-    pub fn cfg(&self) -> Option<&CfgAttr> {
+    #[cfg(feature = "zeroize")]
+    pub fn secret(&self) -> Option<&SecretAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
         match &self {
-            ObakeAttribute::Cfg(cfg) => Some(cfg),
+            ObakeAttribute::Secret(secret) => Some(secret),
             _ => None,
         }
     }
 
-    pub fn inherit(&self) -> Option<&InheritAttr> {
+    #[cfg(feature = "defmt")]
+    pub fn defmt(&self) -> Option<&DefmtAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
         match &self {
-            ObakeAttribute::Inherit(inherit) => Some(inherit),
+            ObakeAttribute::Defmt(defmt) => Some(defmt),
             _ => None,
         }
     }
 
-    pub fn derive(&self) -> Option<&DeriveAttr> {
+    #[cfg(feature = "fake")]
+    pub fn fake(&self) -> Option<&FakeAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
         match &self {
-            ObakeAttribute::Derive(derive) => Some(derive),
+            ObakeAttribute::Fake(fake) => Some(fake),
             _ => None,
         }
     }
 
-    #[cfg(feature = "serde")]
-    pub fn serde(&self) -> Option<&SerdeAttr> {
+    #[cfg(feature = "registry")]
+    pub fn register(&self) -> Option<&RegisterAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
         match &self {
-            ObakeAttribute::Serde(serde) => Some(serde),
+            ObakeAttribute::Register(register) => Some(register),
             _ => None,
         }
     }
@@ -159,6 +1154,10 @@ impl VersionedAttributes {
         self.obake().filter_map(ObakeAttribute::cfg)
     }
 
+    pub fn conditionals(&self) -> impl Iterator<Item = &ConditionalAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::conditional)
+    }
+
     pub fn inherits(&self) -> impl Iterator<Item = &InheritAttr> + '_ {
         self.obake().filter_map(ObakeAttribute::inherit)
     }
@@ -167,11 +1166,260 @@ impl VersionedAttributes {
         self.obake().filter_map(ObakeAttribute::derive)
     }
 
+    pub fn derive_boundses(&self) -> impl Iterator<Item = &DeriveBoundsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::derive_bounds)
+    }
+
+    pub fn downgrades(&self) -> impl Iterator<Item = &DowngradeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::downgrade)
+    }
+
+    pub fn repr_cs(&self) -> impl Iterator<Item = &ReprCAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::repr_c)
+    }
+
+    pub fn dense_migrations(&self) -> impl Iterator<Item = &DenseMigrationsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::dense_migrations)
+    }
+
+    pub fn alias_identicals(&self) -> impl Iterator<Item = &AliasIdenticalAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::alias_identical)
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = &TagAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::tag)
+    }
+
+    pub fn auto_migrates(&self) -> impl Iterator<Item = &AutoMigrateAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::auto_migrate)
+    }
+
+    pub fn auto_downgrade_subsets(&self) -> impl Iterator<Item = &AutoDowngradeSubsetAttr> + '_ {
+        self.obake()
+            .filter_map(ObakeAttribute::auto_downgrade_subset)
+    }
+
+    pub fn dto_moduleses(&self) -> impl Iterator<Item = &DtoModulesAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::dto_modules)
+    }
+
+    pub fn migrate_defaults(&self) -> impl Iterator<Item = &MigrateDefaultAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::migrate_default)
+    }
+
+    pub fn notes(&self) -> impl Iterator<Item = &NoteAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::note)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &NameAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::name)
+    }
+
+    pub fn schemes(&self) -> impl Iterator<Item = &SchemeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::scheme)
+    }
+
+    pub fn no_allocs(&self) -> impl Iterator<Item = &NoAllocAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::no_alloc)
+    }
+
+    pub fn no_macros(&self) -> impl Iterator<Item = &NoMacroAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::no_macro)
+    }
+
+    pub fn stable_hashes(&self) -> impl Iterator<Item = &StableHashAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::stable_hash)
+    }
+
+    pub fn transparents(&self) -> impl Iterator<Item = &TransparentAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::transparent)
+    }
+
+    pub fn ruleses(&self) -> impl Iterator<Item = &RulesAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::rules)
+    }
+
+    pub fn lockfiles(&self) -> impl Iterator<Item = &LockfileAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::lockfile)
+    }
+
+    pub fn public_enums(&self) -> impl Iterator<Item = &PublicEnumAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::public_enum)
+    }
+
+    pub fn skips(&self) -> impl Iterator<Item = &SkipAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::skip)
+    }
+
+    pub fn sinces(&self) -> impl Iterator<Item = &SinceAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::since)
+    }
+
+    pub fn untils(&self) -> impl Iterator<Item = &UntilAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::until)
+    }
+
+    pub fn getters(&self) -> impl Iterator<Item = &GetterAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::getter)
+    }
+
+    pub fn deprecateds(&self) -> impl Iterator<Item = &DeprecatedAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::deprecated)
+    }
+
+    pub fn stables(&self) -> impl Iterator<Item = &StableAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::stable)
+    }
+
+    pub fn stabilities(&self) -> impl Iterator<Item = &StabilityAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::stability)
+    }
+
+    pub fn version_defaults(&self) -> impl Iterator<Item = &VersionDefaultAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::version_default)
+    }
+
+    pub fn assert_fieldses(&self) -> impl Iterator<Item = &AssertFieldsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::assert_fields)
+    }
+
+    pub fn releaseds(&self) -> impl Iterator<Item = &ReleasedAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::released)
+    }
+
+    pub fn fallibles(&self) -> impl Iterator<Item = &FallibleAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::fallible)
+    }
+
+    pub fn cfg_whens(&self) -> impl Iterator<Item = &CfgWhenAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::cfg_when)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn boxeds(&self) -> impl Iterator<Item = &BoxedAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::boxed)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn test_valueses(&self) -> impl Iterator<Item = &TestValuesAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::test_values)
+    }
+
     #[cfg(feature = "serde")]
     pub fn serdes(&self) -> impl Iterator<Item = &SerdeAttr> + '_ {
         self.obake().filter_map(ObakeAttribute::serde)
     }
 
+    #[cfg(feature = "serde")]
+    pub fn strictnesses(&self) -> impl Iterator<Item = &StrictnessAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::strictness)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn tag_as_indexes(&self) -> impl Iterator<Item = &TagAsIndexAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::tag_as_index)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn tag_cases(&self) -> impl Iterator<Item = &TagCaseAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::tag_case)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn on_unrecognizeds(&self) -> impl Iterator<Item = &OnUnrecognizedAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::on_unrecognized)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn externals(&self) -> impl Iterator<Item = &ExternalAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::external)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn displays(&self) -> impl Iterator<Item = &DisplayAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::display)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn json_upgrades(&self) -> impl Iterator<Item = &JsonUpgradeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::json_upgrade)
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn wasms(&self) -> impl Iterator<Item = &WasmAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::wasm)
+    }
+
+    #[cfg(feature = "ts-rs")]
+    pub fn tss(&self) -> impl Iterator<Item = &TsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::ts)
+    }
+
+    #[cfg(feature = "specta")]
+    pub fn spectas(&self) -> impl Iterator<Item = &SpectaAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::specta)
+    }
+
+    #[cfg(feature = "pyo3")]
+    pub fn pyo3s(&self) -> impl Iterator<Item = &Pyo3Attr> + '_ {
+        self.obake().filter_map(ObakeAttribute::pyo3)
+    }
+
+    #[cfg(feature = "abi_stable")]
+    pub fn abi_stables(&self) -> impl Iterator<Item = &AbiStableAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::abi_stable)
+    }
+
+    #[cfg(feature = "uniffi")]
+    pub fn uniffis(&self) -> impl Iterator<Item = &UniffiAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::uniffi)
+    }
+
+    #[cfg(feature = "utoipa")]
+    pub fn utoipas(&self) -> impl Iterator<Item = &UtoipaAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::utoipa)
+    }
+
+    #[cfg(feature = "async-graphql")]
+    pub fn async_graphqls(&self) -> impl Iterator<Item = &AsyncGraphqlAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::async_graphql)
+    }
+
+    #[cfg(feature = "tabular")]
+    pub fn tabulars(&self) -> impl Iterator<Item = &TabularAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::tabular)
+    }
+
+    #[cfg(feature = "idl")]
+    pub fn idls(&self) -> impl Iterator<Item = &IdlAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::idl)
+    }
+
+    #[cfg(feature = "debug-expansion")]
+    pub fn debug_expansions(&self) -> impl Iterator<Item = &DebugExpansionAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::debug_expansion)
+    }
+
+    #[cfg(feature = "zeroize")]
+    pub fn secrets(&self) -> impl Iterator<Item = &SecretAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::secret)
+    }
+
+    #[cfg(feature = "defmt")]
+    pub fn defmts(&self) -> impl Iterator<Item = &DefmtAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::defmt)
+    }
+
+    #[cfg(feature = "fake")]
+    pub fn fakes(&self) -> impl Iterator<Item = &FakeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::fake)
+    }
+
+    #[cfg(feature = "registry")]
+    pub fn registers(&self) -> impl Iterator<Item = &RegisterAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::register)
+    }
+
     pub fn attrs(&self) -> impl Iterator<Item = &syn::Attribute> + '_ {
         self.attrs.iter().filter_map(VersionedAttribute::attr)
     }
@@ -179,13 +1427,28 @@ impl VersionedAttributes {
 
 #[derive(Clone)]
 pub struct VersionedFields {
+    #[allow(dead_code)]
     pub brace_token: syn::token::Brace,
     pub fields: syn::punctuated::Punctuated<VersionedField, Token![,]>,
 }
 
+#[derive(Clone)]
+pub struct VersionedUnnamedField {
+    pub attrs: VersionedAttributes,
+    pub vis: syn::Visibility,
+    pub ty: syn::Type,
+}
+
+#[derive(Clone)]
+pub struct VersionedUnnamedFields {
+    #[allow(dead_code)]
+    pub paren_token: syn::token::Paren,
+    pub fields: syn::punctuated::Punctuated<VersionedUnnamedField, Token![,]>,
+}
+
 #[derive(Clone)]
 pub enum VersionedVariantFields {
-    Unnamed(syn::FieldsUnnamed),
+    Unnamed(VersionedUnnamedFields),
     Named(VersionedFields),
     Unit,
 }
@@ -199,6 +1462,7 @@ pub struct VersionedVariant {
 
 #[derive(Clone)]
 pub struct VersionedVariants {
+    #[allow(dead_code)]
     pub brace_token: syn::token::Brace,
     pub variants: syn::punctuated::Punctuated<VersionedVariant, Token![,]>,
 }
@@ -230,6 +1494,47 @@ pub struct VersionedItem {
     pub kind: VersionedItemKind,
 }
 
+#[derive(Clone)]
+pub struct VersionedItems {
+    pub items: Vec<VersionedItem>,
+}
+
+/// The arguments of `#[obake::extend_versions(base = ...)]`.
+#[derive(Clone)]
+pub struct ExtendArgs {
+    pub base: syn::Path,
+}
+
+/// A versioned data-structure continuing the versions of `base`, declared elsewhere.
+#[derive(Clone)]
+pub struct ExtendedItem {
+    pub base: syn::Path,
+    pub item: VersionedItem,
+}
+
+/// Names a particular version of a versioned data-structure (e.g., `foo::Foo, "0.1.0"`).
+#[derive(Clone)]
+pub struct VersionPath {
+    pub path: syn::Path,
+    pub label: syn::LitStr,
+}
+
+/// A version matched by `obake::any_of!`: the variant of the version-tagged enum holding it,
+/// and (for versions which can be destructured) the version's definition and fields.
+#[derive(Clone)]
+pub struct AnyOfVersion {
+    pub variant: syn::Path,
+    pub definition: Option<(syn::Path, syn::FieldsNamed)>,
+}
+
+/// The versions matched by `obake::any_of!`, as collected by the `Foo!` macro, and the binding
+/// the matched value is bound to.
+#[derive(Clone)]
+pub struct AnyOfPattern {
+    pub binding: syn::Ident,
+    pub versions: Vec<AnyOfVersion>,
+}
+
 impl VersionedItem {
     pub fn ident(&self) -> &syn::Ident {
         match &self.kind {
@@ -238,6 +1543,13 @@ impl VersionedItem {
         }
     }
 
+    pub fn ident_mut(&mut self) -> &mut syn::Ident {
+        match &mut self.kind {
+            VersionedItemKind::Struct(inner) => &mut inner.ident,
+            VersionedItemKind::Enum(inner) => &mut inner.ident,
+        }
+    }
+
     pub fn keyword_span(&self) -> Span {
         match &self.kind {
             VersionedItemKind::Struct(inner) => inner.struct_token.span,
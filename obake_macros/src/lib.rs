@@ -10,7 +10,9 @@ use syn::parse_macro_input;
 
 mod expand;
 mod internal;
+mod lockfile;
 mod parse;
+mod rename;
 
 #[proc_macro_attribute]
 pub fn versioned(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -19,3 +21,44 @@ pub fn versioned(args: TokenStream, input: TokenStream) -> TokenStream {
     let expanded = quote!(#input);
     TokenStream::from(expanded)
 }
+
+#[proc_macro_attribute]
+pub fn extend_versions(args: TokenStream, input: TokenStream) -> TokenStream {
+    let internal::ExtendArgs { base } = parse_macro_input!(args as internal::ExtendArgs);
+    let item = parse_macro_input!(input as internal::VersionedItem);
+    let input = internal::ExtendedItem { base, item };
+    let expanded = quote!(#input);
+    TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(Obake, attributes(obake))]
+pub fn derive_obake(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as internal::VersionedItem);
+    let expanded = match input.into_derived() {
+        Ok(item) => quote!(#item),
+        Err(err) => err.into_compile_error(),
+    };
+    TokenStream::from(expanded)
+}
+
+#[proc_macro]
+pub fn versioned_items(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as internal::VersionedItems);
+    let expanded = quote!(#input);
+    TokenStream::from(expanded)
+}
+
+#[proc_macro]
+pub fn version_of(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as internal::VersionPath);
+    let expanded = quote!(#input);
+    TokenStream::from(expanded)
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __any_of_pattern(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as internal::AnyOfPattern);
+    let expanded = quote!(#input);
+    TokenStream::from(expanded)
+}
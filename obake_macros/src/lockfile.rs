@@ -0,0 +1,167 @@
+//! Reading and writing of the lockfiles named by `#[obake(lockfile = "...")]`, recording the
+//! schema of each version of the data-structures locked in them.
+//!
+//! Each version is recorded as a section headed by the name of its data-structure, its version
+//! and a fingerprint of its schema, followed by one line per field (or variant):
+//!
+//! ```text
+//! [Foo 0.1.0] 5c2b1d6f0e8a9374
+//! bar: u32
+//! baz: Vec<String>
+//! ```
+
+use std::fmt::Write;
+use std::path::Path;
+
+/// The environment variable which, when set, rewrites the schemas of locked versions rather than
+/// checking them.
+pub const UPDATE_VAR: &str = "OBAKE_UPDATE_LOCKFILE";
+
+const HEADER: &str = "\
+# Generated by obake, recording the schema of each version of the data-structures locked here.
+# Versions but the latest are checked against it when compiled. After intentionally changing
+# one, rebuild with `OBAKE_UPDATE_LOCKFILE=1` set to regenerate it.
+";
+
+/// The schema of a version, as recorded in a lockfile.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub item: String,
+    pub version: String,
+    pub fingerprint: u64,
+    pub fields: Vec<String>,
+}
+
+impl Entry {
+    pub fn new(item: String, version: String, fields: Vec<String>) -> Self {
+        let fingerprint = fingerprint(&fields);
+
+        Self {
+            item,
+            version,
+            fingerprint,
+            fields,
+        }
+    }
+}
+
+/// The FNV-1a hash of the lines of a schema, which (unlike `std`'s hashers) is stable across
+/// releases of Rust.
+fn fingerprint(fields: &[String]) -> u64 {
+    fields
+        .iter()
+        .flat_map(|field| field.bytes().chain(Some(b'\n')))
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Read the entries of the lockfile at `path`, of which there are none if it doesn't exist.
+pub fn read(path: &Path) -> Result<Vec<Entry>, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(format!("failed to read `{}`: {}", path.display(), err)),
+    };
+
+    let mut entries: Vec<Entry> = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some(header) = line.strip_prefix('[') else {
+            match entries.last_mut() {
+                Some(entry) => entry.fields.push(line.to_owned()),
+                None => return Err(malformed(path, i)),
+            }
+            continue;
+        };
+
+        let (key, fingerprint) = header.split_once(']').ok_or_else(|| malformed(path, i))?;
+        let (item, version) = key.split_once(' ').ok_or_else(|| malformed(path, i))?;
+        let fingerprint =
+            u64::from_str_radix(fingerprint.trim(), 16).map_err(|_| malformed(path, i))?;
+        entries.push(Entry {
+            item: item.to_owned(),
+            version: version.to_owned(),
+            fingerprint,
+            fields: Vec::new(),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn malformed(path: &Path, line: usize) -> String {
+    format!(
+        "malformed lockfile `{}` at line {}, delete it to regenerate it",
+        path.display(),
+        line + 1
+    )
+}
+
+/// Replace the entries of `item` in the lockfile at `path` with `entries`, leaving those of other
+/// data-structures in place, and writing the lockfile only if it changes.
+pub fn update(
+    path: &Path,
+    mut existing: Vec<Entry>,
+    item: &str,
+    entries: &[Entry],
+) -> Result<(), String> {
+    let before = existing.clone();
+    let at = existing
+        .iter()
+        .position(|entry| entry.item == item)
+        .unwrap_or(existing.len());
+    existing.retain(|entry| entry.item != item);
+    existing.splice(at..at, entries.iter().cloned());
+    if existing == before && path.exists() {
+        return Ok(());
+    }
+
+    let mut contents = HEADER.to_owned();
+    for entry in &existing {
+        let _ = write!(
+            contents,
+            "\n[{} {}] {:016x}\n",
+            entry.item, entry.version, entry.fingerprint
+        );
+        for field in &entry.fields {
+            contents.push_str(field);
+            contents.push('\n');
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|err| format!("failed to write `{}`: {}", path.display(), err))?;
+    }
+
+    std::fs::write(path, contents)
+        .map_err(|err| format!("failed to write `{}`: {}", path.display(), err))
+}
+
+/// Render the tokens of a type as written by hand (e.g., `Vec<String>` rather than
+/// `Vec < String >`).
+pub fn tidy(tokens: &str) -> String {
+    let mut tidied = String::with_capacity(tokens.len());
+    let mut chars = tokens.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ' ' {
+            tidied.push(c);
+            continue;
+        }
+
+        let prev = tidied.chars().last();
+        let next = chars.peek().copied();
+        let glued = matches!(prev, Some('<' | '&' | '(' | '[' | ':'))
+            || matches!(next, Some('<' | '>' | ',' | ')' | ']' | ':' | ';'));
+        if !glued {
+            tidied.push(' ');
+        }
+    }
+
+    tidied
+}
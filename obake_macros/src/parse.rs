@@ -1,17 +1,154 @@
+use std::cell::RefCell;
 use std::convert::{TryFrom, TryInto};
 
 use syn::parse::{Parse, ParseStream, Result};
-use syn::{braced, parenthesized, Token};
+use syn::{braced, bracketed, parenthesized, Token};
 
 use crate::internal::*;
 
 const OBAKE: &str = "obake";
 
+/// How the versions (and version requirements) of the item being parsed are written.
+struct SchemeContext {
+    scheme: Scheme,
+    /// The versions declared on the item, in the order they're declared.
+    declared: Vec<String>,
+}
+
+thread_local! {
+    // Versions are parsed deep inside the attributes of fields and variants, so the scheme of
+    // the item being parsed is set aside here rather than threaded through every parser
+    static SCHEME: RefCell<SchemeContext> = const {
+        RefCell::new(SchemeContext {
+            scheme: Scheme::Semver,
+            declared: Vec::new(),
+        })
+    };
+}
+
+impl SchemeContext {
+    /// Scan the (unparsed) attributes of an item for its scheme and declared versions.
+    fn scan(attrs: &[syn::Attribute]) -> Self {
+        let mut scheme = None;
+        let mut declared = Vec::new();
+
+        let obake = attrs.iter().filter(|attr| attr.path.is_ident(OBAKE));
+        for attr in obake {
+            // Malformed (or duplicate) attributes are reported once the attributes are parsed in
+            // earnest
+            let _ = attr.parse_args_with(|input: ParseStream| {
+                let ident = input.parse::<syn::Ident>()?;
+                if ident == "scheme" && scheme.is_none() {
+                    scheme = Some(input.parse::<SchemeAttr>()?.scheme);
+                } else if ident == "version" {
+                    let content;
+                    parenthesized!(content in input);
+                    declared.push(content.parse::<syn::LitStr>()?.value());
+                }
+                input.parse::<TokenStream2>().map(drop)
+            });
+        }
+
+        Self {
+            scheme: scheme.unwrap_or(Scheme::Semver),
+            declared,
+        }
+    }
+
+    fn parse_tag(&self, label: &str) -> std::result::Result<Tag, String> {
+        let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+        let (version, suffix) = match &self.scheme {
+            Scheme::Semver => {
+                let version = Version::parse(label).map_err(|err| err.to_string())?;
+                let suffix = format!("{}_{}_{}", version.major, version.minor, version.patch);
+                return Ok(Tag {
+                    label: version.to_string(),
+                    version,
+                    suffix,
+                });
+            }
+            Scheme::Integer => {
+                let n = Some(label)
+                    .filter(|label| digits(label) && (*label == "0" || !label.starts_with('0')))
+                    .and_then(|label| label.parse().ok())
+                    .ok_or("expected an integer version (e.g., `3`)")?;
+                (Version::new(n, 0, 0), label.to_owned())
+            }
+            Scheme::Calver => {
+                let parts: Vec<_> = label.split('.').collect();
+                let date = match parts[..] {
+                    [y, m, d] if y.len() == 4 && m.len() == 2 && d.len() == 2 => {
+                        Some((y, m, d)).filter(|_| parts.iter().all(|part| digits(part)))
+                    }
+                    _ => None,
+                };
+                let (y, m, d) = date
+                    .map(|(y, m, d)| (y.parse().unwrap(), m.parse().unwrap(), d.parse().unwrap()))
+                    .filter(|(_, m, d)| (1..=12).contains(m) && (1..=31).contains(d))
+                    .ok_or(
+                        "expected a calendar version of the form `YYYY.MM.DD` (e.g., `2024.01.15`)",
+                    )?;
+                (Version::new(y, m, d), label.replace('.', "_"))
+            }
+            Scheme::Custom(_) => {
+                let i = self
+                    .declared
+                    .iter()
+                    .position(|declared| declared == label)
+                    .ok_or_else(|| format!("undeclared version `{label}`"))?;
+                let suffix = label
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                    .collect();
+                (Version::new(i as u64, 0, 0), suffix)
+            }
+        };
+
+        Ok(Tag {
+            version,
+            label: label.to_owned(),
+            suffix,
+        })
+    }
+
+    /// Parse a version requirement. Outside of semantic versioning, a requirement is a
+    /// comma-separated list of versions which must all be matched, each prefixed by one of `=`, `>`, `>=`, `<` or `<=`
+    /// (where a missing operator means `=`), or `*`.
+    fn parse_req(&self, req: &str) -> std::result::Result<VersionReq, String> {
+        if let Scheme::Semver = self.scheme {
+            return VersionReq::parse(req).map_err(|err| err.to_string());
+        }
+
+        let mut comparators = Vec::new();
+        for comparator in req.split(',').map(str::trim) {
+            if comparator == "*" {
+                continue;
+            }
+
+            let op = [">=", "<=", ">", "<", "="]
+                .iter()
+                .copied()
+                .find(|op| comparator.starts_with(op))
+                .unwrap_or("=");
+            let tag = self.parse_tag(comparator.trim_start_matches(op).trim())?;
+            comparators.push(format!("{op}{}", tag.version));
+        }
+
+        if comparators.is_empty() {
+            return Ok(VersionReq::STAR);
+        }
+
+        VersionReq::parse(&comparators.join(", ")).map_err(|err| err.to_string())
+    }
+}
+
 impl Parse for VersionAttr {
     fn parse(input: ParseStream) -> Result<Self> {
         let version_str = input.parse::<syn::LitStr>()?;
         let span = version_str.span();
-        let version = Version::parse(&version_str.value())
+        let version = SCHEME
+            .with(|scheme| scheme.borrow().parse_tag(&version_str.value()))
             .map_err(|err| syn::Error::new(version_str.span(), err))?;
 
         Ok(Self { version, span })
@@ -22,13 +159,634 @@ impl Parse for CfgAttr {
     fn parse(input: ParseStream) -> Result<Self> {
         let req_str = input.parse::<syn::LitStr>()?;
         let span = req_str.span();
-        let req = VersionReq::parse(&req_str.value())
+        let req = SCHEME
+            .with(|scheme| scheme.borrow().parse_req(&req_str.value()))
             .map_err(|err| syn::Error::new(req_str.span(), err))?;
 
         Ok(Self { req, span })
     }
 }
 
+impl Parse for SchemeAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.parse::<Token![=]>()?.span;
+        let path = input.parse::<syn::Path>()?;
+
+        let scheme = match path.get_ident() {
+            Some(ident) if ident == "semver" => Scheme::Semver,
+            Some(ident) if ident == "integer" => Scheme::Integer,
+            Some(ident) if ident == "calver" => Scheme::Calver,
+            _ => Scheme::Custom(path),
+        };
+
+        Ok(Self { span, scheme })
+    }
+}
+
+impl Parse for ConditionalAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let CfgAttr { req, .. } = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let tokens: TokenStream2 = input.parse()?;
+
+        if tokens.is_empty() {
+            return Err(input.error("expected an attribute"));
+        }
+
+        Ok(Self { req, tokens })
+    }
+}
+
+impl Parse for NameAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.parse::<Token![=]>()?.span;
+
+        Ok(Self {
+            span,
+            ident: input.parse()?,
+        })
+    }
+}
+
+impl Parse for LockfileAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // Either `= "path"`, keying the entries by the name of the data-structure, or
+        // `("path", key = "key")`
+        if !input.peek(syn::token::Paren) {
+            let span = input.parse::<Token![=]>()?.span;
+
+            return Ok(Self {
+                span,
+                path: input.parse()?,
+                key: None,
+            });
+        }
+
+        let content;
+        parenthesized!(content in input);
+        let span = content.span();
+        let path = content.parse()?;
+        content.parse::<Token![,]>()?;
+
+        let key = content.parse::<syn::Ident>()?;
+        if key != "key" {
+            return Err(syn::Error::new(key.span(), "expected `key`"));
+        }
+
+        content.parse::<Token![=]>()?;
+        let key = content.parse::<syn::LitStr>()?;
+        let value = key.value();
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '[' || c == ']') {
+            return Err(syn::Error::new(
+                key.span(),
+                "lockfile keys must be non-empty and contain neither whitespace nor brackets",
+            ));
+        }
+
+        Ok(Self {
+            span,
+            path,
+            key: Some(key),
+        })
+    }
+}
+
+#[cfg(feature = "registry")]
+impl Parse for RegisterAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        if !input.peek(Token![=]) {
+            return Ok(Self { span, name: None });
+        }
+
+        input.parse::<Token![=]>()?;
+        Ok(Self {
+            span,
+            name: Some(input.parse()?),
+        })
+    }
+}
+
+impl Parse for TagAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.parse::<Token![=]>()?.span;
+        let ty = input.parse::<syn::Ident>()?;
+
+        if ty != "u8" && ty != "u16" && ty != "u32" {
+            return Err(syn::Error::new(ty.span(), "expected `u8`, `u16` or `u32`"));
+        }
+
+        Ok(Self { span, ty })
+    }
+}
+
+impl Parse for AutoMigrateAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+
+        // Without a constraint, migrations to every version are generated
+        let req = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            content.parse::<CfgAttr>()?.req
+        } else {
+            VersionReq::STAR
+        };
+
+        Ok(Self { span, req })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl StrictnessAttr {
+    /// Parse `deny_unknown_on_latest`, `deny_unknown_fields("req")` or
+    /// `allow_unknown_fields("req")`, returning `None` for any other `serde` attribute (including
+    /// a bare `deny_unknown_fields`, which applies to the version-tagged enum).
+    fn parse_serde(input: ParseStream) -> Result<Option<Self>> {
+        let fork = input.fork();
+        let Ok(ident) = fork.parse::<syn::Ident>() else {
+            return Ok(None);
+        };
+
+        let deny = match ident {
+            _ if ident == "deny_unknown_on_latest" && fork.is_empty() => {
+                input.parse::<syn::Ident>()?;
+                return Ok(Some(Self {
+                    span: ident.span(),
+                    deny: true,
+                    req: None,
+                }));
+            }
+            _ if ident == "deny_unknown_fields" => true,
+            _ if ident == "allow_unknown_fields" => false,
+            _ => return Ok(None),
+        };
+
+        if !fork.peek(syn::token::Paren) {
+            return Ok(None);
+        }
+
+        input.parse::<syn::Ident>()?;
+        let content;
+        parenthesized!(content in input);
+        let req = content.parse::<CfgAttr>()?.req;
+        if !input.is_empty() {
+            return Err(input.error("unexpected token"));
+        }
+
+        Ok(Some(Self {
+            span: ident.span(),
+            deny,
+            req: Some(req),
+        }))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TagCaseAttr {
+    /// Parse `tag_case = "..."`, returning `None` for any other `serde` attribute.
+    fn parse_serde(input: ParseStream) -> Result<Option<Self>> {
+        let fork = input.fork();
+        match fork.parse::<syn::Ident>() {
+            Ok(ident) if ident == "tag_case" && fork.peek(Token![=]) => {}
+            _ => return Ok(None),
+        }
+
+        let ident: syn::Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let lit: syn::LitStr = input.parse()?;
+        if !input.is_empty() {
+            return Err(input.error("unexpected token"));
+        }
+
+        let case = match lit.value().as_str() {
+            "snake" | "snake_case" => TagCase::Snake,
+            "screaming_snake" | "SCREAMING_SNAKE_CASE" => TagCase::ScreamingSnake,
+            "kebab" | "kebab-case" => TagCase::Kebab,
+            "screaming_kebab" | "SCREAMING-KEBAB-CASE" => TagCase::ScreamingKebab,
+            case => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        "unknown case `{case}`, expected `snake`, `screaming_snake`, `kebab` or \
+                         `screaming_kebab`"
+                    ),
+                ))
+            }
+        };
+
+        Ok(Some(Self {
+            span: ident.span(),
+            case,
+        }))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Parse for ExternalAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let span = content.span();
+
+        // Either `(serde_shim = path)` for every version, or `("req", serde_shim = path)` for
+        // those matching `req`
+        let req = if content.peek(syn::LitStr) {
+            let req = content.parse::<CfgAttr>()?.req;
+            content.parse::<Token![,]>()?;
+            req
+        } else {
+            VersionReq::STAR
+        };
+
+        let key = content.parse::<syn::Ident>()?;
+        if key != "serde_shim" {
+            return Err(syn::Error::new(key.span(), "expected `serde_shim`"));
+        }
+
+        content.parse::<Token![=]>()?;
+
+        Ok(Self {
+            span,
+            req,
+            serde_shim: content.parse()?,
+        })
+    }
+}
+
+impl Parse for FallibleAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let span = content.span();
+
+        // Either `(error = Type)` for the migrations to every version, or
+        // `("req", error = Type)` for those to the versions matching `req`
+        let req = if content.peek(syn::LitStr) {
+            let req = content.parse::<CfgAttr>()?.req;
+            content.parse::<Token![,]>()?;
+            req
+        } else {
+            VersionReq::STAR
+        };
+
+        let key = content.parse::<syn::Ident>()?;
+        if key != "error" {
+            return Err(syn::Error::new(key.span(), "expected `error`"));
+        }
+
+        content.parse::<Token![=]>()?;
+
+        Ok(Self {
+            span,
+            req,
+            error: content.parse()?,
+        })
+    }
+}
+
+impl Parse for MigrateDefaultAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+
+        // Either `= expr` for every version, or `("req", expr)` for those matching `req`
+        if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let req = content.parse::<CfgAttr>()?.req;
+            content.parse::<Token![,]>()?;
+
+            Ok(Self {
+                span,
+                req,
+                expr: content.parse()?,
+            })
+        } else {
+            input.parse::<Token![=]>()?;
+
+            Ok(Self {
+                span,
+                req: VersionReq::STAR,
+                expr: input.parse()?,
+            })
+        }
+    }
+}
+
+impl Parse for NoAllocAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+
+        // Optionally, a bound on the size of the version-tagged enum (e.g., `(max_size = 64)`)
+        let max_size = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let ident = content.parse::<syn::Ident>()?;
+            if ident != "max_size" {
+                return Err(syn::Error::new(ident.span(), "expected `max_size`"));
+            }
+            content.parse::<Token![=]>()?;
+            let max_size = content.parse::<syn::LitInt>()?;
+            max_size.base10_parse::<usize>()?;
+            Some(max_size)
+        } else {
+            None
+        };
+
+        Ok(Self { span, max_size })
+    }
+}
+
+impl Parse for PublicEnumAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+
+        // Optionally, a pattern naming the variants (e.g., `(variants = "V{version}")`)
+        let variants = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let ident = content.parse::<syn::Ident>()?;
+            if ident != "variants" {
+                return Err(syn::Error::new(ident.span(), "expected `variants`"));
+            }
+            content.parse::<Token![=]>()?;
+            Some(content.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { span, variants })
+    }
+}
+
+impl Parse for RulesAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+
+        // Rules grouped by the level they're reported at (e.g., `(deny(max_chain = 5))`)
+        let content;
+        parenthesized!(content in input);
+        let mut rules = Vec::new();
+        while !content.is_empty() {
+            let level_ident = content.parse::<syn::Ident>()?;
+            let level = match level_ident {
+                _ if level_ident == "deny" => RuleLevel::Deny,
+                _ if level_ident == "warn" => RuleLevel::Warn,
+                _ => {
+                    return Err(syn::Error::new(
+                        level_ident.span(),
+                        "expected `deny` or `warn`",
+                    ))
+                }
+            };
+
+            let group;
+            parenthesized!(group in content);
+            while !group.is_empty() {
+                let ident = group.parse::<syn::Ident>()?;
+                let kind = match ident {
+                    _ if ident == "no_removal_without_major" => RuleKind::NoRemovalWithoutMajor,
+                    _ if ident == "new_fields_have_defaults" => RuleKind::NewFieldsHaveDefaults,
+                    _ if ident == "max_chain" => {
+                        group.parse::<Token![=]>()?;
+                        RuleKind::MaxChain(group.parse::<syn::LitInt>()?.base10_parse()?)
+                    }
+                    _ => return Err(syn::Error::new(ident.span(), "unrecognised rule")),
+                };
+                rules.push(Rule {
+                    span: ident.span(),
+                    level,
+                    kind,
+                });
+
+                if !group.is_empty() {
+                    group.parse::<Token![,]>()?;
+                }
+            }
+
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self { span, rules })
+    }
+}
+
+impl Parse for SinceAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+
+        Ok(Self {
+            span: version.span,
+            version,
+        })
+    }
+}
+
+impl Parse for UntilAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let SinceAttr { span, version } = input.parse()?;
+
+        Ok(Self { span, version })
+    }
+}
+
+impl Parse for SkipAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+
+        // Optionally, the expression populating the field (e.g., `= Cache::new()`)
+        let expr = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { span, expr })
+    }
+}
+
+impl Parse for NoteAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+        content.parse::<Token![,]>()?;
+        let note = content.parse()?;
+
+        Ok(Self {
+            span: version.span,
+            version,
+            note,
+        })
+    }
+}
+
+impl Parse for DeprecatedAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+        let note = if content.is_empty() {
+            None
+        } else {
+            content.parse::<Token![,]>()?;
+            Some(content.parse()?)
+        };
+
+        Ok(Self {
+            span: version.span,
+            version,
+            note,
+        })
+    }
+}
+
+impl Parse for StableAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let SinceAttr { span, version } = input.parse()?;
+
+        Ok(Self { span, version })
+    }
+}
+
+impl Parse for VersionDefaultAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+        content.parse::<Token![,]>()?;
+        let ctor = content.call(syn::Path::parse_mod_style)?;
+
+        Ok(Self {
+            span: version.span,
+            version,
+            ctor,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Parse for TestValuesAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+        content.parse::<Token![,]>()?;
+        let path = content.call(syn::Path::parse_mod_style)?;
+
+        Ok(Self {
+            span: version.span,
+            version,
+            path,
+        })
+    }
+}
+
+impl Parse for AssertFieldsAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+        content.parse::<Token![,]>()?;
+        let fields;
+        bracketed!(fields in content);
+
+        Ok(Self {
+            span: version.span,
+            version,
+            fields: fields.parse_terminated(syn::Ident::parse)?,
+        })
+    }
+}
+
+impl Parse for ReleasedAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let version: VersionAttr = content.parse()?;
+        content.parse::<Token![,]>()?;
+        let date: syn::LitStr = content.parse()?;
+
+        // Dates are written `YYYY-MM-DD`, so that they sort (and compare) as strings
+        let value = date.value();
+        let parts: Vec<_> = value.split('-').collect();
+        let valid = parts.len() == 3
+            && parts
+                .iter()
+                .zip([4, 2, 2])
+                .all(|(part, len)| part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+            && (1..=12).contains(&parts[1].parse::<u32>().unwrap_or(0))
+            && (1..=31).contains(&parts[2].parse::<u32>().unwrap_or(0));
+        if !valid {
+            return Err(syn::Error::new(
+                date.span(),
+                "expected a date of the form `YYYY-MM-DD`",
+            ));
+        }
+
+        Ok(Self {
+            span: version.span,
+            version,
+            date,
+        })
+    }
+}
+
+impl Parse for DeriveAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+
+        Ok(Self {
+            span: input.span(),
+            tokens: content.parse()?,
+        })
+    }
+}
+
+impl Parse for DeriveBoundsAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+
+        Ok(Self {
+            span: input.span(),
+            predicates: content.parse_terminated(syn::WherePredicate::parse)?,
+        })
+    }
+}
+
+impl Parse for CfgWhenAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let predicate = content.parse()?;
+        content.parse::<Token![,]>()?;
+        let CfgAttr { req, span } = content.parse()?;
+
+        Ok(Self {
+            span,
+            predicate,
+            req,
+        })
+    }
+}
+
+#[cfg(feature = "debug-expansion")]
+impl Parse for DebugExpansionAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.parse::<Token![=]>()?.span;
+        let path = input.parse()?;
+
+        Ok(Self { span, path })
+    }
+}
+
 impl Parse for ObakeAttribute {
     fn parse(input: ParseStream) -> Result<Self> {
         let ident = input.parse::<syn::Ident>()?;
@@ -44,31 +802,247 @@ impl Parse for ObakeAttribute {
                 parenthesized!(content in input);
                 Self::Cfg(content.parse()?)
             }
-            _ if ident == "inherit" => Self::Inherit(InheritAttr { span: ident.span() }),
-            _ if ident == "derive" => {
+            _ if ident == "cfg_attr" => {
                 let content;
                 parenthesized!(content in input);
-                Self::Derive(DeriveAttr {
-                    span: ident.span(),
-                    tokens: content.parse()?,
-                })
+                Self::Conditional(content.parse()?)
+            }
+            _ if ident == "derive" => Self::Derive(DeriveAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "derive_bounds" => Self::DeriveBounds(DeriveBoundsAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "tag" => Self::Tag(TagAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "auto_migrate" => Self::AutoMigrate(AutoMigrateAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "migrate_default" => Self::MigrateDefault(MigrateDefaultAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "name" => Self::Name(NameAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "cfg_when" => Self::CfgWhen(CfgWhenAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "scheme" => Self::Scheme(SchemeAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "no_alloc" => Self::NoAlloc(NoAllocAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "rules" => Self::Rules(RulesAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "lockfile" => Self::Lockfile(LockfileAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "public_enum" => Self::PublicEnum(PublicEnumAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "skip" => Self::Skip(SkipAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "since" => Self::Since(SinceAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ if ident == "until" => Self::Until(UntilAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ => {
+                let mut attr = Self::parse_flag(&ident);
+                if attr.is_none() {
+                    attr = Self::parse_per_version(&ident, input)?;
+                }
+                if attr.is_none() {
+                    attr = Self::parse_integration(&ident, input)?;
+                }
+
+                attr.ok_or_else(|| {
+                    syn::Error::new(ident.span(), "unrecognised `obake` helper attribute")
+                })?
             }
+        })
+    }
+}
+
+impl ObakeAttribute {
+    /// Parse the attributes taking no arguments, returning `None` if `ident` isn't one of them.
+    fn parse_flag(ident: &syn::Ident) -> Option<Self> {
+        let span = ident.span();
+
+        Some(match ident {
+            _ if ident == "inherit" => Self::Inherit(InheritAttr { span }),
+            _ if ident == "downgrade" => Self::Downgrade(DowngradeAttr { span }),
+            _ if ident == "repr_c" => Self::ReprC(ReprCAttr { span }),
+            _ if ident == "dense_migrations" => Self::DenseMigrations(DenseMigrationsAttr { span }),
+            _ if ident == "alias_identical" => Self::AliasIdentical(AliasIdenticalAttr { span }),
+            _ if ident == "auto_downgrade_subset" => {
+                Self::AutoDowngradeSubset(AutoDowngradeSubsetAttr { span })
+            }
+            _ if ident == "dto_modules" => Self::DtoModules(DtoModulesAttr { span }),
+            _ if ident == "no_macro" => Self::NoMacro(NoMacroAttr { span }),
+            _ if ident == "stable_hash" => Self::StableHash(StableHashAttr { span }),
+            _ if ident == "transparent" => Self::Transparent(TransparentAttr { span }),
+            _ if ident == "getter" => Self::Getter(GetterAttr { span }),
+            _ if ident == "stability" => Self::Stability(StabilityAttr { span }),
+            _ => return None,
+        })
+    }
+
+    /// Parse the attributes describing a particular version (or versions), returning `None` if
+    /// `ident` isn't one of them.
+    fn parse_per_version(ident: &syn::Ident, input: ParseStream) -> Result<Option<Self>> {
+        let span = ident.span();
+
+        Ok(Some(match ident {
+            _ if ident == "note" => Self::Note(NoteAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ if ident == "released" => Self::Released(ReleasedAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ if ident == "deprecated" => Self::Deprecated(DeprecatedAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ if ident == "stable" => Self::Stable(StableAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ if ident == "fallible" => Self::Fallible(FallibleAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ if ident == "version_default" => Self::VersionDefault(VersionDefaultAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ if ident == "assert_fields" => Self::AssertFields(AssertFieldsAttr {
+                span,
+                ..input.parse()?
+            }),
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Parse the attributes enabled by optional features, returning `None` if `ident` isn't
+    /// one of them.
+    // Without any features enabled, there's nothing to parse
+    #[allow(unused_variables, unreachable_code)]
+    fn parse_integration(ident: &syn::Ident, input: ParseStream) -> Result<Option<Self>> {
+        Ok(Some(match ident {
+            #[cfg(feature = "alloc")]
+            _ if ident == "boxed" => Self::Boxed(BoxedAttr { span: ident.span() }),
+            #[cfg(feature = "alloc")]
+            _ if ident == "test_values" => Self::TestValues(TestValuesAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
             #[cfg(feature = "serde")]
             _ if ident == "serde" => {
                 let content;
                 parenthesized!(content in input);
-                Self::Serde(SerdeAttr {
+                if let Some(strictness) = StrictnessAttr::parse_serde(&content)? {
+                    return Ok(Some(Self::Strictness(strictness)));
+                }
+
+                if let Some(tag_case) = TagCaseAttr::parse_serde(&content)? {
+                    return Ok(Some(Self::TagCase(tag_case)));
+                }
+
+                let tokens: TokenStream2 = content.parse()?;
+                if tokens.to_string() == "tag_as_index" {
+                    Self::TagAsIndex(TagAsIndexAttr { span: ident.span() })
+                } else {
+                    Self::Serde(SerdeAttr {
+                        span: ident.span(),
+                        tokens,
+                    })
+                }
+            }
+            #[cfg(feature = "serde")]
+            _ if ident == "on_unrecognized" => {
+                input.parse::<Token![=]>()?;
+                Self::OnUnrecognized(OnUnrecognizedAttr {
                     span: ident.span(),
-                    tokens: content.parse()?,
+                    path: input.parse()?,
                 })
             }
-            _ => {
-                return Err(syn::Error::new(
-                    ident.span(),
-                    "unrecognised `obake` helper attribute",
-                ))
+            #[cfg(feature = "serde")]
+            _ if ident == "external" => Self::External(ExternalAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            #[cfg(feature = "json")]
+            _ if ident == "display" => Self::Display(DisplayAttr { span: ident.span() }),
+            #[cfg(feature = "json")]
+            _ if ident == "json_upgrade" => {
+                Self::JsonUpgrade(JsonUpgradeAttr { span: ident.span() })
             }
-        })
+            #[cfg(feature = "wasm")]
+            _ if ident == "wasm" => Self::Wasm(WasmAttr { span: ident.span() }),
+            #[cfg(feature = "ts-rs")]
+            _ if ident == "ts" => Self::Ts(TsAttr { span: ident.span() }),
+            #[cfg(feature = "specta")]
+            _ if ident == "specta" => Self::Specta(SpectaAttr { span: ident.span() }),
+            #[cfg(feature = "pyo3")]
+            _ if ident == "pyo3" => Self::Pyo3(Pyo3Attr { span: ident.span() }),
+            #[cfg(feature = "abi_stable")]
+            _ if ident == "abi_stable" => Self::AbiStable(AbiStableAttr { span: ident.span() }),
+            #[cfg(feature = "uniffi")]
+            _ if ident == "uniffi" => Self::Uniffi(UniffiAttr { span: ident.span() }),
+            #[cfg(feature = "utoipa")]
+            _ if ident == "utoipa" => Self::Utoipa(UtoipaAttr { span: ident.span() }),
+            #[cfg(feature = "async-graphql")]
+            _ if ident == "async_graphql" => {
+                Self::AsyncGraphql(AsyncGraphqlAttr { span: ident.span() })
+            }
+            #[cfg(feature = "tabular")]
+            _ if ident == "tabular" => Self::Tabular(TabularAttr { span: ident.span() }),
+            #[cfg(feature = "idl")]
+            _ if ident == "idl" => Self::Idl(IdlAttr { span: ident.span() }),
+            #[cfg(feature = "debug-expansion")]
+            _ if ident == "debug_expansion" => {
+                let DebugExpansionAttr { path, .. } = input.parse()?;
+                Self::DebugExpansion(DebugExpansionAttr {
+                    span: ident.span(),
+                    path,
+                })
+            }
+            #[cfg(feature = "zeroize")]
+            _ if ident == "secret" => Self::Secret(SecretAttr { span: ident.span() }),
+            #[cfg(feature = "defmt")]
+            _ if ident == "defmt" => Self::Defmt(DefmtAttr { span: ident.span() }),
+            #[cfg(feature = "fake")]
+            _ if ident == "fake" => Self::Fake(FakeAttr { span: ident.span() }),
+            #[cfg(feature = "registry")]
+            _ if ident == "register" => Self::Register(RegisterAttr {
+                span: ident.span(),
+                ..input.parse()?
+            }),
+            _ => return Ok(None),
+        }))
     }
 }
 
@@ -97,10 +1071,11 @@ impl TryFrom<syn::Attribute> for VersionedAttribute {
     }
 }
 
-impl Parse for VersionedAttributes {
-    fn parse(input: ParseStream) -> Result<VersionedAttributes> {
-        let attrs = input
-            .call(syn::Attribute::parse_outer)?
+impl TryFrom<Vec<syn::Attribute>> for VersionedAttributes {
+    type Error = syn::Error;
+
+    fn try_from(attrs: Vec<syn::Attribute>) -> Result<Self> {
+        let attrs = attrs
             .into_iter()
             .map(TryInto::try_into)
             .collect::<Result<Vec<_>>>()?;
@@ -109,6 +1084,12 @@ impl Parse for VersionedAttributes {
     }
 }
 
+impl Parse for VersionedAttributes {
+    fn parse(input: ParseStream) -> Result<VersionedAttributes> {
+        input.call(syn::Attribute::parse_outer)?.try_into()
+    }
+}
+
 impl Parse for VersionedField {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Self {
@@ -133,6 +1114,28 @@ impl Parse for VersionedFields {
     }
 }
 
+impl Parse for VersionedUnnamedField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            attrs: input.parse()?,
+            vis: input.parse()?,
+            ty: input.parse()?,
+        })
+    }
+}
+
+impl Parse for VersionedUnnamedFields {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let paren_token = parenthesized!(content in input);
+
+        Ok(Self {
+            paren_token,
+            fields: content.parse_terminated(VersionedUnnamedField::parse)?,
+        })
+    }
+}
+
 impl Parse for VersionedVariantFields {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.is_empty() {
@@ -172,11 +1175,32 @@ impl Parse for VersionedVariants {
     }
 }
 
+/// Reject any generic parameters following the name of an item, which aren't supported.
+fn reject_generics(input: ParseStream) -> Result<()> {
+    if !input.peek(Token![<]) {
+        return Ok(());
+    }
+
+    let generics: syn::Generics = input.parse()?;
+    let message = if generics.lifetimes().next().is_some() {
+        "versioned data-structures cannot have lifetime parameters, so fields can't borrow from \
+         their input (e.g., with `#[serde(borrow)]`)"
+    } else {
+        "versioned data-structures cannot have generic parameters"
+    };
+
+    Err(syn::Error::new_spanned(generics, message))
+}
+
 impl Parse for VersionedStruct {
     fn parse(input: ParseStream) -> Result<Self> {
+        let struct_token = input.parse()?;
+        let ident = input.parse()?;
+        reject_generics(input)?;
+
         Ok(Self {
-            struct_token: input.parse()?,
-            ident: input.parse()?,
+            struct_token,
+            ident,
             fields: input.parse()?,
         })
     }
@@ -184,9 +1208,13 @@ impl Parse for VersionedStruct {
 
 impl Parse for VersionedEnum {
     fn parse(input: ParseStream) -> Result<Self> {
+        let enum_token = input.parse()?;
+        let ident = input.parse()?;
+        reject_generics(input)?;
+
         Ok(Self {
-            enum_token: input.parse()?,
-            ident: input.parse()?,
+            enum_token,
+            ident,
             variants: input.parse()?,
         })
     }
@@ -205,12 +1233,110 @@ impl Parse for VersionedItemKind {
     }
 }
 
+impl VersionedItem {
+    /// Parse an item, as though the attributes `shared` were declared on it before its own.
+    fn parse_with_shared(input: ParseStream, shared: &[syn::Attribute]) -> Result<Self> {
+        let mut attrs = shared.to_vec();
+        attrs.extend(input.call(syn::Attribute::parse_outer)?);
+
+        // The scheme must be known before any versions are parsed, wherever they appear
+        let context = SchemeContext::scan(&attrs);
+        let prev = SCHEME.with(|scheme| scheme.replace(context));
+        let item = (|| {
+            Ok(Self {
+                attrs: attrs.try_into()?,
+                vis: input.parse()?,
+                kind: input.parse()?,
+            })
+        })();
+        SCHEME.with(|scheme| scheme.replace(prev));
+
+        item
+    }
+}
+
 impl Parse for VersionedItem {
     fn parse(input: ParseStream) -> Result<Self> {
+        Self::parse_with_shared(input, &[])
+    }
+}
+
+impl Parse for ExtendArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse::<syn::Ident>()?;
+        if ident != "base" {
+            return Err(syn::Error::new(
+                ident.span(),
+                "expected `base = ...` naming the extended data-structure",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let base = input.call(syn::Path::parse_mod_style)?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self { base })
+    }
+}
+
+impl Parse for VersionPath {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.call(syn::Path::parse_mod_style)?;
+        input.parse::<Token![,]>()?;
+        let label = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self { path, label })
+    }
+}
+
+impl Parse for AnyOfVersion {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let variant = input.parse()?;
+        let definition = if input.parse::<Option<Token![,]>>()?.is_some() {
+            Some((input.parse()?, input.parse()?))
+        } else {
+            None
+        };
+
         Ok(Self {
-            attrs: input.parse()?,
-            vis: input.parse()?,
-            kind: input.parse()?,
+            variant,
+            definition,
         })
     }
 }
+
+impl Parse for AnyOfPattern {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let binding = input.parse()?;
+        let mut versions = Vec::new();
+        while !input.is_empty() {
+            let content;
+            parenthesized!(content in input);
+            versions.push(content.parse()?);
+        }
+
+        Ok(Self { binding, versions })
+    }
+}
+
+impl Parse for VersionedItems {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // Inner attributes at the top of the block are shared by every item, as though they
+        // were declared on each item before its own attributes
+        let shared: Vec<_> = input
+            .call(syn::Attribute::parse_inner)?
+            .into_iter()
+            .map(|mut attr| {
+                attr.style = syn::AttrStyle::Outer;
+                attr
+            })
+            .collect();
+
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(VersionedItem::parse_with_shared(input, &shared)?);
+        }
+
+        Ok(Self { items })
+    }
+}
@@ -0,0 +1,100 @@
+//! Resolution of the names that fields and variants are deserialized from by `serde`, following
+//! its `rename`, `rename_all`, `skip` and `flatten` attributes.
+
+use syn::{Lit, Meta, NestedMeta};
+
+/// The items of any `#[serde(...)]` attributes among `metas`.
+pub fn serde_items(metas: impl IntoIterator<Item = Meta>) -> Vec<Meta> {
+    metas
+        .into_iter()
+        .filter_map(|meta| match meta {
+            Meta::List(list) if list.path.is_ident("serde") => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(meta) => Some(meta),
+            NestedMeta::Lit(_) => None,
+        })
+        .collect()
+}
+
+/// The value of a `key = "..."` item, or of `deserialize = "..."` within a `key(...)` item.
+pub fn deserialize_value(items: &[Meta], key: &str) -> Option<String> {
+    let lit_str = |lit: &Lit| match lit {
+        Lit::Str(lit) => Some(lit.value()),
+        _ => None,
+    };
+
+    items.iter().find_map(|meta| match meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident(key) => lit_str(&name_value.lit),
+        Meta::List(list) if list.path.is_ident(key) => {
+            list.nested.iter().find_map(|nested| match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("deserialize") =>
+                {
+                    lit_str(&name_value.lit)
+                }
+                _ => None,
+            })
+        }
+        _ => None,
+    })
+}
+
+/// Whether the items exclude a field (or variant) from deserialized payloads, or make its
+/// name indeterminable (i.e., `flatten`).
+pub fn is_excluded(items: &[Meta]) -> bool {
+    items.iter().any(|meta| match meta {
+        Meta::Path(path) => {
+            path.is_ident("skip") || path.is_ident("skip_deserializing") || path.is_ident("flatten")
+        }
+        _ => false,
+    })
+}
+
+/// Apply a `rename_all` rule to the name of a field (written in `snake_case`) or, if `variant`
+/// is set, of a variant (written in `PascalCase`), returning `None` for unknown rules.
+pub fn apply_rule(rule: &str, name: &str, variant: bool) -> Option<String> {
+    // Normalise both to `snake_case` words, as `serde` does
+    let snake = if variant {
+        let mut snake = String::new();
+        for (i, ch) in name.char_indices() {
+            if i > 0 && ch.is_uppercase() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        }
+        snake
+    } else {
+        name.to_owned()
+    };
+    let pascal: String = snake
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect();
+
+    Some(match rule {
+        "lowercase" if variant => name.to_ascii_lowercase(),
+        "lowercase" | "snake_case" => snake,
+        "UPPERCASE" if variant => name.to_ascii_uppercase(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => snake.to_ascii_uppercase(),
+        "PascalCase" if variant => name.to_owned(),
+        "PascalCase" => pascal,
+        "camelCase" => {
+            let source = if variant { name } else { &pascal };
+            let mut chars = source.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_lowercase().to_string() + chars.as_str()
+            })
+        }
+        "kebab-case" => snake.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake.to_ascii_uppercase().replace('_', "-"),
+        _ => return None,
+    })
+}